@@ -0,0 +1,240 @@
+use crate::dictation::{DictationTarget, TriggerSource};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActionCategory {
+    Window,
+    Dictation,
+    Capture,
+    Gateway,
+    Settings,
+}
+
+/// An action as the command palette lists it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Action {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub category: ActionCategory,
+    /// Whether an on/off action is on now; `None` for the rest.
+    pub checked: Option<bool>,
+}
+
+/// What `invoke_action` can pass along; each action reads only the fields it needs.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionArgs {
+    /// Turn an on/off action on or off instead of flipping it.
+    pub enabled: Option<bool>,
+    /// The conversation for `window.conversation`; the main window when unset.
+    pub conversation_id: Option<String>,
+    /// Where `dictation.toggle` and `capture.system-audio` send the text.
+    pub target: Option<DictationTarget>,
+}
+
+struct Entry {
+    id: &'static str,
+    title: &'static str,
+    category: ActionCategory,
+    /// Reads the current state of an on/off action.
+    state: Option<fn(&tauri::AppHandle) -> bool>,
+    run: fn(&tauri::AppHandle, ActionArgs) -> Result<(), String>,
+}
+
+/// `enabled` when given, otherwise the opposite of `current`.
+fn flip(args: &ActionArgs, current: bool) -> bool {
+    args.enabled.unwrap_or(!current)
+}
+
+const ACTIONS: &[Entry] = &[
+    Entry {
+        id: "window.main",
+        title: "Show or hide the main window",
+        category: ActionCategory::Window,
+        state: None,
+        run: |app, _| {
+            crate::toggle_main_window(app.clone());
+            Ok(())
+        },
+    },
+    Entry {
+        id: "window.quick-ask",
+        title: "Quick ask",
+        category: ActionCategory::Window,
+        state: None,
+        run: |app, _| {
+            crate::quick_ask::toggle(app);
+            Ok(())
+        },
+    },
+    Entry {
+        id: "window.conversation",
+        title: "Open conversation",
+        category: ActionCategory::Window,
+        state: None,
+        run: |app, args| {
+            crate::conversation_windows::show(app, args.conversation_id);
+            Ok(())
+        },
+    },
+    Entry {
+        id: "window.answer",
+        title: "Show last answer",
+        category: ActionCategory::Window,
+        state: None,
+        run: |app, _| {
+            crate::response_window::reveal(app);
+            Ok(())
+        },
+    },
+    Entry {
+        id: "window.always-on-top",
+        title: "Keep main window on top",
+        category: ActionCategory::Window,
+        state: Some(|app| crate::get_always_on_top(app.clone())),
+        run: |app, args| crate::set_always_on_top(app.clone(), flip(&args, crate::get_always_on_top(app.clone()))),
+    },
+    Entry {
+        id: "dictation.toggle",
+        title: "Start or stop dictation",
+        category: ActionCategory::Dictation,
+        state: Some(|_| crate::dictation::is_active()),
+        run: |app, args| {
+            crate::dictation::toggle(app, TriggerSource::Action, args.target.unwrap_or(DictationTarget::Auto));
+            Ok(())
+        },
+    },
+    Entry {
+        id: "dictation.cancel",
+        title: "Cancel dictation",
+        category: ActionCategory::Dictation,
+        state: None,
+        run: |app, _| {
+            crate::dictation::cancel(app);
+            Ok(())
+        },
+    },
+    Entry {
+        id: "dictation.pause",
+        title: "Pause listening",
+        category: ActionCategory::Dictation,
+        state: Some(|_| crate::dictation::is_capture_paused()),
+        run: |app, args| {
+            crate::dictation::set_capture_paused(app.clone(), flip(&args, crate::dictation::is_capture_paused()));
+            Ok(())
+        },
+    },
+    Entry {
+        id: "capture.system-audio",
+        title: "Transcribe system audio",
+        category: ActionCategory::Capture,
+        state: Some(|_| crate::dictation::active_trigger() == Some(TriggerSource::SystemAudio)),
+        run: |app, args| {
+            let running = crate::dictation::active_trigger() == Some(TriggerSource::SystemAudio);
+            if flip(&args, running) {
+                if !running {
+                    crate::loopback::start_system_audio_capture(app.clone(), args.target)?;
+                }
+            } else {
+                crate::loopback::stop_system_audio_capture(app.clone());
+            }
+            Ok(())
+        },
+    },
+    Entry {
+        id: "capture.meeting",
+        title: "Record meeting",
+        category: ActionCategory::Capture,
+        state: Some(|_| crate::meeting::is_running()),
+        run: |app, args| {
+            let running = crate::meeting::is_running();
+            if flip(&args, running) {
+                if !running {
+                    crate::meeting::start_meeting(app.clone())?;
+                }
+            } else {
+                crate::meeting::stop_meeting();
+            }
+            Ok(())
+        },
+    },
+    Entry {
+        id: "gateway.restart",
+        title: "Restart gateway",
+        category: ActionCategory::Gateway,
+        state: None,
+        run: |app, _| {
+            crate::restart_gateway(app.clone());
+            Ok(())
+        },
+    },
+    Entry {
+        id: "gateway.logs",
+        title: "Open log folder",
+        category: ActionCategory::Gateway,
+        state: None,
+        run: |app, _| {
+            crate::tray::open_logs(app);
+            Ok(())
+        },
+    },
+    Entry {
+        id: "settings.notifications",
+        title: "Notifications",
+        category: ActionCategory::Settings,
+        state: Some(|app| crate::notifications::get_notifications_enabled(app.clone())),
+        run: |app, args| {
+            let enabled = flip(&args, crate::notifications::get_notifications_enabled(app.clone()));
+            crate::notifications::set_notifications_enabled(app.clone(), enabled)
+        },
+    },
+    Entry {
+        id: "settings.launch-at-login",
+        title: "Launch at login",
+        category: ActionCategory::Settings,
+        state: Some(|app| crate::launch_at_login::get_launch_at_login(app.clone())),
+        run: |app, args| {
+            let enabled = flip(&args, crate::launch_at_login::get_launch_at_login(app.clone()));
+            crate::launch_at_login::set_launch_at_login(app.clone(), enabled)
+        },
+    },
+    Entry {
+        id: "settings.context-capture",
+        title: "Include active app context",
+        category: ActionCategory::Settings,
+        state: Some(|app| crate::context::get_context_capture(app.clone())),
+        run: |app, args| {
+            let enabled = flip(&args, crate::context::get_context_capture(app.clone()));
+            crate::context::set_context_capture(app.clone(), enabled)
+        },
+    },
+];
+
+/// Run the action `id`, as the command palette, a `spaceduck://action/<id>` link or any
+/// other caller would.
+pub fn invoke(app: &tauri::AppHandle, id: &str, args: ActionArgs) -> Result<(), String> {
+    let entry = ACTIONS.iter().find(|entry| entry.id == id).ok_or_else(|| format!("Unknown action {id}"))?;
+    log::info!("Running action {id}");
+    (entry.run)(app, args)
+}
+
+/// Every native action, in menu order, with the state of the on/off ones.
+#[tauri::command]
+pub fn list_actions(app: tauri::AppHandle) -> Vec<Action> {
+    ACTIONS
+        .iter()
+        .map(|entry| Action {
+            id: entry.id,
+            title: entry.title,
+            category: entry.category,
+            checked: entry.state.map(|state| state(&app)),
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn invoke_action(app: tauri::AppHandle, id: String, args: Option<ActionArgs>) -> Result<(), String> {
+    invoke(&app, &id, args.unwrap_or_default())
+}
@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+use crate::audio::{Recording, PIPELINE_RATE};
+use crate::dictation::DictationMode;
+
+const ARCHIVE_DIR: &str = "recordings";
+const WAV_HEADER_LEN: u64 = 44;
+
+/// Keeps a copy of every native recording so mistranscriptions can be checked later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ArchiveConfig {
+    pub enabled: bool,
+    /// Recordings older than this are deleted; `None` keeps them indefinitely.
+    pub retention_days: Option<u32>,
+    /// Only the newest recordings are kept beyond this count; `None` means no limit.
+    pub max_recordings: Option<u32>,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self { enabled: false, retention_days: Some(30), max_recordings: Some(500) }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedRecording {
+    /// File name, used to address the recording in the other commands.
+    pub id: String,
+    pub path: PathBuf,
+    /// Unix time in milliseconds.
+    pub recorded_at: u64,
+    pub duration_ms: u64,
+    pub mode: String,
+}
+
+fn dir(handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let base = handle.path().app_data_dir().map_err(|e| format!("No app data dir: {e}"))?;
+    Ok(base.join(ARCHIVE_DIR))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+/// Recordings are named `<unix ms>-<mode>.wav`; anything else in the folder is ignored.
+fn parse(path: &Path) -> Option<ArchivedRecording> {
+    let id = path.file_name()?.to_str()?.to_string();
+    let (recorded_at, mode) = id.strip_suffix(".wav")?.split_once('-')?;
+    let size = std::fs::metadata(path).ok()?.len();
+    Some(ArchivedRecording {
+        recorded_at: recorded_at.parse().ok()?,
+        mode: mode.to_string(),
+        duration_ms: size.saturating_sub(WAV_HEADER_LEN) / 2 * 1000 / PIPELINE_RATE as u64,
+        path: path.to_path_buf(),
+        id,
+    })
+}
+
+/// Newest first.
+fn entries(dir: &Path) -> Result<Vec<ArchivedRecording>, String> {
+    let read = match std::fs::read_dir(dir) {
+        Ok(read) => read,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {e}", dir.display())),
+    };
+    let mut recordings: Vec<ArchivedRecording> = read.filter_map(|e| parse(&e.ok()?.path())).collect();
+    recordings.sort_by_key(|r| std::cmp::Reverse(r.recorded_at));
+    Ok(recordings)
+}
+
+/// Write `recording` to the archive if it is enabled, then apply the retention policy.
+/// Failures are logged rather than surfaced; the dictation itself should still go through.
+pub fn save(handle: &tauri::AppHandle, recording: &Recording, mode: DictationMode) {
+    let config = crate::config::read(handle, |c| c.archive.clone());
+    if !config.enabled {
+        return;
+    }
+    let result = dir(handle).and_then(|dir| {
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        let mode = match mode {
+            DictationMode::Chat => "chat",
+            DictationMode::Global => "global",
+        };
+        let path = dir.join(format!("{}-{mode}.wav", now_ms()));
+        std::fs::write(&path, recording.to_wav()).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    });
+    if let Err(e) = result {
+        log::warn!("Could not archive recording: {e}");
+        return;
+    }
+    if let Err(e) = dir(handle).and_then(|dir| prune(&dir, &config, now_ms())) {
+        log::warn!("Could not prune recording archive: {e}");
+    }
+}
+
+/// Delete the recordings in `dir` that are older than the retention period at `now`
+/// (Unix ms) or beyond the newest `max_recordings`.
+fn prune(dir: &Path, config: &ArchiveConfig, now: u64) -> Result<(), String> {
+    let cutoff = config
+        .retention_days
+        .map(|days| now.saturating_sub(Duration::from_secs(days as u64 * 86_400).as_millis() as u64));
+    for (index, recording) in entries(dir)?.into_iter().enumerate() {
+        let expired = cutoff.is_some_and(|cutoff| recording.recorded_at < cutoff);
+        let over_limit = config.max_recordings.is_some_and(|max| index >= max as usize);
+        if expired || over_limit {
+            std::fs::remove_file(&recording.path)
+                .map_err(|e| format!("Failed to delete {}: {e}", recording.path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn find(handle: &tauri::AppHandle, id: &str) -> Result<ArchivedRecording, String> {
+    entries(&dir(handle)?)?
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("Recording {id} not found"))
+}
+
+#[tauri::command]
+pub fn get_audio_archive(app: tauri::AppHandle) -> ArchiveConfig {
+    crate::config::read(&app, |c| c.archive.clone())
+}
+
+/// Changing the limits prunes straight away rather than waiting for the next recording.
+#[tauri::command]
+pub fn set_audio_archive(app: tauri::AppHandle, config: ArchiveConfig) -> Result<(), String> {
+    if config.retention_days == Some(0) || config.max_recordings == Some(0) {
+        return Err("Retention limits must be at least 1".to_string());
+    }
+    crate::config::update(&app, |c| c.archive = config.clone())?;
+    prune(&dir(&app)?, &config, now_ms())
+}
+
+#[tauri::command]
+pub fn list_recordings(app: tauri::AppHandle) -> Result<Vec<ArchivedRecording>, String> {
+    entries(&dir(&app)?)
+}
+
+/// Path of an archived recording, for the webview to play back via the asset protocol.
+#[tauri::command]
+pub fn get_recording_path(app: tauri::AppHandle, id: String) -> Result<PathBuf, String> {
+    find(&app, &id).map(|r| r.path)
+}
+
+/// Delete the given recordings, or all of them when `ids` is `None`. Returns how many were removed.
+#[tauri::command]
+pub fn purge_recordings(app: tauri::AppHandle, ids: Option<Vec<String>>) -> Result<usize, String> {
+    let targets = match ids {
+        Some(ids) => ids.iter().map(|id| find(&app, id)).collect::<Result<Vec<_>, _>>()?,
+        None => entries(&dir(&app)?)?,
+    };
+    for recording in &targets {
+        std::fs::remove_file(&recording.path)
+            .map_err(|e| format!("Failed to delete {}: {e}", recording.path.display()))?;
+    }
+    Ok(targets.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: u64 = 86_400_000;
+    const NOW: u64 = 100 * DAY_MS;
+
+    fn archive(name: &str, ages_in_days: &[u64]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spaceduck-archive-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for days in ages_in_days {
+            std::fs::write(dir.join(format!("{}-global.wav", NOW - days * DAY_MS)), [0u8; 44]).unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "not a recording").unwrap();
+        dir
+    }
+
+    fn ages(dir: &Path) -> Vec<u64> {
+        entries(dir).unwrap().iter().map(|r| (NOW - r.recorded_at) / DAY_MS).collect()
+    }
+
+    #[test]
+    fn lists_recordings_newest_first() {
+        let dir = archive("list", &[3, 1, 2]);
+        let recordings = entries(&dir).unwrap();
+        assert_eq!(ages(&dir), [1, 2, 3]);
+        assert_eq!(recordings[0].mode, "global");
+        assert_eq!(recordings[0].duration_ms, 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn prunes_by_age() {
+        let dir = archive("age", &[1, 29, 31, 60]);
+        let config = ArchiveConfig { enabled: true, retention_days: Some(30), max_recordings: None };
+        prune(&dir, &config, NOW).unwrap();
+        assert_eq!(ages(&dir), [1, 29]);
+        assert!(dir.join("notes.txt").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn prunes_by_count_keeping_the_newest() {
+        let dir = archive("count", &[5, 1, 4, 2, 3]);
+        let config = ArchiveConfig { enabled: true, retention_days: None, max_recordings: Some(3) };
+        prune(&dir, &config, NOW).unwrap();
+        assert_eq!(ages(&dir), [1, 2, 3]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn applies_both_limits_and_keeps_everything_without_them() {
+        let dir = archive("both", &[1, 2, 3, 40]);
+        prune(&dir, &ArchiveConfig { enabled: true, retention_days: None, max_recordings: None }, NOW).unwrap();
+        assert_eq!(ages(&dir), [1, 2, 3, 40]);
+        prune(&dir, &ArchiveConfig { enabled: true, retention_days: Some(30), max_recordings: Some(2) }, NOW).unwrap();
+        assert_eq!(ages(&dir), [1, 2]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn treats_a_missing_archive_as_empty() {
+        let dir = std::env::temp_dir().join(format!("spaceduck-archive-{}-missing", std::process::id()));
+        assert!(entries(&dir).unwrap().is_empty());
+        prune(&dir, &ArchiveConfig::default(), NOW).unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tungstenite::Message;
+
+/// How long a native request may take, tool calls included.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(120);
+const READ_POLL: Duration = Duration::from_millis(500);
+
+/// The slice of the gateway's `/ws` chat protocol native features need.
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum ClientMessage<'a> {
+    #[serde(rename = "message.send", rename_all = "camelCase")]
+    Send { v: u8, request_id: &'a str, content: &'a str },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum ServerMessage {
+    #[serde(rename = "stream.delta")]
+    Delta { delta: String },
+    #[serde(rename = "stream.done")]
+    Done,
+    #[serde(rename = "stream.error")]
+    StreamError { message: String },
+    #[serde(rename = "error")]
+    Error { message: String },
+    #[serde(other)]
+    Other,
+}
+
+/// Send `prompt` to the assistant and wait for the whole answer. `sender` picks the
+/// gateway session, so each native feature keeps its own conversation instead of
+/// cluttering the one open in the chat window. Blocks; call it off the main thread.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn ask(handle: &tauri::AppHandle, sender: &str, prompt: &str) -> Result<String, String> {
+    ask_streaming(handle, sender, prompt, |_| {})
+}
+
+/// `ask`, also handing each piece of the answer to `on_delta` as it arrives.
+pub fn ask_streaming(
+    handle: &tauri::AppHandle,
+    sender: &str,
+    prompt: &str,
+    mut on_delta: impl FnMut(&str),
+) -> Result<String, String> {
+    let gateway = crate::config::read(handle, |c| c.gateway.clone());
+    let mut socket = crate::gateway_stream::connect(&gateway, &format!("/ws?senderId={sender}"), READ_POLL)?;
+    let request_id = format!(
+        "{sender}-{}",
+        std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_millis())
+    );
+    let message = ClientMessage::Send { v: 1, request_id: &request_id, content: prompt };
+    let json = serde_json::to_string(&message).map_err(|e| format!("Failed to encode message: {e}"))?;
+    socket.send(Message::text(json)).map_err(|e| format!("Failed to send to the assistant: {e}"))?;
+
+    let deadline = Instant::now() + ANSWER_TIMEOUT;
+    let mut answer = String::new();
+    while Instant::now() < deadline {
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => return Err("The gateway closed the connection".to_string()),
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+            {
+                continue
+            }
+            Err(e) => return Err(format!("Assistant connection lost: {e}")),
+        };
+        match serde_json::from_str::<ServerMessage>(&text) {
+            Ok(ServerMessage::Delta { delta }) => {
+                on_delta(&delta);
+                answer.push_str(&delta);
+            }
+            Ok(ServerMessage::Done) => {
+                let _ = socket.close(None);
+                return Ok(answer.trim().to_string());
+            }
+            Ok(ServerMessage::StreamError { message } | ServerMessage::Error { message }) => {
+                return Err(format!("Assistant error: {message}"))
+            }
+            Ok(ServerMessage::Other) => {}
+            Err(e) => log::debug!("Ignoring unexpected gateway message: {e}"),
+        }
+    }
+    Err("The assistant did not answer in time".to_string())
+}
@@ -0,0 +1,342 @@
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::Sample;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Mutex, OnceLock};
+use tauri::Emitter;
+
+use crate::dictation::DictationMode;
+
+mod capture;
+mod device;
+mod sink;
+
+pub use capture::start;
+pub(crate) use device::{input, open_stream};
+
+/// Every recording is converted to this rate, mono, whatever the device delivers.
+/// It is what Whisper and the VAD want, and keeps uploads to the gateway small.
+pub const PIPELINE_RATE: u32 = 16_000;
+
+/// Native-side capture settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AudioConfig {
+    /// Record in Rust instead of the webview. The webview keeps using getUserMedia while this is off.
+    pub native_capture: bool,
+    /// Preferred input device id; `None` follows the system default.
+    pub input_device: Option<String>,
+    /// Hands-free (toggle) recordings stop after this much trailing silence; `None` disables.
+    pub auto_stop_silence_ms: Option<u32>,
+    /// Run rnnoise between capture and transcription.
+    pub noise_suppression: bool,
+    /// Boost quiet input towards a steady speaking level.
+    pub auto_gain: bool,
+    /// Prepend this much audio from before the trigger. Keeps the microphone open whenever
+    /// native capture is on and not paused; the audio is held in memory only and discarded
+    /// unless a dictation starts. `None` turns it off.
+    pub pre_roll_ms: Option<u32>,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            native_capture: false,
+            input_device: None,
+            auto_stop_silence_ms: Some(2000),
+            noise_suppression: false,
+            auto_gain: true,
+            pre_roll_ms: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceChanged {
+    /// The saved preference, which is kept even while that device is missing.
+    requested: Option<String>,
+    active: Option<String>,
+    fell_back: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioInput {
+    /// cpal has no stable device identifier, so the device name doubles as the id.
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+}
+
+/// Captured audio, downmixed to mono at `PIPELINE_RATE`.
+pub struct Recording {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+impl Recording {
+    /// Join segments recorded on different devices, converting them to the first one's rate.
+    fn concat(segments: Vec<Recording>) -> Option<Recording> {
+        let sample_rate = segments.first()?.sample_rate;
+        let mut samples = Vec::new();
+        for segment in segments {
+            if segment.sample_rate == sample_rate {
+                samples.extend(segment.samples);
+            } else {
+                samples.extend(resample_linear(&segment.samples, segment.sample_rate, sample_rate));
+            }
+        }
+        Some(Recording { samples, sample_rate })
+    }
+
+    pub fn duration_ms(&self) -> u32 {
+        (self.samples.len() as u64 * 1000 / self.sample_rate.max(1) as u64) as u32
+    }
+
+    /// Encode as 16-bit PCM WAV, the format every STT backend accepts.
+    pub fn to_wav(&self) -> Vec<u8> {
+        let data_len = (self.samples.len() * 2) as u32;
+        let mut out = Vec::with_capacity(44 + data_len as usize);
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data_len).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        out.extend_from_slice(&1u16.to_le_bytes()); // mono
+        out.extend_from_slice(&self.sample_rate.to_le_bytes());
+        out.extend_from_slice(&(self.sample_rate * 2).to_le_bytes());
+        out.extend_from_slice(&2u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        for s in &self.samples {
+            out.extend_from_slice(&i16::from_sample(*s).to_le_bytes());
+        }
+        out
+    }
+
+    /// Decode a WAV written by `to_wav`. Other layouts are rejected rather than guessed at.
+    pub fn from_wav(bytes: &[u8]) -> Result<Recording, String> {
+        let header = bytes.get(..44).ok_or_else(|| "WAV file is truncated".to_string())?;
+        let u16_at = |i: usize| u16::from_le_bytes([header[i], header[i + 1]]);
+        if &header[..4] != b"RIFF" || &header[8..16] != b"WAVEfmt " || u16_at(20) != 1 || u16_at(22) != 1 || u16_at(34) != 16 {
+            return Err("Not a 16-bit mono PCM WAV".to_string());
+        }
+        let sample_rate = u32::from_le_bytes([header[24], header[25], header[26], header[27]]);
+        let samples = bytes[44..]
+            .chunks_exact(2)
+            .map(|b| f32::from_sample(i16::from_le_bytes([b[0], b[1]])))
+            .collect();
+        Ok(Recording { samples, sample_rate })
+    }
+}
+
+pub(crate) fn resample_linear(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    let ratio = from as f64 / to as f64;
+    let len = (samples.len() as f64 / ratio) as usize;
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx.min(samples.len() - 1)];
+            let b = samples[(idx + 1).min(samples.len() - 1)];
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Where a recording's audio comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptureSource {
+    Microphone,
+    /// What is playing through the speakers, e.g. the other side of a call.
+    System,
+}
+
+enum Control {
+    /// The flag marks a hands-free recording, which may stop itself on silence.
+    /// The last field is the language to translate the result into, if any.
+    Start(DictationMode, bool, CaptureSource, Option<String>),
+    Stop(DictationMode),
+    Cancel,
+    /// The preferred device or processing changed; reopen if recording.
+    Switch,
+    /// Pre-roll settings or the pause state changed, or the pre-roll device went away.
+    Arm,
+    /// The open stream reported its device gone.
+    DeviceLost,
+}
+
+/// cpal streams are not `Send`, so one worker thread owns them and takes orders over this channel.
+static CONTROL: OnceLock<Mutex<Sender<Control>>> = OnceLock::new();
+
+/// Bumped on every start so late partial results from an earlier recording can be dropped.
+static SESSION_ID: AtomicU64 = AtomicU64::new(0);
+
+pub fn is_current_session(id: u64) -> bool {
+    SESSION_ID.load(Ordering::SeqCst) == id
+}
+
+fn send(control: Control) {
+    let Some(tx) = CONTROL.get() else { return };
+    let _ = tx.lock().unwrap_or_else(|e| e.into_inner()).send(control);
+}
+
+pub fn native_capture_enabled(handle: &tauri::AppHandle) -> bool {
+    crate::config::read(handle, |c| c.audio.native_capture)
+}
+
+/// Open the input stream. Returns immediately; failures surface as `dictation:error-*`.
+pub fn start_capture(mode: DictationMode, hands_free: bool, source: CaptureSource, translate_to: Option<String>) {
+    send(Control::Start(mode, hands_free, source, translate_to));
+}
+
+/// Close the stream and hand the recording to the transcription pipeline.
+pub fn stop_capture(mode: DictationMode) {
+    send(Control::Stop(mode));
+}
+
+/// Close the stream and drop whatever was recorded.
+pub fn cancel_capture() {
+    send(Control::Cancel);
+}
+
+/// Reopen or close the pre-roll stream to match the current settings.
+pub fn rearm() {
+    send(Control::Arm);
+}
+
+#[tauri::command]
+pub fn list_audio_inputs() -> Result<Vec<AudioInput>, String> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let devices = host
+        .input_devices()
+        .map_err(|e| format!("Failed to enumerate input devices: {e}"))?;
+
+    Ok(devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let config = device.default_input_config().ok();
+            Some(AudioInput {
+                id: name.clone(),
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                sample_rate: config.as_ref().map(|c| c.sample_rate().0),
+                channels: config.as_ref().map(|c| c.channels()),
+                name,
+            })
+        })
+        .collect())
+}
+
+/// Choose the capture device (`None` for the system default). Takes effect immediately,
+/// including mid-recording, and is remembered across restarts.
+#[tauri::command]
+pub fn set_audio_input(app: tauri::AppHandle, device_id: Option<String>) -> Result<(), String> {
+    if let Some(id) = &device_id {
+        let exists = list_audio_inputs()?.iter().any(|d| &d.id == id);
+        if !exists {
+            return Err(format!("Input device {id} not found"));
+        }
+    }
+    crate::config::update(&app, |c| c.audio.input_device = device_id.clone())?;
+    let _ = app.emit(
+        "audio-device-changed",
+        DeviceChanged { requested: device_id.clone(), active: device_id, fell_back: false },
+    );
+    send(Control::Switch);
+    Ok(())
+}
+
+/// How much trailing silence ends a hands-free recording; `None` turns auto-stop off.
+#[tauri::command]
+pub fn set_auto_stop_silence(app: tauri::AppHandle, silence_ms: Option<u32>) -> Result<(), String> {
+    if silence_ms.is_some_and(|ms| !(500..=30_000).contains(&ms)) {
+        return Err("Silence timeout must be between 0.5 and 30 seconds".to_string());
+    }
+    crate::config::update(&app, |c| c.audio.auto_stop_silence_ms = silence_ms)
+}
+
+/// Toggle rnnoise. An open recording switches over immediately, like changing device.
+#[tauri::command]
+pub fn set_noise_suppression(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.audio.noise_suppression = enabled)?;
+    send(Control::Switch);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_auto_gain(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.audio.auto_gain = enabled)?;
+    send(Control::Switch);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_audio_config(app: tauri::AppHandle) -> AudioConfig {
+    crate::config::read(&app, |c| c.audio.clone())
+}
+
+/// How much audio from before the trigger to keep, between 100 ms and 3 s; `None` turns
+/// pre-roll off and releases the microphone between dictations.
+#[tauri::command]
+pub fn set_pre_roll(app: tauri::AppHandle, duration_ms: Option<u32>) -> Result<(), String> {
+    if duration_ms.is_some_and(|ms| !(100..=3000).contains(&ms)) {
+        return Err("Pre-roll must be between 0.1 and 3 seconds".to_string());
+    }
+    crate::config::update(&app, |c| c.audio.pre_roll_ms = duration_ms)?;
+    rearm();
+    Ok(())
+}
+
+/// Switch between native and webview capture. Takes effect from the next recording.
+#[tauri::command]
+pub fn set_native_capture(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.audio.native_capture = enabled)?;
+    rearm();
+    let _ = app.emit("audio-config-changed", crate::config::read(&app, |c| c.audio.clone()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resamples_between_rates() {
+        assert!(resample_linear(&[], 48_000, 16_000).is_empty());
+        let ramp: Vec<f32> = (0..480).map(|i| i as f32).collect();
+        assert_eq!(resample_linear(&ramp, 16_000, 16_000), ramp);
+        let down = resample_linear(&ramp, 48_000, 16_000);
+        assert_eq!(down.len(), 160);
+        assert_eq!(down[..3], [0.0, 3.0, 6.0]);
+    }
+
+    #[test]
+    fn interpolates_when_upsampling() {
+        let up = resample_linear(&[0.0, 1.0, 0.0], 8_000, 16_000);
+        assert_eq!(up, [0.0, 0.5, 1.0, 0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn joins_segments_at_the_first_rate() {
+        let segments = vec![
+            Recording { samples: vec![0.5; 160], sample_rate: 16_000 },
+            Recording { samples: vec![0.25; 480], sample_rate: 48_000 },
+        ];
+        let joined = Recording::concat(segments).unwrap();
+        assert_eq!(joined.sample_rate, 16_000);
+        assert_eq!(joined.samples.len(), 320);
+        assert_eq!(joined.duration_ms(), 20);
+        assert!(Recording::concat(Vec::new()).is_none());
+    }
+}
@@ -0,0 +1,386 @@
+use cpal::traits::DeviceTrait;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use super::device::{input_avoiding, open_stream};
+use super::sink::Sink;
+use super::{send, CaptureSource, Control, Recording, CONTROL, PIPELINE_RATE, SESSION_ID};
+use crate::dictation::DictationMode;
+use crate::gateway_stream::GatewayStream;
+use crate::loopback::LoopbackInput;
+use crate::pre_roll::PreRoll;
+use crate::vad::SilenceDetector;
+
+/// Recordings shorter than this are almost always an accidental tap.
+const MIN_RECORDING_MS: u32 = 200;
+/// How often the growing buffer is re-transcribed for `dictation:partial`.
+const PARTIAL_INTERVAL: Duration = Duration::from_millis(1000);
+/// How often hands-free recordings are checked for trailing silence.
+const VAD_INTERVAL: Duration = Duration::from_millis(100);
+/// How often new audio is forwarded when streaming to the gateway.
+const STREAM_INTERVAL: Duration = Duration::from_millis(100);
+/// A microphone that delivers nothing for this long is treated as disconnected. Some
+/// Bluetooth headsets just stop calling back when they die instead of reporting an error.
+const STALL_TIMEOUT: Duration = Duration::from_millis(2000);
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(500);
+/// After a device is lost the OS needs a moment to promote a new default.
+const REOPEN_ATTEMPTS: u32 = 5;
+const REOPEN_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceSwitched {
+    mode: &'static str,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+/// Run the capture worker on the current thread.
+/// This function blocks forever, so call it from a dedicated thread.
+pub fn start(handle: tauri::AppHandle) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    CONTROL
+        .set(Mutex::new(tx))
+        .map_err(|_| "Audio worker already running".to_string())?;
+
+    let mut active: Option<Session> = None;
+    let mut next_partial = Instant::now();
+    let mut next_vad = Instant::now();
+    let mut next_stream = Instant::now();
+    let mut next_watchdog = Instant::now();
+    let mut pre_roll = arm(&handle);
+    loop {
+        let streaming = active.as_ref().is_some_and(|s| s.stream.is_some());
+        // A streaming gateway sends its own partials.
+        let partials = active.is_some() && !streaming && crate::config::read(&handle, |c| c.partial_results);
+        let vad = active.as_ref().is_some_and(|s| s.silence.is_some());
+        let watch = active.as_ref().is_some_and(|s| s.source == CaptureSource::Microphone && s.capture.is_some());
+        let deadline = [(partials, next_partial), (vad, next_vad), (streaming, next_stream), (watch, next_watchdog)]
+            .into_iter()
+            .filter_map(|(on, at)| on.then_some(at))
+            .min();
+
+        let control = match deadline {
+            Some(deadline) => match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(control) => control,
+                Err(RecvTimeoutError::Timeout) => {
+                    let now = Instant::now();
+                    let Some(session) = active.as_mut() else { continue };
+                    if partials && now >= next_partial {
+                        next_partial = now + PARTIAL_INTERVAL;
+                        if let Some(recording) = session.snapshot() {
+                            if recording.duration_ms() >= MIN_RECORDING_MS {
+                                crate::transcription::submit_partial(&handle, recording, SESSION_ID.load(Ordering::SeqCst));
+                            }
+                        }
+                    }
+                    if streaming && now >= next_stream {
+                        next_stream = now + STREAM_INTERVAL;
+                        session.stream_fresh();
+                    }
+                    if vad && now >= next_vad {
+                        next_vad = now + VAD_INTERVAL;
+                        if session.detect_silence() {
+                            // Comes back to this worker as an ordinary Stop.
+                            crate::dictation::end_on_silence(&handle);
+                        }
+                    }
+                    if watch && now >= next_watchdog {
+                        next_watchdog = now + WATCHDOG_INTERVAL;
+                        if session.stalled(now) {
+                            log::warn!("No audio from the input device for {}ms", STALL_TIMEOUT.as_millis());
+                            session.reopen(&handle, true);
+                        }
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+            None => match rx.recv() {
+                Ok(control) => control,
+                Err(_) => break,
+            },
+        };
+
+        match control {
+            Control::Start(mode, hands_free, source, translate_to) => {
+                active = None;
+                SESSION_ID.fetch_add(1, Ordering::SeqCst);
+                next_partial = Instant::now() + PARTIAL_INTERVAL;
+                next_vad = Instant::now() + VAD_INTERVAL;
+                next_stream = Instant::now() + STREAM_INTERVAL;
+                next_watchdog = Instant::now() + WATCHDOG_INTERVAL;
+                let silence = crate::config::read(&handle, |c| c.audio.auto_stop_silence_ms)
+                    .filter(|_| hands_free)
+                    .map(|ms| (SilenceDetector::new(ms), 0));
+                let lead = match (&pre_roll, source) {
+                    (Some(pre_roll), CaptureSource::Microphone) => pre_roll.take(),
+                    _ => Vec::new(),
+                };
+                match Capture::open(&handle, source, None) {
+                    Ok(capture) => {
+                        let stream = crate::transcription::streams_to_gateway(&handle).then(|| {
+                            let stream = GatewayStream::open(&handle, SESSION_ID.load(Ordering::SeqCst));
+                            stream.send(&lead);
+                            (stream, 0)
+                        });
+                        let segments = if lead.is_empty() {
+                            Vec::new()
+                        } else {
+                            vec![Recording { samples: lead, sample_rate: PIPELINE_RATE }]
+                        };
+                        active = Some(Session {
+                            mode,
+                            source,
+                            translate_to,
+                            capture: Some(capture),
+                            segments,
+                            silence,
+                            stream,
+                            watchdog: (0, Instant::now()),
+                        })
+                    }
+                    Err(e) => {
+                        log::error!("Audio capture failed: {e}");
+                        crate::dictation::abandon(&handle);
+                        drop_recording(&handle, mode, &e);
+                    }
+                }
+            }
+            Control::Stop(mode) => {
+                SESSION_ID.fetch_add(1, Ordering::SeqCst);
+                let Some(mut session) = active.take() else { continue };
+                session.stream_fresh();
+                let stream = session.stream.take().map(|(stream, _)| stream);
+                let translate_to = session.translate_to.take();
+                let Some(recording) = session.finish() else {
+                    drop_recording(&handle, mode, "Nothing was recorded");
+                    continue;
+                };
+                if recording.duration_ms() < MIN_RECORDING_MS {
+                    log::debug!("Dropping {}ms recording", recording.duration_ms());
+                    drop_recording(&handle, mode, "Too short to transcribe");
+                    continue;
+                }
+                crate::archive::save(&handle, &recording, mode);
+                crate::transcription::submit(&handle, recording, mode, stream, translate_to);
+            }
+            Control::Cancel => {
+                SESSION_ID.fetch_add(1, Ordering::SeqCst);
+                active = None;
+            }
+            Control::Arm => pre_roll = arm(&handle),
+            Control::Switch | Control::DeviceLost => {
+                if matches!(control, Control::Switch) {
+                    pre_roll = arm(&handle);
+                }
+                let lost = matches!(control, Control::DeviceLost);
+                if lost {
+                    log::warn!("Audio input device disconnected");
+                }
+                let Some(session) = active.as_mut() else { continue };
+                session.reopen(&handle, lost);
+            }
+        }
+    }
+
+    Err("Audio worker channel closed".to_string())
+}
+
+/// End a recording that will not be transcribed, so the pill and chat input stop waiting for it.
+fn drop_recording(handle: &tauri::AppHandle, mode: DictationMode, message: &str) {
+    crate::transcription::emit_error(handle, mode, message);
+    if mode == DictationMode::Global {
+        crate::pill::idle(handle);
+    }
+}
+
+/// Open the pre-roll stream if it is wanted right now. It is replaced rather than reused
+/// whenever something changes, so it always follows the preferred device.
+fn arm(handle: &tauri::AppHandle) -> Option<PreRoll> {
+    let duration = crate::config::read(handle, |c| c.audio.pre_roll_ms.filter(|_| c.audio.native_capture))?;
+    if crate::dictation::is_capture_paused() || crate::mic_access::is_blocked() {
+        return None;
+    }
+    PreRoll::open(handle, duration, || send(Control::Arm))
+        .map_err(|e| log::warn!("Pre-roll unavailable: {e}"))
+        .ok()
+}
+
+/// A recording in progress, possibly spanning several devices.
+struct Session {
+    mode: DictationMode,
+    source: CaptureSource,
+    translate_to: Option<String>,
+    capture: Option<Capture>,
+    segments: Vec<Recording>,
+    /// Set for hands-free recordings, with how far into the capture buffer it has listened.
+    silence: Option<(SilenceDetector, usize)>,
+    /// Set while streaming to the gateway, with how much of the capture buffer has been sent.
+    stream: Option<(GatewayStream, usize)>,
+    /// Capture buffer length at the last watchdog check, and when it last grew.
+    watchdog: (usize, Instant),
+}
+
+impl Session {
+    /// Close the current device and continue on the preferred one, keeping what was
+    /// captured so far. When the device was `lost` it is avoided, and the switch is
+    /// announced with `dictation:device-switched`.
+    fn reopen(&mut self, handle: &tauri::AppHandle, lost: bool) {
+        self.stream_fresh();
+        let previous = self.capture.as_ref().and_then(|c| c.device.clone());
+        // The new device's audio is appended to what the old one recorded.
+        if let Some(capture) = self.capture.take() {
+            self.segments.push(capture.finish());
+        }
+        if let Some((_, cursor)) = self.silence.as_mut() {
+            *cursor = 0;
+        }
+        if let Some((_, cursor)) = self.stream.as_mut() {
+            *cursor = 0;
+        }
+        self.watchdog = (0, Instant::now());
+
+        let avoid = if lost { previous.as_deref() } else { None };
+        let attempts = if lost { REOPEN_ATTEMPTS } else { 1 };
+        let mut result = Err(String::new());
+        for attempt in 0..attempts {
+            if attempt > 0 {
+                std::thread::sleep(REOPEN_DELAY);
+            }
+            result = Capture::open(handle, self.source, avoid);
+            if result.is_ok() {
+                break;
+            }
+        }
+        match result {
+            Ok(capture) => {
+                if lost {
+                    let to = capture.device.clone();
+                    log::info!("Recording continues on {}", to.as_deref().unwrap_or("the system default"));
+                    let mode = match self.mode {
+                        DictationMode::Chat => "chat",
+                        DictationMode::Global => "global",
+                    };
+                    let _ = handle.emit("dictation:device-switched", DeviceSwitched { mode, from: previous, to });
+                }
+                self.capture = Some(capture);
+            }
+            Err(e) => {
+                log::error!("Audio capture failed: {e}");
+                crate::transcription::emit_error(handle, self.mode, &e);
+            }
+        }
+    }
+
+    /// Whether the capture has produced nothing for `STALL_TIMEOUT`.
+    fn stalled(&mut self, now: Instant) -> bool {
+        let Some(capture) = self.capture.as_ref() else { return false };
+        let len = capture.len();
+        let (last_len, grew_at) = &mut self.watchdog;
+        if len != *last_len {
+            *last_len = len;
+            *grew_at = now;
+            return false;
+        }
+        now.duration_since(*grew_at) >= STALL_TIMEOUT
+    }
+
+    /// Forward audio captured since the last call to the gateway stream.
+    fn stream_fresh(&mut self) {
+        let (Some((stream, cursor)), Some(capture)) = (self.stream.as_mut(), self.capture.as_ref()) else {
+            return;
+        };
+        let fresh = capture.samples_from(*cursor);
+        *cursor += fresh.len();
+        stream.send(&fresh);
+    }
+
+    /// Feed audio captured since the last check to the silence detector.
+    fn detect_silence(&mut self) -> bool {
+        let (Some((detector, cursor)), Some(capture)) = (self.silence.as_mut(), self.capture.as_ref()) else {
+            return false;
+        };
+        let fresh = capture.samples_from(*cursor);
+        *cursor += fresh.len();
+        detector.feed(&fresh, capture.sample_rate)
+    }
+
+    /// Everything recorded so far, without stopping.
+    fn snapshot(&self) -> Option<Recording> {
+        let mut segments: Vec<Recording> = self
+            .segments
+            .iter()
+            .map(|r| Recording { samples: r.samples.clone(), sample_rate: r.sample_rate })
+            .collect();
+        if let Some(capture) = &self.capture {
+            segments.push(capture.snapshot());
+        }
+        Recording::concat(segments)
+    }
+
+    fn finish(mut self) -> Option<Recording> {
+        if let Some(capture) = self.capture.take() {
+            self.segments.push(capture.finish());
+        }
+        Recording::concat(self.segments)
+    }
+}
+
+/// An open input stream and the mono samples it has produced so far.
+struct Capture {
+    /// A cpal stream or a `LoopbackStream`, kept alive until the capture ends.
+    _stream: Box<dyn std::any::Any>,
+    samples: Arc<Mutex<Vec<f32>>>,
+    sample_rate: u32,
+    /// Name of the input device, for microphone captures.
+    device: Option<String>,
+}
+
+impl Capture {
+    fn open(handle: &tauri::AppHandle, source: CaptureSource, avoid: Option<&str>) -> Result<Self, String> {
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let lost = || send(Control::DeviceLost);
+        let (stream, device): (Box<dyn std::any::Any>, _) = match source {
+            CaptureSource::Microphone => {
+                let (device, config) = input_avoiding(handle, avoid)?;
+                let device_rate = config.sample_rate().0;
+                let mut sink = Sink::new(handle, samples.clone(), device_rate)?;
+                let stream = open_stream(&device, &config, move |mono| sink.push(mono), lost)?;
+                let name = device.name().ok();
+                log::info!("Capturing from {} at {device_rate} Hz", name.as_deref().unwrap_or("unknown device"));
+                (Box::new(stream), name)
+            }
+            CaptureSource::System => {
+                let loopback = LoopbackInput::open()?;
+                let mut sink = Sink::new(handle, samples.clone(), loopback.sample_rate())?;
+                log::info!("Capturing system audio at {} Hz", loopback.sample_rate());
+                (Box::new(loopback.start(move |mono| sink.push(mono), lost)?), None)
+            }
+        };
+        Ok(Self { _stream: stream, samples, sample_rate: PIPELINE_RATE, device })
+    }
+
+    fn len(&self) -> usize {
+        self.samples.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    fn samples_from(&self, start: usize) -> Vec<f32> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.get(start..).map(<[f32]>::to_vec).unwrap_or_default()
+    }
+
+    fn snapshot(&self) -> Recording {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        Recording { samples, sample_rate: self.sample_rate }
+    }
+
+    fn finish(self) -> Recording {
+        let samples = std::mem::take(&mut *self.samples.lock().unwrap_or_else(|e| e.into_inner()));
+        Recording { samples, sample_rate: self.sample_rate }
+    }
+}
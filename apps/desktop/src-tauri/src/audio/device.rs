@@ -0,0 +1,126 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
+use tauri::Emitter;
+
+use super::DeviceChanged;
+
+/// The preferred device if it is connected, else the system default.
+/// Falling back emits `audio-device-changed` so the UI can say the headset is gone.
+/// `avoid` names a device that just failed and may still be listed for a moment.
+fn resolve_device(handle: &tauri::AppHandle, avoid: Option<&str>) -> Result<cpal::Device, String> {
+    let host = cpal::default_host();
+    let requested = crate::config::read(handle, |c| c.audio.input_device.clone());
+    let usable = |d: &cpal::Device| avoid.is_none() || d.name().ok().as_deref() != avoid;
+
+    if let Some(id) = requested.as_deref().filter(|id| Some(*id) != avoid) {
+        let found = host
+            .input_devices()
+            .map_err(|e| format!("Failed to enumerate input devices: {e}"))?
+            .find(|d| d.name().ok().as_deref() == Some(id));
+        if let Some(device) = found {
+            return Ok(device);
+        }
+    }
+
+    let device = match host.default_input_device().filter(usable) {
+        Some(device) => device,
+        None => host
+            .input_devices()
+            .map_err(|e| format!("Failed to enumerate input devices: {e}"))?
+            .find(usable)
+            .ok_or_else(|| "No audio input device available".to_string())?,
+    };
+    if let Some(id) = &requested {
+        let active = device.name().ok();
+        log::warn!("Input device {id} not found, using {}", active.as_deref().unwrap_or("system default"));
+        let _ = handle.emit("audio-device-changed", DeviceChanged { requested: requested.clone(), active, fell_back: true });
+    }
+    Ok(device)
+}
+
+/// The device's preferred config, or failing that its highest-rate supported one.
+/// Some interfaces report no default but still work at a fixed format.
+fn input_config(device: &cpal::Device) -> Result<cpal::SupportedStreamConfig, String> {
+    if let Ok(config) = device.default_input_config() {
+        return Ok(config);
+    }
+    device
+        .supported_input_configs()
+        .map_err(|e| format!("Failed to read input config: {e}"))?
+        .max_by_key(|range| range.max_sample_rate())
+        .map(|range| range.with_max_sample_rate())
+        .ok_or_else(|| "The input device reports no usable format".to_string())
+}
+
+/// The input device to record from and the format to open it with.
+pub(crate) fn input(handle: &tauri::AppHandle) -> Result<(cpal::Device, cpal::SupportedStreamConfig), String> {
+    input_avoiding(handle, None)
+}
+
+pub(super) fn input_avoiding(
+    handle: &tauri::AppHandle,
+    avoid: Option<&str>,
+) -> Result<(cpal::Device, cpal::SupportedStreamConfig), String> {
+    let device = resolve_device(handle, avoid)?;
+    let config = input_config(&device)?;
+    Ok((device, config))
+}
+
+/// Start `device` in any sample format cpal supports, handing `on_audio` mono f32 blocks
+/// at the device rate. `on_lost` runs on the audio thread if the device goes away.
+pub(crate) fn open_stream(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    on_audio: impl FnMut(&[f32]) + Send + 'static,
+    on_lost: fn(),
+) -> Result<cpal::Stream, String> {
+    let channels = config.channels() as usize;
+    let stream_config = config.config();
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::I8 => build_stream::<i8>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::I16 => build_stream::<i16>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::I32 => build_stream::<i32>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::I64 => build_stream::<i64>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::U8 => build_stream::<u8>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::U16 => build_stream::<u16>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::U32 => build_stream::<u32>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::U64 => build_stream::<u64>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::F32 => build_stream::<f32>(device, &stream_config, channels, on_audio, on_lost),
+        cpal::SampleFormat::F64 => build_stream::<f64>(device, &stream_config, channels, on_audio, on_lost),
+        other => return Err(format!("Unsupported sample format {other}")),
+    }?;
+    stream.play().map_err(|e| format!("Failed to start input stream: {e}"))?;
+    Ok(stream)
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    channels: usize,
+    mut on_audio: impl FnMut(&[f32]) + Send + 'static,
+    on_lost: fn(),
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let mut mono = Vec::new();
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                mono.clear();
+                mono.extend(
+                    data.chunks(channels)
+                        .map(|frame| frame.iter().map(|s| s.to_sample::<f32>()).sum::<f32>() / channels as f32),
+                );
+                on_audio(&mono);
+            },
+            move |e| match e {
+                cpal::StreamError::DeviceNotAvailable => on_lost(),
+                e => log::warn!("Input stream error: {e}"),
+            },
+            None,
+        )
+        .map_err(|e| format!("Failed to open input stream: {e}"))
+}
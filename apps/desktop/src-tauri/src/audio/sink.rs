@@ -0,0 +1,144 @@
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+
+use super::PIPELINE_RATE;
+use crate::denoise::Denoiser;
+use crate::gain::{Agc, SignalMonitor, SignalWarning};
+use crate::resample::Converter;
+
+/// How often `dictation:level` fires while recording.
+const LEVEL_FRAME_MS: u32 = 50;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Level {
+    /// Both linear, 0.0 to 1.0.
+    rms: f32,
+    peak: f32,
+}
+
+/// Summarises the signal every `LEVEL_FRAME_MS` for the pill's waveform.
+struct LevelMeter {
+    frame_len: usize,
+    count: usize,
+    sum_squares: f32,
+    peak: f32,
+}
+
+impl LevelMeter {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            frame_len: (sample_rate * LEVEL_FRAME_MS / 1000).max(1) as usize,
+            count: 0,
+            sum_squares: 0.0,
+            peak: 0.0,
+        }
+    }
+
+    /// Returns a level each time a frame completes.
+    fn push(&mut self, sample: f32) -> Option<Level> {
+        self.count += 1;
+        self.sum_squares += sample * sample;
+        self.peak = self.peak.max(sample.abs());
+        if self.count < self.frame_len {
+            return None;
+        }
+        let level = Level {
+            rms: (self.sum_squares / self.count as f32).sqrt().min(1.0),
+            peak: self.peak.min(1.0),
+        };
+        self.count = 0;
+        self.sum_squares = 0.0;
+        self.peak = 0.0;
+        Some(level)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignalWarningPayload {
+    kind: SignalWarning,
+    message: &'static str,
+}
+
+/// Everything the audio callback feeds, in order: the signal monitor, the optional
+/// denoiser, conversion to `PIPELINE_RATE`, the optional AGC, then the recording
+/// buffer and the level meter.
+pub(super) struct Sink {
+    samples: Arc<Mutex<Vec<f32>>>,
+    meter: LevelMeter,
+    handle: tauri::AppHandle,
+    denoiser: Option<Denoiser>,
+    converter: Converter,
+    agc: Option<Agc>,
+    monitor: SignalMonitor,
+    scratch: Vec<f32>,
+    converted: Vec<f32>,
+}
+
+impl Sink {
+    pub(super) fn new(handle: &tauri::AppHandle, samples: Arc<Mutex<Vec<f32>>>, input_rate: u32) -> Result<Self, String> {
+        let (noise_suppression, auto_gain) =
+            crate::config::read(handle, |c| (c.audio.noise_suppression, c.audio.auto_gain));
+        let denoiser = noise_suppression.then(|| Denoiser::new(input_rate)).transpose()?;
+        let converter_input = if denoiser.is_some() { crate::denoise::SAMPLE_RATE } else { input_rate };
+        Ok(Self {
+            samples,
+            meter: LevelMeter::new(PIPELINE_RATE),
+            handle: handle.clone(),
+            denoiser,
+            converter: Converter::new(converter_input, PIPELINE_RATE)?,
+            agc: auto_gain.then(Agc::new),
+            monitor: SignalMonitor::new(input_rate),
+            scratch: Vec::new(),
+            converted: Vec::new(),
+        })
+    }
+
+    pub(super) fn push(&mut self, raw: &[f32]) {
+        if let Some(kind) = self.monitor.push(raw) {
+            log::warn!("Input signal warning: {kind:?}");
+            let _ = self
+                .handle
+                .emit("dictation:signal-warning", SignalWarningPayload { kind, message: kind.message() });
+        }
+
+        self.scratch.clear();
+        let input = match self.denoiser.as_mut() {
+            Some(denoiser) => {
+                denoiser.process(raw, &mut self.scratch);
+                &self.scratch
+            }
+            None => raw,
+        };
+        self.converted.clear();
+        self.converter.process(input, &mut self.converted);
+        if let Some(agc) = self.agc.as_mut() {
+            agc.process(&mut self.converted);
+        }
+
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        for &sample in &self.converted {
+            samples.push(sample);
+            if let Some(level) = self.meter.push(sample) {
+                let _ = self.handle.emit("dictation:level", level);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_a_level_per_frame() {
+        let mut meter = LevelMeter::new(1000);
+        let levels: Vec<Level> = [0.5, -0.5].repeat(50).into_iter().filter_map(|s| meter.push(s)).collect();
+        assert_eq!(levels.len(), 2);
+        assert_eq!((levels[0].rms, levels[0].peak), (0.5, 0.5));
+        assert!(meter.push(2.0).is_none());
+        assert_eq!(meter.count, 1);
+    }
+}
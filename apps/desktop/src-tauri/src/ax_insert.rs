@@ -0,0 +1,216 @@
+use core_foundation::base::{CFRange, CFRelease, CFTypeRef, TCFType};
+use core_foundation::number::{CFNumber, CFNumberRef};
+use core_foundation::string::{CFString, CFStringRef};
+use std::ffi::c_void;
+
+type AXUIElementRef = *const c_void;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+    fn AXUIElementCreateApplication(pid: i32) -> AXUIElementRef;
+    fn AXUIElementCopyAttributeValue(element: AXUIElementRef, attribute: CFStringRef, value: *mut CFTypeRef) -> i32;
+    fn AXUIElementSetAttributeValue(element: AXUIElementRef, attribute: CFStringRef, value: CFTypeRef) -> i32;
+    fn AXUIElementIsAttributeSettable(element: AXUIElementRef, attribute: CFStringRef, settable: *mut u8) -> i32;
+    fn AXUIElementCopyParameterizedAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        parameter: CFTypeRef,
+        value: *mut CFTypeRef,
+    ) -> i32;
+    fn AXValueCreate(kind: u32, value: *const c_void) -> CFTypeRef;
+    fn AXValueGetValue(value: CFTypeRef, kind: u32, out: *mut c_void) -> u8;
+}
+
+const AX_ERROR_SUCCESS: i32 = 0;
+const AX_VALUE_CF_RANGE_TYPE: u32 = 4;
+
+/// An owned CF object, released on drop.
+struct Owned(CFTypeRef);
+
+impl Drop for Owned {
+    fn drop(&mut self) {
+        unsafe { CFRelease(self.0) }
+    }
+}
+
+unsafe fn copy_attribute(element: AXUIElementRef, name: &'static str) -> Option<Owned> {
+    let attribute = CFString::from_static_string(name);
+    let mut value: CFTypeRef = std::ptr::null();
+    let status = AXUIElementCopyAttributeValue(element, attribute.as_concrete_TypeRef(), &mut value);
+    (status == AX_ERROR_SUCCESS && !value.is_null()).then_some(Owned(value))
+}
+
+unsafe fn character_count(element: AXUIElementRef) -> Option<i64> {
+    let value = copy_attribute(element, "AXNumberOfCharacters")?;
+    CFNumber::wrap_under_get_rule(value.0 as CFNumberRef).to_i64()
+}
+
+unsafe fn selected_len(element: AXUIElementRef) -> Option<i64> {
+    let value = copy_attribute(element, "AXSelectedText")?;
+    let text = CFString::wrap_under_get_rule(value.0 as CFStringRef).to_string();
+    Some(text.encode_utf16().count() as i64)
+}
+
+/// Replace the selection in the focused text element with `text`, which inserts it at the
+/// caret when nothing is selected. Returns `Ok(false)` when the element does not support
+/// it, including apps that accept the write but silently ignore it, so the caller can
+/// fall back to pasting. Needs the Accessibility permission the key monitor already uses.
+pub fn insert(text: &str) -> Result<bool, String> {
+    unsafe {
+        let system = Owned(AXUIElementCreateSystemWide());
+        let Some(focused) = copy_attribute(system.0, "AXFocusedUIElement") else { return Ok(false) };
+
+        let attribute = CFString::from_static_string("AXSelectedText");
+        let mut settable = 0u8;
+        let status = AXUIElementIsAttributeSettable(focused.0, attribute.as_concrete_TypeRef(), &mut settable);
+        if status != AX_ERROR_SUCCESS || settable == 0 {
+            return Ok(false);
+        }
+
+        let before = character_count(focused.0);
+        let replaced = selected_len(focused.0).unwrap_or(0);
+        let value = CFString::new(text);
+        let status = AXUIElementSetAttributeValue(focused.0, attribute.as_concrete_TypeRef(), value.as_CFTypeRef());
+        if status != AX_ERROR_SUCCESS {
+            return Err(format!("AXUIElementSetAttributeValue failed with {status}"));
+        }
+
+        // Chromium and Electron report success without changing anything; the character
+        // count is the cheapest way to catch that.
+        let expected = before.map(|n| n - replaced + text.encode_utf16().count() as i64);
+        match (expected, character_count(focused.0)) {
+            (Some(expected), Some(after)) if expected != after && Some(after) == before => Ok(false),
+            _ => Ok(true),
+        }
+    }
+}
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn IsSecureEventInputEnabled() -> u8;
+}
+
+/// Whether some app has turned on Secure Event Input, as password fields and terminals
+/// do while focused. macOS then hides keystrokes from us and discards synthetic ones.
+pub fn secure_input_enabled() -> bool {
+    unsafe { IsSecureEventInputEnabled() != 0 }
+}
+
+/// Whether the focused element is a password field.
+pub fn focused_is_secure() -> bool {
+    unsafe {
+        let system = Owned(AXUIElementCreateSystemWide());
+        let Some(focused) = copy_attribute(system.0, "AXFocusedUIElement") else { return false };
+        let Some(subrole) = copy_attribute(focused.0, "AXSubrole") else { return false };
+        CFString::wrap_under_get_rule(subrole.0 as CFStringRef) == "AXSecureTextField"
+    }
+}
+
+/// Character count and selection length of the focused text element, for checking
+/// whether a paste landed.
+pub fn focused_text_state() -> Option<(i64, i64)> {
+    unsafe {
+        let system = Owned(AXUIElementCreateSystemWide());
+        let focused = copy_attribute(system.0, "AXFocusedUIElement")?;
+        Some((character_count(focused.0)?, selected_len(focused.0).unwrap_or(0)))
+    }
+}
+
+/// Delete `text` if it is exactly what precedes the caret in the focused element, which
+/// is where `insert` or a paste leaves it. Returns `Ok(false)` when the element cannot be
+/// read that way or the text is no longer there, so nothing else gets deleted.
+pub fn delete_before_caret(text: &str) -> Result<bool, String> {
+    unsafe {
+        let system = Owned(AXUIElementCreateSystemWide());
+        let Some(focused) = copy_attribute(system.0, "AXFocusedUIElement") else { return Ok(false) };
+        let Some(caret) = selected_range(focused.0) else { return Ok(false) };
+        let len = text.encode_utf16().count() as isize;
+        if caret.length != 0 || caret.location < len {
+            return Ok(false);
+        }
+
+        let target = CFRange::init(caret.location - len, len);
+        if string_for_range(focused.0, target).as_deref() != Some(text) {
+            return Ok(false);
+        }
+        let range = Owned(AXValueCreate(AX_VALUE_CF_RANGE_TYPE, (&target as *const CFRange).cast()));
+        let attribute = CFString::from_static_string("AXSelectedTextRange");
+        let status = AXUIElementSetAttributeValue(focused.0, attribute.as_concrete_TypeRef(), range.0);
+        if status != AX_ERROR_SUCCESS {
+            return Ok(false);
+        }
+        let attribute = CFString::from_static_string("AXSelectedText");
+        let empty = CFString::new("");
+        let status = AXUIElementSetAttributeValue(focused.0, attribute.as_concrete_TypeRef(), empty.as_CFTypeRef());
+        if status != AX_ERROR_SUCCESS {
+            return Err(format!("AXUIElementSetAttributeValue failed with {status}"));
+        }
+        Ok(true)
+    }
+}
+
+unsafe fn selected_range(element: AXUIElementRef) -> Option<CFRange> {
+    let selection = copy_attribute(element, "AXSelectedTextRange")?;
+    let mut range = CFRange::init(0, 0);
+    (AXValueGetValue(selection.0, AX_VALUE_CF_RANGE_TYPE, (&mut range as *mut CFRange).cast()) != 0).then_some(range)
+}
+
+unsafe fn string_for_range(element: AXUIElementRef, range: CFRange) -> Option<String> {
+    let range = Owned(AXValueCreate(AX_VALUE_CF_RANGE_TYPE, (&range as *const CFRange).cast()));
+    let attribute = CFString::from_static_string("AXStringForRange");
+    let mut value: CFTypeRef = std::ptr::null();
+    let status = AXUIElementCopyParameterizedAttributeValue(element, attribute.as_concrete_TypeRef(), range.0, &mut value);
+    if status != AX_ERROR_SUCCESS || value.is_null() {
+        return None;
+    }
+    let value = Owned(value);
+    Some(CFString::wrap_under_get_rule(value.0 as CFStringRef).to_string())
+}
+
+/// Up to `len` UTF-16 units on each side of the selection in the focused element.
+pub fn caret_context(len: isize) -> Option<(String, String)> {
+    unsafe {
+        let system = Owned(AXUIElementCreateSystemWide());
+        let focused = copy_attribute(system.0, "AXFocusedUIElement")?;
+        let selection = selected_range(focused.0)?;
+        let total = character_count(focused.0)? as isize;
+        let start = (selection.location - len).max(0);
+        let before = string_for_range(focused.0, CFRange::init(start, selection.location - start))?;
+        let end = selection.location + selection.length;
+        let after = if end < total {
+            string_for_range(focused.0, CFRange::init(end, len.min(total - end)))?
+        } else {
+            String::new()
+        };
+        Some((before, after))
+    }
+}
+
+/// Title of the focused window of the app with `pid`.
+pub fn window_title(pid: i32) -> Option<String> {
+    unsafe {
+        let app = Owned(AXUIElementCreateApplication(pid));
+        let window = copy_attribute(app.0, "AXFocusedWindow")?;
+        let title = copy_attribute(window.0, "AXTitle")?;
+        let title = CFString::wrap_under_get_rule(title.0 as CFStringRef).to_string();
+        (!title.is_empty()).then_some(title)
+    }
+}
+
+/// Text selected in the focused element of the app with `pid`, unless it is a password
+/// field. Asks the app rather than the system, so it still works once we have focus.
+pub fn selected_text(pid: i32) -> Option<String> {
+    unsafe {
+        let app = Owned(AXUIElementCreateApplication(pid));
+        let focused = copy_attribute(app.0, "AXFocusedUIElement")?;
+        let secure = copy_attribute(focused.0, "AXSubrole")
+            .is_some_and(|subrole| CFString::wrap_under_get_rule(subrole.0 as CFStringRef) == "AXSecureTextField");
+        if secure {
+            return None;
+        }
+        let text = copy_attribute(focused.0, "AXSelectedText")?;
+        let text = CFString::wrap_under_get_rule(text.0 as CFStringRef).to_string();
+        (!text.trim().is_empty()).then_some(text)
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use tauri::window::{Effect, EffectsBuilder};
+use tauri::Manager;
+
+/// Backdrop behind the main window's web UI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowBackdrop {
+    #[default]
+    Solid,
+    /// Tinted by the desktop wallpaper: Mica on Windows 11, the under-window material on macOS.
+    Mica,
+    /// Blurs whatever is behind the window: Acrylic on Windows, the HUD material on macOS.
+    Acrylic,
+}
+
+/// Mica needs Windows 11; Acrylic works from Windows 10 1809, if slowly.
+#[cfg(target_os = "windows")]
+fn required_build(backdrop: WindowBackdrop) -> u32 {
+    match backdrop {
+        WindowBackdrop::Mica => 22000,
+        _ => 17763,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn windows_build() -> Option<u32> {
+    use windows::core::w;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    let mut buffer = [0u16; 16];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            w!("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion"),
+            w!("CurrentBuildNumber"),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return None;
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len]).parse().ok()
+}
+
+#[cfg(target_os = "macos")]
+fn effect(backdrop: WindowBackdrop) -> Result<Option<Effect>, String> {
+    Ok(match backdrop {
+        WindowBackdrop::Solid => None,
+        WindowBackdrop::Mica => Some(Effect::UnderWindowBackground),
+        WindowBackdrop::Acrylic => Some(Effect::HudWindow),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn effect(backdrop: WindowBackdrop) -> Result<Option<Effect>, String> {
+    if backdrop != WindowBackdrop::Solid && windows_build().is_some_and(|b| b < required_build(backdrop)) {
+        return Err(format!("{backdrop:?} needs a newer version of Windows"));
+    }
+    Ok(match backdrop {
+        WindowBackdrop::Solid => None,
+        WindowBackdrop::Mica => Some(Effect::Mica),
+        WindowBackdrop::Acrylic => Some(Effect::Acrylic),
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn effect(backdrop: WindowBackdrop) -> Result<Option<Effect>, String> {
+    match backdrop {
+        WindowBackdrop::Solid => Ok(None),
+        _ => Err("Translucent backdrops are not supported on Linux".to_string()),
+    }
+}
+
+/// Let the backdrop show through the window and webview backgrounds, or paint them again.
+#[cfg(target_os = "macos")]
+fn set_see_through(window: &tauri::WebviewWindow, see_through: bool) -> Result<(), String> {
+    use cocoa::appkit::NSWindow;
+    use cocoa::base::{id, YES};
+
+    if see_through {
+        return crate::make_window_transparent(window)
+            .then_some(())
+            .ok_or_else(|| "Failed to clear the webview background".to_string());
+    }
+    let ns_window = window.ns_window().map_err(|e| e.to_string())? as id;
+    unsafe {
+        ns_window.setOpaque_(YES);
+        let color: *mut objc2::runtime::AnyObject = objc2::msg_send![objc2::class!(NSColor), windowBackgroundColor];
+        ns_window.setBackgroundColor_(color.cast());
+    }
+    window
+        .with_webview(|platform_webview| unsafe {
+            let wk_view: *mut objc2::runtime::AnyObject = platform_webview.inner().cast();
+            let _: () = objc2::msg_send![wk_view, _setDrawsBackground: true];
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn set_see_through(window: &tauri::WebviewWindow, see_through: bool) -> Result<(), String> {
+    let color = see_through.then_some(tauri::window::Color(0, 0, 0, 0));
+    window.set_background_color(color).map_err(|e| e.to_string())
+}
+
+/// Give the main window `backdrop`. It only shows where the web UI leaves its own
+/// background out, which it can decide from `get_window_backdrop`.
+fn apply(window: &tauri::WebviewWindow, backdrop: WindowBackdrop) -> Result<(), String> {
+    match effect(backdrop)? {
+        None => {
+            window.set_effects(None).map_err(|e| e.to_string())?;
+            set_see_through(window, false)
+        }
+        Some(effect) => {
+            set_see_through(window, true)?;
+            window.set_effects(EffectsBuilder::new().effect(effect).build()).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Apply the saved backdrop at startup.
+pub fn restore(app: &tauri::AppHandle) {
+    let backdrop = crate::config::read(app, |c| c.window_backdrop);
+    if backdrop == WindowBackdrop::Solid {
+        return;
+    }
+    let Some(window) = app.get_webview_window("main") else { return };
+    if let Err(e) = apply(&window, backdrop) {
+        log::warn!("Failed to apply the {backdrop:?} backdrop: {e}");
+    }
+}
+
+#[tauri::command]
+pub fn get_window_backdrop(app: tauri::AppHandle) -> WindowBackdrop {
+    crate::config::read(&app, |c| c.window_backdrop)
+}
+
+#[tauri::command]
+pub fn set_window_backdrop(app: tauri::AppHandle, backdrop: WindowBackdrop) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("The main window is gone")?;
+    apply(&window, backdrop).map_err(|e| format!("Failed to change the backdrop: {e}"))?;
+    crate::config::update(&app, |c| c.window_backdrop = backdrop)
+}
@@ -0,0 +1,154 @@
+use serde::Serialize;
+
+use crate::frontmost::FrontmostApp;
+
+/// The page open in the frontmost browser window. Best effort: what can be read depends
+/// on the browser.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserTab {
+    pub browser: String,
+    pub url: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Window titles end in the browser's name, which is not part of the page's.
+const TITLE_SUFFIXES: &[&str] = &[
+    " - Google Chrome",
+    " - Microsoft\u{200b} Edge",
+    " - Microsoft Edge",
+    " — Mozilla Firefox",
+    " - Mozilla Firefox",
+    " - Brave",
+];
+
+/// The page title from a browser's window title.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+fn page_title(window_title: Option<&str>) -> Option<String> {
+    let title = window_title?;
+    let title = TITLE_SUFFIXES.iter().find_map(|suffix| title.strip_suffix(suffix)).unwrap_or(title);
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// AppleScript for the browsers that have it, which asks for Automation permission the
+/// first time; the window title for Firefox, which has none.
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::BrowserTab;
+    use crate::frontmost::FrontmostApp;
+
+    /// Bundle id, name, and whether it speaks Chromium's AppleScript dictionary rather
+    /// than Safari's.
+    const BROWSERS: &[(&str, &str, bool)] = &[
+        ("com.apple.Safari", "Safari", false),
+        ("com.apple.SafariTechnologyPreview", "Safari", false),
+        ("com.google.Chrome", "Chrome", true),
+        ("com.microsoft.edgemac", "Edge", true),
+        ("com.brave.Browser", "Brave", true),
+        ("company.thebrowser.Browser", "Arc", true),
+        ("org.mozilla.firefox", "Firefox", false),
+    ];
+
+    fn run_script(bundle_id: &str, chromium: bool) -> Option<(String, String)> {
+        let (tab, title) = if chromium { ("active tab", "title") } else { ("current tab", "name") };
+        let script = format!(
+            "tell application id \"{bundle_id}\" to tell {tab} of front window to return (URL as text) & linefeed & ({title} as text)"
+        );
+        let output = std::process::Command::new("osascript").args(["-e", &script]).output().ok()?;
+        if !output.status.success() {
+            log::debug!("Browser tab script failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+            return None;
+        }
+        let output = String::from_utf8_lossy(&output.stdout);
+        let (url, title) = output.trim_end().split_once('\n')?;
+        Some((url.to_string(), title.to_string()))
+    }
+
+    pub fn tab(app: &FrontmostApp) -> Option<BrowserTab> {
+        let bundle_id = app.bundle_id.as_deref()?;
+        let &(_, browser, chromium) = BROWSERS.iter().find(|(id, _, _)| *id == bundle_id)?;
+        let scripted = (bundle_id != "org.mozilla.firefox").then(|| run_script(bundle_id, chromium)).flatten();
+        let (url, title) = match scripted {
+            Some((url, title)) => (Some(url).filter(|u| !u.is_empty()), Some(title).filter(|t| !t.is_empty())),
+            None => (None, super::page_title(app.window_title.as_deref())),
+        };
+        Some(BrowserTab { browser: browser.to_string(), url, title })
+    }
+}
+
+/// The address bar through UI Automation: the first edit control in Chromium browsers,
+/// `urlbar-input` in Firefox.
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::BrowserTab;
+    use crate::frontmost::FrontmostApp;
+    use windows::Win32::System::Variant::VARIANT;
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+    use windows::Win32::UI::Accessibility::{
+        CUIAutomation, IUIAutomation, IUIAutomationValuePattern, TreeScope_Descendants, UIA_AutomationIdPropertyId,
+        UIA_ControlTypePropertyId, UIA_EditControlTypeId, UIA_ValuePatternId,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    /// Executable name, name, and whether it is Firefox.
+    const BROWSERS: &[(&str, &str, bool)] = &[
+        ("chrome.exe", "Chrome", false),
+        ("msedge.exe", "Edge", false),
+        ("brave.exe", "Brave", false),
+        ("firefox.exe", "Firefox", true),
+    ];
+
+    fn address_bar(firefox: bool) -> Option<String> {
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+            let window = automation.ElementFromHandle(GetForegroundWindow()).ok()?;
+            let condition = if firefox {
+                automation.CreatePropertyCondition(UIA_AutomationIdPropertyId, &VARIANT::from("urlbar-input"))
+            } else {
+                automation.CreatePropertyCondition(UIA_ControlTypePropertyId, &VARIANT::from(UIA_EditControlTypeId.0))
+            }
+            .ok()?;
+            let bar = window.FindFirst(TreeScope_Descendants, &condition).ok()?;
+            let value = bar.GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId).ok()?;
+            let url = value.CurrentValue().ok()?.to_string();
+            (!url.is_empty()).then_some(url)
+        }
+    }
+
+    pub fn tab(app: &FrontmostApp) -> Option<BrowserTab> {
+        let exe = app.bundle_id.as_deref()?;
+        let &(_, browser, firefox) = BROWSERS.iter().find(|(name, _, _)| *name == exe)?;
+        // The address bar leaves out the scheme of ordinary web pages.
+        let url = address_bar(firefox).map(|url| if url.contains("://") { url } else { format!("https://{url}") });
+        Some(BrowserTab { browser: browser.to_string(), url, title: super::page_title(app.window_title.as_deref()) })
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::BrowserTab;
+    use crate::frontmost::FrontmostApp;
+
+    /// There is no frontmost app to look at on Linux.
+    pub fn tab(_app: &FrontmostApp) -> Option<BrowserTab> {
+        None
+    }
+}
+
+/// The tab open in `app`, if it is a browser we know how to ask.
+pub fn tab(app: &FrontmostApp) -> Option<BrowserTab> {
+    platform::tab(app)
+}
+
+/// The page open in the frontmost browser, or in the one that was in front when quick ask
+/// or dictation last started.
+#[tauri::command]
+pub async fn get_active_browser_tab() -> Result<Option<BrowserTab>, String> {
+    tauri::async_runtime::spawn_blocking(|| match crate::frontmost::frontmost_app().filter(|app| !app.is_self()) {
+        Some(app) => tab(&app),
+        None => crate::context::last().and_then(|context| context.browser_tab),
+    })
+    .await
+    .map_err(|e| format!("Failed to read the browser tab: {e}"))
+}
@@ -0,0 +1,64 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Copy an assistant response. Markdown goes on the clipboard as HTML alongside the
+/// source, so Notes, Mail and Google Docs paste it formatted while plain-text targets
+/// get the Markdown; `plain_only` skips the HTML flavor.
+#[tauri::command]
+pub fn copy_rich_text(app: tauri::AppHandle, markdown: String, plain_only: Option<bool>) -> Result<(), String> {
+    let clipboard = app.clipboard();
+    if plain_only.unwrap_or(false) {
+        return clipboard.write_text(markdown).map_err(|e| format!("Clipboard write failed: {e}"));
+    }
+    let html = crate::markdown::to_html(&markdown);
+    clipboard.write_html(html, Some(markdown)).map_err(|e| format!("Clipboard write failed: {e}"))
+}
+
+/// A prompt asking the assistant to summarize the text on the clipboard.
+pub fn summary_prompt(app: &tauri::AppHandle) -> Result<String, String> {
+    let text = app.clipboard().read_text().unwrap_or_default();
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("There is no text on the clipboard".to_string());
+    }
+    let text: String = text.chars().take(crate::context::MAX_SELECTION_CHARS).collect();
+    Ok(format!("Summarize this briefly:\n\"\"\"\n{text}\n\"\"\""))
+}
+
+/// A PNG to put on the clipboard: a file, such as a screenshot, or the encoded bytes of
+/// a generated image.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageSource {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+/// Decode `source` and write it as a bitmap: an NSImage on macOS, a DIB on Windows.
+fn write_image(app: &tauri::AppHandle, source: ImageSource) -> Result<(), String> {
+    let image = match source {
+        ImageSource::Path(path) => {
+            tauri::image::Image::from_path(&path).map_err(|e| format!("Failed to read image {}: {e}", path.display()))?
+        }
+        ImageSource::Bytes(bytes) => {
+            tauri::image::Image::from_bytes(&bytes).map_err(|e| format!("Failed to decode image: {e}"))?
+        }
+    };
+    app.clipboard().write_image(&image).map_err(|e| format!("Clipboard write failed: {e}"))
+}
+
+#[tauri::command]
+pub fn copy_image_to_clipboard(app: tauri::AppHandle, source: ImageSource) -> Result<(), String> {
+    write_image(&app, source)
+}
+
+/// Paste an image into the focused app, like `paste_transcription` does for text.
+/// The image stays on the clipboard afterwards.
+#[tauri::command]
+pub fn paste_image(app: tauri::AppHandle, source: ImageSource) -> Result<(), String> {
+    write_image(&app, source)?;
+    // Bitmaps take longer than text to land on the pasteboard.
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    crate::simulate_paste().map_err(|e| format!("Paste simulation failed: {e}"))
+}
@@ -0,0 +1,338 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Emitter;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(750);
+/// Used instead of `POLL_INTERVAL` in Low Power Mode or on a low battery.
+const SAVING_POLL_INTERVAL: Duration = Duration::from_millis(2000);
+const MAX_ENTRIES: usize = 500;
+/// Longer copies, such as whole files, are skipped rather than held in memory.
+const MAX_ENTRY_CHARS: usize = 20_000;
+
+/// Password managers whose copies are never recorded, on top of those that mark their
+/// clipboard contents as concealed.
+const PASSWORD_MANAGERS: &[&str] = &[
+    "com.1password.1password",
+    "com.agilebits.onepassword7",
+    "com.bitwarden.desktop",
+    "org.keepassxc.keepassxc",
+    "com.apple.keychainaccess",
+    "com.apple.passwords",
+    "1password.exe",
+    "bitwarden.exe",
+    "keepass.exe",
+    "keepassxc.exe",
+];
+
+/// Opt-in history of copied text, kept in memory only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClipboardHistoryConfig {
+    pub enabled: bool,
+    pub max_entries: usize,
+    /// Apps, by bundle id or executable name, whose copies are not recorded.
+    pub excluded_apps: Vec<String>,
+}
+
+impl Default for ClipboardHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: 50,
+            excluded_apps: PASSWORD_MANAGERS.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardEntry {
+    pub text: String,
+    /// Unix time in milliseconds.
+    pub copied_at: u64,
+    /// The app that was frontmost when it was copied.
+    pub app: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangedPayload {
+    count: usize,
+}
+
+/// Newest first.
+static HISTORY: Mutex<VecDeque<ClipboardEntry>> = Mutex::new(VecDeque::new());
+
+fn history() -> std::sync::MutexGuard<'static, VecDeque<ClipboardEntry>> {
+    HISTORY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn emit_changed(handle: &tauri::AppHandle, count: usize) {
+    let _ = handle.emit("clipboard-history-changed", ChangedPayload { count });
+}
+
+/// A value that changes whenever anything is copied, so the contents only have to be read then.
+#[cfg(target_os = "macos")]
+fn change_count() -> Option<i64> {
+    use objc2::runtime::{AnyClass, AnyObject};
+
+    unsafe {
+        let pasteboard: *mut AnyObject = objc2::msg_send![AnyClass::get("NSPasteboard")?, generalPasteboard];
+        if pasteboard.is_null() {
+            return None;
+        }
+        Some(objc2::msg_send![pasteboard, changeCount])
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn change_count() -> Option<i64> {
+    Some(unsafe { windows::Win32::System::DataExchange::GetClipboardSequenceNumber() } as i64)
+}
+
+/// Linux has no cheap change counter, so the contents are compared instead.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn change_count() -> Option<i64> {
+    None
+}
+
+/// Whether the copying app asked clipboard managers to look away, per the
+/// nspasteboard.org markers most macOS password managers set.
+#[cfg(target_os = "macos")]
+fn concealed() -> bool {
+    use objc2::runtime::{AnyClass, AnyObject};
+
+    const MARKERS: [&std::ffi::CStr; 3] =
+        [c"org.nspasteboard.ConcealedType", c"org.nspasteboard.TransientType", c"org.nspasteboard.AutoGeneratedType"];
+    unsafe {
+        let Some(pasteboard_class) = AnyClass::get("NSPasteboard") else { return false };
+        let Some(string_class) = AnyClass::get("NSString") else { return false };
+        let pasteboard: *mut AnyObject = objc2::msg_send![pasteboard_class, generalPasteboard];
+        if pasteboard.is_null() {
+            return false;
+        }
+        MARKERS.iter().any(|marker| {
+            let kind: *mut AnyObject = objc2::msg_send![string_class, stringWithUTF8String: marker.as_ptr()];
+            let data: *mut AnyObject = objc2::msg_send![pasteboard, dataForType: kind];
+            !data.is_null()
+        })
+    }
+}
+
+/// The formats Windows' own clipboard history and KeePass use to mark secrets.
+#[cfg(target_os = "windows")]
+fn concealed() -> bool {
+    use windows::core::w;
+    use windows::Win32::System::DataExchange::{IsClipboardFormatAvailable, RegisterClipboardFormatW};
+
+    unsafe {
+        [w!("ExcludeClipboardContentFromMonitorProcessing"), w!("Clipboard Viewer Ignore")]
+            .into_iter()
+            .any(|name| {
+                let format = RegisterClipboardFormatW(name);
+                format != 0 && IsClipboardFormatAvailable(format).is_ok()
+            })
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn concealed() -> bool {
+    false
+}
+
+#[cfg(target_os = "macos")]
+fn secure_input() -> bool {
+    crate::ax_insert::secure_input_enabled()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn secure_input() -> bool {
+    false
+}
+
+/// Whether a copy of `text` from `app` belongs in the history. `concealed` is set when the
+/// copying app marked it secret or secure input is on.
+fn should_record(text: &str, app: Option<&str>, config: &ClipboardHistoryConfig, concealed: bool) -> bool {
+    !concealed
+        && !text.trim().is_empty()
+        && text.chars().count() <= MAX_ENTRY_CHARS
+        && !app.is_some_and(|app| config.excluded_apps.iter().any(|e| e.eq_ignore_ascii_case(app)))
+}
+
+/// Put `entry` first, dropping an older copy of the same text and anything past `max_entries`.
+fn push(history: &mut VecDeque<ClipboardEntry>, entry: ClipboardEntry, max_entries: usize) -> usize {
+    history.retain(|e| e.text != entry.text);
+    history.push_front(entry);
+    history.truncate(max_entries);
+    history.len()
+}
+
+fn record(handle: &tauri::AppHandle, config: &ClipboardHistoryConfig, text: String) {
+    let app = crate::frontmost::frontmost_app().and_then(|a| a.bundle_id);
+    if !should_record(&text, app.as_deref(), config, secure_input() || concealed()) {
+        return;
+    }
+    let copied_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    let count = push(&mut history(), ClipboardEntry { text, copied_at, app }, config.max_entries);
+    emit_changed(handle, count);
+}
+
+/// What the watcher last saw on the clipboard.
+#[derive(Debug, Default)]
+struct Seen {
+    count: Option<i64>,
+    text: Option<String>,
+}
+
+impl Seen {
+    /// The newly copied text, if there is any. `read` is only called once `count` has moved,
+    /// or every time where there is no change counter.
+    fn copied(&mut self, count: Option<i64>, read: impl FnOnce() -> Option<String>) -> Option<String> {
+        // Seed on the first pass so enabling the history does not record what was already copied.
+        let first = self.text.is_none() && self.count.is_none();
+        if count.is_some() && count == self.count {
+            return None;
+        }
+        self.count = count;
+        let text = read()?;
+        if self.text.as_ref() == Some(&text) {
+            return None;
+        }
+        self.text = Some(text.clone());
+        (!first).then_some(text)
+    }
+}
+
+/// Watch the clipboard while the history is enabled.
+pub fn start(handle: tauri::AppHandle) -> Result<(), String> {
+    let mut seen = Seen::default();
+    loop {
+        let saving_power = crate::power::status().saving_power();
+        std::thread::sleep(if saving_power { SAVING_POLL_INTERVAL } else { POLL_INTERVAL });
+        let config = crate::config::read(&handle, |c| c.clipboard_history.clone());
+        if !config.enabled {
+            seen = Seen::default();
+            continue;
+        }
+        if let Some(text) = seen.copied(change_count(), || handle.clipboard().read_text().ok()) {
+            record(&handle, &config, text);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_clipboard_history_config(app: tauri::AppHandle) -> ClipboardHistoryConfig {
+    crate::config::read(&app, |c| c.clipboard_history.clone())
+}
+
+/// Turning the history off also forgets it.
+#[tauri::command]
+pub fn set_clipboard_history_config(app: tauri::AppHandle, config: ClipboardHistoryConfig) -> Result<(), String> {
+    if config.max_entries == 0 || config.max_entries > MAX_ENTRIES {
+        return Err(format!("History size must be between 1 and {MAX_ENTRIES}"));
+    }
+    let count = {
+        let mut history = history();
+        if !config.enabled {
+            history.clear();
+        }
+        history.truncate(config.max_entries);
+        history.len()
+    };
+    crate::config::update(&app, |c| c.clipboard_history = config)?;
+    emit_changed(&app, count);
+    Ok(())
+}
+
+/// Entries containing `query` (case-insensitive), newest first, at most `limit` of them.
+#[tauri::command]
+pub fn list_clipboard_history(query: Option<String>, limit: Option<usize>) -> Vec<ClipboardEntry> {
+    let query = query.map(|q| q.to_lowercase()).filter(|q| !q.is_empty());
+    history()
+        .iter()
+        .filter(|e| query.as_ref().is_none_or(|q| e.text.to_lowercase().contains(q)))
+        .take(limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect()
+}
+
+/// Insert the `index`th most recent entry (0 is the newest) into the focused app.
+#[tauri::command]
+pub fn paste_clipboard_history(app: tauri::AppHandle, index: usize) -> Result<crate::insertion::InsertOutcome, String> {
+    let text = history()
+        .get(index)
+        .map(|e| e.text.clone())
+        .ok_or_else(|| format!("No clipboard history entry {index}"))?;
+    Ok(crate::insertion::insert(&app, &text))
+}
+
+#[tauri::command]
+pub fn clear_clipboard_history(app: tauri::AppHandle) {
+    history().clear();
+    emit_changed(&app, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str) -> ClipboardEntry {
+        ClipboardEntry { text: text.to_string(), copied_at: 0, app: None }
+    }
+
+    #[test]
+    fn skips_concealed_blank_and_oversized_copies() {
+        let config = ClipboardHistoryConfig::default();
+        assert!(should_record("hello", None, &config, false));
+        assert!(!should_record("hunter2", None, &config, true));
+        assert!(!should_record(" \n\t", None, &config, false));
+        assert!(should_record(&"é".repeat(MAX_ENTRY_CHARS), None, &config, false));
+        assert!(!should_record(&"a".repeat(MAX_ENTRY_CHARS + 1), None, &config, false));
+    }
+
+    #[test]
+    fn skips_excluded_apps_in_any_case() {
+        let config = ClipboardHistoryConfig::default();
+        assert!(!should_record("hunter2", Some("com.bitwarden.desktop"), &config, false));
+        assert!(!should_record("hunter2", Some("KeePassXC.exe"), &config, false));
+        assert!(should_record("hello", Some("com.apple.Safari"), &config, false));
+        let config = ClipboardHistoryConfig { excluded_apps: vec!["Code.exe".to_string()], ..config };
+        assert!(!should_record("hello", Some("code.exe"), &config, false));
+        assert!(should_record("hunter2", Some("bitwarden.exe"), &config, false));
+    }
+
+    #[test]
+    fn moves_repeated_copies_to_the_front_and_truncates() {
+        let mut history = VecDeque::new();
+        for text in ["a", "b", "c"] {
+            push(&mut history, entry(text), 3);
+        }
+        assert_eq!(push(&mut history, entry("a"), 3), 3);
+        assert_eq!(history.iter().map(|e| e.text.as_str()).collect::<Vec<_>>(), ["a", "c", "b"]);
+        assert_eq!(push(&mut history, entry("d"), 2), 2);
+        assert_eq!(history.iter().map(|e| e.text.as_str()).collect::<Vec<_>>(), ["d", "a"]);
+    }
+
+    #[test]
+    fn seeds_on_the_first_pass() {
+        let mut seen = Seen::default();
+        assert_eq!(seen.copied(Some(1), || Some("old".to_string())), None);
+        assert_eq!(seen.copied(Some(1), || panic!("read without a change")), None);
+        assert_eq!(seen.copied(Some(2), || Some("new".to_string())), Some("new".to_string()));
+        assert_eq!(seen.copied(Some(3), || Some("new".to_string())), None);
+    }
+
+    #[test]
+    fn compares_the_text_without_a_change_counter() {
+        let mut seen = Seen::default();
+        assert_eq!(seen.copied(None, || None), None);
+        assert_eq!(seen.copied(None, || Some("old".to_string())), None);
+        assert_eq!(seen.copied(None, || Some("old".to_string())), None);
+        assert_eq!(seen.copied(None, || Some("new".to_string())), Some("new".to_string()));
+    }
+}
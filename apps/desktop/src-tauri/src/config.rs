@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::Manager;
+
+use crate::archive::ArchiveConfig;
+use crate::audio::AudioConfig;
+use crate::backdrop::WindowBackdrop;
+use crate::clipboard_history::ClipboardHistoryConfig;
+use crate::dictation::{DictationHotkey, DictationTarget, DictationTrigger, MediaKeyTrigger, MouseTrigger, TriggerKey};
+use crate::dnd::DuringDnd;
+use crate::feedback::FeedbackConfig;
+use crate::hid_trigger::HidTrigger;
+use crate::insertion::InsertionConfig;
+use crate::pill::PillConfig;
+use crate::pill_position::PillPosition;
+use crate::snippets::SnippetsConfig;
+use crate::transcription::{GatewayConfig, SttBackend, SttPowerPolicy};
+use crate::voice_commands::VoiceCommandsConfig;
+use crate::wakeword::WakeWordConfig;
+use crate::window_state::WindowGeometry;
+
+const CONFIG_FILE: &str = "desktop.json";
+
+/// Native-side settings persisted as `desktop.json` in the app config dir.
+/// Unknown or missing fields fall back to their defaults so older files keep loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DesktopConfig {
+    pub dictation_triggers: Vec<DictationTrigger>,
+    pub dictation_hotkey: Option<DictationHotkey>,
+    pub dictation_mouse_button: Option<MouseTrigger>,
+    pub dictation_hid_trigger: Option<HidTrigger>,
+    pub dictation_media_key: Option<MediaKeyTrigger>,
+    pub wake_word: WakeWordConfig,
+    pub feedback: FeedbackConfig,
+    pub audio: AudioConfig,
+    pub archive: ArchiveConfig,
+    pub gateway: GatewayConfig,
+    pub stt_backend: SttBackend,
+    /// Retry on the other backend when `sttBackend` fails.
+    pub stt_fallback: bool,
+    /// Stream gateway recordings over a WebSocket while capturing.
+    pub stt_streaming: bool,
+    /// ggml/gguf Whisper model used when `sttBackend` is `local`.
+    pub whisper_model: Option<PathBuf>,
+    /// Swap in a lighter Whisper model while on battery.
+    pub stt_power_policy: SttPowerPolicy,
+    /// Run local Whisper on the GPU when the build has a backend for it.
+    pub whisper_gpu: bool,
+    /// Language code passed to the STT engine; `None` auto-detects.
+    pub dictation_language: Option<String>,
+    /// Language dictations are translated into before they are pasted; `None` keeps what was said.
+    pub translate_to: Option<String>,
+    /// Names and terms passed to the STT engine as a prompt.
+    pub vocabulary: Vec<String>,
+    /// Emit `dictation:partial` while recording.
+    pub partial_results: bool,
+    pub insertion: InsertionConfig,
+    pub clipboard_history: ClipboardHistoryConfig,
+    pub snippets: SnippetsConfig,
+    /// Global dictations with an engine confidence below this wait for confirmation.
+    pub paste_confirm_below: Option<f32>,
+    /// Global dictations always wait for confirmation in an overlay before they are inserted.
+    pub confirm_before_insert: bool,
+    /// Phrases that, said at the start of a global dictation, send the rest to the assistant
+    /// and show its answer instead of typing it.
+    pub ask_phrases: Vec<String>,
+    pub voice_commands: VoiceCommandsConfig,
+    /// Extra bundle identifiers treated like spaceduck being focused, e.g. a browser running the web UI.
+    pub chat_focus_bundle_ids: Vec<String>,
+    pub pill: PillConfig,
+    /// Global shortcut, as an accelerator like `CommandOrControl+Shift+Space`, that toggles
+    /// the quick ask window; `None` turns it off.
+    pub quick_ask_shortcut: Option<String>,
+    /// Global shortcut that shows or hides the main window.
+    pub main_window_shortcut: Option<String>,
+    /// Global shortcut that explains the text selected in any app, next to the pointer.
+    pub explain_selection_shortcut: Option<String>,
+    /// Global shortcut that captures a region of the screen and opens quick ask with it.
+    pub screenshot_ask_shortcut: Option<String>,
+    /// Ask "explain this" about the screenshot straight away.
+    pub screenshot_ask_auto_submit: bool,
+    /// Keep the main window above other windows.
+    pub main_window_pinned: bool,
+    /// Native translucent material behind the main window.
+    pub window_backdrop: WindowBackdrop,
+    /// Main window geometry per display arrangement, oldest first.
+    pub window_geometry: Vec<WindowGeometry>,
+    /// Where the dictation pill was dragged to, per display.
+    pub pill_positions: Vec<PillPosition>,
+    /// Conversations open in windows of their own, reopened at launch.
+    pub conversation_windows: Vec<String>,
+    /// Stay in the tray, without the main window, when launched at login.
+    pub start_hidden: bool,
+    /// Native notifications for reminders and background task results.
+    pub notifications_enabled: bool,
+    /// What happens to notifications while Do Not Disturb or Focus is on.
+    pub notifications_during_dnd: DuringDnd,
+    /// Show reminders even while Do Not Disturb or Focus is on.
+    pub reminders_override_dnd: bool,
+    /// Ask about text sent from the macOS Services menu right away, instead of leaving it
+    /// in quick ask to edit first.
+    pub services_auto_submit: bool,
+    /// Send the frontmost app, its window title and the selected text along with quick ask
+    /// and spoken questions. Off until the user turns it on.
+    pub context_capture: bool,
+    /// Include the page open in the frontmost browser in that context. macOS asks once for
+    /// permission to control each browser.
+    pub context_browser_tab: bool,
+    /// Seconds without keyboard or mouse input before `system-idle` reports the user as
+    /// away; 0 never does.
+    pub idle_away_after_secs: u32,
+}
+
+impl Default for DesktopConfig {
+    fn default() -> Self {
+        Self {
+            dictation_triggers: vec![DictationTrigger {
+                key: TriggerKey::Fn,
+                target: DictationTarget::Auto,
+            }],
+            dictation_hotkey: None,
+            dictation_mouse_button: None,
+            dictation_hid_trigger: None,
+            dictation_media_key: None,
+            wake_word: WakeWordConfig::default(),
+            feedback: FeedbackConfig::default(),
+            audio: AudioConfig::default(),
+            archive: ArchiveConfig::default(),
+            gateway: GatewayConfig::default(),
+            stt_backend: SttBackend::Gateway,
+            stt_fallback: true,
+            stt_streaming: false,
+            whisper_model: None,
+            stt_power_policy: SttPowerPolicy::default(),
+            whisper_gpu: crate::whisper::GPU_BACKEND.is_some(),
+            dictation_language: None,
+            translate_to: None,
+            vocabulary: Vec::new(),
+            partial_results: true,
+            insertion: InsertionConfig::default(),
+            clipboard_history: ClipboardHistoryConfig::default(),
+            snippets: SnippetsConfig::default(),
+            paste_confirm_below: None,
+            confirm_before_insert: false,
+            ask_phrases: vec!["hey spaceduck".to_string(), "ask spaceduck".to_string()],
+            voice_commands: VoiceCommandsConfig::default(),
+            chat_focus_bundle_ids: Vec::new(),
+            pill: PillConfig::default(),
+            quick_ask_shortcut: Some("CommandOrControl+Shift+Space".to_string()),
+            main_window_shortcut: None,
+            explain_selection_shortcut: None,
+            screenshot_ask_shortcut: None,
+            screenshot_ask_auto_submit: false,
+            main_window_pinned: false,
+            window_backdrop: WindowBackdrop::default(),
+            window_geometry: Vec::new(),
+            pill_positions: Vec::new(),
+            conversation_windows: Vec::new(),
+            start_hidden: true,
+            notifications_enabled: true,
+            notifications_during_dnd: DuringDnd::Queue,
+            reminders_override_dnd: false,
+            services_auto_submit: false,
+            context_capture: false,
+            context_browser_tab: true,
+            idle_away_after_secs: 300,
+        }
+    }
+}
+
+/// Managed Tauri state holding the loaded config and where to write it back.
+pub struct ConfigStore {
+    path: Option<PathBuf>,
+    config: RwLock<DesktopConfig>,
+}
+
+impl ConfigStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = match app.path().app_config_dir() {
+            Ok(dir) => Some(dir.join(CONFIG_FILE)),
+            Err(e) => {
+                log::warn!("No app config dir, desktop settings will not persist: {e}");
+                None
+            }
+        };
+
+        let config = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|raw| match serde_json::from_str(&raw) {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    log::warn!("Ignoring malformed {CONFIG_FILE}: {e}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Self { path, config: RwLock::new(config) }
+    }
+
+    pub fn read<R>(&self, f: impl FnOnce(&DesktopConfig) -> R) -> R {
+        let config = self.config.read().unwrap_or_else(|e| e.into_inner());
+        f(&config)
+    }
+
+    /// Apply `f` and persist the result. The in-memory config is updated even if the write fails.
+    pub fn update(&self, f: impl FnOnce(&mut DesktopConfig)) -> Result<(), String> {
+        let snapshot = {
+            let mut config = self.config.write().unwrap_or_else(|e| e.into_inner());
+            f(&mut config);
+            config.clone()
+        };
+        self.persist(&snapshot)
+    }
+
+    fn persist(&self, config: &DesktopConfig) -> Result<(), String> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(config)
+            .map_err(|e| format!("Failed to serialize config: {e}"))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {CONFIG_FILE}: {e}"))
+    }
+}
+
+pub fn read<R>(app: &tauri::AppHandle, f: impl FnOnce(&DesktopConfig) -> R) -> R {
+    app.state::<ConfigStore>().read(f)
+}
+
+pub fn update(app: &tauri::AppHandle, f: impl FnOnce(&mut DesktopConfig)) -> Result<(), String> {
+    app.state::<ConfigStore>().update(f)
+}
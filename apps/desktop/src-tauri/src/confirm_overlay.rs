@@ -0,0 +1,48 @@
+use std::sync::Mutex;
+use tauri::Manager;
+
+const LABEL: &str = "confirm";
+const WIDTH: f64 = 420.0;
+const HEIGHT: f64 = 160.0;
+
+/// The window that had focus before the overlay took it, so the confirmed text goes back there.
+static TARGET: Mutex<Option<crate::frontmost::FocusTarget>> = Mutex::new(None);
+
+/// Open the overlay showing the held-back dictation, with Enter to paste it and Escape to
+/// discard it. The overlay takes keyboard focus so those keys never reach the target app.
+pub fn show(handle: &tauri::AppHandle) {
+    *TARGET.lock().unwrap_or_else(|e| e.into_inner()) = crate::frontmost::focus_target();
+    if let Some(window) = handle.get_webview_window(LABEL) {
+        let _ = window.close();
+    }
+    let url = if cfg!(debug_assertions) {
+        tauri::WebviewUrl::External("http://localhost:1420/?window=confirm".parse().unwrap())
+    } else {
+        tauri::WebviewUrl::App("index.html?window=confirm".into())
+    };
+    let result = tauri::WebviewWindowBuilder::new(handle, LABEL, url)
+        .title("Confirm dictation")
+        .inner_size(WIDTH, HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .focused(true)
+        .build();
+    if let Err(e) = result {
+        log::error!("Failed to open the confirmation overlay: {e}");
+    }
+}
+
+/// Close the overlay and give focus back to the app it was opened over.
+pub fn close(handle: &tauri::AppHandle) {
+    if let Some(window) = handle.get_webview_window(LABEL) {
+        let _ = window.close();
+    }
+    if let Some(target) = TARGET.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        crate::frontmost::refocus(target);
+        // Let the focus change land before anything is typed into it.
+        std::thread::sleep(std::time::Duration::from_millis(150));
+    }
+}
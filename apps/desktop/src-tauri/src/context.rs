@@ -0,0 +1,134 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+use crate::frontmost::FrontmostApp;
+
+/// Enough for a long passage without drowning out the question.
+pub const MAX_SELECTION_CHARS: usize = 8000;
+
+/// What the user was looking at in another app.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveContext {
+    pub app: FrontmostApp,
+    pub selected_text: Option<String>,
+    pub browser_tab: Option<crate::browser_tab::BrowserTab>,
+}
+
+/// Captured when quick ask or the last dictation started, before spaceduck took focus.
+static LAST: Mutex<Option<ActiveContext>> = Mutex::new(None);
+
+/// The text selected in `app`, read through the accessibility APIs.
+#[cfg(target_os = "macos")]
+pub fn selected_text(app: &FrontmostApp) -> Option<String> {
+    crate::ax_insert::selected_text(app.pid)
+}
+
+#[cfg(target_os = "windows")]
+pub fn selected_text(_app: &FrontmostApp) -> Option<String> {
+    crate::uia_insert::selected_text(MAX_SELECTION_CHARS as i32)
+}
+
+/// Reading another app's selection takes the AT-SPI bus, which isn't wired up here.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn selected_text(_app: &FrontmostApp) -> Option<String> {
+    None
+}
+
+/// The frontmost app, unless spaceduck is in front.
+fn frontmost() -> Option<FrontmostApp> {
+    crate::frontmost::frontmost_app().filter(|app| !app.is_self())
+}
+
+/// `app` with its selection and, for a browser, the page open in it.
+fn describe(handle: &tauri::AppHandle, app: FrontmostApp) -> ActiveContext {
+    let selected_text = selected_text(&app).map(|text| text.chars().take(MAX_SELECTION_CHARS).collect());
+    let browser_tab = crate::config::read(handle, |c| c.context_browser_tab)
+        .then(|| crate::browser_tab::tab(&app))
+        .flatten();
+    ActiveContext { app, selected_text, browser_tab }
+}
+
+/// The frontmost app, its window title, its selection and, for a browser, the page
+/// open in it, unless spaceduck is in front.
+pub fn capture(handle: &tauri::AppHandle) -> Option<ActiveContext> {
+    frontmost().map(|app| describe(handle, app))
+}
+
+/// The context remembered when quick ask or dictation last started.
+pub fn last() -> Option<ActiveContext> {
+    LAST.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Capture the context for the question about to be asked, when the user allowed it.
+/// Keeps the last one while spaceduck itself is in front, as when quick ask is reopened.
+pub fn remember(app: &tauri::AppHandle) {
+    if !crate::config::read(app, |c| c.context_capture) {
+        *LAST.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        return;
+    }
+    let Some(front) = frontmost() else { return };
+    // macOS asks the app itself, which still works once spaceduck has focus, and the
+    // first browser lookup waits for the user to answer a permission prompt.
+    #[cfg(target_os = "macos")]
+    {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let context = describe(&app, front);
+            *LAST.lock().unwrap_or_else(|e| e.into_inner()) = Some(context);
+        });
+    }
+    // UI Automation reads whatever has focus, so this has to happen before we take it.
+    #[cfg(not(target_os = "macos"))]
+    {
+        let context = describe(app, front);
+        *LAST.lock().unwrap_or_else(|e| e.into_inner()) = Some(context);
+    }
+}
+
+/// `prompt` with the remembered context in front of it, so "summarize this" has
+/// something to work on.
+pub fn with_context(app: &tauri::AppHandle, prompt: &str) -> String {
+    if !crate::config::read(app, |c| c.context_capture) {
+        return prompt.to_string();
+    }
+    let Some(context) = last() else { return prompt.to_string() };
+    let name = context.app.name.as_deref().or(context.app.bundle_id.as_deref()).unwrap_or("another app");
+    let mut preamble = match &context.app.window_title {
+        Some(title) => format!("I'm in {name}, in the window \"{title}\"."),
+        None => format!("I'm in {name}."),
+    };
+    if let Some(tab) = &context.browser_tab {
+        match (&tab.title, &tab.url) {
+            (Some(title), Some(url)) => preamble.push_str(&format!(" The page is \"{title}\" at {url}.")),
+            (None, Some(url)) => preamble.push_str(&format!(" The page is {url}.")),
+            (Some(title), None) => preamble.push_str(&format!(" The page is \"{title}\".")),
+            (None, None) => {}
+        }
+    }
+    if let Some(text) = &context.selected_text {
+        preamble.push_str(&format!(" I have this selected:\n\"\"\"\n{text}\n\"\"\""));
+    }
+    format!("{preamble}\n\n{prompt}")
+}
+
+/// The app and selection the user is working with: captured now if another app is in
+/// front, otherwise the one remembered when quick ask or dictation last started.
+#[tauri::command]
+pub async fn capture_active_context(app: tauri::AppHandle) -> Result<Option<ActiveContext>, String> {
+    tauri::async_runtime::spawn_blocking(move || capture(&app).or_else(last))
+        .await
+        .map_err(|e| format!("Failed to capture the active context: {e}"))
+}
+
+#[tauri::command]
+pub fn get_context_capture(app: tauri::AppHandle) -> bool {
+    crate::config::read(&app, |c| c.context_capture)
+}
+
+/// Let quick ask and spoken questions include the frontmost app, its window title and
+/// the selected text.
+#[tauri::command]
+pub fn set_context_capture(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.context_capture = enabled)
+}
@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+const LABEL_PREFIX: &str = "conversation-";
+const WIDTH: f64 = 720.0;
+const HEIGHT: f64 = 760.0;
+const MAX_ID_LEN: usize = 128;
+
+static NEXT_LABEL: AtomicU32 = AtomicU32::new(1);
+/// Window label and conversation id of each open conversation window, oldest first.
+static OPEN: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+fn open_windows() -> std::sync::MutexGuard<'static, Vec<(String, String)>> {
+    OPEN.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Remember which conversations have windows, for the next launch.
+fn save(app: &tauri::AppHandle) {
+    let ids: Vec<String> = open_windows().iter().map(|(_, id)| id.clone()).collect();
+    if let Err(e) = crate::config::update(app, |c| c.conversation_windows = ids) {
+        log::warn!("Failed to save the open conversation windows: {e}");
+    }
+}
+
+/// Whether `label` is one of ours.
+pub fn is_conversation_window(label: &str) -> bool {
+    label.starts_with(LABEL_PREFIX)
+}
+
+/// Whether `conversation_id` has a window of its own.
+pub fn is_open(conversation_id: &str) -> bool {
+    open_windows().iter().any(|(_, id)| id == conversation_id)
+}
+
+/// The conversation a window was opened on.
+pub fn conversation_of(label: &str) -> Option<String> {
+    open_windows().iter().find(|(l, _)| l == label).map(|(_, id)| id.clone())
+}
+
+/// Focus the window showing `conversation_id`, opening one if there is none.
+pub fn open(app: &tauri::AppHandle, conversation_id: &str) -> Result<tauri::WebviewWindow, String> {
+    let existing = open_windows().iter().find(|(_, id)| id == conversation_id).map(|(label, _)| label.clone());
+    if let Some(window) = existing.and_then(|label| app.get_webview_window(&label)) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(window);
+    }
+
+    let mut query = tauri::Url::parse("http://localhost/").expect("static URL");
+    query.query_pairs_mut().append_pair("conversation", conversation_id);
+    let query = query.query().unwrap_or_default().to_string();
+    let url = if cfg!(debug_assertions) {
+        tauri::WebviewUrl::External(format!("http://localhost:1420/?{query}").parse().unwrap())
+    } else {
+        tauri::WebviewUrl::App(format!("index.html?{query}").into())
+    };
+    let label = format!("{LABEL_PREFIX}{}", NEXT_LABEL.fetch_add(1, Ordering::SeqCst));
+    let window = tauri::WebviewWindowBuilder::new(app, &label, url)
+        .title("spaceduck")
+        .inner_size(WIDTH, HEIGHT)
+        .min_inner_size(400.0, 400.0)
+        .build()
+        .map_err(|e| format!("Failed to open the conversation window: {e}"))?;
+
+    crate::file_drop::watch(&window);
+    open_windows().push((label.clone(), conversation_id.to_string()));
+    // Closing one window forgets it; quitting destroys them all without asking, so they
+    // are still saved for the next launch.
+    let handle = app.clone();
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::CloseRequested { .. } => {
+            open_windows().retain(|(l, _)| *l != label);
+            save(&handle);
+        }
+        tauri::WindowEvent::Destroyed => {
+            open_windows().retain(|(l, _)| *l != label);
+            crate::session::changed(&handle);
+        }
+        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => crate::session::changed(&handle),
+        _ => {}
+    });
+    save(app);
+    crate::session::changed(app);
+    Ok(window)
+}
+
+/// Bring up `conversation_id` in its own window if it has one, otherwise in the main
+/// window, which is all that comes up when there is no conversation.
+pub fn show(app: &tauri::AppHandle, conversation_id: Option<String>) {
+    if let Some(id) = &conversation_id {
+        if is_open(id) {
+            if let Err(e) = open(app, id) {
+                log::warn!("Failed to focus conversation {id}: {e}");
+            }
+            return;
+        }
+    }
+    let Some(main) = app.get_webview_window("main") else { return };
+    let _ = main.unminimize();
+    let _ = main.show();
+    let _ = main.set_focus();
+    if let Some(id) = conversation_id {
+        let _ = main.emit_to("main", "open-conversation", id);
+    }
+}
+
+/// Reopen the conversation windows that were up when the app last quit.
+pub fn restore(app: &tauri::AppHandle) {
+    let ids = crate::config::read(app, |c| c.conversation_windows.clone());
+    for id in ids {
+        if let Err(e) = open(app, &id) {
+            log::warn!("Failed to reopen conversation {id}: {e}");
+        }
+    }
+}
+
+/// Open `conversation_id` in a window of its own, next to the main one.
+#[tauri::command]
+pub fn open_conversation_window(app: tauri::AppHandle, conversation_id: String) -> Result<(), String> {
+    let conversation_id = conversation_id.trim();
+    if conversation_id.is_empty() || conversation_id.len() > MAX_ID_LEN {
+        return Err("Invalid conversation id".to_string());
+    }
+    open(&app, conversation_id).map(|_| ())
+}
@@ -0,0 +1,364 @@
+use std::sync::OnceLock;
+
+const SCHEME: &str = "spaceduck";
+const MAX_ID_LEN: usize = 128;
+const MAX_TEXT_LEN: usize = 4000;
+/// The actions a link may run. Anyone can hand the app a link, so recording, capture and
+/// settings stay out of reach.
+const LINK_ACTIONS: &[&str] =
+    &["window.main", "window.quick-ask", "window.conversation", "window.answer", "window.always-on-top"];
+
+/// The OS hands links over on threads the app does not own, so the handle lives here.
+static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+fn links_in_args() -> Vec<String> {
+    std::env::args().skip(1).filter(|arg| arg.starts_with(&format!("{SCHEME}:"))).collect()
+}
+
+/// What a `spaceduck://` link asks for.
+#[derive(Debug, PartialEq)]
+enum Link {
+    /// A conversation, or the main window for `None`.
+    Chat(Option<String>),
+    Ask(String),
+    Dictate,
+    SummarizeClipboard,
+    Action { id: String, enabled: Option<bool> },
+}
+
+/// Parse a `spaceduck://` link: `spaceduck://chat/<id>` opens a conversation,
+/// `spaceduck://ask?text=…` opens quick ask with the text filled in, `spaceduck://dictate`
+/// starts or stops a dictation, `spaceduck://summarize-clipboard` summarizes the copied
+/// text next to the pointer and `spaceduck://action/<id>?enabled=…` runs one of the
+/// `LINK_ACTIONS`.
+fn parse(link: &str) -> Result<Link, String> {
+    let url = match tauri::Url::parse(link) {
+        Ok(url) if url.scheme() == SCHEME => url,
+        Ok(_) | Err(_) => return Err(format!("Ignoring a link that is not {SCHEME}://")),
+    };
+    let query = |name: &str| url.query_pairs().find(|(key, _)| key == name).map(|(_, value)| value.into_owned());
+    match url.host_str() {
+        Some("chat") => {
+            let id = url.path().trim_matches('/');
+            Ok(Link::Chat((!id.is_empty() && id.len() <= MAX_ID_LEN).then(|| id.to_string())))
+        }
+        Some("ask") => Ok(Link::Ask(query("text").unwrap_or_default().chars().take(MAX_TEXT_LEN).collect())),
+        Some("dictate") => Ok(Link::Dictate),
+        Some("summarize-clipboard") => Ok(Link::SummarizeClipboard),
+        Some("action") => {
+            let id = url.path().trim_matches('/');
+            if !LINK_ACTIONS.contains(&id) {
+                return Err("Only window actions can be run from links".to_string());
+            }
+            Ok(Link::Action { id: id.to_string(), enabled: query("enabled").and_then(|value| value.parse().ok()) })
+        }
+        _ => Err(format!("Ignoring an unknown {SCHEME}:// link")),
+    }
+}
+
+/// Act on a `spaceduck://` link, as `parse` describes.
+pub fn handle(app: &tauri::AppHandle, link: &str) {
+    let parsed = match parse(link) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            log::warn!("{e}");
+            return;
+        }
+    };
+    // Without the query, which can carry the text of a question.
+    log::info!("Opening link {}", link.split('?').next().unwrap_or_default());
+    match parsed {
+        Link::Chat(id) => crate::conversation_windows::show(app, id),
+        Link::Ask(text) => crate::quick_ask::prefill(app, text, false),
+        Link::Dictate => {
+            crate::dictation::toggle(
+                app,
+                crate::dictation::TriggerSource::DeepLink,
+                crate::dictation::DictationTarget::Auto,
+            );
+        }
+        Link::SummarizeClipboard => match crate::clipboard::summary_prompt(app) {
+            Ok(prompt) => crate::response_window::ask_near_cursor(app, "Summarize clipboard", prompt),
+            Err(e) => crate::response_window::fail_near_cursor(app, "Summarize clipboard", &e),
+        },
+        Link::Action { id, enabled } => {
+            let args = crate::actions::ActionArgs { enabled, ..Default::default() };
+            if let Err(e) = crate::actions::invoke(app, &id, args) {
+                log::warn!("Link action {id} failed: {e}");
+            }
+        }
+    }
+}
+
+/// Pass any links this process was launched with to an instance that is already running.
+/// Returns whether there was one, in which case this process should exit. macOS always
+/// sends links to the running instance itself.
+pub fn forward_to_running() -> bool {
+    let links = links_in_args();
+    !links.is_empty() && platform::forward(&links)
+}
+
+/// Register the scheme with the OS, listen for links from later launches, and act on the
+/// ones this launch came with.
+pub fn start(app: &tauri::AppHandle) {
+    let _ = HANDLE.set(app.clone());
+    if let Err(e) = platform::register(app) {
+        log::warn!("Failed to register {SCHEME}:// links: {e}");
+    }
+    platform::listen(app);
+    for link in links_in_args() {
+        handle(app, &link);
+    }
+}
+
+/// The scheme comes from CFBundleURLTypes in Info.plist and links arrive as
+/// `RunEvent::Opened`, so there is nothing to do here.
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn forward(_links: &[String]) -> bool {
+        false
+    }
+
+    pub fn register(_app: &tauri::AppHandle) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn listen(_app: &tauri::AppHandle) {}
+}
+
+/// The scheme lives under HKCU\Software\Classes and runs a new instance with the link,
+/// which hands it to the running one in a WM_COPYDATA to a hidden window.
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::Foundation::{ERROR_SUCCESS, HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::DataExchange::COPYDATASTRUCT;
+    use windows::Win32::System::Registry::{RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        AllowSetForegroundWindow, CreateWindowExW, DefWindowProcW, DispatchMessageW, FindWindowW, GetMessageW,
+        RegisterClassW, SendMessageW, ASFW_ANY, MSG, WINDOW_EX_STYLE, WINDOW_STYLE, WM_COPYDATA, WNDCLASSW,
+    };
+
+    const CLASS_NAME: PCWSTR = w!("SpaceduckDeepLink");
+
+    pub fn forward(links: &[String]) -> bool {
+        let Ok(window) = (unsafe { FindWindowW(CLASS_NAME, None) }) else { return false };
+        // Let the running instance bring its windows to the front.
+        let _ = unsafe { AllowSetForegroundWindow(ASFW_ANY) };
+        for link in links {
+            let data = COPYDATASTRUCT {
+                dwData: 0,
+                cbData: link.len() as u32,
+                lpData: link.as_ptr() as *mut _,
+            };
+            unsafe { SendMessageW(window, WM_COPYDATA, None, Some(LPARAM(&data as *const _ as isize))) };
+        }
+        true
+    }
+
+    fn set(key: &str, name: Option<&str>, value: &str) -> Result<(), String> {
+        let data: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
+        let name = name.map(HSTRING::from);
+        let status = unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                &HSTRING::from(key),
+                name.as_ref().map_or(PCWSTR::null(), |n| PCWSTR(n.as_ptr())),
+                REG_SZ.0,
+                Some(data.as_ptr().cast()),
+                (data.len() * 2) as u32,
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return Err(format!("Failed to write HKCU\\{key}: error {}", status.0));
+        }
+        Ok(())
+    }
+
+    pub fn register(_app: &tauri::AppHandle) -> Result<(), String> {
+        let program = std::env::current_exe().map_err(|e| format!("Cannot find the app's executable: {e}"))?;
+        let key = format!("Software\\Classes\\{}", super::SCHEME);
+        set(&key, None, &format!("URL:{}", super::SCHEME))?;
+        set(&key, Some("URL Protocol"), "")?;
+        set(&format!("{key}\\shell\\open\\command"), None, &format!("\"{}\" \"%1\"", program.display()))
+    }
+
+    unsafe extern "system" fn window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if msg == WM_COPYDATA {
+            let data = &*(lparam.0 as *const COPYDATASTRUCT);
+            if !data.lpData.is_null() {
+                let bytes = std::slice::from_raw_parts(data.lpData as *const u8, data.cbData as usize);
+                if let (Some(app), Ok(link)) = (super::HANDLE.get(), std::str::from_utf8(bytes)) {
+                    super::handle(app, link);
+                }
+            }
+            return LRESULT(1);
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    pub fn listen(_app: &tauri::AppHandle) {
+        std::thread::spawn(|| unsafe {
+            let class = WNDCLASSW { lpfnWndProc: Some(window_proc), lpszClassName: CLASS_NAME, ..Default::default() };
+            if RegisterClassW(&class) == 0 {
+                log::warn!("Failed to listen for links: RegisterClassW failed");
+                return;
+            }
+            let window = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                CLASS_NAME,
+                w!(""),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                None,
+                None,
+            );
+            if let Err(e) = window {
+                log::warn!("Failed to listen for links: {e}");
+                return;
+            }
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                DispatchMessageW(&msg);
+            }
+        });
+    }
+}
+
+/// The scheme is claimed by a hidden desktop entry, and later launches hand links to the
+/// running instance over a Unix socket in the runtime directory. Without one there is no
+/// place only this user can reach, so links from later launches are not taken.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use std::io::{BufRead, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::PathBuf;
+
+    fn socket_path() -> Option<PathBuf> {
+        std::env::var_os("XDG_RUNTIME_DIR")
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| PathBuf::from(dir).join("spaceduck-links.sock"))
+    }
+
+    pub fn forward(links: &[String]) -> bool {
+        let Some(path) = socket_path() else { return false };
+        let Ok(mut stream) = UnixStream::connect(path) else { return false };
+        for link in links {
+            let _ = writeln!(stream, "{link}");
+        }
+        true
+    }
+
+    pub fn register(app: &tauri::AppHandle) -> Result<(), String> {
+        let program = std::env::var_os("APPIMAGE")
+            .map(PathBuf::from)
+            .map_or_else(std::env::current_exe, Ok)
+            .map_err(|e| format!("Cannot find the app's executable: {e}"))?;
+        let data = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+            .ok_or("Neither XDG_DATA_HOME nor HOME is set")?;
+        let name = format!("{}-links.desktop", app.config().identifier);
+        let path = data.join("applications").join(&name);
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=Spaceduck\nExec=\"{}\" %u\nMimeType=x-scheme-handler/{};\nNoDisplay=true\n",
+            program.display(),
+            super::SCHEME
+        );
+        // Rewritten only when the program moved, so xdg-mime does not run on every launch.
+        if std::fs::read_to_string(&path).is_ok_and(|existing| existing == entry) {
+            return Ok(());
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        std::fs::write(&path, entry).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        std::process::Command::new("xdg-mime")
+            .args(["default", &name, &format!("x-scheme-handler/{}", super::SCHEME)])
+            .status()
+            .map_err(|e| format!("Failed to run xdg-mime: {e}"))?;
+        Ok(())
+    }
+
+    pub fn listen(app: &tauri::AppHandle) {
+        let Some(path) = socket_path() else {
+            log::warn!("XDG_RUNTIME_DIR is not set, so links from later launches are ignored");
+            return;
+        };
+        // Left behind by an instance that crashed; a live one would have taken the link.
+        let _ = std::fs::remove_file(&path);
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::warn!("Failed to listen for links on {}: {e}", path.display());
+                return;
+            }
+        };
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                for link in std::io::BufReader::new(stream).lines().map_while(Result::ok) {
+                    super::handle(&app, link.trim());
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_a_conversation_or_the_main_window() {
+        assert_eq!(parse("spaceduck://chat/abc-123"), Ok(Link::Chat(Some("abc-123".to_string()))));
+        assert_eq!(parse("spaceduck://chat/"), Ok(Link::Chat(None)));
+        assert_eq!(parse("spaceduck://chat"), Ok(Link::Chat(None)));
+        let long = format!("spaceduck://chat/{}", "x".repeat(MAX_ID_LEN + 1));
+        assert_eq!(parse(&long), Ok(Link::Chat(None)));
+    }
+
+    #[test]
+    fn decodes_and_caps_the_question() {
+        assert_eq!(parse("spaceduck://ask?text=What%27s%20up%3F"), Ok(Link::Ask("What's up?".to_string())));
+        assert_eq!(parse("spaceduck://ask?text=caf%C3%A9+au+lait"), Ok(Link::Ask("café au lait".to_string())));
+        assert_eq!(parse("spaceduck://ask"), Ok(Link::Ask(String::new())));
+        let long = format!("spaceduck://ask?text={}", "é".repeat(MAX_TEXT_LEN + 10));
+        assert_eq!(parse(&long), Ok(Link::Ask("é".repeat(MAX_TEXT_LEN))));
+    }
+
+    #[test]
+    fn reads_actions_and_their_switch() {
+        assert_eq!(parse("spaceduck://dictate"), Ok(Link::Dictate));
+        assert_eq!(parse("spaceduck://summarize-clipboard"), Ok(Link::SummarizeClipboard));
+        assert_eq!(
+            parse("spaceduck://action/window.always-on-top?enabled=false"),
+            Ok(Link::Action { id: "window.always-on-top".to_string(), enabled: Some(false) })
+        );
+        assert_eq!(
+            parse("spaceduck://action/window.main?enabled=maybe"),
+            Ok(Link::Action { id: "window.main".to_string(), enabled: None })
+        );
+    }
+
+    #[test]
+    fn keeps_capture_and_settings_actions_out_of_reach() {
+        assert!(parse("spaceduck://action/capture.meeting?enabled=true").is_err());
+        assert!(parse("spaceduck://action/capture.system-audio").is_err());
+        assert!(parse("spaceduck://action/settings.context-capture?enabled=true").is_err());
+        assert!(parse("spaceduck://action/settings.launch-at-login").is_err());
+        assert!(parse("spaceduck://action/").is_err());
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_hosts() {
+        assert!(parse("https://chat/abc").is_err());
+        assert!(parse("spaceduck://unknown").is_err());
+        assert!(parse("not a link").is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use nnnoiseless::DenoiseState;
+use std::time::{Duration, Instant};
+
+use crate::resample::Converter;
+
+/// rnnoise is trained on 48 kHz audio in 10 ms frames.
+pub const SAMPLE_RATE: u32 = 48_000;
+const FRAME_LEN: usize = DenoiseState::FRAME_SIZE;
+const FRAME_DURATION: Duration = Duration::from_millis(10);
+/// A frame may use at most this much of its own duration, or audio (and partial
+/// results) would start lagging behind the microphone.
+const BUDGET: Duration = Duration::from_millis(5);
+/// Consecutive slow frames before the stage gives up for the rest of the recording.
+const OVER_BUDGET_LIMIT: u32 = 50;
+
+/// Noise suppression stage run on the audio callback thread. Output is always 48 kHz mono.
+pub struct Denoiser {
+    state: Box<DenoiseState<'static>>,
+    converter: Converter,
+    pending: Vec<f32>,
+    frame_out: [f32; FRAME_LEN],
+    over_budget: u32,
+    bypassed: bool,
+}
+
+impl Denoiser {
+    pub fn new(input_rate: u32) -> Result<Self, String> {
+        Ok(Self {
+            state: DenoiseState::new(),
+            converter: Converter::new(input_rate, SAMPLE_RATE)?,
+            pending: Vec::with_capacity(FRAME_LEN * 4),
+            frame_out: [0.0; FRAME_LEN],
+            over_budget: 0,
+            bypassed: false,
+        })
+    }
+
+    /// Append denoised audio for `samples` to `out`. Up to one frame is held back until complete.
+    pub fn process(&mut self, samples: &[f32], out: &mut Vec<f32>) {
+        self.converter.process(samples, &mut self.pending);
+
+        let frames = self.pending.len() / FRAME_LEN;
+        for i in 0..frames {
+            let frame = &self.pending[i * FRAME_LEN..(i + 1) * FRAME_LEN];
+            if self.bypassed {
+                out.extend_from_slice(frame);
+                continue;
+            }
+
+            // rnnoise expects 16-bit sample magnitudes rather than -1.0..1.0.
+            let started = Instant::now();
+            let scaled: Vec<f32> = frame.iter().map(|s| s * i16::MAX as f32).collect();
+            self.state.process_frame(&mut self.frame_out, &scaled);
+            out.extend(self.frame_out.iter().map(|s| s / i16::MAX as f32));
+
+            if started.elapsed() > BUDGET {
+                self.over_budget += 1;
+                if self.over_budget >= OVER_BUDGET_LIMIT {
+                    log::warn!(
+                        "Noise suppression is slower than {}ms per {}ms frame, bypassing it for this recording",
+                        BUDGET.as_millis(),
+                        FRAME_DURATION.as_millis()
+                    );
+                    self.bypassed = true;
+                }
+            } else {
+                self.over_budget = 0;
+            }
+        }
+        self.pending.drain(..frames * FRAME_LEN);
+    }
+}
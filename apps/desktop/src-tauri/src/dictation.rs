@@ -0,0 +1,566 @@
+// Key and chord triggers only exist on macOS so far.
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex, MutexGuard, OnceLock};
+use tauri::Emitter;
+
+use crate::audio::{self, CaptureSource};
+use crate::feedback::{self, Cue};
+use crate::hotkey::{Hotkey, HotkeyWarning};
+
+/// Physical keys that can act as a push-to-talk trigger.
+/// The discriminant doubles as the bit index in the monitor's key-state mask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerKey {
+    Fn = 0,
+    RightCommand = 1,
+    RightOption = 2,
+    RightControl = 3,
+    RightShift = 4,
+}
+
+/// Where a trigger sends its dictation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DictationTarget {
+    /// Chat when the main window is focused, otherwise paste into the focused app.
+    #[default]
+    Auto,
+    Chat,
+    Global,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationTrigger {
+    pub key: TriggerKey,
+    #[serde(default)]
+    pub target: DictationTarget,
+}
+
+/// A key chord trigger, pressed to start and released to stop like the modifier triggers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationHotkey {
+    pub hotkey: Hotkey,
+    #[serde(default)]
+    pub target: DictationTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseButton {
+    Middle,
+    /// Side button usually mapped to "back" (button 4 / XBUTTON1).
+    Back,
+    /// Side button usually mapped to "forward" (button 5 / XBUTTON2).
+    Forward,
+    /// Zero-based platform button number, for mice with extra buttons (macOS only).
+    Other(u8),
+}
+
+/// A mouse button held for push-to-talk, behaving exactly like a trigger key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MouseTrigger {
+    pub button: MouseButton,
+    #[serde(default)]
+    pub target: DictationTarget,
+}
+
+/// The headset or keyboard play/pause key, pressed once to start and again to stop.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaKeyTrigger {
+    #[serde(default)]
+    pub target: DictationTarget,
+}
+
+/// What started a session; only the same source can stop it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerSource {
+    Key(TriggerKey),
+    Hotkey,
+    Mouse,
+    Hid,
+    MediaKey,
+    WakeWord,
+    SystemAudio,
+    /// A `spaceduck://dictate` link.
+    DeepLink,
+    /// The "start dictation" command, from Shortcuts or a script.
+    Shortcuts,
+    /// The `dictation.toggle` action, from the command palette or a link.
+    Action,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DictationMode {
+    Chat,
+    Global,
+}
+
+impl DictationMode {
+    fn event_suffix(self) -> &'static str {
+        match self {
+            DictationMode::Chat => "chat",
+            DictationMode::Global => "global",
+        }
+    }
+}
+
+/// Pick the session mode for `target`, consulting focus only for `auto`.
+pub fn resolve_mode(handle: &tauri::AppHandle, target: DictationTarget) -> DictationMode {
+    match target {
+        DictationTarget::Chat => DictationMode::Chat,
+        DictationTarget::Global => DictationMode::Global,
+        DictationTarget::Auto if chat_is_focused(handle) => DictationMode::Chat,
+        DictationTarget::Auto => DictationMode::Global,
+    }
+}
+
+/// Chat mode applies when spaceduck itself is frontmost, or an app listed in `chatFocusBundleIds`.
+#[cfg(target_os = "macos")]
+fn chat_is_focused(handle: &tauri::AppHandle) -> bool {
+    let Some(app) = crate::frontmost::frontmost_app() else { return false };
+    if app.is_self() {
+        return true;
+    }
+    let Some(bundle_id) = app.bundle_id else { return false };
+    crate::config::read(handle, |c| c.chat_focus_bundle_ids.contains(&bundle_id))
+}
+
+#[cfg(target_os = "windows")]
+fn chat_is_focused(_handle: &tauri::AppHandle) -> bool {
+    crate::frontmost::frontmost_app().is_some_and(|app| app.is_self())
+}
+
+#[cfg(target_os = "linux")]
+fn chat_is_focused(handle: &tauri::AppHandle) -> bool {
+    use tauri::Manager;
+
+    handle
+        .get_webview_window("main")
+        .and_then(|w| w.is_focused().ok())
+        .unwrap_or(false)
+}
+
+struct Session {
+    trigger: TriggerSource,
+    mode: DictationMode,
+    /// Started by a toggle rather than a held trigger, so it may end on silence.
+    hands_free: bool,
+    source: CaptureSource,
+}
+
+impl Session {
+    /// The webview records the microphone itself on `dictation:start-*`, so it only
+    /// hears about microphone sessions; system audio is captured natively only.
+    fn notifies_webview(&self) -> bool {
+        self.source == CaptureSource::Microphone
+    }
+}
+
+/// Why a recording stopped, sent with `dictation:stop-*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StopReason {
+    Trigger,
+    Silence,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StopPayload {
+    reason: StopReason,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockedPayload {
+    mode: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StartPayload {
+    /// Language this dictation will be translated into, for the webview's own recordings.
+    translate_to: Option<String>,
+    /// The app a global dictation will be inserted into, so output can suit it.
+    target_app: Option<crate::frontmost::FrontmostApp>,
+    /// The app records this session itself and sends the text as `dictation:result`,
+    /// so the webview must not record it too.
+    native_capture: bool,
+}
+
+/// The in-flight recording, if any. Only one trigger can own a session at a time.
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+/// When set, no trigger may start a recording and native audio capture stays closed.
+static CAPTURE_PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_capture_paused() -> bool {
+    CAPTURE_PAUSED.load(Ordering::SeqCst)
+}
+
+/// Whether any trigger currently owns a recording.
+pub fn is_active() -> bool {
+    session().is_some()
+}
+
+/// The trigger that owns the recording in progress.
+pub fn active_trigger() -> Option<TriggerSource> {
+    session().as_ref().map(|s| s.trigger)
+}
+
+fn session() -> MutexGuard<'static, Option<Session>> {
+    SESSION.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start a recording owned by `trigger`. Ignored while capture is paused or another trigger is recording.
+pub fn begin(handle: &tauri::AppHandle, trigger: TriggerSource, mode: DictationMode) -> bool {
+    start_session(handle, trigger, mode, false, CaptureSource::Microphone)
+}
+
+/// Start transcribing speaker output instead of the microphone. Needs native capture.
+pub fn begin_system_audio(handle: &tauri::AppHandle, mode: DictationMode) -> bool {
+    start_session(handle, TriggerSource::SystemAudio, mode, false, CaptureSource::System)
+}
+
+fn start_session(
+    handle: &tauri::AppHandle,
+    trigger: TriggerSource,
+    mode: DictationMode,
+    hands_free: bool,
+    source: CaptureSource,
+) -> bool {
+    if is_capture_paused() {
+        return false;
+    }
+    {
+        let mut session = session();
+        if session.is_some() {
+            return false;
+        }
+        if source == CaptureSource::Microphone && crate::mic_access::is_blocked() {
+            log::warn!("Microphone access denied, not starting dictation");
+            let _ = handle.emit("dictation:blocked-no-mic", BlockedPayload { mode: mode.event_suffix() });
+            return false;
+        }
+        *session = Some(Session { trigger, mode, hands_free, source });
+    }
+
+    if mode == DictationMode::Global {
+        crate::context::remember(handle);
+        crate::pill::show(handle);
+    }
+
+    let target_app = (mode == DictationMode::Global).then(crate::frontmost::frontmost_app).flatten();
+    // Read once so a settings change mid-recording cannot split the webview and native sides.
+    let translate_to = crate::config::read(handle, |c| c.translate_to.clone());
+    let native_capture = audio::native_capture_enabled(handle);
+    if source == CaptureSource::Microphone {
+        let _ = handle.emit(
+            &format!("dictation:start-{}", mode.event_suffix()),
+            StartPayload { translate_to: translate_to.clone(), target_app, native_capture },
+        );
+    }
+    feedback::play(handle, Cue::Start);
+    crate::tray::sync(handle);
+    if native_capture {
+        audio::start_capture(mode, hands_free, source, translate_to);
+    }
+    true
+}
+
+/// Stop the recording if `trigger` is the one that started it.
+pub fn end(handle: &tauri::AppHandle, trigger: TriggerSource) -> bool {
+    let ended = {
+        let mut session = session();
+        match session.as_ref() {
+            Some(s) if s.trigger == trigger => session.take(),
+            _ => None,
+        }
+    };
+    finish(handle, ended, StopReason::Trigger)
+}
+
+/// Stop a hands-free recording because the speaker went quiet. Held triggers are left alone.
+pub fn end_on_silence(handle: &tauri::AppHandle) -> bool {
+    let ended = {
+        let mut session = session();
+        match session.as_ref() {
+            Some(s) if s.hands_free => session.take(),
+            _ => None,
+        }
+    };
+    finish(handle, ended, StopReason::Silence)
+}
+
+fn finish(handle: &tauri::AppHandle, ended: Option<Session>, reason: StopReason) -> bool {
+    let Some(ended) = ended else { return false };
+    let mode = ended.mode;
+    if ended.notifies_webview() {
+        let _ = handle.emit(&format!("dictation:stop-{}", mode.event_suffix()), StopPayload { reason });
+    }
+    feedback::play(handle, Cue::Stop);
+    crate::tray::sync(handle);
+    if audio::native_capture_enabled(handle) {
+        audio::stop_capture(mode);
+    }
+    true
+}
+
+/// Stop the recording `trigger` owns, or start one if nothing is recording.
+/// For triggers that have no release event, like the media key. Such recordings are
+/// hands-free and also stop on their own after trailing silence.
+pub fn toggle(handle: &tauri::AppHandle, trigger: TriggerSource, target: DictationTarget) -> bool {
+    end(handle, trigger) || start_session(handle, trigger, resolve_mode(handle, target), true, CaptureSource::Microphone)
+}
+
+/// Forget the active recording without the stop or cancel events, because native capture
+/// could not record it and has reported why.
+pub fn abandon(handle: &tauri::AppHandle) {
+    if session().take().is_some() {
+        crate::tray::sync(handle);
+    }
+}
+
+/// Discard the active recording regardless of which trigger owns it.
+pub fn cancel(handle: &tauri::AppHandle) -> bool {
+    let cancelled = session().take();
+    emit_cancel(handle, cancelled)
+}
+
+/// Discard the recording only if `trigger` is the one that started it.
+pub fn cancel_owned(handle: &tauri::AppHandle, trigger: TriggerSource) -> bool {
+    let cancelled = {
+        let mut session = session();
+        match session.as_ref() {
+            Some(s) if s.trigger == trigger => session.take(),
+            _ => None,
+        }
+    };
+    emit_cancel(handle, cancelled)
+}
+
+fn emit_cancel(handle: &tauri::AppHandle, cancelled: Option<Session>) -> bool {
+    match cancelled {
+        Some(cancelled) => {
+            let mode = cancelled.mode;
+            if cancelled.notifies_webview() {
+                let _ = handle.emit(&format!("dictation:cancel-{}", mode.event_suffix()), ());
+            }
+            feedback::play(handle, Cue::Cancel);
+            crate::tray::sync(handle);
+            audio::cancel_capture();
+            if mode == DictationMode::Global {
+                crate::pill::idle(handle);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// A trigger press or release seen by an input hook or event tap.
+#[derive(Debug, Clone, Copy)]
+pub enum Edge {
+    Press(TriggerSource, DictationTarget),
+    Release(TriggerSource),
+    /// The play/pause key in the Windows keyboard hook.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Toggle(TriggerSource, DictationTarget),
+    /// Escape while a trigger is held.
+    Cancel,
+    /// The trigger key turned out to be the modifier of a key combo.
+    CancelOwned(TriggerSource),
+}
+
+static EDGES: OnceLock<Mutex<mpsc::Sender<Edge>>> = OnceLock::new();
+/// How long a trigger key's press is held back in case it turns out to start a combo.
+const COMBO_GRACE: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Hand `edge` to the trigger worker and return at once. Hooks and taps that take too long
+/// are dropped or disabled by the OS, and starting a session looks up the frontmost app,
+/// shows the pill and plays a cue. Edges are applied in the order they were posted.
+pub fn post(handle: &tauri::AppHandle, edge: Edge) {
+    let sender = EDGES.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        let handle = handle.clone();
+        std::thread::spawn(move || {
+            let mut next = None;
+            loop {
+                let edge = match next.take() {
+                    Some(edge) => edge,
+                    None => match rx.recv() {
+                        Ok(edge) => edge,
+                        Err(_) => break,
+                    },
+                };
+                // A trigger key may be the modifier of a combo like Fn+F11, so its press
+                // waits to see whether another key follows before the cue and pill.
+                if let Edge::Press(trigger @ TriggerSource::Key(_), _) = edge {
+                    match rx.recv_timeout(COMBO_GRACE) {
+                        Ok(Edge::CancelOwned(owner) | Edge::Release(owner)) if owner == trigger => {
+                            log::debug!("{trigger:?} released or used as a modifier before dictation started");
+                            continue;
+                        }
+                        Ok(Edge::Cancel) => continue,
+                        Ok(later) => next = Some(later),
+                        Err(mpsc::RecvTimeoutError::Timeout) => {}
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                apply(&handle, edge);
+            }
+        });
+        Mutex::new(tx)
+    });
+    let _ = sender.lock().unwrap_or_else(|e| e.into_inner()).send(edge);
+}
+
+fn apply(handle: &tauri::AppHandle, edge: Edge) {
+    match edge {
+        Edge::Press(trigger, target) => {
+            begin(handle, trigger, resolve_mode(handle, target));
+        }
+        Edge::Release(trigger) => {
+            end(handle, trigger);
+        }
+        Edge::Toggle(trigger, target) => {
+            toggle(handle, trigger, target);
+        }
+        Edge::Cancel => {
+            cancel(handle);
+        }
+        Edge::CancelOwned(trigger) => {
+            if cancel_owned(handle, trigger) {
+                log::debug!("{trigger:?} used as a modifier, discarding dictation");
+            }
+        }
+    }
+}
+
+/// Discard the recording in progress, as the pill's cancel button does.
+#[tauri::command]
+pub fn cancel_dictation(app: tauri::AppHandle) -> bool {
+    cancel(&app)
+}
+
+#[tauri::command]
+pub fn get_capture_paused() -> bool {
+    is_capture_paused()
+}
+
+/// Suspend or resume all listening. Pausing discards a recording that is in progress.
+#[tauri::command]
+pub fn set_capture_paused(app: tauri::AppHandle, paused: bool) {
+    if CAPTURE_PAUSED.swap(paused, Ordering::SeqCst) == paused {
+        return;
+    }
+    if paused {
+        cancel(&app);
+    }
+    audio::rearm();
+    log::info!("Capture {}", if paused { "paused" } else { "resumed" });
+    crate::tray::sync(&app);
+    let _ = app.emit("capture-paused-changed", paused);
+}
+
+#[tauri::command]
+pub fn get_dictation_triggers(app: tauri::AppHandle) -> Vec<DictationTrigger> {
+    crate::config::read(&app, |c| c.dictation_triggers.clone())
+}
+
+#[tauri::command]
+pub fn set_dictation_triggers(app: tauri::AppHandle, triggers: Vec<DictationTrigger>) -> Result<(), String> {
+    for (i, trigger) in triggers.iter().enumerate() {
+        if triggers[..i].iter().any(|t| t.key == trigger.key) {
+            return Err(format!("{:?} is assigned to more than one trigger", trigger.key));
+        }
+    }
+    crate::config::update(&app, |c| c.dictation_triggers = triggers)
+}
+
+#[tauri::command]
+pub fn get_dictation_hotkey(app: tauri::AppHandle) -> Option<DictationHotkey> {
+    crate::config::read(&app, |c| c.dictation_hotkey.clone())
+}
+
+/// Save (or clear with `None`) the chord trigger. The chord is saved even when it
+/// looks taken; the returned warning lets the UI tell the user it may never fire.
+#[tauri::command]
+pub fn set_dictation_hotkey(
+    app: tauri::AppHandle,
+    hotkey: Option<DictationHotkey>,
+) -> Result<Option<HotkeyWarning>, String> {
+    if let Some(h) = &hotkey {
+        if h.hotkey.trigger.is_some() {
+            return Err(format!("{} is a lone modifier; use set_dictation_triggers instead", h.hotkey.label));
+        }
+    }
+
+    let warning = hotkey.as_ref().and_then(|h| crate::hotkey::find_conflict(&app, &h.hotkey));
+    if let Some(w) = &warning {
+        log::warn!("Dictation hotkey conflict: {}", w.message);
+    }
+
+    crate::config::update(&app, |c| c.dictation_hotkey = hotkey)?;
+    Ok(warning)
+}
+
+#[tauri::command]
+pub fn get_dictation_mouse_button(app: tauri::AppHandle) -> Option<MouseTrigger> {
+    crate::config::read(&app, |c| c.dictation_mouse_button.clone())
+}
+
+#[tauri::command]
+pub fn set_dictation_mouse_button(app: tauri::AppHandle, trigger: Option<MouseTrigger>) -> Result<(), String> {
+    if let Some(MouseTrigger { button: MouseButton::Other(n), .. }) = &trigger {
+        if cfg!(not(target_os = "macos")) {
+            return Err(format!("Mouse button {n} is not supported on this platform"));
+        }
+        if *n < 2 {
+            return Err("The primary and secondary buttons cannot be used for dictation".to_string());
+        }
+    }
+    crate::config::update(&app, |c| c.dictation_mouse_button = trigger)
+}
+
+#[tauri::command]
+pub fn get_dictation_media_key(app: tauri::AppHandle) -> Option<MediaKeyTrigger> {
+    crate::config::read(&app, |c| c.dictation_media_key.clone())
+}
+
+/// Enable (or disable with `None`) play/pause as a dictation toggle. While enabled the key
+/// no longer reaches music players, so this is off by default.
+#[tauri::command]
+pub fn set_dictation_media_key(app: tauri::AppHandle, trigger: Option<MediaKeyTrigger>) -> Result<(), String> {
+    if trigger.is_some() && cfg!(target_os = "linux") {
+        return Err("Media key dictation is not supported on this platform".to_string());
+    }
+    crate::config::update(&app, |c| c.dictation_media_key = trigger)?;
+    #[cfg(target_os = "macos")]
+    crate::media_key::apply(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_chat_focus_apps(app: tauri::AppHandle) -> Vec<String> {
+    crate::config::read(&app, |c| c.chat_focus_bundle_ids.clone())
+}
+
+/// Bundle identifiers that make `auto` triggers dictate into chat. spaceduck itself always counts.
+#[tauri::command]
+pub fn set_chat_focus_apps(app: tauri::AppHandle, bundle_ids: Vec<String>) -> Result<(), String> {
+    let bundle_ids = bundle_ids
+        .into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    crate::config::update(&app, |c| c.chat_focus_bundle_ids = bundle_ids)
+}
@@ -0,0 +1,151 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Reconfiguring sends a burst of notifications, one per display and step; act once it
+/// has been quiet this long.
+const SETTLE: Duration = Duration::from_millis(500);
+
+/// The OS callbacks get no user data we can use, so the handle lives here.
+static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static WAITING: AtomicBool = AtomicBool::new(false);
+static LAST_CHANGE: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn last_change() -> std::sync::MutexGuard<'static, Option<Instant>> {
+    LAST_CHANGE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A display was added, removed, moved or rescaled, or the Dock or taskbar changed size
+/// or side.
+fn changed() {
+    *last_change() = Some(Instant::now());
+    if WAITING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        while last_change().is_some_and(|t| t.elapsed() < SETTLE) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        WAITING.store(false, Ordering::SeqCst);
+        if let Some(handle) = HANDLE.get() {
+            log::info!("Displays changed");
+            crate::pill::displays_changed(handle);
+        }
+    });
+}
+
+/// Watch for display changes through Quartz, and for the Dock's preferences changing
+/// (size, magnification, side, hiding), which Quartz does not report. Call on the main
+/// thread, whose run loop delivers both.
+#[cfg(target_os = "macos")]
+pub fn start(handle: &tauri::AppHandle) {
+    use block2::RcBlock;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use std::ffi::c_void;
+
+    /// kCGDisplayBeginConfigurationFlag: sent before the change, when nothing has moved yet.
+    const BEGIN_CONFIGURATION: u32 = 1;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGDisplayRegisterReconfigurationCallback(
+            callback: extern "C" fn(u32, u32, *mut c_void),
+            user_info: *mut c_void,
+        ) -> i32;
+    }
+
+    extern "C" fn reconfigured(_display: u32, flags: u32, _user_info: *mut c_void) {
+        if flags & BEGIN_CONFIGURATION == 0 {
+            changed();
+        }
+    }
+
+    let _ = HANDLE.set(handle.clone());
+    let status = unsafe { CGDisplayRegisterReconfigurationCallback(reconfigured, std::ptr::null_mut()) };
+    if status != 0 {
+        log::warn!("Failed to watch for display changes: CGError {status}");
+    }
+
+    unsafe {
+        let Some(cls) = AnyClass::get("NSDistributedNotificationCenter") else { return };
+        let center: *mut AnyObject = objc2::msg_send![cls, defaultCenter];
+        let Some(string_cls) = AnyClass::get("NSString") else { return };
+        let name: *mut AnyObject =
+            objc2::msg_send![string_cls, stringWithUTF8String: c"com.apple.dock.prefchanged".as_ptr()];
+        let block = RcBlock::new(|_notification: *mut AnyObject| changed());
+        // The center keeps the block and the observer token for as long as the app runs.
+        let _: *mut AnyObject = objc2::msg_send![
+            center,
+            addObserverForName: name,
+            object: std::ptr::null_mut::<AnyObject>(),
+            queue: std::ptr::null_mut::<AnyObject>(),
+            usingBlock: &*block
+        ];
+    }
+}
+
+/// Watch for WM_DISPLAYCHANGE and work area changes with a hidden window on its own
+/// thread. It has to be a top-level window; message-only ones miss the broadcasts.
+#[cfg(target_os = "windows")]
+pub fn start(handle: &tauri::AppHandle) {
+    use windows::core::w;
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, MSG, WINDOW_EX_STYLE,
+        WINDOW_STYLE, WM_DISPLAYCHANGE, WM_SETTINGCHANGE, WNDCLASSW,
+    };
+
+    /// SPI_SETWORKAREA, which WM_SETTINGCHANGE carries when the taskbar moves, resizes or
+    /// starts or stops hiding.
+    const SET_WORK_AREA: usize = 0x002F;
+
+    unsafe extern "system" fn window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if msg == WM_DISPLAYCHANGE || (msg == WM_SETTINGCHANGE && wparam.0 == SET_WORK_AREA) {
+            changed();
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    let _ = HANDLE.set(handle.clone());
+    std::thread::spawn(|| unsafe {
+        let class_name = w!("SpaceduckDisplayWatch");
+        let class = WNDCLASSW { lpfnWndProc: Some(window_proc), lpszClassName: class_name, ..Default::default() };
+        if RegisterClassW(&class) == 0 {
+            log::warn!("Failed to watch for display changes: RegisterClassW failed");
+            return;
+        }
+        let window = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            w!(""),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            None,
+            None,
+        );
+        if let Err(e) = window {
+            log::warn!("Failed to watch for display changes: {e}");
+            return;
+        }
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+            DispatchMessageW(&msg);
+        }
+    });
+}
+
+/// Watch for GDK's monitors-changed signal. Call on the GTK main thread.
+#[cfg(target_os = "linux")]
+pub fn start(handle: &tauri::AppHandle) {
+    let _ = HANDLE.set(handle.clone());
+    let Some(screen) = gtk::gdk::Screen::default() else {
+        log::warn!("Failed to watch for display changes: no default screen");
+        return;
+    };
+    screen.connect_monitors_changed(|_| changed());
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+/// What happens to a notification that arrives while Do Not Disturb is on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DuringDnd {
+    /// Hold it and show it once Do Not Disturb ends.
+    #[default]
+    Queue,
+    /// Drop it; the result is still in the conversation.
+    Suppress,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DndStatus {
+    /// Whether this platform can tell; `active` is always false when it cannot.
+    pub supported: bool,
+    /// A Focus mode, Focus Assist, a presentation or a full-screen game is keeping
+    /// notifications quiet.
+    pub active: bool,
+}
+
+/// A Focus turned on by hand or from Control Centre leaves an assertion in the Do Not
+/// Disturb database. Focus modes that start on a schedule do not, and reading it needs
+/// Full Disk Access on some versions.
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn active() -> Option<bool> {
+        let home = std::env::var_os("HOME")?;
+        let path = std::path::Path::new(&home).join("Library/DoNotDisturb/DB/Assertions.json");
+        let json: serde_json::Value = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).ok()?,
+            // Created the first time a Focus is turned on.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Some(false),
+            Err(_) => return None,
+        };
+        let records = json["data"].as_array()?.iter().filter_map(|store| store["storeAssertionRecords"].as_array());
+        Some(records.into_iter().any(|records| !records.is_empty()))
+    }
+}
+
+/// Focus Assist's profile from its WNF state, which has no documented API, together
+/// with the shell's own quiet states: presentation mode, full-screen apps and games.
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::UI::Shell::{SHQueryUserNotificationState, QUNS_ACCEPTS_NOTIFICATIONS};
+
+    /// WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED
+    const QUIET_HOURS_PROFILE: u64 = 0x0d83_063e_a3bf_1c75;
+
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtQueryWnfStateData(
+            state_name: *const u64,
+            type_id: *const std::ffi::c_void,
+            explicit_scope: *const std::ffi::c_void,
+            change_stamp: *mut u32,
+            buffer: *mut std::ffi::c_void,
+            buffer_size: *mut u32,
+        ) -> i32;
+    }
+
+    /// 0 when off, 1 for priority only, 2 for alarms only.
+    fn focus_assist_profile() -> Option<u32> {
+        let (mut stamp, mut profile, mut size) = (0u32, 0u32, std::mem::size_of::<u32>() as u32);
+        let status = unsafe {
+            NtQueryWnfStateData(
+                &QUIET_HOURS_PROFILE,
+                std::ptr::null(),
+                std::ptr::null(),
+                &mut stamp,
+                (&mut profile as *mut u32).cast(),
+                &mut size,
+            )
+        };
+        (status >= 0).then_some(profile)
+    }
+
+    pub fn active() -> Option<bool> {
+        let shell_quiet = unsafe { SHQueryUserNotificationState() }.ok().map(|state| state != QUNS_ACCEPTS_NOTIFICATIONS);
+        match (focus_assist_profile(), shell_quiet) {
+            (None, None) => None,
+            (profile, quiet) => Some(profile.is_some_and(|p| p != 0) || quiet.unwrap_or(false)),
+        }
+    }
+}
+
+/// GNOME's "Do Not Disturb" switch, which turns off banners, or the notification server's
+/// `Inhibited` property, which KDE Plasma sets.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use gtk::gio;
+    use gtk::gio::prelude::*;
+    use gtk::glib;
+
+    const GNOME_SCHEMA: &str = "org.gnome.desktop.notifications";
+
+    fn gnome() -> Option<bool> {
+        // Settings::new aborts on a schema that is not installed.
+        gio::SettingsSchemaSource::default()?.lookup(GNOME_SCHEMA, true)?;
+        Some(!gio::Settings::new(GNOME_SCHEMA).boolean("show-banners"))
+    }
+
+    fn inhibited() -> Option<bool> {
+        let connection = gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE).ok()?;
+        let reply = connection
+            .call_sync(
+                Some("org.freedesktop.Notifications"),
+                "/org/freedesktop/Notifications",
+                "org.freedesktop.DBus.Properties",
+                "Get",
+                Some(&("org.freedesktop.Notifications", "Inhibited").to_variant()),
+                Some(glib::VariantTy::new("(v)").expect("static type")),
+                gio::DBusCallFlags::NONE,
+                1000,
+                gio::Cancellable::NONE,
+            )
+            .ok()?;
+        reply.child_value(0).as_variant()?.get::<bool>()
+    }
+
+    pub fn active() -> Option<bool> {
+        gnome().or_else(inhibited)
+    }
+}
+
+/// Whether the system is asking apps to keep quiet right now.
+pub fn status() -> DndStatus {
+    match platform::active() {
+        Some(active) => DndStatus { supported: true, active },
+        None => DndStatus { supported: false, active: false },
+    }
+}
+
+#[tauri::command]
+pub fn get_dnd_status() -> DndStatus {
+    status()
+}
+
+/// Choose whether notifications that arrive during Do Not Disturb wait for it to end or
+/// are dropped.
+#[tauri::command]
+pub fn set_notifications_during_dnd(app: tauri::AppHandle, during: DuringDnd) -> Result<(), String> {
+    crate::config::update(&app, |c| c.notifications_during_dnd = during)
+}
+
+/// Let reminders through Do Not Disturb.
+#[tauri::command]
+pub fn set_reminders_override_dnd(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.reminders_override_dnd = enabled)
+}
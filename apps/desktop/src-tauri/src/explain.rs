@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::frontmost::FrontmostApp;
+
+/// How long an app gets to put the selection on the clipboard after a synthetic copy.
+const COPY_TIMEOUT: Duration = Duration::from_millis(600);
+const COPY_POLL: Duration = Duration::from_millis(30);
+
+#[cfg(target_os = "macos")]
+mod platform {
+    /// Cmd+C, by the key that types "c" in the current layout.
+    pub fn copy() -> Result<(), String> {
+        use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+        let mut enigo = Enigo::new(&Settings::default()).map_err(|e| format!("Failed to create enigo instance: {e}"))?;
+        // kVK_ANSI_C, for when no installed layout can type a "c".
+        let c = crate::keyboard_layout::key_code_for('c').unwrap_or(8);
+        enigo.key(Key::Meta, Direction::Press).map_err(|e| e.to_string())?;
+        enigo.key(Key::Other(c as u32), Direction::Click).map_err(|e| e.to_string())?;
+        enigo.key(Key::Meta, Direction::Release).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    pub fn copy() -> Result<(), String> {
+        crate::win_inject::copy()
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub fn copy() -> Result<(), String> {
+        crate::linux_inject::copy()
+    }
+}
+
+/// Copy the selection with a synthetic Cmd/Ctrl+C and put the clipboard back as it was.
+/// Only text survives the round trip; an image on the clipboard is lost.
+fn copy_selection(app: &tauri::AppHandle) -> Option<String> {
+    let saved = app.clipboard().read_text().ok().filter(|text| !text.is_empty());
+    // Cleared first, so an app that ignores the copy is not taken to have copied the old text.
+    if let Err(e) = app.clipboard().clear() {
+        log::warn!("Failed to clear the clipboard before copying the selection: {e}");
+        return None;
+    }
+    let copied = match platform::copy() {
+        Ok(()) => {
+            let deadline = Instant::now() + COPY_TIMEOUT;
+            loop {
+                std::thread::sleep(COPY_POLL);
+                match app.clipboard().read_text() {
+                    Ok(text) if !text.trim().is_empty() => break Some(text),
+                    _ if Instant::now() >= deadline => break None,
+                    _ => {}
+                }
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to copy the selection: {e}");
+            None
+        }
+    };
+    let restored = match saved {
+        Some(text) => app.clipboard().write_text(text),
+        None => app.clipboard().clear(),
+    };
+    if let Err(e) = restored {
+        log::warn!("Failed to restore the clipboard: {e}");
+    }
+    copied
+}
+
+/// The text selected in `front`, read through the accessibility APIs where the app
+/// exposes it and copied otherwise.
+fn selection(app: &tauri::AppHandle, front: Option<&FrontmostApp>) -> Option<String> {
+    let text = front
+        .and_then(crate::context::selected_text)
+        .filter(|text| !text.trim().is_empty())
+        .or_else(|| copy_selection(app))?;
+    Some(text.trim().chars().take(crate::context::MAX_SELECTION_CHARS).collect())
+}
+
+/// The explain-selection shortcut: grab the selection and stream an explanation of it
+/// into the response window next to the pointer.
+fn explain_selection(app: &tauri::AppHandle) {
+    let app = app.clone();
+    // Copying waits on the other app, which must not hold up the shortcut handler.
+    std::thread::spawn(move || {
+        let front = crate::frontmost::frontmost_app().filter(|front| !front.is_self());
+        match selection(&app, front.as_ref()) {
+            Some(text) => crate::response_window::explain(&app, text, front.and_then(|front| front.name)),
+            None => crate::response_window::fail_near_cursor(&app, "Explain selection", "Select some text to explain first."),
+        }
+    });
+}
+
+/// Register the explain-selection shortcut from the config at launch.
+pub fn start(app: &tauri::AppHandle) {
+    let Some(shortcut) = crate::config::read(app, |c| c.explain_selection_shortcut.clone()) else { return };
+    if let Err(e) = crate::register_shortcut(app, &shortcut, explain_selection) {
+        log::warn!("Explain selection shortcut unavailable: {e}");
+    }
+}
+
+#[tauri::command]
+pub fn get_explain_selection_shortcut(app: tauri::AppHandle) -> Option<String> {
+    crate::config::read(&app, |c| c.explain_selection_shortcut.clone())
+}
+
+/// Change the global shortcut that explains the selected text; `None` turns it off.
+#[tauri::command]
+pub fn set_explain_selection_shortcut(app: tauri::AppHandle, shortcut: Option<String>) -> Result<(), String> {
+    let previous = crate::config::read(&app, |c| c.explain_selection_shortcut.clone());
+    crate::replace_shortcut(&app, previous.as_deref(), shortcut.as_deref(), explain_selection)?;
+    crate::config::update(&app, |c| c.explain_selection_shortcut = shortcut)
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+/// Sound and haptic confirmation played natively when a recording changes state,
+/// so there is feedback even when the pill is off-screen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FeedbackConfig {
+    pub sounds: bool,
+    /// 0.0 to 1.0.
+    pub volume: f32,
+    /// Force Touch trackpad tap on macOS; ignored elsewhere.
+    pub haptics: bool,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self { sounds: false, volume: 0.5, haptics: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    Start,
+    Stop,
+    Cancel,
+}
+
+/// Play the configured feedback for `cue`. Never blocks the caller.
+pub fn play(handle: &tauri::AppHandle, cue: Cue) {
+    let config = crate::config::read(handle, |c| c.feedback.clone());
+    if !config.sounds && !config.haptics {
+        return;
+    }
+    platform::play(handle, cue, &config);
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::runtime::{AnyClass, AnyObject};
+
+    use super::{Cue, FeedbackConfig};
+
+    /// NSHapticFeedbackPatternGeneric / NSHapticFeedbackPatternLevelChange
+    const PATTERN_GENERIC: isize = 0;
+    const PATTERN_LEVEL_CHANGE: isize = 2;
+    /// NSHapticFeedbackPerformanceTimeNow
+    const PERFORM_NOW: usize = 1;
+
+    fn sound_name(cue: Cue) -> &'static str {
+        match cue {
+            Cue::Start => "Tink",
+            Cue::Stop => "Pop",
+            Cue::Cancel => "Basso",
+        }
+    }
+
+    /// AppKit objects, so hop to the main thread.
+    pub fn play(handle: &tauri::AppHandle, cue: Cue, config: &FeedbackConfig) {
+        let config = config.clone();
+        let result = handle.run_on_main_thread(move || unsafe {
+            if config.sounds {
+                play_sound(sound_name(cue), config.volume.clamp(0.0, 1.0));
+            }
+            if config.haptics {
+                let pattern = if cue == Cue::Start { PATTERN_LEVEL_CHANGE } else { PATTERN_GENERIC };
+                perform_haptic(pattern);
+            }
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to play dictation feedback: {e}");
+        }
+    }
+
+    unsafe fn play_sound(name: &str, volume: f32) {
+        use cocoa::foundation::NSString;
+
+        let Some(ns_sound) = AnyClass::get("NSSound") else { return };
+        let ns_name = NSString::alloc(cocoa::base::nil).init_str(name) as *mut AnyObject;
+        let sound: *mut AnyObject = objc2::msg_send![ns_sound, soundNamed: ns_name];
+        let _: () = objc2::msg_send![ns_name, release];
+        if sound.is_null() {
+            log::warn!("System sound {name} not found");
+            return;
+        }
+        // soundNamed: returns a shared instance, so stop it first in case it is still playing.
+        let _: bool = objc2::msg_send![sound, stop];
+        let _: () = objc2::msg_send![sound, setVolume: volume];
+        let _: bool = objc2::msg_send![sound, play];
+    }
+
+    unsafe fn perform_haptic(pattern: isize) {
+        let Some(manager) = AnyClass::get("NSHapticFeedbackManager") else { return };
+        let performer: *mut AnyObject = objc2::msg_send![manager, defaultPerformer];
+        if !performer.is_null() {
+            let _: () = objc2::msg_send![performer, performFeedbackPattern: pattern, performanceTime: PERFORM_NOW];
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::core::HSTRING;
+    use windows::Win32::Media::Audio::{waveOutSetVolume, PlaySoundW, SND_ASYNC, SND_FILENAME, SND_NODEFAULT};
+
+    use super::{Cue, FeedbackConfig};
+
+    /// The Windows Speech Recognition sounds, shipped with every install.
+    fn sound_file(cue: Cue) -> &'static str {
+        match cue {
+            Cue::Start => "Speech On.wav",
+            Cue::Stop => "Speech Off.wav",
+            Cue::Cancel => "Speech Misrecognition.wav",
+        }
+    }
+
+    pub fn play(_handle: &tauri::AppHandle, cue: Cue, config: &FeedbackConfig) {
+        if !config.sounds {
+            return;
+        }
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string());
+        let path = HSTRING::from(format!(r"{root}\Media\{}", sound_file(cue)));
+        // Same level on both channels: low word is left, high word is right.
+        let level = (config.volume.clamp(0.0, 1.0) * 0xFFFF as f32) as u32;
+        unsafe {
+            waveOutSetVolume(None, level | (level << 16));
+            if !PlaySoundW(&path, None, SND_FILENAME | SND_ASYNC | SND_NODEFAULT).as_bool() {
+                log::warn!("Failed to play {path}");
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Cue, FeedbackConfig};
+
+    pub fn play(_handle: &tauri::AppHandle, _cue: Cue, _config: &FeedbackConfig) {}
+}
+
+#[tauri::command]
+pub fn get_dictation_feedback(app: tauri::AppHandle) -> FeedbackConfig {
+    crate::config::read(&app, |c| c.feedback.clone())
+}
+
+#[tauri::command]
+pub fn set_dictation_feedback(app: tauri::AppHandle, feedback: FeedbackConfig) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&feedback.volume) {
+        return Err("Volume must be between 0 and 1".to_string());
+    }
+    crate::config::update(&app, |c| c.feedback = feedback)
+}
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+/// The gateway's default upload limit.
+const MAX_BYTES: u64 = 50 * 1024 * 1024;
+/// What the gateway accepts, by extension, with the bytes the file must start with.
+const ACCEPTED: &[(&str, &str, &[u8])] = &[("pdf", "application/pdf", b"%PDF-")];
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const BOUNDARY: &str = "----spaceduck-desktop-upload";
+
+static NEXT_UPLOAD: AtomicU64 = AtomicU64::new(1);
+
+/// An uploaded file as the gateway describes it, ready to attach to a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadProgress {
+    upload_id: String,
+    filename: String,
+    sent: u64,
+    total: u64,
+    /// `uploading`, `done` or `failed`.
+    state: &'static str,
+    attachment: Option<Attachment>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UploadError {
+    error: Option<String>,
+}
+
+/// Counts what the request has read of the body, for progress events.
+struct Progress<R> {
+    inner: R,
+    sent: u64,
+    on_progress: Box<dyn FnMut(u64) + Send>,
+    last: Instant,
+}
+
+impl<R: Read> Read for Progress<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.sent += n as u64;
+        if self.last.elapsed() >= PROGRESS_INTERVAL {
+            (self.on_progress)(self.sent);
+            self.last = Instant::now();
+        }
+        Ok(n)
+    }
+}
+
+/// The MIME type of `path` if the gateway takes it, checking the contents as well as the
+/// name and the size.
+fn validate(path: &Path) -> Result<&'static str, String> {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let Some(&(_, mime_type, magic)) = ACCEPTED.iter().find(|(ext, _, _)| *ext == extension) else {
+        return Err(format!("{name}: only PDF files can be attached"));
+    };
+    let metadata = std::fs::metadata(path).map_err(|e| format!("{name}: {e}"))?;
+    if !metadata.is_file() {
+        return Err(format!("{name} is not a file"));
+    }
+    if metadata.len() > MAX_BYTES {
+        return Err(format!("{name} is too large (max {} MB)", MAX_BYTES / 1024 / 1024));
+    }
+    let mut header = vec![0u8; magic.len()];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map_err(|_| format!("{name} is not a valid PDF"))?;
+    if header != magic {
+        return Err(format!("{name} is not a valid PDF"));
+    }
+    Ok(mime_type)
+}
+
+/// Send `path` to the gateway's upload endpoint as multipart form data, streaming it
+/// from disk.
+fn upload(app: &tauri::AppHandle, path: &Path, mime_type: &str, on_progress: Box<dyn FnMut(u64) + Send>) -> Result<Attachment, String> {
+    let gateway = crate::config::read(app, |c| c.gateway.clone());
+    let url = format!("{}/api/upload", gateway.url.trim_end_matches('/'));
+    let filename = path.file_name().map(|n| n.to_string_lossy().replace('"', "")).unwrap_or_default();
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open {filename}: {e}"))?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let head = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\nContent-Type: {mime_type}\r\n\r\n"
+    );
+    let tail = format!("\r\n--{BOUNDARY}--\r\n");
+    let length = head.len() as u64 + size + tail.len() as u64;
+    let body = Progress {
+        inner: std::io::Cursor::new(head.into_bytes()).chain(file).chain(std::io::Cursor::new(tail.into_bytes())),
+        sent: 0,
+        on_progress,
+        last: Instant::now(),
+    };
+
+    let mut request = ureq::post(&url)
+        .timeout(UPLOAD_TIMEOUT)
+        .set("Content-Type", &format!("multipart/form-data; boundary={BOUNDARY}"))
+        .set("Content-Length", &length.to_string());
+    if let Some(token) = &gateway.token {
+        request = request.set("Authorization", &format!("Bearer {token}"));
+    }
+    match request.send(body) {
+        Ok(response) => response.into_json().map_err(|e| format!("Malformed upload response: {e}")),
+        Err(ureq::Error::Status(status, response)) => {
+            let body: Option<UploadError> = response.into_json().ok();
+            Err(body.and_then(|b| b.error).unwrap_or_else(|| format!("Gateway returned {status}")))
+        }
+        Err(e) => Err(format!("Failed to reach gateway: {e}")),
+    }
+}
+
+/// Files dropped on `label`. Each one is checked and uploaded on its own thread, with
+/// `file-upload` progress events to the window whose composer gets the attachment: the
+/// one dropped on, or the main window for the pill.
+pub fn dropped(app: &tauri::AppHandle, label: &str, paths: Vec<PathBuf>) {
+    let target = if label == "main" || crate::conversation_windows::is_conversation_window(label) {
+        label.to_string()
+    } else {
+        crate::conversation_windows::show(app, None);
+        "main".to_string()
+    };
+    for path in paths {
+        let app = app.clone();
+        let target = target.clone();
+        std::thread::spawn(move || {
+            let upload_id = format!("upload-{}", NEXT_UPLOAD.fetch_add(1, Ordering::SeqCst));
+            let filename = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let total = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let event = {
+                let (app, target, upload_id, filename) = (app.clone(), target.clone(), upload_id.clone(), filename.clone());
+                move |sent: u64, state: &'static str, attachment: Option<Attachment>, error: Option<String>| {
+                    let progress = UploadProgress {
+                        upload_id: upload_id.clone(),
+                        filename: filename.clone(),
+                        sent: sent.min(total),
+                        total,
+                        state,
+                        attachment,
+                        error,
+                    };
+                    let _ = app.emit_to(target.as_str(), "file-upload", progress);
+                }
+            };
+            let result = validate(&path).and_then(|mime_type| {
+                event(0, "uploading", None, None);
+                let progress = event.clone();
+                upload(&app, &path, mime_type, Box::new(move |sent| progress(sent, "uploading", None, None)))
+            });
+            match result {
+                Ok(attachment) => {
+                    log::info!("Uploaded dropped file {filename} as {}", attachment.id);
+                    event(total, "done", Some(attachment), None);
+                }
+                Err(e) => {
+                    log::warn!("Dropped file not attached: {e}");
+                    event(0, "failed", None, Some(e));
+                }
+            }
+        });
+    }
+}
+
+/// Take over file drops on `window`.
+pub fn watch(window: &tauri::WebviewWindow) {
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+            dropped(&app, &label, paths.clone());
+        }
+    });
+}
@@ -1,25 +1,51 @@
 use core_graphics::event::{
     CGEvent, CGEventFlags, CGEventTap, CGEventTapLocation, CGEventTapOptions,
-    CGEventTapPlacement, CGEventType,
+    CGEventTapPlacement, CGEventType, EventField,
 };
 use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
-use std::sync::atomic::{AtomicBool, AtomicU8, AtomicPtr, Ordering};
-use tauri::Emitter;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU8, Ordering};
 
-static FN_IS_DOWN: AtomicBool = AtomicBool::new(false);
-/// 0 = not recording, 1 = chat mode (focused), 2 = global mode (background)
-static RECORDING_MODE: AtomicU8 = AtomicU8::new(0);
+use crate::dictation::{self, Edge, MouseButton, TriggerKey, TriggerSource};
+use crate::hotkey::{self, Hotkey};
+use crate::monitor_health;
+
+/// Bitmask of trigger keys currently held, indexed by `TriggerKey as u8`.
+static KEYS_DOWN: AtomicU8 = AtomicU8::new(0);
+/// Whether the configured chord trigger is currently held.
+static HOTKEY_DOWN: AtomicBool = AtomicBool::new(false);
+/// Whether the configured push-to-talk mouse button is currently held.
+static MOUSE_DOWN: AtomicBool = AtomicBool::new(false);
 /// Stored mach port so the callback can re-enable the tap when macOS disables it.
 static TAP_PORT: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_mut());
 
+/// kVK_Escape
+const ESCAPE_KEYCODE: i64 = 53;
+
+/// kVK_Delete, the Backspace key.
+const DELETE_KEYCODE: i64 = 51;
+/// Return, Tab, Escape, forward delete, Home, End, Page Up/Down and the arrows move or
+/// leave the caret, so anything typed before them no longer ends at it.
+const CARET_KEYCODES: [i64; 12] = [36, 48, 53, 117, 115, 119, 116, 121, 123, 124, 125, 126];
+
 extern "C" {
     fn CGEventTapEnable(tap: *mut std::ffi::c_void, enable: bool);
+    fn CGEventKeyboardGetUnicodeString(
+        event: *mut std::ffi::c_void,
+        max_len: std::ffi::c_ulong,
+        actual_len: *mut std::ffi::c_ulong,
+        buffer: *mut u16,
+    );
 }
 
-/// Start a CGEventTap on the current thread that monitors Fn key press/release.
-/// Emits high-level dictation commands based on window focus state at press time.
+/// Start a CGEventTap on the current thread that monitors the configured trigger keys,
+/// the optional chord trigger and the optional push-to-talk mouse button.
+/// Each trigger has its own press/release state; `auto` triggers pick chat or global
+/// mode from the frontmost app at press time.
+/// Pressing Escape while a trigger is held cancels the recording instead of stopping it,
+/// and so does any other key pressed while a trigger key is held (an Fn or modifier combo).
 /// Uses HID-level tap to intercept Fn/Globe before macOS routes it to the emoji picker.
 /// Requires both Accessibility and Input Monitoring permissions.
+/// Re-enables and install failures are reported to `monitor_health`.
 /// This function blocks forever (runs a CFRunLoop), so call it from a dedicated thread.
 pub fn start(handle: tauri::AppHandle) -> Result<(), String> {
     loop {
@@ -27,6 +53,7 @@ pub fn start(handle: tauri::AppHandle) -> Result<(), String> {
             Ok(()) => break,
             Err(e) => {
                 log::warn!("CGEventTap stopped: {e}. Reinstalling in 2s...");
+                monitor_health::record_failure(&handle, &e);
                 std::thread::sleep(std::time::Duration::from_secs(2));
             }
         }
@@ -41,7 +68,14 @@ fn run_tap(handle: &tauri::AppHandle) -> Result<(), String> {
         CGEventTapLocation::HID,
         CGEventTapPlacement::HeadInsertEventTap,
         CGEventTapOptions::ListenOnly,
-        vec![CGEventType::FlagsChanged],
+        vec![
+            CGEventType::FlagsChanged,
+            CGEventType::KeyDown,
+            CGEventType::KeyUp,
+            CGEventType::OtherMouseDown,
+            CGEventType::OtherMouseUp,
+            CGEventType::LeftMouseDown,
+        ],
         move |_proxy, event_type, event: &CGEvent| -> Option<CGEvent> {
             let raw_type = unsafe { std::mem::transmute::<CGEventType, u32>(event_type) };
 
@@ -51,53 +85,58 @@ fn run_tap(handle: &tauri::AppHandle) -> Result<(), String> {
                 if !port.is_null() {
                     unsafe { CGEventTapEnable(port, true); }
                 }
+                monitor_health::record_reenabled(&handle);
                 return None;
             }
 
-            let flags = event.get_flags();
-            let fn_down = flags.contains(CGEventFlags::CGEventFlagSecondaryFn);
-            let was_down = FN_IS_DOWN.load(Ordering::SeqCst);
-
-            if fn_down && !was_down {
-                FN_IS_DOWN.store(true, Ordering::SeqCst);
-
-                let main_window_is_key: bool = unsafe {
-                    let cls = objc2::runtime::AnyClass::get("NSApplication").unwrap();
-                    let app: *mut objc2::runtime::AnyObject = objc2::msg_send![cls, sharedApplication];
-                    let key_win: *mut objc2::runtime::AnyObject = objc2::msg_send![app, keyWindow];
-                    if key_win.is_null() {
-                        false
-                    } else {
-                        let title: *mut objc2::runtime::AnyObject = objc2::msg_send![key_win, title];
-                        if title.is_null() {
-                            false
-                        } else {
-                            let utf8: *const u8 = objc2::msg_send![title, UTF8String];
-                            if utf8.is_null() {
-                                false
-                            } else {
-                                let s = std::ffi::CStr::from_ptr(utf8 as *const std::ffi::c_char).to_string_lossy();
-                                s == "spaceduck"
-                            }
-                        }
-                    }
-                };
-
-                if main_window_is_key {
-                    RECORDING_MODE.store(1, Ordering::SeqCst);
-                    let _ = handle.emit("dictation:start-chat", ());
-                } else {
-                    RECORDING_MODE.store(2, Ordering::SeqCst);
-                    crate::reposition_pill_near_dock(&handle);
-                    let _ = handle.emit("dictation:start-global", ());
+            if crate::hotkey::is_capturing() {
+                return None;
+            }
+
+            if raw_type == CGEventType::LeftMouseDown as u32 {
+                crate::snippets::reset();
+                return None;
+            }
+
+            if raw_type == CGEventType::OtherMouseDown as u32 || raw_type == CGEventType::OtherMouseUp as u32 {
+                handle_mouse_button(&handle, event, raw_type == CGEventType::OtherMouseDown as u32);
+                return None;
+            }
+
+            let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE);
+
+            if raw_type == CGEventType::KeyDown as u32 || raw_type == CGEventType::KeyUp as u32 {
+                let is_down = raw_type == CGEventType::KeyDown as u32;
+                let trigger_held = KEYS_DOWN.load(Ordering::SeqCst) != 0
+                    || HOTKEY_DOWN.load(Ordering::SeqCst)
+                    || MOUSE_DOWN.load(Ordering::SeqCst);
+                if is_down && keycode == ESCAPE_KEYCODE && trigger_held {
+                    // The session is gone, so the eventual trigger release is a no-op.
+                    dictation::post(&handle, Edge::Cancel);
+                    return None;
                 }
-            } else if !fn_down && was_down {
-                FN_IS_DOWN.store(false, Ordering::SeqCst);
-                let mode = RECORDING_MODE.swap(0, Ordering::SeqCst);
-                match mode {
-                    1 => { let _ = handle.emit("dictation:stop-chat", ()); }
-                    2 => { let _ = handle.emit("dictation:stop-global", ()); }
-                    _ => {}
+                if is_down {
+                    cancel_key_combos(&handle, keycode);
+                    track_snippet_keys(&handle, event, keycode);
+                }
+                handle_chord(&handle, event, keycode, is_down);
+                return None;
+            }
+
+            let flags = event.get_flags();
+            let triggers = crate::config::read(&handle, |c| c.dictation_triggers.clone());
+
+            for trigger in triggers {
+                let Some(is_down) = key_state(trigger.key, flags, keycode) else { continue };
+                let bit = 1u8 << trigger.key as u8;
+                let was_down = KEYS_DOWN.load(Ordering::SeqCst) & bit != 0;
+
+                if is_down && !was_down {
+                    KEYS_DOWN.fetch_or(bit, Ordering::SeqCst);
+                    dictation::post(&handle, Edge::Press(TriggerSource::Key(trigger.key), trigger.target));
+                } else if !is_down && was_down {
+                    KEYS_DOWN.fetch_and(!bit, Ordering::SeqCst);
+                    dictation::post(&handle, Edge::Release(TriggerSource::Key(trigger.key)));
                 }
             }
 
@@ -117,6 +156,7 @@ fn run_tap(handle: &tauri::AppHandle) -> Result<(), String> {
             .map_err(|_| "Failed to create run loop source".to_string())?;
         CFRunLoop::get_current().add_source(&source, kCFRunLoopDefaultMode);
         tap.enable();
+        monitor_health::record_started();
 
         loop {
             let result = CFRunLoop::run_in_mode(kCFRunLoopDefaultMode, std::time::Duration::from_secs(5), false);
@@ -127,3 +167,178 @@ fn run_tap(handle: &tauri::AppHandle) -> Result<(), String> {
 
     Err("CFRunLoop exited unexpectedly".to_string())
 }
+
+/// Another key pressed while a trigger key is held means the user is typing a combo
+/// like Fn+F11 or Right Cmd+K. Within `dictation::post`'s grace period this drops the
+/// press before anything shows; after it, the recording that trigger started.
+/// The chord trigger's own key is exempt, since chords may be built on a trigger modifier.
+fn cancel_key_combos(handle: &tauri::AppHandle, keycode: i64) {
+    let held = KEYS_DOWN.load(Ordering::SeqCst);
+    if held == 0 {
+        return;
+    }
+    let chord_key = crate::config::read(handle, |c| c.dictation_hotkey.as_ref().map(|h| h.hotkey.key_code as i64));
+    if chord_key == Some(keycode) {
+        return;
+    }
+    let triggers = crate::config::read(handle, |c| c.dictation_triggers.clone());
+    for trigger in triggers {
+        if held & (1u8 << trigger.key as u8) != 0 {
+            dictation::post(handle, Edge::CancelOwned(TriggerSource::Key(trigger.key)));
+        }
+    }
+}
+
+/// Feed typed characters to snippet expansion. Shortcuts and caret movement start the
+/// typed text over.
+fn track_snippet_keys(handle: &tauri::AppHandle, event: &CGEvent, keycode: i64) {
+    use foreign_types::ForeignType;
+
+    let flags = event.get_flags();
+    if flags.intersects(CGEventFlags::CGEventFlagCommand | CGEventFlags::CGEventFlagControl)
+        || CARET_KEYCODES.contains(&keycode)
+    {
+        crate::snippets::reset();
+        return;
+    }
+    if keycode == DELETE_KEYCODE {
+        crate::snippets::key_deleted();
+        return;
+    }
+    let mut buffer = [0u16; 4];
+    let mut len: std::ffi::c_ulong = 0;
+    unsafe {
+        CGEventKeyboardGetUnicodeString(event.as_ptr() as *mut _, buffer.len() as _, &mut len, buffer.as_mut_ptr());
+    }
+    let len = (len as usize).min(buffer.len());
+    for c in char::decode_utf16(buffer[..len].iter().copied()).filter_map(Result::ok) {
+        if !c.is_control() {
+            crate::snippets::key_typed(handle, c);
+        }
+    }
+}
+
+/// Start on a KeyDown matching the chord exactly, stop on the KeyUp of its key.
+/// Auto-repeat KeyDowns while the chord is held are ignored.
+fn handle_chord(handle: &tauri::AppHandle, event: &CGEvent, keycode: i64, is_down: bool) {
+    let Some(chord) = crate::config::read(handle, |c| c.dictation_hotkey.clone()) else { return };
+    if keycode != chord.hotkey.key_code as i64 {
+        return;
+    }
+
+    if is_down {
+        let repeat = event.get_integer_value_field(EventField::KEYBOARD_EVENT_AUTOREPEAT) != 0;
+        let exact = hotkey::from_key_down(chord.hotkey.key_code, event.get_flags()).modifiers == chord.hotkey.modifiers;
+        if !repeat && exact && !HOTKEY_DOWN.swap(true, Ordering::SeqCst) {
+            dictation::post(handle, Edge::Press(TriggerSource::Hotkey, chord.target));
+        }
+    } else if HOTKEY_DOWN.swap(false, Ordering::SeqCst) {
+        dictation::post(handle, Edge::Release(TriggerSource::Hotkey));
+    }
+}
+
+/// Treat the configured mouse button like a trigger key: down starts, up stops.
+fn handle_mouse_button(handle: &tauri::AppHandle, event: &CGEvent, is_down: bool) {
+    let Some(trigger) = crate::config::read(handle, |c| c.dictation_mouse_button.clone()) else { return };
+    let wanted = match trigger.button {
+        MouseButton::Middle => 2,
+        MouseButton::Back => 3,
+        MouseButton::Forward => 4,
+        MouseButton::Other(n) => n as i64,
+    };
+    if event.get_integer_value_field(EventField::MOUSE_EVENT_BUTTON_NUMBER) != wanted {
+        return;
+    }
+
+    if is_down {
+        if !MOUSE_DOWN.swap(true, Ordering::SeqCst) {
+            dictation::post(handle, Edge::Press(TriggerSource::Mouse, trigger.target));
+        }
+    } else if MOUSE_DOWN.swap(false, Ordering::SeqCst) {
+        dictation::post(handle, Edge::Release(TriggerSource::Mouse));
+    }
+}
+
+/// Install a short-lived tap on the current thread and record the next key or chord.
+/// A modifier counts only if it is released without another key in between, so
+/// holding Cmd and then pressing K records Cmd+K rather than Cmd.
+pub fn capture_next_key(timeout: std::time::Duration) -> Result<Option<Hotkey>, String> {
+    use std::cell::{Cell, RefCell};
+
+    let captured: RefCell<Option<Hotkey>> = RefCell::new(None);
+    let pending_modifier: Cell<Option<u16>> = Cell::new(None);
+
+    let tap = CGEventTap::new(
+        CGEventTapLocation::HID,
+        CGEventTapPlacement::HeadInsertEventTap,
+        CGEventTapOptions::ListenOnly,
+        vec![CGEventType::FlagsChanged, CGEventType::KeyDown],
+        |_proxy, event_type, event: &CGEvent| -> Option<CGEvent> {
+            let raw_type = event_type as u32;
+            let keycode = event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE) as u16;
+            let flags = event.get_flags();
+
+            let done = if raw_type == CGEventType::KeyDown as u32 {
+                pending_modifier.set(None);
+                let hotkey = hotkey::from_key_down(keycode, flags);
+                // A bare Escape aborts the capture.
+                if !(keycode as i64 == ESCAPE_KEYCODE && hotkey.modifiers.is_empty()) {
+                    *captured.borrow_mut() = Some(hotkey);
+                }
+                true
+            } else if raw_type == CGEventType::FlagsChanged as u32 && hotkey::is_modifier_key_code(keycode) {
+                if hotkey::modifier_is_down(keycode, flags) {
+                    pending_modifier.set(Some(keycode));
+                    false
+                } else if pending_modifier.get() == Some(keycode) {
+                    *captured.borrow_mut() = Some(hotkey::from_lone_modifier(keycode));
+                    true
+                } else {
+                    false
+                }
+            } else {
+                false
+            };
+
+            if done {
+                CFRunLoop::get_current().stop();
+            }
+            None
+        },
+    )
+    .map_err(|_| "Failed to create CGEventTap. Is Input Monitoring permission granted?".to_string())?;
+
+    let source = tap
+        .mach_port
+        .create_runloop_source(0)
+        .map_err(|_| "Failed to create run loop source".to_string())?;
+    let run_loop = CFRunLoop::get_current();
+    unsafe {
+        run_loop.add_source(&source, kCFRunLoopDefaultMode);
+        tap.enable();
+        CFRunLoop::run_in_mode(kCFRunLoopDefaultMode, timeout, false);
+
+        use core_foundation::base::TCFType;
+        CGEventTapEnable(tap.mach_port.as_concrete_TypeRef() as *mut std::ffi::c_void, false);
+        run_loop.remove_source(&source, kCFRunLoopDefaultMode);
+    }
+    drop(tap);
+
+    Ok(captured.into_inner())
+}
+
+/// Whether a FlagsChanged event reports `key` as held. `None` means the event is about some other key.
+fn key_state(key: TriggerKey, flags: CGEventFlags, keycode: i64) -> Option<bool> {
+    // Device-dependent modifier bits from IOLLEvent.h distinguish left from right.
+    let (expected_keycode, device_mask) = match key {
+        TriggerKey::Fn => return Some(flags.contains(CGEventFlags::CGEventFlagSecondaryFn)),
+        TriggerKey::RightCommand => (54, 0x0000_0010),
+        TriggerKey::RightOption => (61, 0x0000_0040),
+        TriggerKey::RightControl => (62, 0x0000_2000),
+        TriggerKey::RightShift => (60, 0x0000_0004),
+    };
+    if keycode != expected_keycode {
+        return None;
+    }
+    Some(flags.bits() & device_mask != 0)
+}
@@ -0,0 +1,163 @@
+#[cfg(target_os = "macos")]
+use objc2::runtime::{AnyClass, AnyObject};
+use serde::Serialize;
+
+/// The application that currently receives keyboard input.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontmostApp {
+    /// Bundle id on macOS; lowercase executable name, such as `code.exe`, on Windows.
+    pub bundle_id: Option<String>,
+    pub name: Option<String>,
+    pub pid: i32,
+    pub window_title: Option<String>,
+}
+
+impl FrontmostApp {
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+    pub fn is_self(&self) -> bool {
+        self.pid as u32 == std::process::id()
+    }
+}
+
+/// Ask NSWorkspace for the frontmost application. Safe to call off the main thread.
+#[cfg(target_os = "macos")]
+pub fn frontmost_app() -> Option<FrontmostApp> {
+    unsafe {
+        let cls = AnyClass::get("NSWorkspace")?;
+        let workspace: *mut AnyObject = objc2::msg_send![cls, sharedWorkspace];
+        if workspace.is_null() {
+            return None;
+        }
+        let app: *mut AnyObject = objc2::msg_send![workspace, frontmostApplication];
+        if app.is_null() {
+            return None;
+        }
+        let pid: i32 = objc2::msg_send![app, processIdentifier];
+        let bundle_id: *mut AnyObject = objc2::msg_send![app, bundleIdentifier];
+        let name: *mut AnyObject = objc2::msg_send![app, localizedName];
+        Some(FrontmostApp {
+            bundle_id: nsstring_to_string(bundle_id),
+            name: nsstring_to_string(name),
+            pid,
+            window_title: crate::ax_insert::window_title(pid),
+        })
+    }
+}
+
+/// The owner of the foreground window.
+#[cfg(target_os = "windows")]
+pub fn frontmost_app() -> Option<FrontmostApp> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return None;
+        }
+        let mut title = [0u16; 512];
+        let len = GetWindowTextW(hwnd, &mut title);
+        let window_title = (len > 0).then(|| String::from_utf16_lossy(&title[..len as usize]));
+
+        let mut path = None;
+        if let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
+            let mut buffer = [0u16; 1024];
+            let mut len = buffer.len() as u32;
+            if QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut len).is_ok() {
+                path = Some(std::path::PathBuf::from(String::from_utf16_lossy(&buffer[..len as usize])));
+            }
+            let _ = CloseHandle(process);
+        }
+        Some(FrontmostApp {
+            bundle_id: path
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_lowercase()),
+            name: path.as_ref().and_then(|p| p.file_stem()).map(|n| n.to_string_lossy().into_owned()),
+            pid: pid as i32,
+            window_title,
+        })
+    }
+}
+
+/// Neither X11 nor Wayland offers a portable way to ask.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn frontmost_app() -> Option<FrontmostApp> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn nsstring_to_string(s: *mut AnyObject) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+    let utf8: *const u8 = objc2::msg_send![s, UTF8String];
+    if utf8.is_null() {
+        return None;
+    }
+    Some(std::ffi::CStr::from_ptr(utf8 as *const std::ffi::c_char).to_string_lossy().into_owned())
+}
+
+/// Something to hand keyboard focus back to after one of our windows took it: the app's
+/// pid on macOS, the window on Windows.
+#[cfg(target_os = "macos")]
+pub type FocusTarget = i32;
+#[cfg(target_os = "windows")]
+pub type FocusTarget = isize;
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub type FocusTarget = ();
+
+/// What has keyboard focus now, unless it is spaceduck itself.
+#[cfg(target_os = "macos")]
+pub fn focus_target() -> Option<FocusTarget> {
+    frontmost_app().filter(|a| !a.is_self()).map(|a| a.pid)
+}
+
+#[cfg(target_os = "windows")]
+pub fn focus_target() -> Option<FocusTarget> {
+    frontmost_app().filter(|a| !a.is_self())?;
+    let window = unsafe { windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow() };
+    (!window.is_invalid()).then_some(window.0 as isize)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn focus_target() -> Option<FocusTarget> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+pub fn refocus(pid: FocusTarget) {
+    /// NSApplicationActivateIgnoringOtherApps
+    const IGNORING_OTHER_APPS: usize = 1 << 1;
+    unsafe {
+        let Some(cls) = AnyClass::get("NSRunningApplication") else { return };
+        let app: *mut AnyObject = objc2::msg_send![cls, runningApplicationWithProcessIdentifier: pid];
+        if !app.is_null() {
+            let _: bool = objc2::msg_send![app, activateWithOptions: IGNORING_OTHER_APPS];
+        }
+    }
+}
+
+/// Windows only lets the foreground process do this, which we are while one of our
+/// windows is focused.
+#[cfg(target_os = "windows")]
+pub fn refocus(window: FocusTarget) {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+
+    let _ = unsafe { SetForegroundWindow(HWND(window as *mut std::ffi::c_void)) };
+}
+
+/// Window managers hand focus back to the previous window when ours goes away.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn refocus(_target: FocusTarget) {}
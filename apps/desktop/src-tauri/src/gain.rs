@@ -0,0 +1,176 @@
+use serde::Serialize;
+
+/// RMS the AGC steers speech towards (about -20 dBFS).
+const TARGET_RMS: f32 = 0.1;
+const MAX_GAIN: f32 = 20.0;
+/// Below this the block is treated as silence and the gain is left alone,
+/// so pauses do not get pumped up into hiss.
+const GATE_RMS: f32 = 0.002;
+/// Per-block smoothing: drop gain quickly on loud input, raise it slowly.
+const ATTACK: f32 = 0.5;
+const RELEASE: f32 = 0.05;
+
+const CLIP_LEVEL: f32 = 0.99;
+/// More than this share of clipped samples in a window counts as clipping.
+const CLIP_RATIO: f32 = 0.005;
+/// Input RMS this low for a full window means the mic is barely picking anything up.
+const QUIET_RMS: f32 = 0.003;
+const WINDOW_MS: u32 = 2000;
+
+/// Automatic gain control for quiet laptop mics. Works in blocks as they arrive from the device.
+pub struct Agc {
+    gain: f32,
+}
+
+impl Agc {
+    pub fn new() -> Self {
+        Self { gain: 1.0 }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        if rms > GATE_RMS {
+            let wanted = (TARGET_RMS / rms).clamp(1.0, MAX_GAIN);
+            let rate = if wanted < self.gain { ATTACK } else { RELEASE };
+            self.gain += (wanted - self.gain) * rate;
+        }
+        for s in samples.iter_mut() {
+            // Soft limit so the boosted signal never clips hard.
+            *s = (*s * self.gain).tanh();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SignalWarning {
+    Clipping,
+    TooQuiet,
+}
+
+impl SignalWarning {
+    pub fn message(self) -> &'static str {
+        match self {
+            SignalWarning::Clipping => "Your microphone is clipping. Lower its input level or move it further away",
+            SignalWarning::TooQuiet => "Your microphone is very quiet. Raise its input level or move closer",
+        }
+    }
+}
+
+/// Watches the raw input, before any gain, for clipping and near-silence.
+/// Each warning fires at most once per recording.
+pub struct SignalMonitor {
+    window_len: usize,
+    count: usize,
+    clipped: usize,
+    sum_squares: f32,
+    clipping_reported: bool,
+    quiet_reported: bool,
+}
+
+impl SignalMonitor {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            window_len: (sample_rate * WINDOW_MS / 1000) as usize,
+            count: 0,
+            clipped: 0,
+            sum_squares: 0.0,
+            clipping_reported: false,
+            quiet_reported: false,
+        }
+    }
+
+    pub fn push(&mut self, samples: &[f32]) -> Option<SignalWarning> {
+        let mut warning = None;
+        for &s in samples {
+            self.count += 1;
+            self.sum_squares += s * s;
+            if s.abs() >= CLIP_LEVEL {
+                self.clipped += 1;
+            }
+            if self.count < self.window_len {
+                continue;
+            }
+
+            let rms = (self.sum_squares / self.count as f32).sqrt();
+            if !self.clipping_reported && self.clipped as f32 / self.count as f32 > CLIP_RATIO {
+                self.clipping_reported = true;
+                warning = Some(SignalWarning::Clipping);
+            } else if !self.quiet_reported && rms < QUIET_RMS {
+                self.quiet_reported = true;
+                warning = Some(SignalWarning::TooQuiet);
+            }
+            self.count = 0;
+            self.clipped = 0;
+            self.sum_squares = 0.0;
+        }
+        warning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn tone(amplitude: f32) -> Vec<f32> {
+        (0..160).map(|i| amplitude * (i as f32 * 0.2).sin()).collect()
+    }
+
+    #[test]
+    fn raises_quiet_speech_towards_the_target() {
+        let mut agc = Agc::new();
+        let mut block = tone(0.01);
+        for _ in 0..200 {
+            block = tone(0.01);
+            agc.process(&mut block);
+        }
+        assert!((0.08..0.12).contains(&rms(&block)), "rms {}", rms(&block));
+        assert!(agc.gain <= MAX_GAIN);
+    }
+
+    #[test]
+    fn leaves_silence_and_loud_input_alone() {
+        let mut agc = Agc::new();
+        let mut silence = vec![0.0; 160];
+        agc.process(&mut silence);
+        assert_eq!(silence, vec![0.0; 160]);
+        assert_eq!(agc.gain, 1.0);
+
+        let mut loud = tone(0.9);
+        agc.process(&mut loud);
+        assert_eq!(agc.gain, 1.0);
+        assert!(loud.iter().all(|s| s.abs() < 1.0));
+    }
+
+    #[test]
+    fn drops_gain_quickly_when_the_input_gets_loud() {
+        let mut agc = Agc::new();
+        for _ in 0..200 {
+            agc.process(&mut tone(0.01));
+        }
+        let boosted = agc.gain;
+        agc.process(&mut tone(0.5));
+        agc.process(&mut tone(0.5));
+        assert!(agc.gain < boosted / 3.0, "gain {} from {boosted}", agc.gain);
+    }
+
+    #[test]
+    fn warns_about_clipping_and_quiet_input_once() {
+        let mut monitor = SignalMonitor::new(1000);
+        assert_eq!(monitor.push(&vec![1.0; 1999]), None);
+        assert_eq!(monitor.push(&[1.0]), Some(SignalWarning::Clipping));
+        assert_eq!(monitor.push(&vec![1.0; 2000]), None);
+
+        let mut monitor = SignalMonitor::new(1000);
+        assert_eq!(monitor.push(&vec![0.1; 2000]), None);
+        assert_eq!(monitor.push(&vec![0.001; 2000]), Some(SignalWarning::TooQuiet));
+        assert_eq!(monitor.push(&vec![0.001; 2000]), None);
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::audio::PIPELINE_RATE;
+use crate::transcription::{GatewayConfig, Transcript};
+
+/// How long a read may block before the thread goes back to sending audio.
+const READ_POLL: Duration = Duration::from_millis(20);
+/// After `stop`, how long to wait for the gateway's final transcript.
+const FINAL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Protocol of `/api/stt/stream`: one `start` message, binary frames of 16-bit
+/// little-endian mono PCM at `sampleRate`, then `stop`. The gateway answers with any
+/// number of `partial` messages and a single `final` or `error`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ClientMessage<'a> {
+    #[serde(rename_all = "camelCase")]
+    Start { sample_rate: u32, encoding: &'static str, language: Option<&'a str> },
+    Stop,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ServerMessage {
+    Partial { text: String },
+    Final { text: String, language: Option<String> },
+    Error { message: String },
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PartialPayload {
+    text: String,
+}
+
+enum Chunk {
+    Audio(Vec<f32>),
+    Stop,
+}
+
+/// A recording being streamed to the gateway as it is captured. Dropping it closes the
+/// socket without waiting for a result.
+pub struct GatewayStream {
+    chunks: Sender<Chunk>,
+    result: Receiver<Result<Transcript, String>>,
+}
+
+impl GatewayStream {
+    /// Connect on a background thread, so a slow gateway never stalls capture.
+    /// Audio sent before the socket opens is queued.
+    pub fn open(handle: &tauri::AppHandle, session: u64) -> Self {
+        let (gateway, language) = crate::config::read(handle, |c| (c.gateway.clone(), c.dictation_language.clone()));
+        let (chunks, chunk_rx) = mpsc::channel();
+        let (result_tx, result) = mpsc::channel();
+        let handle = handle.clone();
+        std::thread::spawn(move || {
+            let outcome = run(&handle, &gateway, language.as_deref(), &chunk_rx, session);
+            let _ = result_tx.send(outcome);
+        });
+        Self { chunks, result }
+    }
+
+    pub fn send(&self, samples: &[f32]) {
+        if !samples.is_empty() {
+            let _ = self.chunks.send(Chunk::Audio(samples.to_vec()));
+        }
+    }
+
+    /// End the stream and wait for the final transcript.
+    pub fn finish(self) -> Result<Transcript, String> {
+        let _ = self.chunks.send(Chunk::Stop);
+        self.result
+            .recv_timeout(FINAL_TIMEOUT + Duration::from_secs(5))
+            .map_err(|_| "Streaming transcription did not finish".to_string())?
+    }
+}
+
+/// `path` on the gateway as a WebSocket URL.
+fn ws_url(gateway: &GatewayConfig, path: &str) -> String {
+    let base = gateway.url.trim_end_matches('/');
+    let base = base
+        .strip_prefix("https://")
+        .map(|rest| format!("wss://{rest}"))
+        .or_else(|| base.strip_prefix("http://").map(|rest| format!("ws://{rest}")))
+        .unwrap_or_else(|| base.to_string());
+    format!("{base}{path}")
+}
+
+/// Open a WebSocket to `path` on the gateway, authenticated with its token. Reads give
+/// up after `read_timeout` so callers can interleave them with other work.
+pub fn connect(
+    gateway: &GatewayConfig,
+    path: &str,
+    read_timeout: Duration,
+) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, String> {
+    let url = ws_url(gateway, path);
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| format!("Invalid streaming URL {url}: {e}"))?;
+    if let Some(token) = &gateway.token {
+        let value = format!("Bearer {token}")
+            .parse()
+            .map_err(|_| "Gateway token is not a valid header value".to_string())?;
+        request.headers_mut().insert("Authorization", value);
+    }
+    let (socket, _) = tungstenite::connect(request).map_err(|e| format!("Failed to open {url}: {e}"))?;
+
+    let tcp = match socket.get_ref() {
+        MaybeTlsStream::Plain(tcp) => tcp,
+        MaybeTlsStream::Rustls(tls) => tls.get_ref(),
+        _ => return Err("Unsupported WebSocket transport".to_string()),
+    };
+    tcp.set_read_timeout(Some(read_timeout))
+        .map_err(|e| format!("Failed to configure socket: {e}"))?;
+    Ok(socket)
+}
+
+fn to_pcm(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|s| ((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes())
+        .collect()
+}
+
+fn send_json(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>, message: &ClientMessage) -> Result<(), String> {
+    let json = serde_json::to_string(message).map_err(|e| format!("Failed to encode message: {e}"))?;
+    socket.send(Message::text(json)).map_err(|e| format!("Streaming send failed: {e}"))
+}
+
+/// Pump audio out and transcripts in until the gateway sends its final result.
+fn run(
+    handle: &tauri::AppHandle,
+    gateway: &GatewayConfig,
+    language: Option<&str>,
+    chunks: &Receiver<Chunk>,
+    session: u64,
+) -> Result<Transcript, String> {
+    let mut socket = connect(gateway, "/api/stt/stream", READ_POLL)?;
+    send_json(&mut socket, &ClientMessage::Start { sample_rate: PIPELINE_RATE, encoding: "pcm_s16le", language })?;
+
+    let mut stop_deadline: Option<Instant> = None;
+    loop {
+        if stop_deadline.is_none() {
+            match chunks.recv_timeout(READ_POLL) {
+                Ok(Chunk::Audio(samples)) => {
+                    socket
+                        .send(Message::binary(to_pcm(&samples)))
+                        .map_err(|e| format!("Streaming send failed: {e}"))?;
+                }
+                Ok(Chunk::Stop) => {
+                    send_json(&mut socket, &ClientMessage::Stop)?;
+                    stop_deadline = Some(Instant::now() + FINAL_TIMEOUT);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                // Cancelled: the recording was dropped.
+                Err(RecvTimeoutError::Disconnected) => {
+                    let _ = socket.close(None);
+                    return Err("Streaming transcription cancelled".to_string());
+                }
+            }
+        }
+        if stop_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            let _ = socket.close(None);
+            return Err("Timed out waiting for the final transcript".to_string());
+        }
+
+        let message = match socket.read() {
+            Ok(message) => message,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+            {
+                continue;
+            }
+            Err(e) => return Err(format!("Streaming connection lost: {e}")),
+        };
+        let Message::Text(text) = message else { continue };
+        match serde_json::from_str::<ServerMessage>(text.as_str()) {
+            Ok(ServerMessage::Partial { text }) => {
+                if crate::audio::is_current_session(session) && !text.is_empty() {
+                    let text = crate::voice_commands::apply(handle, &text);
+                    let _ = handle.emit("dictation:partial", PartialPayload { text });
+                }
+            }
+            Ok(ServerMessage::Final { text, language }) => {
+                let _ = socket.close(None);
+                return Ok(Transcript { text, language, segments: Vec::new() });
+            }
+            Ok(ServerMessage::Error { message }) => {
+                let _ = socket.close(None);
+                return Err(message);
+            }
+            Err(e) => log::debug!("Ignoring streaming message: {e}"),
+        }
+    }
+}
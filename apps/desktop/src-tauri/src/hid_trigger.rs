@@ -0,0 +1,235 @@
+use hidapi::{HidApi, HidDevice};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::dictation::{self, DictationTarget, TriggerSource};
+
+const READ_TIMEOUT_MS: i32 = 250;
+const IDLE_POLL: Duration = Duration::from_millis(500);
+const REOPEN_DELAY: Duration = Duration::from_secs(2);
+const DEFAULT_LEARN_TIMEOUT_MS: u64 = 10_000;
+const REPORT_LEN: usize = 64;
+
+/// Set while `learn_hid_button` owns the device, so the listener lets go of it.
+static LEARNING: AtomicBool = AtomicBool::new(false);
+
+/// Which bit of an input report means "pressed".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HidButton {
+    pub byte: usize,
+    pub mask: u8,
+}
+
+/// A pedal or macro-pad button that acts as push-to-talk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HidTrigger {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    /// Picks one interface on composite devices; `None` opens the first match.
+    #[serde(default)]
+    pub usage_page: Option<u16>,
+    pub button: HidButton,
+    #[serde(default)]
+    pub target: DictationTarget,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HidDeviceInfo {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub usage_page: u16,
+    pub usage: u16,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HidStatus {
+    connected: bool,
+    vendor_id: u16,
+    product_id: u16,
+}
+
+fn open(api: &HidApi, vendor_id: u16, product_id: u16, usage_page: Option<u16>) -> Result<HidDevice, String> {
+    api.device_list()
+        .find(|d| {
+            d.vendor_id() == vendor_id
+                && d.product_id() == product_id
+                && usage_page.is_none_or(|page| d.usage_page() == page)
+        })
+        .ok_or_else(|| format!("HID device {vendor_id:04x}:{product_id:04x} not found"))?
+        .open_device(api)
+        .map_err(|e| format!("Failed to open HID device {vendor_id:04x}:{product_id:04x}: {e}"))
+}
+
+/// Watch the configured HID trigger on the current thread, reopening it when it is
+/// unplugged and plugged back in. Config changes take effect on the next read.
+/// This function blocks forever, so call it from a dedicated thread.
+pub fn start(handle: tauri::AppHandle) -> Result<(), String> {
+    let mut api = HidApi::new().map_err(|e| format!("Failed to initialise hidapi: {e}"))?;
+    let mut open_trigger: Option<(HidTrigger, HidDevice)> = None;
+    let mut pressed = false;
+
+    loop {
+        let wanted = crate::config::read(&handle, |c| c.dictation_hid_trigger.clone());
+
+        let stale = match (&open_trigger, &wanted) {
+            (Some((current, _)), Some(wanted)) => current != wanted,
+            (Some(_), None) => true,
+            _ => false,
+        };
+        if stale || LEARNING.load(Ordering::SeqCst) {
+            release(&handle, &mut pressed);
+            if let Some((trigger, _)) = open_trigger.take() {
+                emit_status(&handle, &trigger, false);
+            }
+        }
+
+        let Some(trigger) = wanted.filter(|_| !LEARNING.load(Ordering::SeqCst)) else {
+            std::thread::sleep(IDLE_POLL);
+            continue;
+        };
+
+        if open_trigger.is_none() {
+            let _ = api.refresh_devices();
+            match open(&api, trigger.vendor_id, trigger.product_id, trigger.usage_page) {
+                Ok(device) => {
+                    log::info!("HID trigger {:04x}:{:04x} connected", trigger.vendor_id, trigger.product_id);
+                    emit_status(&handle, &trigger, true);
+                    open_trigger = Some((trigger, device));
+                }
+                Err(e) => {
+                    log::debug!("{e}");
+                    std::thread::sleep(REOPEN_DELAY);
+                    continue;
+                }
+            }
+        }
+
+        let Some((trigger, device)) = &open_trigger else { continue };
+        let mut buf = [0u8; REPORT_LEN];
+        match device.read_timeout(&mut buf, READ_TIMEOUT_MS) {
+            Ok(0) => {}
+            Ok(n) => {
+                let down = buf[..n]
+                    .get(trigger.button.byte)
+                    .is_some_and(|b| b & trigger.button.mask != 0);
+                if down && !pressed {
+                    pressed = true;
+                    dictation::begin(&handle, TriggerSource::Hid, dictation::resolve_mode(&handle, trigger.target));
+                } else if !down {
+                    release(&handle, &mut pressed);
+                }
+            }
+            Err(e) => {
+                log::warn!("HID trigger disconnected: {e}");
+                release(&handle, &mut pressed);
+                emit_status(&handle, trigger, false);
+                open_trigger = None;
+            }
+        }
+    }
+}
+
+/// An unplugged pedal counts as released so the recording is not left running.
+fn release(handle: &tauri::AppHandle, pressed: &mut bool) {
+    if std::mem::take(pressed) {
+        dictation::end(handle, TriggerSource::Hid);
+    }
+}
+
+fn emit_status(handle: &tauri::AppHandle, trigger: &HidTrigger, connected: bool) {
+    let _ = handle.emit(
+        "hid-trigger-status",
+        HidStatus { connected, vendor_id: trigger.vendor_id, product_id: trigger.product_id },
+    );
+}
+
+/// Devices the user can bind. Keyboards and mice are left out; the key monitor covers those.
+#[tauri::command]
+pub fn list_hid_devices() -> Result<Vec<HidDeviceInfo>, String> {
+    let api = HidApi::new().map_err(|e| format!("Failed to initialise hidapi: {e}"))?;
+    let mut devices: Vec<HidDeviceInfo> = api
+        .device_list()
+        .filter(|d| !(d.usage_page() == 0x01 && matches!(d.usage(), 0x02 | 0x06)))
+        .map(|d| HidDeviceInfo {
+            vendor_id: d.vendor_id(),
+            product_id: d.product_id(),
+            usage_page: d.usage_page(),
+            usage: d.usage(),
+            manufacturer: d.manufacturer_string().map(str::to_string),
+            product: d.product_string().map(str::to_string),
+        })
+        .collect();
+    devices.dedup_by(|a, b| a.vendor_id == b.vendor_id && a.product_id == b.product_id && a.usage_page == b.usage_page);
+    Ok(devices)
+}
+
+/// Wait for a button on the given device and report which report bit it sets.
+/// Resolves to `None` if nothing is pressed before the timeout.
+#[tauri::command]
+pub async fn learn_hid_button(
+    vendor_id: u16,
+    product_id: u16,
+    usage_page: Option<u16>,
+    timeout_ms: Option<u64>,
+) -> Result<Option<HidButton>, String> {
+    if LEARNING.swap(true, Ordering::SeqCst) {
+        return Err("Already waiting for a HID button".to_string());
+    }
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_LEARN_TIMEOUT_MS));
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        // Give the listener a read cycle to close its handle first.
+        std::thread::sleep(Duration::from_millis(READ_TIMEOUT_MS as u64));
+        let api = HidApi::new().map_err(|e| format!("Failed to initialise hidapi: {e}"))?;
+        let device = open(&api, vendor_id, product_id, usage_page)?;
+
+        let deadline = Instant::now() + timeout;
+        let mut previous = [0u8; REPORT_LEN];
+        while Instant::now() < deadline {
+            let mut buf = [0u8; REPORT_LEN];
+            let n = device
+                .read_timeout(&mut buf, READ_TIMEOUT_MS)
+                .map_err(|e| format!("HID read failed: {e}"))?;
+            if n == 0 {
+                continue;
+            }
+            for (byte, (new, old)) in buf[..n].iter().zip(previous.iter()).enumerate() {
+                let pressed = new & !old;
+                if pressed != 0 {
+                    return Ok(Some(HidButton { byte, mask: 1 << pressed.trailing_zeros() }));
+                }
+            }
+            previous = buf;
+        }
+        Ok(None)
+    })
+    .await
+    .map_err(|e| format!("HID learn task failed: {e}"))
+    .and_then(|r| r);
+
+    LEARNING.store(false, Ordering::SeqCst);
+    result
+}
+
+#[tauri::command]
+pub fn get_dictation_hid_trigger(app: tauri::AppHandle) -> Option<HidTrigger> {
+    crate::config::read(&app, |c| c.dictation_hid_trigger.clone())
+}
+
+#[tauri::command]
+pub fn set_dictation_hid_trigger(app: tauri::AppHandle, trigger: Option<HidTrigger>) -> Result<(), String> {
+    if let Some(t) = &trigger {
+        if t.button.byte >= REPORT_LEN || t.button.mask == 0 {
+            return Err("Invalid HID button".to_string());
+        }
+    }
+    crate::config::update(&app, |c| c.dictation_hid_trigger = trigger)
+}
@@ -0,0 +1,460 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::dictation::TriggerKey;
+
+const DEFAULT_CAPTURE_TIMEOUT_MS: u64 = 5_000;
+const MAX_CAPTURE_TIMEOUT_MS: u64 = 30_000;
+
+/// Set while `capture_next_hotkey` is waiting so the trigger monitor ignores the keys being recorded.
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Modifier {
+    Control,
+    Option,
+    Shift,
+    Command,
+}
+
+/// A key or chord recorded from the keyboard, serializable for the settings UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hotkey {
+    /// Platform virtual key code (kVK_* on macOS). For a lone modifier this is the modifier's own code.
+    pub key_code: u16,
+    /// Layout-independent W3C `KeyboardEvent.code` name, e.g. `KeyK` or `F5`.
+    pub code: String,
+    pub modifiers: Vec<Modifier>,
+    /// Set when the hotkey is a lone modifier usable as a push-to-talk trigger.
+    pub trigger: Option<TriggerKey>,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictKind {
+    /// Claimed by the OS, e.g. a macOS symbolic hotkey like Spotlight.
+    SystemShortcut,
+    /// The OS refused a global registration, usually because another app owns it.
+    RegisteredElsewhere,
+}
+
+/// Best-effort warning that a chord will probably never reach spaceduck.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyWarning {
+    pub kind: ConflictKind,
+    pub message: String,
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_capturing() -> bool {
+    CAPTURING.load(Ordering::SeqCst)
+}
+
+/// Wait for the next key or chord and return it. Resolves to `None` on timeout or a bare Escape.
+#[tauri::command]
+pub async fn capture_next_hotkey(timeout_ms: Option<u64>) -> Result<Option<Hotkey>, String> {
+    let timeout = Duration::from_millis(
+        timeout_ms
+            .unwrap_or(DEFAULT_CAPTURE_TIMEOUT_MS)
+            .min(MAX_CAPTURE_TIMEOUT_MS),
+    );
+
+    if CAPTURING.swap(true, Ordering::SeqCst) {
+        return Err("A hotkey capture is already in progress".to_string());
+    }
+
+    let result = tauri::async_runtime::spawn_blocking(move || capture(timeout))
+        .await
+        .map_err(|e| format!("Hotkey capture task failed: {e}"))
+        .and_then(|r| r);
+
+    CAPTURING.store(false, Ordering::SeqCst);
+    result
+}
+
+/// Look for an existing owner of `hotkey`. Lone-modifier triggers never conflict.
+pub fn find_conflict(app: &tauri::AppHandle, hotkey: &Hotkey) -> Option<HotkeyWarning> {
+    if hotkey.trigger.is_some() || hotkey.modifiers.is_empty() {
+        return None;
+    }
+
+    if let Some(name) = system_shortcut_name(hotkey) {
+        return Some(HotkeyWarning {
+            kind: ConflictKind::SystemShortcut,
+            message: format!("{} is used by {SYSTEM} for \"{name}\"", hotkey.label),
+        });
+    }
+
+    probe_registration(app, hotkey)
+}
+
+/// What the OS is called in conflict warnings.
+#[cfg(target_os = "macos")]
+const SYSTEM: &str = "macOS";
+#[cfg(target_os = "windows")]
+const SYSTEM: &str = "Windows";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const SYSTEM: &str = "the desktop";
+
+/// Chords the OS handles itself. On macOS these never show up in the symbolic hotkeys plist.
+#[cfg(target_os = "macos")]
+const RESERVED: &[(&str, &[Modifier], &str)] = &[
+    ("Tab", &[Modifier::Command], "Switch applications"),
+    ("Backquote", &[Modifier::Command], "Cycle windows"),
+    ("Escape", &[Modifier::Option, Modifier::Command], "Force Quit"),
+];
+
+#[cfg(not(target_os = "macos"))]
+const RESERVED: &[(&str, &[Modifier], &str)] = &[
+    ("Tab", &[Modifier::Option], "Switch windows"),
+    ("F4", &[Modifier::Option], "Close window"),
+    ("Delete", &[Modifier::Control, Modifier::Option], "Security options"),
+    ("Escape", &[Modifier::Control, Modifier::Shift], "Task Manager"),
+    ("KeyL", &[Modifier::Command], "Lock screen"),
+    ("KeyD", &[Modifier::Command], "Show desktop"),
+];
+
+fn system_shortcut_name(hotkey: &Hotkey) -> Option<String> {
+    let same_modifiers = |modifiers: &[Modifier]| {
+        modifiers.len() == hotkey.modifiers.len() && modifiers.iter().all(|m| hotkey.modifiers.contains(m))
+    };
+    if let Some((_, _, name)) = RESERVED
+        .iter()
+        .find(|(code, modifiers, _)| *code == hotkey.code && same_modifiers(modifiers))
+    {
+        return Some(name.to_string());
+    }
+
+    #[cfg(target_os = "macos")]
+    return macos::symbolic_hotkey(hotkey);
+    #[cfg(not(target_os = "macos"))]
+    None
+}
+
+/// Briefly register the chord as a global shortcut; RegisterHotKey/RegisterEventHotKey
+/// fail when another process already holds it.
+fn probe_registration(app: &tauri::AppHandle, hotkey: &Hotkey) -> Option<HotkeyWarning> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+    let mut accelerator: Vec<&str> = hotkey
+        .modifiers
+        .iter()
+        .map(|m| match m {
+            Modifier::Control => "control",
+            Modifier::Option => "alt",
+            Modifier::Shift => "shift",
+            Modifier::Command => "super",
+        })
+        .collect();
+    accelerator.push(&hotkey.code);
+
+    let shortcut: Shortcut = accelerator.join("+").parse().ok()?;
+    let shortcuts = app.global_shortcut();
+    if shortcuts.is_registered(shortcut) {
+        return None;
+    }
+
+    match shortcuts.register(shortcut) {
+        Ok(()) => {
+            let _ = shortcuts.unregister(shortcut);
+            None
+        }
+        Err(e) => Some(HotkeyWarning {
+            kind: ConflictKind::RegisteredElsewhere,
+            message: format!("{} could not be registered ({e}); another app may be using it", hotkey.label),
+        }),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn capture(timeout: Duration) -> Result<Option<Hotkey>, String> {
+    crate::fn_key_monitor::capture_next_key(timeout)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn capture(_timeout: Duration) -> Result<Option<Hotkey>, String> {
+    Err("Hotkey capture is only supported on macOS".to_string())
+}
+
+#[cfg(target_os = "macos")]
+pub use macos::{from_key_down, from_lone_modifier, is_modifier_key_code, modifier_is_down};
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::{Hotkey, Modifier};
+    use crate::dictation::TriggerKey;
+    use core_graphics::event::CGEventFlags;
+
+    /// Build a chord from a KeyDown event and the modifiers held with it.
+    pub fn from_key_down(key_code: u16, flags: CGEventFlags) -> Hotkey {
+        let mut modifiers = Vec::new();
+        for (flag, modifier) in [
+            (CGEventFlags::CGEventFlagControl, Modifier::Control),
+            (CGEventFlags::CGEventFlagAlternate, Modifier::Option),
+            (CGEventFlags::CGEventFlagShift, Modifier::Shift),
+            (CGEventFlags::CGEventFlagCommand, Modifier::Command),
+        ] {
+            if flags.contains(flag) {
+                modifiers.push(modifier);
+            }
+        }
+
+        let (code, key_label) = key_info(key_code);
+        let mut label: String = modifiers.iter().copied().map(modifier_symbol).collect();
+        label.push_str(&key_label);
+
+        Hotkey { key_code, code: code.to_string(), modifiers, trigger: None, label }
+    }
+
+    /// Build a hotkey for a modifier that was pressed and released on its own.
+    pub fn from_lone_modifier(key_code: u16) -> Hotkey {
+        let trigger = match key_code {
+            63 => Some(TriggerKey::Fn),
+            54 => Some(TriggerKey::RightCommand),
+            61 => Some(TriggerKey::RightOption),
+            62 => Some(TriggerKey::RightControl),
+            60 => Some(TriggerKey::RightShift),
+            _ => None,
+        };
+        let (code, label) = key_info(key_code);
+        Hotkey { key_code, code: code.to_string(), modifiers: Vec::new(), trigger, label }
+    }
+
+    pub fn is_modifier_key_code(key_code: u16) -> bool {
+        (54..=63).contains(&key_code) && key_code != 57
+    }
+
+    /// Whether the modifier `key_code` is held according to the device-dependent bits in `flags`.
+    pub fn modifier_is_down(key_code: u16, flags: CGEventFlags) -> bool {
+        let device_mask = match key_code {
+            55 => 0x0000_0008,
+            54 => 0x0000_0010,
+            56 => 0x0000_0002,
+            60 => 0x0000_0004,
+            58 => 0x0000_0020,
+            61 => 0x0000_0040,
+            59 => 0x0000_0001,
+            62 => 0x0000_2000,
+            63 => return flags.contains(CGEventFlags::CGEventFlagSecondaryFn),
+            _ => return false,
+        };
+        flags.bits() & device_mask != 0
+    }
+
+    /// Match `hotkey` against the user's enabled entries in com.apple.symbolichotkeys.
+    pub fn symbolic_hotkey(hotkey: &Hotkey) -> Option<String> {
+        let wanted = mask(&hotkey.modifiers);
+        let home = std::env::var_os("HOME")?;
+        let path = std::path::Path::new(&home).join("Library/Preferences/com.apple.symbolichotkeys.plist");
+        let plist = plist::Value::from_file(path).ok()?;
+        let entries = plist.as_dictionary()?.get("AppleSymbolicHotKeys")?.as_dictionary()?;
+
+        entries.iter().find_map(|(id, entry)| {
+            let entry = entry.as_dictionary()?;
+            if !entry.get("enabled")?.as_boolean()? {
+                return None;
+            }
+            let params = entry.get("value")?.as_dictionary()?.get("parameters")?.as_array()?;
+            let key_code = params.get(1)?.as_signed_integer()?;
+            let flags = params.get(2)?.as_signed_integer()? as u64;
+            if key_code != hotkey.key_code as i64 || flags & MODIFIER_MASK != wanted {
+                return None;
+            }
+            Some(symbolic_hotkey_name(id))
+        })
+    }
+
+    /// NSEvent modifier flags for ⌃⌥⇧⌘, the same bits the plist stores.
+    pub(super) const MODIFIER_MASK: u64 = 0x0004_0000 | 0x0008_0000 | 0x0002_0000 | 0x0010_0000;
+
+    pub(super) fn mask(modifiers: &[Modifier]) -> u64 {
+        modifiers.iter().fold(0, |acc, m| {
+            acc | match m {
+                Modifier::Control => 0x0004_0000,
+                Modifier::Option => 0x0008_0000,
+                Modifier::Shift => 0x0002_0000,
+                Modifier::Command => 0x0010_0000,
+            }
+        })
+    }
+
+    fn symbolic_hotkey_name(id: &str) -> String {
+        let name = match id {
+            "7" => "Move focus to the menu bar",
+            "8" => "Move focus to the Dock",
+            "9" => "Move focus to active or next window",
+            "27" => "Move focus to next window",
+            "28" => "Save picture of screen as a file",
+            "29" => "Copy picture of screen to the clipboard",
+            "30" => "Save picture of selected area as a file",
+            "31" => "Copy picture of selected area to the clipboard",
+            "32" => "Mission Control",
+            "33" => "Application windows",
+            "36" => "Show Desktop",
+            "60" => "Select the previous input source",
+            "61" => "Select next source in Input menu",
+            "64" => "Show Spotlight search",
+            "65" => "Show Finder search window",
+            "79" | "80" => "Move left a space",
+            "81" | "82" => "Move right a space",
+            "118" => "Switch to Desktop 1",
+            "119" => "Switch to Desktop 2",
+            "120" => "Switch to Desktop 3",
+            "121" => "Switch to Desktop 4",
+            "160" => "Show Launchpad",
+            "184" => "Screenshot and recording options",
+            _ => return format!("system shortcut {id}"),
+        };
+        name.to_string()
+    }
+
+    fn modifier_symbol(modifier: Modifier) -> &'static str {
+        match modifier {
+            Modifier::Control => "⌃",
+            Modifier::Option => "⌥",
+            Modifier::Shift => "⇧",
+            Modifier::Command => "⌘",
+        }
+    }
+
+    /// W3C `KeyboardEvent.code` and display label for a kVK_* code, using the ANSI layout.
+    fn key_info(key_code: u16) -> (&'static str, String) {
+        let (code, label) = match key_code {
+            0 => ("KeyA", "A"),
+            1 => ("KeyS", "S"),
+            2 => ("KeyD", "D"),
+            3 => ("KeyF", "F"),
+            4 => ("KeyH", "H"),
+            5 => ("KeyG", "G"),
+            6 => ("KeyZ", "Z"),
+            7 => ("KeyX", "X"),
+            8 => ("KeyC", "C"),
+            9 => ("KeyV", "V"),
+            11 => ("KeyB", "B"),
+            12 => ("KeyQ", "Q"),
+            13 => ("KeyW", "W"),
+            14 => ("KeyE", "E"),
+            15 => ("KeyR", "R"),
+            16 => ("KeyY", "Y"),
+            17 => ("KeyT", "T"),
+            18 => ("Digit1", "1"),
+            19 => ("Digit2", "2"),
+            20 => ("Digit3", "3"),
+            21 => ("Digit4", "4"),
+            22 => ("Digit6", "6"),
+            23 => ("Digit5", "5"),
+            24 => ("Equal", "="),
+            25 => ("Digit9", "9"),
+            26 => ("Digit7", "7"),
+            27 => ("Minus", "-"),
+            28 => ("Digit8", "8"),
+            29 => ("Digit0", "0"),
+            30 => ("BracketRight", "]"),
+            31 => ("KeyO", "O"),
+            32 => ("KeyU", "U"),
+            33 => ("BracketLeft", "["),
+            34 => ("KeyI", "I"),
+            35 => ("KeyP", "P"),
+            37 => ("KeyL", "L"),
+            38 => ("KeyJ", "J"),
+            39 => ("Quote", "'"),
+            40 => ("KeyK", "K"),
+            41 => ("Semicolon", ";"),
+            42 => ("Backslash", "\\"),
+            43 => ("Comma", ","),
+            44 => ("Slash", "/"),
+            45 => ("KeyN", "N"),
+            46 => ("KeyM", "M"),
+            47 => ("Period", "."),
+            50 => ("Backquote", "`"),
+            36 => ("Enter", "Return"),
+            48 => ("Tab", "Tab"),
+            49 => ("Space", "Space"),
+            51 => ("Backspace", "Delete"),
+            53 => ("Escape", "Escape"),
+            117 => ("Delete", "Forward Delete"),
+            115 => ("Home", "Home"),
+            119 => ("End", "End"),
+            116 => ("PageUp", "Page Up"),
+            121 => ("PageDown", "Page Down"),
+            123 => ("ArrowLeft", "←"),
+            124 => ("ArrowRight", "→"),
+            125 => ("ArrowDown", "↓"),
+            126 => ("ArrowUp", "↑"),
+            122 => ("F1", "F1"),
+            120 => ("F2", "F2"),
+            99 => ("F3", "F3"),
+            118 => ("F4", "F4"),
+            96 => ("F5", "F5"),
+            97 => ("F6", "F6"),
+            98 => ("F7", "F7"),
+            100 => ("F8", "F8"),
+            101 => ("F9", "F9"),
+            109 => ("F10", "F10"),
+            103 => ("F11", "F11"),
+            111 => ("F12", "F12"),
+            105 => ("F13", "F13"),
+            107 => ("F14", "F14"),
+            113 => ("F15", "F15"),
+            106 => ("F16", "F16"),
+            64 => ("F17", "F17"),
+            79 => ("F18", "F18"),
+            80 => ("F19", "F19"),
+            90 => ("F20", "F20"),
+            55 => ("MetaLeft", "Left ⌘"),
+            54 => ("MetaRight", "Right ⌘"),
+            56 => ("ShiftLeft", "Left ⇧"),
+            60 => ("ShiftRight", "Right ⇧"),
+            58 => ("AltLeft", "Left ⌥"),
+            61 => ("AltRight", "Right ⌥"),
+            59 => ("ControlLeft", "Left ⌃"),
+            62 => ("ControlRight", "Right ⌃"),
+            63 => ("Fn", "fn"),
+            _ => return ("Unidentified", format!("Key {key_code}")),
+        };
+        (code, label.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(code: &str, modifiers: &[Modifier]) -> Hotkey {
+        Hotkey { key_code: 0, code: code.to_string(), modifiers: modifiers.to_vec(), trigger: None, label: String::new() }
+    }
+
+    #[test]
+    fn names_reserved_chords_in_any_modifier_order() {
+        #[cfg(target_os = "macos")]
+        let (code, modifiers, name) = ("Escape", [Modifier::Command, Modifier::Option], "Force Quit");
+        #[cfg(not(target_os = "macos"))]
+        let (code, modifiers, name) = ("Delete", [Modifier::Option, Modifier::Control], "Security options");
+        assert_eq!(system_shortcut_name(&chord(code, &modifiers)).as_deref(), Some(name));
+        // The same key with an extra modifier is a different chord.
+        let mut more = modifiers.to_vec();
+        more.push(Modifier::Shift);
+        assert_eq!(system_shortcut_name(&chord(code, &more)), None);
+    }
+
+    #[test]
+    fn leaves_free_chords_alone() {
+        let all = [Modifier::Control, Modifier::Option, Modifier::Shift, Modifier::Command];
+        assert_eq!(system_shortcut_name(&chord("F19", &all)), None);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn masks_modifiers_as_the_plist_stores_them() {
+        assert_eq!(macos::mask(&[]), 0);
+        assert_eq!(macos::mask(&[Modifier::Command]), 0x0010_0000);
+        assert_eq!(macos::mask(&[Modifier::Shift, Modifier::Control]), 0x0006_0000);
+        let all = [Modifier::Control, Modifier::Option, Modifier::Shift, Modifier::Command];
+        assert_eq!(macos::mask(&all), macos::MODIFIER_MASK);
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::Emitter;
+
+/// How often `system-idle` goes out.
+const POLL: Duration = Duration::from_secs(15);
+
+static AWAY: AtomicBool = AtomicBool::new(false);
+
+/// How long the user has been away from the keyboard and mouse.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemIdle {
+    /// `None` where the platform cannot say.
+    pub idle_seconds: Option<f64>,
+    /// Idle for at least `idle_away_after_secs`.
+    pub away: bool,
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    /// kCGEventSourceStateCombinedSessionState
+    const COMBINED_SESSION_STATE: i32 = 0;
+    /// kCGAnyInputEventType
+    const ANY_INPUT_EVENT: u32 = !0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+
+    pub fn idle_seconds() -> Option<f64> {
+        Some(unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT) })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    /// Both are tick counts that wrap after 49.7 days, so the difference is taken wrapping.
+    pub fn idle_seconds() -> Option<f64> {
+        let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+        unsafe { GetLastInputInfo(&mut info) }.as_bool().then(|| {
+            let now = unsafe { GetTickCount() };
+            now.wrapping_sub(info.dwTime) as f64 / 1000.0
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    /// There is no session-wide way to ask on Linux: X11 needs the screensaver extension
+    /// and each Wayland compositor has its own interface.
+    pub fn idle_seconds() -> Option<f64> {
+        None
+    }
+}
+
+/// Seconds since the last keyboard, mouse or trackpad input anywhere in the session.
+pub fn idle_seconds() -> Option<f64> {
+    platform::idle_seconds()
+}
+
+fn current(handle: &tauri::AppHandle) -> SystemIdle {
+    let idle_seconds = idle_seconds();
+    let away_after = crate::config::read(handle, |c| c.idle_away_after_secs);
+    let away = away_after > 0 && idle_seconds.is_some_and(|secs| secs >= away_after as f64);
+    SystemIdle { idle_seconds, away }
+}
+
+/// Emit `system-idle` every `POLL`, so the UI and background jobs can tell when the user
+/// has stepped away.
+pub fn start(handle: &tauri::AppHandle) {
+    if idle_seconds().is_none() {
+        log::info!("Idle time is not available on this platform");
+        return;
+    }
+    let handle = handle.clone();
+    std::thread::spawn(move || loop {
+        let idle = current(&handle);
+        if AWAY.swap(idle.away, Ordering::SeqCst) != idle.away {
+            log::info!("User {}", if idle.away { "is away" } else { "is back" });
+        }
+        let _ = handle.emit("system-idle", idle);
+        std::thread::sleep(POLL);
+    });
+}
+
+#[tauri::command]
+pub fn get_system_idle(app: tauri::AppHandle) -> SystemIdle {
+    current(&app)
+}
+
+/// Set how many seconds without input count as away; 0 never does.
+#[tauri::command]
+pub fn set_idle_away_after(app: tauri::AppHandle, seconds: u32) -> Result<(), String> {
+    crate::config::update(&app, |c| c.idle_away_after_secs = seconds)
+}
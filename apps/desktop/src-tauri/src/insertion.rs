@@ -0,0 +1,409 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub mod blocked;
+pub mod chunking;
+pub mod undo;
+mod verify;
+
+/// How dictated text gets into the focused app.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InsertionMethod {
+    /// Put the text on the clipboard and send Cmd/Ctrl+V.
+    #[default]
+    Paste,
+    /// Write it into the focused control through the Accessibility API (macOS) or UI
+    /// Automation (Windows), pasting instead where the control does not support that.
+    Accessibility,
+    /// Send each character as a keystroke, for terminals, VMs and remote desktops that
+    /// ignore a synthetic paste.
+    Type,
+}
+
+/// What follows the dictation once it is inserted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Terminator {
+    #[default]
+    None,
+    Space,
+    Newline,
+    /// Press Return after inserting, which sends the message in most chat apps and runs
+    /// the command in a terminal.
+    Enter,
+}
+
+impl Terminator {
+    fn suffix(self) -> &'static str {
+        match self {
+            Terminator::Space => " ",
+            Terminator::Newline => "\n",
+            Terminator::None | Terminator::Enter => "",
+        }
+    }
+}
+
+/// Always use `method` when `app` is focused. `app` is a bundle id on macOS and an
+/// executable name such as `mstsc.exe` on Windows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInsertion {
+    pub app: String,
+    pub method: InsertionMethod,
+    /// Overrides `InsertionConfig::terminator` for this app.
+    #[serde(default)]
+    pub terminator: Option<Terminator>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InsertionConfig {
+    pub method: InsertionMethod,
+    pub terminator: Terminator,
+    pub per_app: Vec<AppInsertion>,
+    /// Pause between typed characters; some remote sessions drop keys sent faster.
+    pub typing_delay_ms: u32,
+    /// Type the text when pasting fails.
+    pub type_on_paste_failure: bool,
+    /// Adjust spacing and capitalisation to the text around the caret, where it can be read.
+    pub smart_spacing: bool,
+    /// Insert text longer than this many characters in pieces, `chunk_delay_ms` apart,
+    /// so long answers do not overwhelm the target. 0 inserts everything at once.
+    pub chunk_chars: u32,
+    pub chunk_delay_ms: u32,
+}
+
+impl Default for InsertionConfig {
+    fn default() -> Self {
+        Self {
+            method: InsertionMethod::Paste,
+            terminator: Terminator::None,
+            per_app: Vec::new(),
+            typing_delay_ms: 5,
+            type_on_paste_failure: true,
+            smart_spacing: true,
+            chunk_chars: 2000,
+            chunk_delay_ms: 150,
+        }
+    }
+}
+
+const MAX_TYPING_DELAY_MS: u32 = 200;
+const MAX_CHUNK_DELAY_MS: u32 = 5000;
+/// Smaller chunks mostly add delays; the point is to keep each paste manageable.
+const MIN_CHUNK_CHARS: u32 = 100;
+
+/// Set while text is going in, so key monitors can tell our keystrokes from the user's.
+static INSERTING: AtomicBool = AtomicBool::new(false);
+
+/// What happened to a dictation handed to `insert`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum InsertOutcome {
+    /// Inserted, or at least sent to a control that cannot be read back.
+    Pasted,
+    /// The paste went out but the focused control did not change. The text is still
+    /// on the clipboard for a manual paste.
+    CopiedOnly,
+    Failed { reason: String },
+}
+
+/// How `insert_with` delivered the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    /// Written through the accessibility APIs, which check the result themselves.
+    Direct,
+    Pasted,
+    Typed,
+}
+
+#[cfg(target_os = "macos")]
+fn insert_direct(text: &str) -> Result<bool, String> {
+    crate::ax_insert::insert(text)
+}
+
+#[cfg(target_os = "windows")]
+fn insert_direct(text: &str) -> Result<bool, String> {
+    crate::uia_insert::insert(text)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn insert_direct(_text: &str) -> Result<bool, String> {
+    Ok(false)
+}
+
+/// How much of the surrounding text `smart_space` looks at.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const CONTEXT_CHARS: i32 = 3;
+
+#[cfg(target_os = "macos")]
+fn caret_context() -> Option<(String, String)> {
+    crate::ax_insert::caret_context(CONTEXT_CHARS as isize)
+}
+
+#[cfg(target_os = "windows")]
+fn caret_context() -> Option<(String, String)> {
+    crate::uia_insert::caret_context(CONTEXT_CHARS)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn caret_context() -> Option<(String, String)> {
+    None
+}
+
+/// Fit `text` between `before` and `after`, the text on either side of the caret: a space
+/// after a preceding word but never a doubled one, and a capital at the start of a sentence.
+fn smart_space(before: &str, after: &str, text: &str) -> String {
+    let mut text = text.to_string();
+    match before.chars().last() {
+        Some(c) if c.is_whitespace() => text = text.trim_start_matches(' ').to_string(),
+        Some(c) if !"([{\"'“‘/-".contains(c) && text.starts_with(char::is_alphanumeric) => text.insert(0, ' '),
+        _ => {}
+    }
+    let previous = before.trim_end();
+    if previous.is_empty() || previous.ends_with(['.', '!', '?']) {
+        if let Some((i, c)) = text.char_indices().find(|(_, c)| !c.is_whitespace()) {
+            let upper: String = c.to_uppercase().collect();
+            text.replace_range(i..i + c.len_utf8(), &upper);
+        }
+    }
+    if after.starts_with(|c: char| c.is_whitespace() || ".,;:!?)]}".contains(c)) {
+        text.truncate(text.trim_end_matches(' ').len());
+    }
+    text
+}
+
+#[cfg(target_os = "macos")]
+fn backspaces(count: usize) -> Result<(), String> {
+    use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| format!("Failed to create enigo instance: {e}"))?;
+    for _ in 0..count {
+        enigo.key(Key::Backspace, Direction::Click).map_err(|e| format!("Failed to press Backspace: {e}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn backspaces(count: usize) -> Result<(), String> {
+    crate::win_inject::backspaces(count)
+}
+
+#[cfg(target_os = "linux")]
+fn backspaces(count: usize) -> Result<(), String> {
+    crate::linux_inject::backspaces(count)
+}
+
+/// Identifier of the focused app as used by `AppInsertion::app`.
+fn focused_app() -> Option<String> {
+    crate::frontmost::frontmost_app()?.bundle_id
+}
+
+#[cfg(target_os = "macos")]
+fn press_enter() -> Result<(), String> {
+    use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| format!("Failed to create enigo instance: {e}"))?;
+    enigo.key(Key::Return, Direction::Click).map_err(|e| format!("Failed to press Return: {e}"))
+}
+
+#[cfg(target_os = "windows")]
+fn press_enter() -> Result<(), String> {
+    crate::win_inject::type_text("\n", 0)
+}
+
+#[cfg(target_os = "linux")]
+fn press_enter() -> Result<(), String> {
+    crate::linux_inject::press_enter()
+}
+
+#[cfg(target_os = "windows")]
+fn type_text(text: &str, delay_ms: u32) -> Result<(), String> {
+    crate::win_inject::type_text(text, delay_ms)
+}
+
+#[cfg(target_os = "linux")]
+fn type_text(text: &str, delay_ms: u32) -> Result<(), String> {
+    crate::linux_inject::type_text(text, delay_ms)
+}
+
+/// Send `text` as unicode keystrokes, `delay_ms` apart.
+#[cfg(target_os = "macos")]
+fn type_text(text: &str, delay_ms: u32) -> Result<(), String> {
+    use enigo::{Enigo, Keyboard, Settings};
+
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| format!("Failed to create enigo instance: {e}"))?;
+    if delay_ms == 0 {
+        return enigo.text(text).map_err(|e| format!("Typing failed: {e}"));
+    }
+    let delay = std::time::Duration::from_millis(delay_ms as u64);
+    let mut buffer = [0u8; 4];
+    for c in text.chars() {
+        enigo.text(c.encode_utf8(&mut buffer)).map_err(|e| format!("Typing failed: {e}"))?;
+        std::thread::sleep(delay);
+    }
+    Ok(())
+}
+
+/// A CJK input method mid-composition would mix the insertion into its buffer. Switching
+/// to the ASCII layout commits the composition on macOS, so that is done for the duration
+/// of the insertion.
+#[cfg(target_os = "macos")]
+fn pause_input_method() -> Option<crate::keyboard_layout::InputMethodPause> {
+    crate::keyboard_layout::pause_input_method()
+}
+
+/// Windows waits for compositions in `blocked_reason` instead, and Linux cannot see them.
+#[cfg(not(target_os = "macos"))]
+fn pause_input_method() -> Option<()> {
+    None
+}
+
+/// Put `text` into the focused app using the method configured for it, then check that
+/// it arrived where the focused control can be read back. Refuses, emitting
+/// `insertion-blocked` so the text can still be copied, when the focus is a password
+/// field or the OS would silently drop the input.
+pub fn insert(handle: &tauri::AppHandle, text: &str) -> InsertOutcome {
+    INSERTING.store(true, Ordering::SeqCst);
+    let outcome = insert_inner(handle, text);
+    INSERTING.store(false, Ordering::SeqCst);
+    outcome
+}
+
+/// Whether `insert` or `delete_typed` is sending keystrokes right now.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn is_inserting() -> bool {
+    INSERTING.load(Ordering::SeqCst)
+}
+
+/// Remove the `count` characters just typed before the caret, as snippet expansion does
+/// with its trigger.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn delete_typed(count: usize) -> Result<(), String> {
+    INSERTING.store(true, Ordering::SeqCst);
+    let result = backspaces(count);
+    INSERTING.store(false, Ordering::SeqCst);
+    result
+}
+
+fn insert_inner(handle: &tauri::AppHandle, text: &str) -> InsertOutcome {
+    let config = crate::config::read(handle, |c| c.insertion.clone());
+    let app = focused_app();
+    if let Some(reason) = blocked::blocked_reason() {
+        return blocked::refuse(handle, reason, app, text);
+    }
+    // Held until the insertion, including any Enter, is done.
+    let _input_method = pause_input_method();
+    let rule = app.as_deref().and_then(|app| config.per_app.iter().find(|o| o.app.eq_ignore_ascii_case(app)));
+    let method = rule.map_or(config.method, |o| o.method);
+    let terminator = rule.and_then(|o| o.terminator).unwrap_or(config.terminator);
+
+    let mut text = format!("{text}{}", terminator.suffix());
+    if config.smart_spacing {
+        if let Some((before, after)) = caret_context() {
+            text = smart_space(&before, &after, &text);
+        }
+    }
+    let before = verify::field_state();
+    let pieces = chunking::chunks(&text, config.chunk_chars as usize);
+    let chunked = pieces.len() > 1;
+    let result = if chunked {
+        chunking::insert_chunked(handle, &config, method, &pieces, &app)
+    } else {
+        insert_with(handle, &config, method, &text)
+    };
+    let delivery = match result {
+        Ok(delivery) => delivery,
+        Err(reason) => return InsertOutcome::Failed { reason },
+    };
+    // Only the last chunk is left on the clipboard, so a failed check could not offer the rest.
+    if !chunked && delivery != Delivery::Direct && verify::verify(before, &text) == Some(false) {
+        log::warn!("Focused control did not change after inserting the dictation");
+        return match delivery {
+            // The clipboard write succeeded, so the text is still there.
+            Delivery::Pasted => InsertOutcome::CopiedOnly,
+            _ => InsertOutcome::Failed { reason: "The focused app ignored the typed text".to_string() },
+        };
+    }
+    let submitted = terminator == Terminator::Enter;
+    undo::remember(text, app, submitted);
+    if submitted {
+        // Give the target a moment to take the text before submitting it.
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        if let Err(reason) = press_enter() {
+            return InsertOutcome::Failed { reason };
+        }
+    }
+    InsertOutcome::Pasted
+}
+
+fn insert_with(handle: &tauri::AppHandle, config: &InsertionConfig, method: InsertionMethod, text: &str) -> Result<Delivery, String> {
+    match method {
+        InsertionMethod::Type => return type_text(text, config.typing_delay_ms).map(|_| Delivery::Typed),
+        InsertionMethod::Accessibility => match insert_direct(text) {
+            Ok(true) => return Ok(Delivery::Direct),
+            Ok(false) => log::debug!("Focused control does not accept direct insertion, pasting instead"),
+            Err(e) => log::warn!("Direct insertion failed, pasting instead: {e}"),
+        },
+        InsertionMethod::Paste => {}
+    }
+    match crate::clipboard_paste(handle, text) {
+        Ok(()) => Ok(Delivery::Pasted),
+        Err(e) if config.type_on_paste_failure => {
+            log::warn!("{e}; typing the text instead");
+            type_text(text, config.typing_delay_ms).map(|_| Delivery::Typed)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+pub fn get_insertion_config(app: tauri::AppHandle) -> InsertionConfig {
+    crate::config::read(&app, |c| c.insertion.clone())
+}
+
+#[tauri::command]
+pub fn set_insertion_config(app: tauri::AppHandle, config: InsertionConfig) -> Result<(), String> {
+    if config.typing_delay_ms > MAX_TYPING_DELAY_MS {
+        return Err(format!("Typing delay must be at most {MAX_TYPING_DELAY_MS}ms"));
+    }
+    if config.chunk_delay_ms > MAX_CHUNK_DELAY_MS {
+        return Err(format!("Chunk delay must be at most {MAX_CHUNK_DELAY_MS}ms"));
+    }
+    if config.chunk_chars != 0 && config.chunk_chars < MIN_CHUNK_CHARS {
+        return Err(format!("Chunks must be at least {MIN_CHUNK_CHARS} characters, or 0 to turn chunking off"));
+    }
+    if config.per_app.iter().any(|o| o.app.trim().is_empty()) {
+        return Err("Per-app insertion rules need an app".to_string());
+    }
+    crate::config::update(&app, |c| c.insertion = config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spaces_after_a_word_but_not_after_whitespace() {
+        assert_eq!(smart_space("Hello", "", "world"), " world");
+        assert_eq!(smart_space("Hello ", "", " world"), "world");
+        assert_eq!(smart_space("(", "", "aside"), "aside");
+        assert_eq!(smart_space("Hello", "", ", world"), ", world");
+    }
+
+    #[test]
+    fn capitalises_the_start_of_a_sentence() {
+        assert_eq!(smart_space("", "", "hello"), "Hello");
+        assert_eq!(smart_space("Done.", "", "next"), " Next");
+        assert_eq!(smart_space("Really? ", "", "élan"), "Élan");
+        assert_eq!(smart_space("one,", "", "two"), " two");
+    }
+
+    #[test]
+    fn drops_trailing_spaces_before_punctuation_or_a_space() {
+        assert_eq!(smart_space("Hi", ".", "there "), " there");
+        assert_eq!(smart_space("Hi", " again", "there "), " there");
+        assert_eq!(smart_space("Hi", "again", "there "), " there ");
+    }
+}
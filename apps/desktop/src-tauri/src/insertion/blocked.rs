@@ -0,0 +1,107 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+use super::InsertOutcome;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockedPayload {
+    reason: BlockedReason,
+    app: Option<String>,
+    text: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) enum BlockedReason {
+    /// The target runs as administrator and we do not, so Windows discards our input.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    ElevatedTarget,
+    /// macOS Secure Event Input is on, so synthetic keystrokes are discarded.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    SecureInput,
+    /// The focused control is a password field.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+    PasswordField,
+    /// An input method kept composing text for longer than we waited for it.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Composing,
+}
+
+/// How long a Windows insertion waits for an input method composition to finish.
+#[cfg(target_os = "windows")]
+const COMPOSITION_WAIT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Dictation that was not inserted because of a `BlockedReason`, for `copy_blocked_insertion`.
+static BLOCKED_TEXT: Mutex<Option<String>> = Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+pub(super) fn blocked_reason() -> Option<BlockedReason> {
+    if crate::ax_insert::focused_is_secure() {
+        Some(BlockedReason::PasswordField)
+    } else if crate::ax_insert::secure_input_enabled() {
+        Some(BlockedReason::SecureInput)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(super) fn blocked_reason() -> Option<BlockedReason> {
+    if crate::win_inject::target_is_elevated() {
+        Some(BlockedReason::ElevatedTarget)
+    } else if crate::uia_insert::focused_is_password() {
+        Some(BlockedReason::PasswordField)
+    } else if !composition_ended() {
+        Some(BlockedReason::Composing)
+    } else {
+        None
+    }
+}
+
+/// Pasting into a CJK input method's composition corrupts it, and another process's
+/// composition cannot be committed from here, so wait for the user to finish it.
+#[cfg(target_os = "windows")]
+fn composition_ended() -> bool {
+    let deadline = std::time::Instant::now() + COMPOSITION_WAIT;
+    while crate::uia_insert::focused_is_composing() {
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    true
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(super) fn blocked_reason() -> Option<BlockedReason> {
+    None
+}
+
+/// Keep `text` for `copy_blocked_insertion`, tell the UI with `insertion-blocked` and
+/// say why it was not inserted.
+pub(super) fn refuse(handle: &tauri::AppHandle, reason: BlockedReason, app: Option<String>, text: &str) -> InsertOutcome {
+    use tauri::Emitter;
+
+    *BLOCKED_TEXT.lock().unwrap_or_else(|e| e.into_inner()) = Some(text.to_string());
+    let _ = handle.emit("insertion-blocked", BlockedPayload { reason, app: app.clone(), text: text.to_string() });
+    let target = app.as_deref().unwrap_or("The focused app");
+    let reason = match reason {
+        BlockedReason::ElevatedTarget => {
+            format!("{target} is running as administrator, so text cannot be inserted into it")
+        }
+        BlockedReason::SecureInput => format!("{target} has secure keyboard entry on, so text cannot be inserted"),
+        BlockedReason::PasswordField => "Dictation is not inserted into password fields".to_string(),
+        BlockedReason::Composing => "An input method is still composing text, so the dictation was not inserted".to_string(),
+    };
+    InsertOutcome::Failed { reason }
+}
+
+/// Copy the last dictation refused by `insert` to the clipboard. Returns whether there was one.
+#[tauri::command]
+pub fn copy_blocked_insertion(app: tauri::AppHandle) -> Result<bool, String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    let Some(text) = BLOCKED_TEXT.lock().unwrap_or_else(|e| e.into_inner()).take() else { return Ok(false) };
+    app.clipboard().write_text(text).map_err(|e| format!("Clipboard write failed: {e}"))?;
+    Ok(true)
+}
@@ -0,0 +1,124 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::{insert_with, Delivery, InsertionConfig, InsertionMethod};
+
+/// Set while a chunked insertion runs; cleared to stop it after the current chunk.
+static CHUNKING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProgressPayload {
+    inserted: usize,
+    total: usize,
+}
+
+/// Split `text` into pieces of at most `size` characters, breaking after whitespace
+/// where that does not leave a piece under half the size, both counted in characters.
+pub(super) fn chunks(text: &str, size: usize) -> Vec<&str> {
+    if size == 0 {
+        return vec![text];
+    }
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while let Some((end, _)) = rest.char_indices().nth(size) {
+        let cut = rest[..end]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .filter(|&(i, _)| rest[..i].chars().count() + 1 > size / 2)
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(end);
+        pieces.push(&rest[..cut]);
+        rest = &rest[cut..];
+    }
+    pieces.push(rest);
+    pieces
+}
+
+/// Insert `pieces` one after another, emitting `insertion-progress` after each. Stops
+/// early when `cancel_insertion` is called; what went in by then can still be undone.
+pub(super) fn insert_chunked(
+    handle: &tauri::AppHandle,
+    config: &InsertionConfig,
+    method: InsertionMethod,
+    pieces: &[&str],
+    app: &Option<String>,
+) -> Result<Delivery, String> {
+    use tauri::Emitter;
+
+    let total = pieces.iter().map(|p| p.chars().count()).sum();
+    let mut inserted = 0;
+    let mut delivery = Delivery::Direct;
+    CHUNKING.store(true, Ordering::SeqCst);
+    let mut result = Ok(());
+    for (n, piece) in pieces.iter().enumerate() {
+        if n > 0 {
+            std::thread::sleep(std::time::Duration::from_millis(config.chunk_delay_ms as u64));
+        }
+        if !CHUNKING.load(Ordering::SeqCst) {
+            result = Err(format!("Insertion cancelled after {inserted} of {total} characters"));
+            break;
+        }
+        match insert_with(handle, config, method, piece) {
+            Ok(d) => delivery = d,
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+        inserted += piece.chars().count();
+        let _ = handle.emit("insertion-progress", ProgressPayload { inserted, total });
+    }
+    CHUNKING.store(false, Ordering::SeqCst);
+    if let Err(e) = result {
+        if inserted > 0 {
+            let text: String = pieces.concat().chars().take(inserted).collect();
+            super::undo::remember(text, app.clone(), false);
+        }
+        return Err(e);
+    }
+    Ok(delivery)
+}
+
+/// Stop a chunked insertion after the chunk being inserted. Returns whether one was running.
+#[tauri::command]
+pub fn cancel_insertion() -> bool {
+    CHUNKING.swap(false, Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_text_whole_when_it_fits_or_size_is_zero() {
+        assert_eq!(chunks("short text", 100), vec!["short text"]);
+        assert_eq!(chunks("anything", 0), vec!["anything"]);
+    }
+
+    #[test]
+    fn breaks_after_whitespace() {
+        assert_eq!(chunks("aaaa bbbb cccc", 8), vec!["aaaa ", "bbbb ", "cccc"]);
+    }
+
+    #[test]
+    fn cuts_mid_word_rather_than_leave_a_tiny_piece() {
+        assert_eq!(chunks("a bcdefghij", 6), vec!["a bcde", "fghij"]);
+    }
+
+    #[test]
+    fn counts_characters_and_never_splits_one() {
+        let pieces = chunks("ééééé ñññññ 日本語日本語", 4);
+        assert_eq!(pieces.concat(), "ééééé ñññññ 日本語日本語");
+        assert!(pieces.iter().all(|piece| piece.chars().count() <= 4));
+        assert_eq!(pieces[0], "éééé");
+    }
+
+    #[test]
+    fn weighs_the_break_in_characters_not_bytes() {
+        // Three characters of eight is too small a piece, however many bytes they take.
+        assert_eq!(chunks("日本 abcdefgh", 8), vec!["日本 abcde", "fgh"]);
+        assert_eq!(chunks("日本語 abcdef", 6), vec!["日本語 ", "abcdef"]);
+    }
+}
@@ -0,0 +1,64 @@
+use std::sync::Mutex;
+
+use super::{backspaces, focused_app};
+
+/// The last dictation `insert` put into an app, for `undo_last_paste`.
+struct LastInsertion {
+    text: String,
+    app: Option<String>,
+    /// Return was pressed after it, so it has most likely been sent.
+    submitted: bool,
+}
+
+static LAST_INSERTION: Mutex<Option<LastInsertion>> = Mutex::new(None);
+
+/// Remember `text` as what `undo_last_paste` takes back out of `app`.
+pub(super) fn remember(text: String, app: Option<String>, submitted: bool) {
+    *LAST_INSERTION.lock().unwrap_or_else(|e| e.into_inner()) = Some(LastInsertion { text, app, submitted });
+}
+
+#[cfg(target_os = "macos")]
+fn delete_direct(text: &str) -> Result<bool, String> {
+    crate::ax_insert::delete_before_caret(text)
+}
+
+#[cfg(target_os = "windows")]
+fn delete_direct(text: &str) -> Result<bool, String> {
+    crate::uia_insert::delete_before_caret(text)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn delete_direct(_text: &str) -> Result<bool, String> {
+    Ok(false)
+}
+
+/// Remove the last dictation from the app it went into, which has to be focused again.
+/// Where the focused control can be read, only text still matching the dictation right
+/// before the caret is deleted; elsewhere one Backspace per character is sent. Returns
+/// whether there was anything to undo.
+#[tauri::command]
+pub fn undo_last_paste() -> Result<bool, String> {
+    let mut last = LAST_INSERTION.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(insertion) = last.as_ref() else { return Ok(false) };
+    if insertion.submitted {
+        return Err("The dictation was already sent with Return".to_string());
+    }
+    if focused_app() != insertion.app {
+        return Err(format!(
+            "Focus {} again to undo the dictation",
+            insertion.app.as_deref().unwrap_or("the app it went into")
+        ));
+    }
+    let deleted = match delete_direct(&insertion.text) {
+        Ok(deleted) => deleted,
+        Err(e) => {
+            log::warn!("Direct deletion failed, using Backspace instead: {e}");
+            false
+        }
+    };
+    if !deleted {
+        backspaces(insertion.text.chars().count())?;
+    }
+    *last = None;
+    Ok(true)
+}
@@ -0,0 +1,71 @@
+/// How long apps get to apply a paste before it is checked.
+const VERIFY_ATTEMPTS: u32 = 3;
+const VERIFY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+#[cfg(target_os = "macos")]
+pub(super) type FieldState = (i64, i64);
+
+/// The focused control's content, or enough of it to tell afterwards whether an insertion changed it.
+#[cfg(target_os = "macos")]
+pub(super) fn field_state() -> Option<FieldState> {
+    crate::ax_insert::focused_text_state()
+}
+
+/// Whether `text` went into the control described by `before`: `None` when the change
+/// cannot be attributed either way, e.g. the app reformatted what was pasted.
+#[cfg(target_os = "macos")]
+fn landed(before: &FieldState, text: &str) -> Option<bool> {
+    let (count, _) = crate::ax_insert::focused_text_state()?;
+    let expected = before.0 - before.1 + text.encode_utf16().count() as i64;
+    if count == expected {
+        Some(true)
+    } else {
+        (count == before.0).then_some(false)
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(super) type FieldState = String;
+
+#[cfg(target_os = "windows")]
+pub(super) fn field_state() -> Option<FieldState> {
+    crate::uia_insert::focused_value()
+}
+
+#[cfg(target_os = "windows")]
+fn landed(before: &FieldState, text: &str) -> Option<bool> {
+    let after = crate::uia_insert::focused_value()?;
+    if after.len() > before.len() && after.contains(text.trim()) {
+        Some(true)
+    } else {
+        (after == *before).then_some(false)
+    }
+}
+
+/// Nothing on Linux can read the focused control back.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(super) struct FieldState;
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(super) fn field_state() -> Option<FieldState> {
+    None
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn landed(_before: &FieldState, _text: &str) -> Option<bool> {
+    None
+}
+
+/// Poll until the insertion shows up; `Some(false)` only when the control never changed.
+pub(super) fn verify(before: Option<FieldState>, text: &str) -> Option<bool> {
+    let before = before?;
+    let mut result = None;
+    for _ in 0..VERIFY_ATTEMPTS {
+        std::thread::sleep(VERIFY_INTERVAL);
+        result = landed(&before, text);
+        if result == Some(true) {
+            break;
+        }
+    }
+    result
+}
@@ -0,0 +1,185 @@
+/// Identifier of the active keyboard layout: the input source id on macOS, such as
+/// `com.apple.keylayout.Dvorak`, and the input locale handle on Windows, such as
+/// `040C040C` for French.
+#[tauri::command]
+pub fn get_keyboard_layout() -> Option<String> {
+    current()
+}
+
+#[cfg(target_os = "macos")]
+pub fn current() -> Option<String> {
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+
+    unsafe {
+        let source = mac::Source(mac::TISCopyCurrentKeyboardInputSource());
+        if source.0.is_null() {
+            return None;
+        }
+        let id = mac::TISGetInputSourceProperty(source.0, mac::kTISPropertyInputSourceID);
+        (!id.is_null()).then(|| CFString::wrap_under_get_rule(id as CFStringRef).to_string())
+    }
+}
+
+/// The layout of the thread owning the foreground window, which is what that window
+/// will interpret keystrokes with.
+#[cfg(target_os = "windows")]
+pub fn current() -> Option<String> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyboardLayout;
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+        // The low word is the language, the high word the layout variant.
+        let layout = GetKeyboardLayout(thread).0 as usize as u32;
+        (layout != 0).then(|| format!("{layout:08X}"))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn current() -> Option<String> {
+    None
+}
+
+/// Key code that types `c` on the active layout, falling back to the ASCII-capable layout
+/// macOS uses for shortcuts when the active one cannot (Cyrillic, Greek, Hebrew, ...).
+/// Shortcuts sent by a hard-coded ANSI key code land on the wrong key with AZERTY or Dvorak.
+#[cfg(target_os = "macos")]
+pub fn key_code_for(c: char) -> Option<u16> {
+    unsafe {
+        let current = mac::Source(mac::TISCopyCurrentKeyboardLayoutInputSource());
+        if let Some(code) = mac::find_key(&current, c) {
+            return Some(code);
+        }
+        let ascii = mac::Source(mac::TISCopyCurrentASCIICapableKeyboardLayoutInputSource());
+        mac::find_key(&ascii, c)
+    }
+}
+
+/// Restores the input method `pause_input_method` switched away from when dropped.
+#[cfg(target_os = "macos")]
+pub struct InputMethodPause(mac::Source);
+
+#[cfg(target_os = "macos")]
+impl Drop for InputMethodPause {
+    fn drop(&mut self) {
+        // Keystrokes already posted are still read with the ASCII layout.
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        unsafe {
+            mac::TISSelectInputSource(self.0 .0);
+        }
+    }
+}
+
+/// Switch from an input method (Japanese, Chinese, Korean, ...) to the ASCII-capable
+/// layout, which makes macOS commit whatever it is composing. `None` when the active
+/// input source is a plain layout.
+#[cfg(target_os = "macos")]
+pub fn pause_input_method() -> Option<InputMethodPause> {
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+
+    unsafe {
+        let current = mac::Source(mac::TISCopyCurrentKeyboardInputSource());
+        if current.0.is_null() {
+            return None;
+        }
+        let kind = mac::TISGetInputSourceProperty(current.0, mac::kTISPropertyInputSourceType);
+        if kind.is_null() || CFString::wrap_under_get_rule(kind as CFStringRef) != CFString::wrap_under_get_rule(mac::kTISTypeKeyboardInputMode) {
+            return None;
+        }
+        let ascii = mac::Source(mac::TISCopyCurrentASCIICapableKeyboardLayoutInputSource());
+        if ascii.0.is_null() || mac::TISSelectInputSource(ascii.0) != 0 {
+            return None;
+        }
+        // Give the focused app a moment to commit its composition.
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        Some(InputMethodPause(current))
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(non_upper_case_globals)]
+mod mac {
+    use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
+    use core_foundation::data::{CFData, CFDataRef};
+    use core_foundation::string::CFStringRef;
+    use std::ffi::c_void;
+
+    pub type TISInputSourceRef = *const c_void;
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        pub fn TISCopyCurrentKeyboardInputSource() -> TISInputSourceRef;
+        pub fn TISCopyCurrentKeyboardLayoutInputSource() -> TISInputSourceRef;
+        pub fn TISCopyCurrentASCIICapableKeyboardLayoutInputSource() -> TISInputSourceRef;
+        pub fn TISGetInputSourceProperty(source: TISInputSourceRef, key: CFStringRef) -> CFTypeRef;
+        pub fn TISSelectInputSource(source: TISInputSourceRef) -> i32;
+        pub static kTISPropertyInputSourceID: CFStringRef;
+        pub static kTISPropertyInputSourceType: CFStringRef;
+        /// Input source type of an input method's modes, as opposed to a keyboard layout.
+        pub static kTISTypeKeyboardInputMode: CFStringRef;
+        static kTISPropertyUnicodeKeyLayoutData: CFStringRef;
+        fn LMGetKbdType() -> u8;
+        fn UCKeyTranslate(
+            layout: *const c_void,
+            key_code: u16,
+            key_action: u16,
+            modifier_state: u32,
+            keyboard_type: u32,
+            options: u32,
+            dead_key_state: *mut u32,
+            max_length: usize,
+            actual_length: *mut usize,
+            unicode: *mut u16,
+        ) -> i32;
+    }
+
+    const K_UC_KEY_ACTION_DISPLAY: u16 = 3;
+    const K_UC_KEY_TRANSLATE_NO_DEAD_KEYS_MASK: u32 = 1;
+    /// Virtual key codes below this cover the whole main keyboard block.
+    const KEY_CODES: u16 = 128;
+
+    /// An owned input source, released on drop.
+    pub struct Source(pub TISInputSourceRef);
+
+    impl Drop for Source {
+        fn drop(&mut self) {
+            if !self.0.is_null() {
+                unsafe { CFRelease(self.0) }
+            }
+        }
+    }
+
+    pub unsafe fn find_key(source: &Source, c: char) -> Option<u16> {
+        if source.0.is_null() {
+            return None;
+        }
+        let data = TISGetInputSourceProperty(source.0, kTISPropertyUnicodeKeyLayoutData);
+        if data.is_null() {
+            return None;
+        }
+        let data = CFData::wrap_under_get_rule(data as CFDataRef);
+        let layout = data.bytes().as_ptr().cast::<c_void>();
+        let mut wanted = [0u16; 2];
+        let wanted = c.encode_utf16(&mut wanted);
+        (0..KEY_CODES).find(|&code| {
+            let mut dead = 0u32;
+            let mut len = 0usize;
+            let mut out = [0u16; 4];
+            let status = UCKeyTranslate(
+                layout,
+                code,
+                K_UC_KEY_ACTION_DISPLAY,
+                0,
+                LMGetKbdType() as u32,
+                K_UC_KEY_TRANSLATE_NO_DEAD_KEYS_MASK,
+                &mut dead,
+                out.len(),
+                &mut len,
+                out.as_mut_ptr(),
+            );
+            status == 0 && &out[..len] == wanted
+        })
+    }
+}
@@ -0,0 +1,176 @@
+/// Passed by the login item, so a launch at login can tell itself apart from the user
+/// opening the app.
+const AUTOSTART_ARG: &str = "--autostart";
+
+/// The program the login item should run. Inside an AppImage that is the image, not the
+/// binary unpacked from it for this run.
+fn program() -> Result<std::path::PathBuf, String> {
+    #[cfg(target_os = "linux")]
+    if let Some(image) = std::env::var_os("APPIMAGE") {
+        return Ok(image.into());
+    }
+    std::env::current_exe().map_err(|e| format!("Cannot find the app's executable: {e}"))
+}
+
+/// A LaunchAgent in ~/Library/LaunchAgents, named after the bundle identifier.
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::PathBuf;
+
+    fn agent_path(identifier: &str) -> Result<PathBuf, String> {
+        let home = std::env::var_os("HOME").ok_or("HOME is not set")?;
+        Ok(PathBuf::from(home).join("Library/LaunchAgents").join(format!("{identifier}.plist")))
+    }
+
+    pub fn is_enabled(identifier: &str) -> bool {
+        agent_path(identifier).is_ok_and(|path| path.exists())
+    }
+
+    pub fn enable(identifier: &str) -> Result<(), String> {
+        let path = agent_path(identifier)?;
+        let program = super::program()?;
+        let mut agent = plist::Dictionary::new();
+        agent.insert("Label".into(), identifier.into());
+        agent.insert(
+            "ProgramArguments".into(),
+            plist::Value::Array(vec![program.to_string_lossy().into_owned().into(), super::AUTOSTART_ARG.into()]),
+        );
+        agent.insert("RunAtLoad".into(), true.into());
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        plist::Value::Dictionary(agent)
+            .to_file_xml(&path)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    pub fn disable(identifier: &str) -> Result<(), String> {
+        let path = agent_path(identifier)?;
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {}: {e}", path.display())),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A value under HKCU\...\Run, named after the bundle identifier.
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use windows::Win32::System::Registry::{
+        RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ,
+    };
+
+    const RUN_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+
+    pub fn is_enabled(identifier: &str) -> bool {
+        let name = HSTRING::from(identifier);
+        let status = unsafe { RegGetValueW(HKEY_CURRENT_USER, RUN_KEY, &name, RRF_RT_REG_SZ, None, None, None) };
+        status == ERROR_SUCCESS
+    }
+
+    pub fn enable(identifier: &str) -> Result<(), String> {
+        let program = super::program()?;
+        let command: Vec<u16> = format!("\"{}\" {}", program.display(), super::AUTOSTART_ARG)
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let name = HSTRING::from(identifier);
+        let status = unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                RUN_KEY,
+                &name,
+                REG_SZ.0,
+                Some(command.as_ptr().cast()),
+                (command.len() * 2) as u32,
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return Err(format!("Failed to add the login item: error {}", status.0));
+        }
+        Ok(())
+    }
+
+    pub fn disable(identifier: &str) -> Result<(), String> {
+        let name = HSTRING::from(identifier);
+        let status = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, RUN_KEY, &name) };
+        if status != ERROR_SUCCESS && status != ERROR_FILE_NOT_FOUND {
+            return Err(format!("Failed to remove the login item: error {}", status.0));
+        }
+        Ok(())
+    }
+}
+
+/// An XDG autostart entry in ~/.config/autostart, named after the bundle identifier.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use std::path::PathBuf;
+
+    fn entry_path(identifier: &str) -> Result<PathBuf, String> {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .ok_or("Neither XDG_CONFIG_HOME nor HOME is set")?;
+        Ok(config.join("autostart").join(format!("{identifier}.desktop")))
+    }
+
+    pub fn is_enabled(identifier: &str) -> bool {
+        entry_path(identifier).is_ok_and(|path| path.exists())
+    }
+
+    pub fn enable(identifier: &str) -> Result<(), String> {
+        let path = entry_path(identifier)?;
+        let program = super::program()?;
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=Spaceduck\nExec=\"{}\" {}\nX-GNOME-Autostart-enabled=true\n",
+            program.display(),
+            super::AUTOSTART_ARG
+        );
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        std::fs::write(&path, entry).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+
+    pub fn disable(identifier: &str) -> Result<(), String> {
+        let path = entry_path(identifier)?;
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {}: {e}", path.display())),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Whether this run was started by the login item and should stay in the tray.
+pub fn start_hidden(app: &tauri::AppHandle) -> bool {
+    std::env::args().any(|arg| arg == AUTOSTART_ARG) && crate::config::read(app, |c| c.start_hidden)
+}
+
+#[tauri::command]
+pub fn get_launch_at_login(app: tauri::AppHandle) -> bool {
+    platform::is_enabled(&app.config().identifier)
+}
+
+/// Add or remove the login item that starts the app, and with it the gateway, at login.
+#[tauri::command]
+pub fn set_launch_at_login(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let identifier = &app.config().identifier;
+    if enabled {
+        platform::enable(identifier)
+    } else {
+        platform::disable(identifier)
+    }
+}
+
+#[tauri::command]
+pub fn get_start_hidden(app: tauri::AppHandle) -> bool {
+    crate::config::read(&app, |c| c.start_hidden)
+}
+
+/// Keep the main window closed when launched at login, leaving only the tray icon.
+#[tauri::command]
+pub fn set_start_hidden(app: tauri::AppHandle, hidden: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.start_hidden = hidden)
+}
@@ -1,10 +1,105 @@
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use tauri_plugin_shell::ShellExt;
 use tauri_plugin_shell::process::CommandEvent;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
+mod actions;
+mod archive;
+mod assistant;
+mod audio;
+mod backdrop;
+mod browser_tab;
+mod clipboard;
+mod clipboard_history;
+mod config;
+mod confirm_overlay;
+mod context;
+mod conversation_windows;
+mod deep_link;
+mod denoise;
+mod dictation;
+mod explain;
+mod display_watch;
+mod dnd;
+mod feedback;
+mod file_drop;
+mod frontmost;
+mod gain;
+mod gateway_stream;
+mod hid_trigger;
+mod hotkey;
+mod idle;
+mod insertion;
+mod keyboard_layout;
+mod launch_at_login;
+mod logging;
+mod loopback;
+mod markdown;
+mod meeting;
+mod mic_access;
+mod models;
+mod network;
+mod notifications;
+mod ocr;
+mod paths;
+mod pill;
+mod pill_position;
+mod power;
+mod pre_roll;
+mod quick_ask;
+mod monitor_health;
+mod resample;
+mod response_window;
+mod screenshot;
+mod screenshot_ask;
+#[cfg(target_os = "macos")]
+mod services;
+mod session;
+#[cfg(target_os = "macos")]
+mod shortcuts;
+mod sleep_wake;
+mod snippets;
+mod stt_queue;
+mod task_progress;
+mod transcription;
+mod transcripts;
+mod tray;
+mod vad;
+mod voice_commands;
+mod wakeword;
+mod whisper;
+mod window_state;
+#[cfg(target_os = "windows")]
+mod uia_insert;
+#[cfg(target_os = "linux")]
+mod linux_inject;
+#[cfg(target_os = "windows")]
+mod win_inject;
+#[cfg(target_os = "windows")]
+mod win_input_monitor;
+#[cfg(target_os = "macos")]
+mod ax_insert;
 #[cfg(target_os = "macos")]
 mod fn_key_monitor;
+#[cfg(target_os = "macos")]
+mod media_key;
+
+/// The running gateway, so it can be restarted.
+static SIDECAR: std::sync::Mutex<Option<tauri_plugin_shell::process::CommandChild>> = std::sync::Mutex::new(None);
+/// Bumped on every spawn, so a restarted gateway's old process exiting is not taken for the new one.
+static SIDECAR_GENERATION: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+static SIDECAR_RUNNING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Whether the gateway sidecar this app started is still running.
+pub fn is_gateway_running() -> bool {
+    SIDECAR_RUNNING.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Whether a gateway this app started has since exited. Not when it never started, as
+/// when one was already running on its own.
+pub fn has_gateway_stopped() -> bool {
+    SIDECAR_GENERATION.load(std::sync::atomic::Ordering::SeqCst) > 0 && !is_gateway_running()
+}
 
 fn try_spawn_sidecar(handle: &tauri::AppHandle) {
     let sidecar = match handle.shell().sidecar("spaceduck-server") {
@@ -15,13 +110,17 @@ fn try_spawn_sidecar(handle: &tauri::AppHandle) {
         }
     };
 
-    let (mut rx, _child) = match sidecar.spawn() {
+    let (mut rx, child) = match sidecar.spawn() {
         Ok(pair) => pair,
         Err(e) => {
             log::warn!("Could not spawn sidecar: {e}. Is the gateway already running?");
             return;
         }
     };
+    let generation = SIDECAR_GENERATION.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+    *SIDECAR.lock().unwrap_or_else(|e| e.into_inner()) = Some(child);
+    SIDECAR_RUNNING.store(true, std::sync::atomic::Ordering::SeqCst);
+    tray::sync(handle);
 
     let log_handle = handle.clone();
     tauri::async_runtime::spawn(async move {
@@ -37,7 +136,12 @@ fn try_spawn_sidecar(handle: &tauri::AppHandle) {
                 }
                 CommandEvent::Terminated(status) => {
                     log::error!("[sidecar] terminated with {:?}", status);
-                    let _ = log_handle.emit("sidecar-terminated", ());
+                    if SIDECAR_GENERATION.load(std::sync::atomic::Ordering::SeqCst) == generation {
+                        SIDECAR.lock().unwrap_or_else(|e| e.into_inner()).take();
+                        SIDECAR_RUNNING.store(false, std::sync::atomic::Ordering::SeqCst);
+                        tray::sync(&log_handle);
+                        let _ = log_handle.emit("sidecar-terminated", ());
+                    }
                     break;
                 }
                 _ => {}
@@ -46,10 +150,29 @@ fn try_spawn_sidecar(handle: &tauri::AppHandle) {
     });
 }
 
+/// Stop the gateway sidecar, if it is running, and start it again.
 #[tauri::command]
-fn paste_transcription(app: tauri::AppHandle, text: String) -> Result<(), String> {
+fn restart_gateway(app: tauri::AppHandle) {
+    if let Some(child) = SIDECAR.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        log::info!("Restarting the gateway");
+        if let Err(e) = child.kill() {
+            log::warn!("Failed to stop the gateway: {e}");
+        }
+        SIDECAR_RUNNING.store(false, std::sync::atomic::Ordering::SeqCst);
+    }
+    try_spawn_sidecar(&app);
+}
+
+#[tauri::command]
+fn paste_transcription(app: tauri::AppHandle, text: String) -> insertion::InsertOutcome {
+    let outcome = insertion::insert(&app, &text);
+    pill::idle(&app);
+    outcome
+}
+
+fn clipboard_paste(app: &tauri::AppHandle, text: &str) -> Result<(), String> {
     app.clipboard()
-        .write_text(&text)
+        .write_text(text)
         .map_err(|e| format!("Clipboard write failed: {e}"))?;
 
     std::thread::sleep(std::time::Duration::from_millis(50));
@@ -57,37 +180,47 @@ fn paste_transcription(app: tauri::AppHandle, text: String) -> Result<(), String
     simulate_paste().map_err(|e| format!("Paste simulation failed: {e}"))
 }
 
+#[cfg(target_os = "windows")]
+fn simulate_paste() -> Result<(), String> {
+    win_inject::paste()
+}
+
+#[cfg(target_os = "linux")]
+fn simulate_paste() -> Result<(), String> {
+    linux_inject::paste()
+}
+
+#[cfg(target_os = "macos")]
 fn simulate_paste() -> Result<(), String> {
     use enigo::{Enigo, Key, Keyboard, Direction, Settings};
 
     let mut enigo = Enigo::new(&Settings::default())
         .map_err(|e| format!("Failed to create enigo instance: {e}"))?;
 
-    #[cfg(target_os = "macos")]
-    {
-        enigo.key(Key::Meta, Direction::Press)
-            .map_err(|e| e.to_string())?;
-        enigo.key(Key::Unicode('v'), Direction::Click)
-            .map_err(|e| e.to_string())?;
-        enigo.key(Key::Meta, Direction::Release)
-            .map_err(|e| e.to_string())?;
-    }
+    // kVK_ANSI_V, for when no installed layout can type a "v".
+    let v = keyboard_layout::key_code_for('v').unwrap_or(9);
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        enigo.key(Key::Control, Direction::Press)
-            .map_err(|e| e.to_string())?;
-        enigo.key(Key::Unicode('v'), Direction::Click)
-            .map_err(|e| e.to_string())?;
-        enigo.key(Key::Control, Direction::Release)
-            .map_err(|e| e.to_string())?;
-    }
+    enigo.key(Key::Meta, Direction::Press)
+        .map_err(|e| e.to_string())?;
+    enigo.key(Key::Other(v as u32), Direction::Click)
+        .map_err(|e| e.to_string())?;
+    enigo.key(Key::Meta, Direction::Release)
+        .map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
+/// Whether the pill window could be made see-through. Without it the webview fills the
+/// window with a solid background instead of floating a rounded pill.
+static PILL_TRANSPARENT: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[tauri::command]
+fn get_pill_transparent() -> bool {
+    PILL_TRANSPARENT.load(std::sync::atomic::Ordering::SeqCst)
+}
+
 #[cfg(target_os = "macos")]
-fn make_window_transparent(window: &tauri::WebviewWindow) {
+fn make_window_transparent(window: &tauri::WebviewWindow) -> bool {
     use cocoa::appkit::{NSColor, NSWindow};
     use cocoa::base::{id, nil};
 
@@ -98,83 +231,200 @@ fn make_window_transparent(window: &tauri::WebviewWindow) {
         ns_window.setBackgroundColor_(clear);
     }
 
-    window.with_webview(|platform_webview| {
+    let result = window.with_webview(|platform_webview| {
         unsafe {
             let wk_view: *mut objc2::runtime::AnyObject = platform_webview.inner().cast();
             let _: () = objc2::msg_send![wk_view, _setDrawsBackground: false];
         }
-    }).unwrap_or_else(|e| log::error!("Failed to set webview transparency: {e}"));
+    });
+    result.map_err(|e| log::error!("Failed to set webview transparency: {e}")).is_ok()
 }
 
-#[cfg(target_os = "macos")]
-pub fn reposition_pill_near_dock(app: &tauri::AppHandle) {
-    use cocoa::appkit::NSScreen;
-    use cocoa::base::{id, nil};
-    use tauri::Manager;
-
-    let pill = match app.get_webview_window("dictation") {
-        Some(w) => w,
-        None => return,
-    };
-
-    let pill_w = 280.0_f64;
-    let pill_h = 48.0_f64;
+/// The window is built transparent; that only shows through with DWM composition,
+/// which Windows 7 can have turned off.
+#[cfg(target_os = "windows")]
+fn make_window_transparent(_window: &tauri::WebviewWindow) -> bool {
+    unsafe { windows::Win32::Graphics::Dwm::DwmIsCompositionEnabled() }.is_ok_and(|enabled| enabled.as_bool())
+}
 
-    unsafe {
-        let mouse_loc: cocoa::foundation::NSPoint = cocoa::appkit::NSEvent::mouseLocation(nil);
+/// The window is built transparent; that needs a compositing window manager with an
+/// ARGB visual, which bare X11 sessions lack.
+#[cfg(target_os = "linux")]
+fn make_window_transparent(window: &tauri::WebviewWindow) -> bool {
+    let Ok(gtk_window) = window.gtk_window() else { return false };
+    let Some(screen) = gtk::prelude::WidgetExt::screen(&gtk_window) else { return false };
+    screen.is_composited() && screen.rgba_visual().is_some()
+}
 
-        let screens = NSScreen::screens(nil);
-        let count: usize = cocoa::foundation::NSArray::count(screens) as usize;
+/// Call `on_press` whenever the `accelerator` global shortcut is pressed.
+fn register_shortcut(app: &tauri::AppHandle, accelerator: &str, on_press: fn(&tauri::AppHandle)) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-        let mut target_frame = None;
+    let shortcut: Shortcut = accelerator.parse().map_err(|e| format!("Invalid shortcut {accelerator}: {e}"))?;
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                on_press(app);
+            }
+        })
+        .map_err(|e| format!("Failed to register {accelerator}: {e}"))
+}
 
-        for i in 0..count {
-            let scr: id = cocoa::foundation::NSArray::objectAtIndex(screens, i as u64);
-            let frame = NSScreen::frame(scr);
-            let contains = mouse_loc.x >= frame.origin.x
-                && mouse_loc.x <= frame.origin.x + frame.size.width
-                && mouse_loc.y >= frame.origin.y
-                && mouse_loc.y <= frame.origin.y + frame.size.height;
-            if contains {
-                target_frame = Some(frame);
-                break;
+/// Swap the global shortcut bound to `on_press` from `previous` to `next`, keeping
+/// `previous` when `next` cannot be registered.
+fn replace_shortcut(
+    app: &tauri::AppHandle,
+    previous: Option<&str>,
+    next: Option<&str>,
+    on_press: fn(&tauri::AppHandle),
+) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    if previous == next {
+        return Ok(());
+    }
+    if let Some(previous) = previous {
+        let _ = app.global_shortcut().unregister(previous);
+    }
+    if let Some(next) = next {
+        if let Err(e) = register_shortcut(app, next, on_press) {
+            if let Some(previous) = previous {
+                let _ = register_shortcut(app, previous, on_press);
             }
+            return Err(e);
         }
+    }
+    Ok(())
+}
 
-        if target_frame.is_none() {
-            let scr = NSScreen::mainScreen(nil);
-            if scr != nil {
-                target_frame = Some(NSScreen::frame(scr));
-            }
+/// The app that was focused when the main window was summoned, for when it is dismissed.
+static MAIN_WINDOW_RETURN: std::sync::Mutex<Option<frontmost::FocusTarget>> = std::sync::Mutex::new(None);
+
+/// Bring the main window to the display under the cursor, keeping its size, unless it
+/// is on that display already.
+fn move_to_cursor_display(app: &tauri::AppHandle, window: &tauri::WebviewWindow) {
+    let Some(monitor) = app
+        .cursor_position()
+        .ok()
+        .and_then(|p| app.monitor_from_point(p.x, p.y).ok().flatten())
+    else {
+        return;
+    };
+    let current = window.current_monitor().ok().flatten();
+    if current.is_some_and(|m| m.name() == monitor.name() && m.position() == monitor.position()) {
+        return;
+    }
+    let Ok(size) = window.outer_size() else { return };
+    let area = monitor.work_area();
+    let x = area.position.x + (area.size.width as i32 - size.width as i32).max(0) / 2;
+    let y = area.position.y + (area.size.height as i32 - size.height as i32).max(0) / 2;
+    let _ = window.set_position(tauri::PhysicalPosition::new(x, y));
+}
+
+/// Summon the main window with the chat input focused or, when it already has focus,
+/// hide it and give focus back to the app that had it before.
+#[tauri::command]
+fn toggle_main_window(app: tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else { return };
+    let focused = window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false);
+    if focused {
+        let _ = window.hide();
+        if let Some(target) = MAIN_WINDOW_RETURN.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            frontmost::refocus(target);
         }
+        return;
+    }
+    *MAIN_WINDOW_RETURN.lock().unwrap_or_else(|e| e.into_inner()) = frontmost::focus_target();
+    move_to_cursor_display(&app, &window);
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+    let _ = window.emit("focus-chat-input", ());
+}
 
-        if let Some(frame) = target_frame {
-            let x = (frame.size.width - pill_w) / 2.0 + frame.origin.x;
-            let bottom_gap = 100.0;
-            let y = frame.origin.y + bottom_gap;
-
-            // Find the true primary screen (origin 0,0) — NOT mainScreen which follows focus
-            let screen_h_total = {
-                let mut h = frame.size.height;
-                for i in 0..count {
-                    let scr: id = cocoa::foundation::NSArray::objectAtIndex(screens, i as u64);
-                    let f = NSScreen::frame(scr);
-                    if f.origin.x == 0.0 && f.origin.y == 0.0 {
-                        h = f.size.height;
-                        break;
-                    }
-                }
-                h
-            };
-            let tauri_y = screen_h_total - y - pill_h;
+fn toggle_main_window_shortcut(app: &tauri::AppHandle) {
+    toggle_main_window(app.clone());
+}
 
-            let _ = pill.set_position(tauri::LogicalPosition::new(x, tauri_y));
+#[tauri::command]
+fn get_main_window_shortcut(app: tauri::AppHandle) -> Option<String> {
+    config::read(&app, |c| c.main_window_shortcut.clone())
+}
+
+/// Change the global shortcut for `toggle_main_window`; `None` turns it off.
+#[tauri::command]
+fn set_main_window_shortcut(app: tauri::AppHandle, shortcut: Option<String>) -> Result<(), String> {
+    let previous = config::read(&app, |c| c.main_window_shortcut.clone());
+    replace_shortcut(&app, previous.as_deref(), shortcut.as_deref(), toggle_main_window_shortcut)?;
+    config::update(&app, |c| c.main_window_shortcut = shortcut)
+}
+
+/// Open the main window on whichever Space is active instead of switching to the one it
+/// was last on.
+#[cfg(target_os = "macos")]
+fn follow_active_space(window: &tauri::WebviewWindow) {
+    use cocoa::appkit::{NSWindow, NSWindowCollectionBehavior};
+    use cocoa::base::id;
+
+    let Ok(ns_window) = window.ns_window() else { return };
+    unsafe {
+        let ns_window = ns_window as id;
+        let behavior = ns_window.collectionBehavior() | NSWindowCollectionBehavior::NSWindowCollectionBehaviorMoveToActiveSpace;
+        ns_window.setCollectionBehavior_(behavior);
+    }
+}
+
+/// Keep `window` above other apps' windows, full-screen ones included, or put it back at
+/// the normal level. Plain always-on-top is the floating level, which macOS hides while
+/// another app is full screen, so there it also joins full-screen Spaces.
+#[cfg(target_os = "macos")]
+fn float_over_full_screen(window: &tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+    use cocoa::appkit::{NSWindow, NSWindowCollectionBehavior};
+    use cocoa::base::id;
+
+    /// NSStatusWindowLevel
+    const STATUS_LEVEL: i64 = 25;
+    /// NSNormalWindowLevel
+    const NORMAL_LEVEL: i64 = 0;
+
+    let ns_window = window.ns_window().map_err(|e| e.to_string())? as id;
+    unsafe {
+        let auxiliary = NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary;
+        let behavior = ns_window.collectionBehavior();
+        if enabled {
+            ns_window.setCollectionBehavior_(behavior | auxiliary);
+            ns_window.setLevel_(STATUS_LEVEL);
+        } else {
+            ns_window.setCollectionBehavior_(behavior & !auxiliary);
+            ns_window.setLevel_(NORMAL_LEVEL);
         }
     }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn float_over_full_screen(window: &tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+    window.set_always_on_top(enabled).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_always_on_top(app: tauri::AppHandle) -> bool {
+    config::read(&app, |c| c.main_window_pinned)
+}
+
+/// Pin the main window above every other window, or unpin it. Remembered across launches.
+#[tauri::command]
+fn set_always_on_top(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("The main window is gone")?;
+    float_over_full_screen(&window, enabled).map_err(|e| format!("Failed to pin the window: {e}"))?;
+    config::update(&app, |c| c.main_window_pinned = enabled)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    if deep_link::forward_to_running() {
+        return;
+    }
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
@@ -183,7 +433,57 @@ pub fn run() {
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .setup(|app| {
             let handle = app.handle().clone();
+            if let Err(e) = logging::init(&handle) {
+                eprintln!("{e}");
+            }
+            app.manage(config::ConfigStore::load(&handle));
+            if let Err(e) = tray::create(&handle) {
+                log::warn!("{e}");
+            }
             try_spawn_sidecar(&handle);
+            notifications::start(&handle);
+
+            quick_ask::start(&handle);
+            if let Some(shortcut) = config::read(&handle, |c| c.main_window_shortcut.clone()) {
+                if let Err(e) = register_shortcut(&handle, &shortcut, toggle_main_window_shortcut) {
+                    log::warn!("Main window shortcut unavailable: {e}");
+                }
+            }
+            explain::start(&handle);
+            screenshot_ask::start(&handle);
+            if let Some(main) = app.get_webview_window("main") {
+                window_state::restore(&handle);
+                backdrop::restore(&handle);
+                file_drop::watch(&main);
+                let state_handle = handle.clone();
+                main.on_window_event(move |event| {
+                    if matches!(event, tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)) {
+                        window_state::changed(&state_handle);
+                        session::changed(&state_handle);
+                    }
+                });
+                #[cfg(target_os = "macos")]
+                follow_active_space(&main);
+                if config::read(&handle, |c| c.main_window_pinned) {
+                    if let Err(e) = float_over_full_screen(&main, true) {
+                        log::warn!("Failed to pin the main window: {e}");
+                    }
+                }
+                if launch_at_login::start_hidden(&handle) {
+                    let _ = main.hide();
+                }
+            }
+            conversation_windows::restore(&handle);
+            session::start(&handle);
+            idle::start(&handle);
+            sleep_wake::start(&handle);
+            network::start(&handle);
+            power::start(&handle);
+            deep_link::start(&handle);
+            #[cfg(target_os = "macos")]
+            services::start(&handle);
+            #[cfg(target_os = "macos")]
+            shortcuts::start(&handle);
 
             #[cfg(target_os = "macos")]
             {
@@ -193,6 +493,62 @@ pub fn run() {
                         log::error!("Fn key monitor failed: {e}");
                     }
                 });
+                media_key::apply(&handle);
+            }
+
+            {
+                let audio_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = audio::start(audio_handle) {
+                        log::error!("Audio worker failed: {e}");
+                    }
+                });
+            }
+
+            {
+                let hid_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = hid_trigger::start(hid_handle) {
+                        log::error!("HID trigger listener failed: {e}");
+                    }
+                });
+            }
+
+            {
+                let queue_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = stt_queue::start(queue_handle) {
+                        log::error!("Transcription queue failed: {e}");
+                    }
+                });
+            }
+
+            {
+                let history_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = clipboard_history::start(history_handle) {
+                        log::error!("Clipboard history failed: {e}");
+                    }
+                });
+            }
+
+            {
+                let wake_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = wakeword::start(wake_handle) {
+                        log::error!("Wake word listener failed: {e}");
+                    }
+                });
+            }
+
+            #[cfg(target_os = "windows")]
+            {
+                let monitor_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = win_input_monitor::start(monitor_handle) {
+                        log::error!("Input monitor failed: {e}");
+                    }
+                });
             }
 
             // Create floating dictation pill window
@@ -203,8 +559,7 @@ pub fn run() {
                     tauri::WebviewUrl::App("index.html?window=dictation".into())
                 };
 
-                let pill_w = 280.0_f64;
-                let pill_h = 48.0_f64;
+                let (pill_w, pill_h) = pill::compact_size(app.handle());
 
                 #[allow(unused_mut)]
                 let mut builder = tauri::WebviewWindowBuilder::new(app, "dictation", url)
@@ -216,17 +571,12 @@ pub fn run() {
                     .skip_taskbar(true)
                     .shadow(false)
                     .focused(false)
-                    .visible(true);
+                    .visible(!config::read(app.handle(), |c| c.pill.auto_hide));
 
+                // macOS is made transparent through AppKit once the window exists.
                 #[cfg(not(target_os = "macos"))]
-                if let Some(monitor) = app.primary_monitor().ok().flatten() {
-                    let size = monitor.size();
-                    let scale = monitor.scale_factor();
-                    let screen_w = size.width as f64 / scale;
-                    let screen_h = size.height as f64 / scale;
-                    let x = (screen_w - pill_w) / 2.0;
-                    let y = screen_h - pill_h - 80.0;
-                    builder = builder.position(x, y);
+                {
+                    builder = builder.transparent(true);
                 }
 
                 let pill = builder
@@ -237,18 +587,201 @@ pub fn run() {
                     })
                     .ok();
 
-                #[cfg(target_os = "macos")]
-                if let Some(ref _pill) = pill {
-                    make_window_transparent(_pill);
-                    reposition_pill_near_dock(app.handle());
+                if let Some(ref pill) = pill {
+                    PILL_TRANSPARENT.store(make_window_transparent(pill), std::sync::atomic::Ordering::SeqCst);
+                    pill::apply_size(app.handle(), pill);
+                    let all_spaces = config::read(app.handle(), |c| c.pill.all_spaces);
+                    if let Err(e) = pill::make_overlay(pill, all_spaces) {
+                        log::warn!("Failed to lift the pill over full-screen apps: {e}");
+                    }
+                    display_watch::start(app.handle());
+                    pill::reposition(app.handle());
+                    pill.on_window_event(|event| {
+                        if let tauri::WindowEvent::Moved(_) = event {
+                            pill_position::moved();
+                        }
+                    });
+                    file_drop::watch(pill);
                 }
-
-                let _ = pill;
             }
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![paste_transcription])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(tauri::generate_handler![
+            actions::list_actions,
+            actions::invoke_action,
+            paste_transcription,
+            quick_ask::hide_quick_ask,
+            quick_ask::submit_quick_ask,
+            quick_ask::get_quick_ask_shortcut,
+            quick_ask::set_quick_ask_shortcut,
+            toggle_main_window,
+            get_main_window_shortcut,
+            set_main_window_shortcut,
+            explain::get_explain_selection_shortcut,
+            explain::set_explain_selection_shortcut,
+            screenshot_ask::get_screenshot_ask_shortcut,
+            screenshot_ask::set_screenshot_ask_shortcut,
+            screenshot_ask::set_screenshot_ask_auto_submit,
+            screenshot_ask::get_quick_ask_screenshot,
+            screenshot_ask::detach_quick_ask_screenshot,
+            idle::get_system_idle,
+            idle::set_idle_away_after,
+            network::get_network_status,
+            dnd::get_dnd_status,
+            power::get_battery_status,
+            dnd::set_notifications_during_dnd,
+            dnd::set_reminders_override_dnd,
+            get_always_on_top,
+            backdrop::get_window_backdrop,
+            backdrop::set_window_backdrop,
+            conversation_windows::open_conversation_window,
+            restart_gateway,
+            quick_ask::take_quick_ask_prefill,
+            notifications::get_notifications_enabled,
+            notifications::set_notifications_enabled,
+            launch_at_login::get_launch_at_login,
+            launch_at_login::set_launch_at_login,
+            launch_at_login::get_start_hidden,
+            launch_at_login::set_start_hidden,
+            browser_tab::get_active_browser_tab,
+            context::capture_active_context,
+            context::get_context_capture,
+            context::set_context_capture,
+            ocr::ocr_image,
+            paths::open_path,
+            paths::reveal_in_file_manager,
+            screenshot::capture_screenshot,
+            screenshot::check_screen_recording_access,
+            screenshot::get_screenshot_region_image,
+            screenshot::finish_screenshot_region,
+            session::set_window_conversation,
+            session::get_previous_session,
+            session::restore_previous_session,
+            set_always_on_top,
+            get_pill_transparent,
+            pill::get_pill_config,
+            pill::set_pill_config,
+            pill::set_pill_click_through,
+            pill::set_pill_state,
+            pill::set_pill_placement,
+            pill::set_pill_size,
+            task_progress::set_taskbar_progress,
+            task_progress::set_dock_badge,
+            response_window::resize_response_window,
+            response_window::dismiss_response_window,
+            response_window::set_response_window_pinned,
+            response_window::get_ask_phrases,
+            response_window::set_ask_phrases,
+            pill_position::start_pill_drag,
+            pill_position::reset_pill_positions,
+            insertion::get_insertion_config,
+            insertion::set_insertion_config,
+            insertion::blocked::copy_blocked_insertion,
+            insertion::undo::undo_last_paste,
+            insertion::chunking::cancel_insertion,
+            keyboard_layout::get_keyboard_layout,
+            clipboard::copy_rich_text,
+            clipboard::copy_image_to_clipboard,
+            clipboard::paste_image,
+            clipboard_history::get_clipboard_history_config,
+            clipboard_history::set_clipboard_history_config,
+            clipboard_history::list_clipboard_history,
+            clipboard_history::paste_clipboard_history,
+            clipboard_history::clear_clipboard_history,
+            snippets::get_snippets_config,
+            snippets::set_snippets_config,
+            audio::list_audio_inputs,
+            audio::set_audio_input,
+            audio::set_auto_stop_silence,
+            audio::set_noise_suppression,
+            audio::set_auto_gain,
+            audio::set_pre_roll,
+            audio::get_audio_config,
+            audio::set_native_capture,
+            transcription::settings::get_gateway_connection,
+            transcription::settings::set_gateway_connection,
+            transcription::settings::get_stt_settings,
+            transcription::settings::set_stt_backend,
+            transcription::settings::set_partial_results,
+            transcription::settings::set_stt_fallback,
+            transcription::settings::set_stt_streaming,
+            transcription::pending::get_pending_paste,
+            transcription::pending::confirm_pending_paste,
+            transcription::pending::discard_pending_paste,
+            transcription::pending::get_paste_confirmation_threshold,
+            transcription::pending::set_paste_confirmation_threshold,
+            transcription::pending::get_confirm_before_insert,
+            transcription::pending::set_confirm_before_insert,
+            stt_queue::list_pending_transcriptions,
+            stt_queue::retry_pending_transcriptions,
+            stt_queue::discard_pending_transcriptions,
+            transcription::settings::get_stt_power_policy,
+            transcription::settings::set_stt_power_policy,
+            transcription::settings::get_dictation_language,
+            transcription::settings::set_dictation_language,
+            transcription::settings::get_dictation_translation,
+            transcription::settings::set_dictation_translation,
+            transcription::settings::list_vocabulary,
+            transcription::settings::add_vocabulary_word,
+            transcription::settings::remove_vocabulary_word,
+            dictation::cancel_dictation,
+            dictation::get_capture_paused,
+            dictation::set_capture_paused,
+            dictation::get_dictation_triggers,
+            dictation::set_dictation_triggers,
+            dictation::get_dictation_hotkey,
+            dictation::set_dictation_hotkey,
+            dictation::get_dictation_mouse_button,
+            dictation::set_dictation_mouse_button,
+            dictation::get_dictation_media_key,
+            dictation::set_dictation_media_key,
+            hid_trigger::list_hid_devices,
+            hid_trigger::learn_hid_button,
+            hid_trigger::get_dictation_hid_trigger,
+            hid_trigger::set_dictation_hid_trigger,
+            feedback::get_dictation_feedback,
+            feedback::set_dictation_feedback,
+            dictation::get_chat_focus_apps,
+            dictation::set_chat_focus_apps,
+            hotkey::capture_next_hotkey,
+            monitor_health::get_hotkey_monitor_health,
+            archive::get_audio_archive,
+            archive::set_audio_archive,
+            archive::list_recordings,
+            archive::get_recording_path,
+            archive::purge_recordings,
+            models::list_whisper_models,
+            models::download_whisper_model,
+            models::cancel_model_download,
+            models::delete_whisper_model,
+            whisper::get_gpu_support,
+            whisper::set_whisper_gpu,
+            whisper::benchmark_whisper,
+            wakeword::get_wake_word,
+            wakeword::get_wake_word_active,
+            wakeword::set_wake_word,
+            voice_commands::get_voice_commands,
+            voice_commands::set_voice_commands,
+            loopback::start_system_audio_capture,
+            loopback::stop_system_audio_capture,
+            mic_access::check_microphone_access,
+            mic_access::request_microphone_access,
+            meeting::start_meeting,
+            meeting::stop_meeting,
+            meeting::get_meeting_transcript,
+            transcripts::export_transcript,
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            tauri::RunEvent::Exit => session::clean_exit(app),
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Opened { urls } => {
+                for url in urls {
+                    deep_link::handle(app, url.as_str());
+                }
+            }
+            _ => {}
+        });
 }
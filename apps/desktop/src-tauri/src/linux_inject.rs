@@ -0,0 +1,162 @@
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// How synthetic input reaches the focused window. Wayland compositors ignore XTest
+/// for native clients, so there we go through a helper that speaks a protocol they accept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    /// XTest through enigo.
+    X11,
+    /// `wtype`, which uses the virtual-keyboard protocol (wlroots compositors, KDE).
+    Wtype,
+    /// `ydotool`, which writes to uinput through `ydotoold` and so works on GNOME too.
+    Ydotool,
+}
+
+static BACKEND: OnceLock<Backend> = OnceLock::new();
+
+/// evdev codes for `ydotool key`. These are key positions, so on layouts that move V
+/// (Dvorak) the paste shortcut goes astray; wtype uploads its own keymap and is preferred.
+const KEY_LEFTCTRL: u32 = 29;
+const KEY_V: u32 = 47;
+const KEY_C: u32 = 46;
+const KEY_ENTER: u32 = 28;
+const KEY_BACKSPACE: u32 = 14;
+
+fn has_command(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+}
+
+fn wayland_session() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"))
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// Picked once from the session type. Without a helper on Wayland we still try XTest,
+/// which reaches XWayland windows.
+fn backend() -> Backend {
+    *BACKEND.get_or_init(|| {
+        let backend = if !wayland_session() {
+            Backend::X11
+        } else if has_command("wtype") {
+            Backend::Wtype
+        } else if has_command("ydotool") {
+            Backend::Ydotool
+        } else {
+            log::warn!("Wayland session without wtype or ydotool; only XWayland apps will receive dictations");
+            Backend::X11
+        };
+        log::info!("Linux text injection backend: {backend:?}");
+        backend
+    })
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command.output().map_err(|e| format!("Failed to run {program}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("{program} failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+fn enigo() -> Result<enigo::Enigo, String> {
+    enigo::Enigo::new(&enigo::Settings::default()).map_err(|e| format!("Failed to create enigo instance: {e}"))
+}
+
+pub fn paste() -> Result<(), String> {
+    match backend() {
+        Backend::X11 => {
+            use enigo::{Direction, Key, Keyboard};
+            let mut enigo = enigo()?;
+            enigo.key(Key::Control, Direction::Press).map_err(|e| e.to_string())?;
+            enigo.key(Key::Unicode('v'), Direction::Click).map_err(|e| e.to_string())?;
+            enigo.key(Key::Control, Direction::Release).map_err(|e| e.to_string())
+        }
+        Backend::Wtype => run(Command::new("wtype").args(["-M", "ctrl", "v", "-m", "ctrl"])),
+        Backend::Ydotool => run(Command::new("ydotool").args([
+            "key".to_string(),
+            format!("{KEY_LEFTCTRL}:1"),
+            format!("{KEY_V}:1"),
+            format!("{KEY_V}:0"),
+            format!("{KEY_LEFTCTRL}:0"),
+        ])),
+    }
+}
+
+/// Ctrl+C. Over XTest, Shift, Alt and Super are let go first, since a copy sent from a
+/// global shortcut would otherwise pick up the ones still held for it.
+pub fn copy() -> Result<(), String> {
+    match backend() {
+        Backend::X11 => {
+            use enigo::{Direction, Key, Keyboard};
+            let mut enigo = enigo()?;
+            for held in [Key::Shift, Key::Alt, Key::Meta] {
+                enigo.key(held, Direction::Release).map_err(|e| e.to_string())?;
+            }
+            enigo.key(Key::Control, Direction::Press).map_err(|e| e.to_string())?;
+            enigo.key(Key::Unicode('c'), Direction::Click).map_err(|e| e.to_string())?;
+            enigo.key(Key::Control, Direction::Release).map_err(|e| e.to_string())
+        }
+        Backend::Wtype => run(Command::new("wtype").args(["-M", "ctrl", "c", "-m", "ctrl"])),
+        Backend::Ydotool => run(Command::new("ydotool").args([
+            "key".to_string(),
+            format!("{KEY_LEFTCTRL}:1"),
+            format!("{KEY_C}:1"),
+            format!("{KEY_C}:0"),
+            format!("{KEY_LEFTCTRL}:0"),
+        ])),
+    }
+}
+
+/// Send `text` as unicode keystrokes, `delay_ms` apart.
+pub fn type_text(text: &str, delay_ms: u32) -> Result<(), String> {
+    match backend() {
+        Backend::X11 => {
+            use enigo::Keyboard;
+            let mut enigo = enigo()?;
+            if delay_ms == 0 {
+                return enigo.text(text).map_err(|e| format!("Typing failed: {e}"));
+            }
+            let delay = std::time::Duration::from_millis(delay_ms as u64);
+            let mut buffer = [0u8; 4];
+            for c in text.chars() {
+                enigo.text(c.encode_utf8(&mut buffer)).map_err(|e| format!("Typing failed: {e}"))?;
+                std::thread::sleep(delay);
+            }
+            Ok(())
+        }
+        Backend::Wtype => run(Command::new("wtype").args(["-d", &delay_ms.to_string(), "--", text])),
+        // ydotool types through the active keyboard layout, so text it cannot map is dropped.
+        Backend::Ydotool => run(Command::new("ydotool").args(["type", "-d", &delay_ms.to_string(), "--", text])),
+    }
+}
+
+pub fn press_enter() -> Result<(), String> {
+    match backend() {
+        Backend::X11 => {
+            use enigo::{Direction, Key, Keyboard};
+            enigo()?.key(Key::Return, Direction::Click).map_err(|e| format!("Failed to press Return: {e}"))
+        }
+        Backend::Wtype => run(Command::new("wtype").args(["-k", "Return"])),
+        Backend::Ydotool => run(Command::new("ydotool").args(["key".to_string(), format!("{KEY_ENTER}:1"), format!("{KEY_ENTER}:0")])),
+    }
+}
+
+pub fn backspaces(count: usize) -> Result<(), String> {
+    match backend() {
+        Backend::X11 => {
+            use enigo::{Direction, Key, Keyboard};
+            let mut enigo = enigo()?;
+            for _ in 0..count {
+                enigo.key(Key::Backspace, Direction::Click).map_err(|e| format!("Failed to press Backspace: {e}"))?;
+            }
+            Ok(())
+        }
+        Backend::Wtype => run(Command::new("wtype").args((0..count).flat_map(|_| ["-k", "BackSpace"]))),
+        Backend::Ydotool => run(Command::new("ydotool")
+            .arg("key")
+            .args((0..count).flat_map(|_| [format!("{KEY_BACKSPACE}:1"), format!("{KEY_BACKSPACE}:0")]))),
+    }
+}
@@ -0,0 +1,63 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+const LOG_FILE: &str = "spaceduck.log";
+/// Past this the log is moved aside at launch, keeping one previous file.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+struct FileLogger {
+    file: Mutex<File>,
+}
+
+impl log::Log for FileLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let line = format!("{secs} {:<5} {}: {}\n", record.level(), record.target(), record.args());
+        if cfg!(debug_assertions) {
+            eprint!("{line}");
+        }
+        let _ = self.file.lock().unwrap_or_else(|e| e.into_inner()).write_all(line.as_bytes());
+    }
+
+    fn flush(&self) {
+        let _ = self.file.lock().unwrap_or_else(|e| e.into_inner()).flush();
+    }
+}
+
+/// Send `log` output to a file in the app's log directory. Nothing is logged before this.
+pub fn init(app: &tauri::AppHandle) -> Result<(), String> {
+    let dir = app.path().app_log_dir().map_err(|e| format!("No log dir: {e}"))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let path = dir.join(LOG_FILE);
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let _ = std::fs::rename(&path, path.with_extension("log.old"));
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    log::set_logger(Box::leak(Box::new(FileLogger { file: Mutex::new(file) })))
+        .map_err(|e| format!("Logger already set: {e}"))?;
+    log::set_max_level(log::LevelFilter::Info);
+    let _ = DIR.set(dir);
+    Ok(())
+}
+
+/// Where the log file is, once `init` has run.
+pub fn dir() -> Option<PathBuf> {
+    DIR.get().cloned()
+}
@@ -0,0 +1,150 @@
+#[cfg(not(target_os = "macos"))]
+use cpal::traits::{DeviceTrait, HostTrait};
+
+/// Speaker output captured as an input: ScreenCaptureKit on macOS, WASAPI loopback on
+/// Windows and the PulseAudio/PipeWire monitor source on Linux.
+pub struct LoopbackInput {
+    #[cfg(not(target_os = "macos"))]
+    device: cpal::Device,
+    #[cfg(not(target_os = "macos"))]
+    config: cpal::SupportedStreamConfig,
+}
+
+#[cfg(target_os = "macos")]
+mod screen_capture {
+    use screencapturekit::prelude::*;
+    use std::sync::Mutex;
+
+    /// ScreenCaptureKit resamples for us; ask for rnnoise's rate so denoising is free.
+    pub const SAMPLE_RATE: u32 = 48_000;
+
+    pub struct Stream(SCStream);
+
+    impl Drop for Stream {
+        fn drop(&mut self) {
+            if let Err(e) = self.0.stop_capture() {
+                log::warn!("Failed to stop system audio capture: {e}");
+            }
+        }
+    }
+
+    pub fn start(mut on_audio: impl FnMut(&[f32]) + Send + 'static) -> Result<Stream, String> {
+        let content = SCShareableContent::get()
+            .map_err(|e| format!("System audio needs Screen Recording permission: {e}"))?;
+        let display = content
+            .displays()
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No display to capture system audio from".to_string())?;
+        let filter = SCContentFilter::create().with_display(&display).with_excluding_windows(&[]).build();
+        // Video cannot be turned off, so keep it as small and infrequent as possible.
+        let config = SCStreamConfiguration::new()
+            .with_width(2)
+            .with_height(2)
+            .with_minimum_frame_interval(&CMTime::new(1, 1))
+            .with_captures_audio(true)
+            .with_excludes_current_process_audio(true)
+            .with_sample_rate(SAMPLE_RATE as i32)
+            .with_channel_count(1);
+
+        let mut stream = SCStream::new(&filter, &config);
+        let mut samples = Vec::new();
+        let on_audio = Mutex::new(move |data: &[u8]| {
+            samples.clear();
+            samples.extend(data.chunks_exact(4).map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]])));
+            on_audio(&samples);
+        });
+        stream.add_output_handler(
+            move |sample: CMSampleBuffer, kind: SCStreamOutputType| {
+                if kind != SCStreamOutputType::Audio {
+                    return;
+                }
+                let Some(buffers) = sample.audio_buffer_list() else { return };
+                // Mono float32, so the first buffer holds everything.
+                if let Some(buffer) = buffers.iter().next() {
+                    (on_audio.lock().unwrap_or_else(|e| e.into_inner()))(buffer.data());
+                }
+            },
+            SCStreamOutputType::Audio,
+        );
+        stream
+            .start_capture()
+            .map_err(|e| format!("Failed to start system audio capture: {e}"))?;
+        Ok(Stream(stream))
+    }
+}
+
+/// Keeps system audio flowing until dropped.
+#[cfg(target_os = "macos")]
+pub type LoopbackStream = screen_capture::Stream;
+#[cfg(not(target_os = "macos"))]
+pub type LoopbackStream = cpal::Stream;
+
+impl LoopbackInput {
+    #[cfg(target_os = "macos")]
+    pub fn open() -> Result<Self, String> {
+        Ok(Self {})
+    }
+
+    /// WASAPI records an output device when it is opened as an input.
+    #[cfg(target_os = "windows")]
+    pub fn open() -> Result<Self, String> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| "No audio output device to capture".to_string())?;
+        let config = device
+            .default_output_config()
+            .map_err(|e| format!("Failed to read output config: {e}"))?;
+        Ok(Self { device, config })
+    }
+
+    /// Pulse and PipeWire expose each sink's output as a "Monitor of ..." input.
+    #[cfg(target_os = "linux")]
+    pub fn open() -> Result<Self, String> {
+        let device = cpal::default_host()
+            .input_devices()
+            .map_err(|e| format!("Failed to enumerate input devices: {e}"))?
+            .find(|d| d.name().is_ok_and(|name| name.to_lowercase().contains("monitor")))
+            .ok_or_else(|| "No monitor source found; system audio capture needs PulseAudio or PipeWire".to_string())?;
+        let config = device
+            .default_input_config()
+            .map_err(|e| format!("Failed to read monitor config: {e}"))?;
+        Ok(Self { device, config })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        #[cfg(target_os = "macos")]
+        return screen_capture::SAMPLE_RATE;
+        #[cfg(not(target_os = "macos"))]
+        return self.config.sample_rate().0;
+    }
+
+    pub fn start(self, on_audio: impl FnMut(&[f32]) + Send + 'static, on_lost: fn()) -> Result<LoopbackStream, String> {
+        #[cfg(target_os = "macos")]
+        {
+            let _ = on_lost;
+            screen_capture::start(on_audio)
+        }
+        #[cfg(not(target_os = "macos"))]
+        crate::audio::open_stream(&self.device, &self.config, on_audio, on_lost)
+    }
+}
+
+/// Transcribe system audio through the normal pipeline; the result arrives as
+/// `dictation:result` for `target` (chat unless given). Stop with `stop_system_audio_capture`.
+#[tauri::command]
+pub fn start_system_audio_capture(app: tauri::AppHandle, target: Option<crate::dictation::DictationTarget>) -> Result<(), String> {
+    if !crate::audio::native_capture_enabled(&app) {
+        return Err("Turn on native capture to transcribe system audio".to_string());
+    }
+    let mode = crate::dictation::resolve_mode(&app, target.unwrap_or(crate::dictation::DictationTarget::Chat));
+    if !crate::dictation::begin_system_audio(&app, mode) {
+        return Err("Another recording is already running".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_system_audio_capture(app: tauri::AppHandle) -> bool {
+    crate::dictation::end(&app, crate::dictation::TriggerSource::SystemAudio)
+}
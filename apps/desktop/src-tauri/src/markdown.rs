@@ -0,0 +1,379 @@
+/// Render the Markdown the assistant writes as HTML for the clipboard: headings,
+/// paragraphs, emphasis, code, links, quotes, lists and GFM tables. Anything else comes
+/// through as text, which is what rich-text editors would show for it anyway.
+pub fn to_html(markdown: &str) -> String {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut out = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+
+        if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+            flush_paragraph(&mut paragraph, &mut out);
+            out.push_str("<pre><code>");
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with(fence) {
+                escape_into(lines[i], &mut out);
+                out.push('\n');
+                i += 1;
+            }
+            out.push_str("</code></pre>\n");
+            i += 1;
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut out);
+            i += 1;
+            continue;
+        }
+        if let Some((level, text)) = heading(trimmed) {
+            flush_paragraph(&mut paragraph, &mut out);
+            out.push_str(&format!("<h{level}>"));
+            inline(text, &mut out);
+            out.push_str(&format!("</h{level}>\n"));
+            i += 1;
+            continue;
+        }
+        if is_rule(trimmed) {
+            flush_paragraph(&mut paragraph, &mut out);
+            out.push_str("<hr>\n");
+            i += 1;
+            continue;
+        }
+        if trimmed.starts_with('>') {
+            flush_paragraph(&mut paragraph, &mut out);
+            let mut quoted = Vec::new();
+            while let Some(rest) = lines.get(i).and_then(|l| l.trim_start().strip_prefix('>')) {
+                quoted.push(rest.strip_prefix(' ').unwrap_or(rest));
+                i += 1;
+            }
+            out.push_str("<blockquote>\n");
+            out.push_str(&to_html(&quoted.join("\n")));
+            out.push_str("</blockquote>\n");
+            continue;
+        }
+        if let Some((ordered, _, _)) = list_item(line) {
+            flush_paragraph(&mut paragraph, &mut out);
+            i = list(&lines, i, ordered, &mut out);
+            continue;
+        }
+        if paragraph.is_empty() && line.contains('|') && lines.get(i + 1).is_some_and(|l| is_table_separator(l)) {
+            i = table(&lines, i, &mut out);
+            continue;
+        }
+        paragraph.push(trimmed);
+        i += 1;
+    }
+    flush_paragraph(&mut paragraph, &mut out);
+    out
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, out: &mut String) {
+    if paragraph.is_empty() {
+        return;
+    }
+    out.push_str("<p>");
+    for (n, line) in paragraph.iter().enumerate() {
+        if n > 0 {
+            out.push('\n');
+        }
+        // A trailing backslash or two spaces is a hard line break.
+        let hard = line.ends_with('\\') || line.ends_with("  ");
+        inline(line.strip_suffix('\\').unwrap_or(line).trim_end(), out);
+        if hard && n + 1 < paragraph.len() {
+            out.push_str("<br>");
+        }
+    }
+    out.push_str("</p>\n");
+    paragraph.clear();
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' ')))
+        .then(|| (level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let marks: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_'].into_iter().any(|m| marks.chars().all(|c| c == m))
+}
+
+/// Whether `line` starts a list item: `(ordered, indent, content)`.
+fn list_item(line: &str) -> Option<(bool, usize, &str)> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    if let Some(content) = ["- ", "* ", "+ "].into_iter().find_map(|m| rest.strip_prefix(m)) {
+        return Some((false, indent, content));
+    }
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    let content = rest[digits..].strip_prefix(". ").or_else(|| rest[digits..].strip_prefix(") "));
+    (digits > 0 && digits < 10).then_some(content).flatten().map(|c| (true, indent, c))
+}
+
+/// Render the list starting at `start`; returns the first line after it. Lines indented
+/// past the marker, nested lists included, belong to the item above them.
+fn list(lines: &[&str], start: usize, ordered: bool, out: &mut String) -> usize {
+    let base = list_item(lines[start]).map_or(0, |(_, indent, _)| indent);
+    out.push_str(if ordered { "<ol>\n" } else { "<ul>\n" });
+    let mut i = start;
+    while let Some((kind, indent, content)) = lines.get(i).and_then(|l| list_item(l)) {
+        if kind != ordered || indent != base {
+            break;
+        }
+        let mut body = vec![content.to_string()];
+        i += 1;
+        while let Some(line) = lines.get(i) {
+            let indent = line.len() - line.trim_start().len();
+            if line.trim().is_empty() {
+                // A blank line only continues the list when more of it follows.
+                let next = lines.get(i + 1).map(|l| (l.len() - l.trim_start().len(), list_item(l)));
+                match next {
+                    Some((next_indent, _)) if next_indent > base => body.push(String::new()),
+                    Some((_, Some((kind, next_indent, _)))) if kind == ordered && next_indent == base => {}
+                    _ => break,
+                }
+                i += 1;
+                continue;
+            }
+            if indent <= base && (list_item(line).is_some() || heading(line.trim_start()).is_some()) {
+                break;
+            }
+            body.push(line[indent.min(base + 2)..].to_string());
+            i += 1;
+        }
+        let html = to_html(&body.join("\n"));
+        // Tight items render their first paragraph inline, as browsers expect.
+        let html = match html.strip_prefix("<p>").and_then(|rest| rest.split_once("</p>\n")) {
+            Some((first, rest)) => format!("{first}{}{rest}", if rest.is_empty() { "" } else { "\n" }),
+            None => html,
+        };
+        out.push_str("<li>");
+        out.push_str(html.trim_end());
+        out.push_str("</li>\n");
+    }
+    out.push_str(if ordered { "</ol>\n" } else { "</ul>\n" });
+    i
+}
+
+fn is_table_separator(line: &str) -> bool {
+    let line = line.trim();
+    line.contains('-') && line.contains('|') && line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+fn cells(line: &str) -> Vec<&str> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+    line.split('|').map(str::trim).collect()
+}
+
+fn table(lines: &[&str], start: usize, out: &mut String) -> usize {
+    out.push_str("<table>\n<thead><tr>");
+    for cell in cells(lines[start]) {
+        out.push_str("<th>");
+        inline(cell, out);
+        out.push_str("</th>");
+    }
+    out.push_str("</tr></thead>\n<tbody>\n");
+    let mut i = start + 2;
+    while let Some(line) = lines.get(i).filter(|l| l.contains('|') && !l.trim().is_empty()) {
+        out.push_str("<tr>");
+        for cell in cells(line) {
+            out.push_str("<td>");
+            inline(cell, out);
+            out.push_str("</td>");
+        }
+        out.push_str("</tr>\n");
+        i += 1;
+    }
+    out.push_str("</tbody>\n</table>\n");
+    i
+}
+
+const EMPHASIS: [(&str, &str); 5] = [("**", "strong"), ("__", "strong"), ("~~", "del"), ("*", "em"), ("_", "em")];
+
+fn inline(text: &str, out: &mut String) {
+    let mut rest = text;
+    let mut previous = ' ';
+    'outer: while let Some(c) = rest.chars().next() {
+        if c == '\\' {
+            if let Some(next) = rest[1..].chars().next().filter(char::is_ascii_punctuation) {
+                escape_into(&rest[1..2], out);
+                rest = &rest[1 + next.len_utf8()..];
+                previous = next;
+                continue;
+            }
+        }
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                out.push_str("<code>");
+                escape_into(&rest[1..1 + end], out);
+                out.push_str("</code>");
+                rest = &rest[end + 2..];
+                previous = '`';
+                continue;
+            }
+        }
+        if c == '[' {
+            if let Some((label, url, len)) = link(rest) {
+                out.push_str("<a href=\"");
+                escape_into(url, out);
+                out.push_str("\">");
+                inline(label, out);
+                out.push_str("</a>");
+                rest = &rest[len..];
+                previous = ')';
+                continue;
+            }
+        }
+        // Underscores inside words, as in snake_case, are not emphasis.
+        if !(c == '_' && previous.is_alphanumeric()) {
+            for (delim, tag) in EMPHASIS {
+                let Some(after) = rest.strip_prefix(delim) else { continue };
+                if after.starts_with(char::is_whitespace) {
+                    continue;
+                }
+                if let Some(end) = closing(after, delim) {
+                    out.push_str(&format!("<{tag}>"));
+                    inline(&after[..end], out);
+                    out.push_str(&format!("</{tag}>"));
+                    rest = &after[end + delim.len()..];
+                    previous = c;
+                    continue 'outer;
+                }
+            }
+        }
+        escape_into(&rest[..c.len_utf8()], out);
+        rest = &rest[c.len_utf8()..];
+        previous = c;
+    }
+}
+
+/// Offset of the delimiter closing `delim` in `text`, skipping doubled delimiters when
+/// looking for a single one so `*a **b** c*` nests.
+fn closing(text: &str, delim: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(found) = text[offset..].find(delim) {
+        let at = offset + found;
+        let doubled = delim.len() == 1 && text[at + 1..].starts_with(delim);
+        if doubled {
+            match closing(&text[at + 2..], &delim.repeat(2)) {
+                Some(end) => offset = at + 2 + end + 2,
+                None => offset = at + 2,
+            }
+            continue;
+        }
+        if at > 0 && !text[..at].ends_with(char::is_whitespace) {
+            return Some(at);
+        }
+        offset = at + delim.len();
+    }
+    None
+}
+
+/// `[label](url)` at the start of `text`: `(label, url, length)`. Only web and mail links
+/// count, so a `javascript:` or `data:` one stays text when pasted.
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let label_end = text.find("](")?;
+    let url_end = label_end + 2 + text[label_end + 2..].find(')')?;
+    let url = text[label_end + 2..url_end].trim();
+    // Drop an optional "title".
+    let url = url.split_once(' ').map_or(url, |(url, _)| url);
+    let scheme = url.split_once(':').map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if !matches!(scheme.as_deref(), Some("http" | "https" | "mailto")) {
+        return None;
+    }
+    Some((&text[1..label_end], url, url_end + 1))
+}
+
+fn escape_into(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_headings_rules_and_paragraphs() {
+        assert_eq!(to_html("# Title #\n\n---\nSome\ntext"), "<h1>Title</h1>\n<hr>\n<p>Some\ntext</p>\n");
+        assert_eq!(to_html("####### seven"), "<p>####### seven</p>\n");
+        assert_eq!(to_html("line one\\\nline two"), "<p>line one<br>\nline two</p>\n");
+    }
+
+    #[test]
+    fn nests_emphasis() {
+        assert_eq!(
+            to_html("*a **b** c* and ~~gone~~"),
+            "<p><em>a <strong>b</strong> c</em> and <del>gone</del></p>\n"
+        );
+        assert_eq!(to_html("2 * 3 * 4"), "<p>2 * 3 * 4</p>\n");
+    }
+
+    #[test]
+    fn leaves_underscores_inside_words() {
+        assert_eq!(to_html("call snake_case_name or _this_"), "<p>call snake_case_name or <em>this</em></p>\n");
+    }
+
+    #[test]
+    fn escapes_html_and_markdown() {
+        assert_eq!(to_html("a <b> & \\*not em\\*"), "<p>a &lt;b&gt; &amp; *not em*</p>\n");
+        assert_eq!(to_html("`<tag> *x*`"), "<p><code>&lt;tag&gt; *x*</code></p>\n");
+    }
+
+    #[test]
+    fn keeps_fenced_code_verbatim() {
+        assert_eq!(
+            to_html("```rust\nlet a = *b;\n# not a heading\n```\nafter"),
+            "<pre><code>let a = *b;\n# not a heading\n</code></pre>\n<p>after</p>\n"
+        );
+        assert_eq!(to_html("~~~\nunclosed"), "<pre><code>unclosed\n</code></pre>\n");
+    }
+
+    #[test]
+    fn links_only_to_web_and_mail() {
+        assert_eq!(
+            to_html("[site](https://example.com \"Title\") and [me](mailto:a@b.c)"),
+            "<p><a href=\"https://example.com\">site</a> and <a href=\"mailto:a@b.c\">me</a></p>\n"
+        );
+        assert_eq!(to_html("[x](javascript:alert(1))"), "<p>[x](javascript:alert(1))</p>\n");
+        assert_eq!(to_html("[x](data:text/html,hi)"), "<p>[x](data:text/html,hi)</p>\n");
+        assert_eq!(to_html("[x](JavaScript:go)"), "<p>[x](JavaScript:go)</p>\n");
+    }
+
+    #[test]
+    fn renders_tight_and_loose_lists() {
+        assert_eq!(to_html("- one\n- two"), "<ul>\n<li>one</li>\n<li>two</li>\n</ul>\n");
+        assert_eq!(to_html("1. one\n\n2. two"), "<ol>\n<li>one</li>\n<li>two</li>\n</ol>\n");
+        assert_eq!(
+            to_html("- one\n\n  more\n- two"),
+            "<ul>\n<li>one\n<p>more</p></li>\n<li>two</li>\n</ul>\n"
+        );
+        assert_eq!(
+            to_html("- outer\n  - inner\n- next"),
+            "<ul>\n<li>outer\n<ul>\n<li>inner</li>\n</ul></li>\n<li>next</li>\n</ul>\n"
+        );
+    }
+
+    #[test]
+    fn renders_quotes_and_tables() {
+        assert_eq!(to_html("> quoted\n> text"), "<blockquote>\n<p>quoted\ntext</p>\n</blockquote>\n");
+        assert_eq!(
+            to_html("| A | B |\n|---|:-:|\n| *1* | 2 |"),
+            "<table>\n<thead><tr><th>A</th><th>B</th></tr></thead>\n<tbody>\n<tr><td><em>1</em></td><td>2</td></tr>\n</tbody>\n</table>\n"
+        );
+    }
+}
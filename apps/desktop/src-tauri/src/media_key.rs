@@ -0,0 +1,108 @@
+use block2::RcBlock;
+use objc2::runtime::{AnyClass, AnyObject};
+use std::sync::Mutex;
+
+use crate::dictation::{self, TriggerSource};
+
+#[link(name = "MediaPlayer", kind = "framework")]
+extern "C" {}
+
+const HANDLER_STATUS_SUCCESS: isize = 0;
+const HANDLER_STATUS_COMMAND_FAILED: isize = 200;
+const PLAYBACK_STATE_PAUSED: usize = 2;
+const PLAYBACK_STATE_STOPPED: usize = 3;
+
+/// An `MPRemoteCommand` and the opaque target token `addTargetWithHandler:` returned for it.
+struct Registration {
+    command: *mut AnyObject,
+    target: *mut AnyObject,
+}
+
+// Both objects are only touched on the main thread; the mutex just holds them in between.
+unsafe impl Send for Registration {}
+
+static REGISTRATIONS: Mutex<Vec<Registration>> = Mutex::new(Vec::new());
+
+/// Register or remove the remote command handlers to match the saved setting.
+/// AirPods and other headsets deliver play/pause here rather than as a key event.
+pub fn apply(handle: &tauri::AppHandle) {
+    let enabled = crate::config::read(handle, |c| c.dictation_media_key.is_some());
+    let app = handle.clone();
+    let result = handle.run_on_main_thread(move || unsafe {
+        let mut registrations = REGISTRATIONS.lock().unwrap_or_else(|e| e.into_inner());
+        let registered = !registrations.is_empty();
+        if enabled == registered {
+            return;
+        }
+        if enabled {
+            register(&app, &mut registrations);
+        } else {
+            unregister(&mut registrations);
+        }
+    });
+    if let Err(e) = result {
+        log::error!("Failed to update media key handlers: {e}");
+    }
+}
+
+unsafe fn command_center() -> Option<*mut AnyObject> {
+    let cls = AnyClass::get("MPRemoteCommandCenter")?;
+    let center: *mut AnyObject = objc2::msg_send![cls, sharedCommandCenter];
+    (!center.is_null()).then_some(center)
+}
+
+/// Headsets send play, pause or toggle depending on what they think is playing, so all three toggle.
+unsafe fn commands(center: *mut AnyObject) -> [*mut AnyObject; 3] {
+    [
+        objc2::msg_send![center, togglePlayPauseCommand],
+        objc2::msg_send![center, playCommand],
+        objc2::msg_send![center, pauseCommand],
+    ]
+}
+
+/// Only the "now playing" app receives remote commands, so claim that slot while enabled.
+unsafe fn set_playback_state(state: usize) {
+    let Some(cls) = AnyClass::get("MPNowPlayingInfoCenter") else { return };
+    let center: *mut AnyObject = objc2::msg_send![cls, defaultCenter];
+    if !center.is_null() {
+        let _: () = objc2::msg_send![center, setPlaybackState: state];
+    }
+}
+
+unsafe fn register(app: &tauri::AppHandle, registrations: &mut Vec<Registration>) {
+    let Some(center) = command_center() else {
+        log::warn!("MPRemoteCommandCenter unavailable, media key dictation disabled");
+        return;
+    };
+
+    for command in commands(center) {
+        if command.is_null() {
+            continue;
+        }
+        let handle = app.clone();
+        let handler = RcBlock::new(move |_event: *mut AnyObject| -> isize {
+            let Some(trigger) = crate::config::read(&handle, |c| c.dictation_media_key.clone()) else {
+                return HANDLER_STATUS_COMMAND_FAILED;
+            };
+            if dictation::toggle(&handle, TriggerSource::MediaKey, trigger.target) {
+                HANDLER_STATUS_SUCCESS
+            } else {
+                HANDLER_STATUS_COMMAND_FAILED
+            }
+        });
+        let _: () = objc2::msg_send![command, setEnabled: true];
+        let target: *mut AnyObject = objc2::msg_send![command, addTargetWithHandler: &*handler];
+        registrations.push(Registration { command, target });
+    }
+
+    set_playback_state(PLAYBACK_STATE_PAUSED);
+    log::info!("Media key dictation enabled");
+}
+
+unsafe fn unregister(registrations: &mut Vec<Registration>) {
+    for Registration { command, target } in registrations.drain(..) {
+        let _: () = objc2::msg_send![command, removeTarget: target];
+    }
+    set_playback_state(PLAYBACK_STATE_STOPPED);
+    log::info!("Media key dictation disabled");
+}
@@ -0,0 +1,221 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::audio::{Recording, PIPELINE_RATE};
+use crate::loopback::LoopbackInput;
+use crate::resample::Converter;
+use crate::transcripts::{self, Cue, SessionKind};
+use crate::vad::{Segmenter, Utterance};
+
+/// How often both buffers are checked for finished utterances.
+const POLL: Duration = Duration::from_millis(500);
+/// Long monologues are cut here so the transcript keeps up with the meeting.
+const MAX_SEGMENT_MS: u32 = 20_000;
+
+/// Set while a meeting is recording; clearing it ends the meeting thread.
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// The transcript of the current or most recent meeting.
+static TRANSCRIPT: Mutex<Vec<MeetingSegment>> = Mutex::new(Vec::new());
+/// Export id of the most recent meeting, set once it has ended.
+static SESSION_ID: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Speaker {
+    /// The microphone.
+    Me,
+    /// System audio, i.e. everyone else on the call.
+    Others,
+}
+
+impl Speaker {
+    fn label(self) -> &'static str {
+        match self {
+            Speaker::Me => "Me",
+            Speaker::Others => "Others",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingSegment {
+    pub speaker: Speaker,
+    /// Offsets from the start of the meeting.
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingTranscript {
+    pub segments: Vec<MeetingSegment>,
+    /// `[hh:mm:ss] Me: ...` lines in time order, ready to hand to the assistant.
+    pub text: String,
+    /// Pass to `export_transcript`; `None` until the meeting has ended.
+    pub session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatePayload {
+    active: bool,
+}
+
+/// One side of the meeting: a 16 kHz buffer filled by its stream, plus how far into the
+/// meeting that stream started so both sides share one timeline.
+struct Channel {
+    speaker: Speaker,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    offset: usize,
+    segmenter: Segmenter,
+}
+
+impl Channel {
+    fn new(speaker: Speaker, started: Instant) -> Self {
+        let offset = (started.elapsed().as_secs_f64() * PIPELINE_RATE as f64) as usize;
+        Self { speaker, buffer: Arc::default(), offset, segmenter: Segmenter::splitting(MAX_SEGMENT_MS) }
+    }
+
+    /// A stream callback that resamples to the pipeline rate into this channel's buffer.
+    fn feeder(&self, input_rate: u32) -> Result<impl FnMut(&[f32]) + Send + 'static, String> {
+        let buffer = self.buffer.clone();
+        let mut converter = Converter::new(input_rate, PIPELINE_RATE)?;
+        let mut converted = Vec::new();
+        Ok(move |mono: &[f32]| {
+            converted.clear();
+            converter.process(mono, &mut converted);
+            buffer.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(&converted);
+        })
+    }
+
+    fn drain(&mut self, utterances: &mut Vec<(Speaker, Utterance)>, flush: bool) {
+        let fresh = std::mem::take(&mut *self.buffer.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut found = Vec::new();
+        self.segmenter.feed(&fresh, &mut found);
+        if flush {
+            self.segmenter.flush(&mut found);
+        }
+        utterances.extend(found.into_iter().map(|u| (self.speaker, Utterance { start: u.start + self.offset, ..u })));
+    }
+}
+
+fn to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / PIPELINE_RATE as u64
+}
+
+fn timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+fn snapshot() -> MeetingTranscript {
+    let mut segments = TRANSCRIPT.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    segments.sort_by_key(|s| s.start_ms);
+    let text = segments
+        .iter()
+        .map(|s| format!("[{}] {}: {}", timestamp(s.start_ms), s.speaker.label(), s.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let session_id = SESSION_ID.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    MeetingTranscript { segments, text, session_id }
+}
+
+fn transcribe(handle: &tauri::AppHandle, utterances: Vec<(Speaker, Utterance)>) {
+    for (speaker, utterance) in utterances {
+        let start_ms = to_ms(utterance.start);
+        let end_ms = to_ms(utterance.start + utterance.samples.len());
+        let recording = Recording { samples: utterance.samples, sample_rate: PIPELINE_RATE };
+        match crate::transcription::transcribe_with_fallback(handle, &recording) {
+            Ok(transcript) if !transcript.text.trim().is_empty() => {
+                let segment = MeetingSegment { speaker, start_ms, end_ms, text: transcript.text.trim().to_string() };
+                let _ = handle.emit("meeting:segment", segment.clone());
+                TRANSCRIPT.lock().unwrap_or_else(|e| e.into_inner()).push(segment);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Meeting segment transcription failed: {e}"),
+        }
+    }
+}
+
+/// Own both streams on this thread until `RUNNING` is cleared, transcribing each side's
+/// utterances as they finish.
+fn run(handle: &tauri::AppHandle) -> Result<(), String> {
+    let started = Instant::now();
+
+    let (device, config) = crate::audio::input(handle)?;
+    let mut mic = Channel::new(Speaker::Me, started);
+    let _mic_stream = crate::audio::open_stream(&device, &config, mic.feeder(config.sample_rate().0)?, || {
+        log::warn!("Meeting microphone disconnected")
+    })?;
+
+    let loopback = LoopbackInput::open()?;
+    let mut others = Channel::new(Speaker::Others, started);
+    let feeder = others.feeder(loopback.sample_rate())?;
+    let _loopback_stream = loopback.start(feeder, || log::warn!("Meeting system audio disconnected"))?;
+
+    log::info!("Meeting recording started");
+    loop {
+        std::thread::sleep(POLL);
+        let stopping = !RUNNING.load(Ordering::SeqCst);
+        let mut utterances = Vec::new();
+        mic.drain(&mut utterances, stopping);
+        others.drain(&mut utterances, stopping);
+        transcribe(handle, utterances);
+        if stopping {
+            return Ok(());
+        }
+    }
+}
+
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Record the microphone and system audio side by side, emitting `meeting:segment` as
+/// each utterance is transcribed. Speaker labels come from which stream it was heard on.
+#[tauri::command]
+pub fn start_meeting(app: tauri::AppHandle) -> Result<(), String> {
+    if !crate::audio::native_capture_enabled(&app) {
+        return Err("Turn on native capture to record meetings".to_string());
+    }
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A meeting is already being recorded".to_string());
+    }
+    TRANSCRIPT.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    *SESSION_ID.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    let _ = app.emit("meeting-state", StatePayload { active: true });
+    std::thread::spawn(move || {
+        if let Err(e) = run(&app) {
+            log::error!("Meeting recording failed: {e}");
+            let _ = app.emit("meeting:error", e);
+        }
+        let cues = snapshot()
+            .segments
+            .into_iter()
+            .map(|s| Cue { start_ms: s.start_ms, end_ms: s.end_ms, speaker: Some(s.speaker.label().to_string()), text: s.text })
+            .collect();
+        *SESSION_ID.lock().unwrap_or_else(|e| e.into_inner()) = Some(transcripts::record(SessionKind::Meeting, None, cues));
+        RUNNING.store(false, Ordering::SeqCst);
+        let _ = app.emit("meeting-state", StatePayload { active: false });
+        let _ = app.emit("meeting:transcript", snapshot());
+    });
+    Ok(())
+}
+
+/// Stop recording. The final segments are still being transcribed when this returns;
+/// `meeting:transcript` fires with the merged result once they are done.
+#[tauri::command]
+pub fn stop_meeting() -> bool {
+    RUNNING.swap(false, Ordering::SeqCst)
+}
+
+/// The merged transcript so far, or of the last meeting.
+#[tauri::command]
+pub fn get_meeting_transcript() -> MeetingTranscript {
+    snapshot()
+}
@@ -0,0 +1,168 @@
+use serde::Serialize;
+
+/// Whether the OS lets this app record from the microphone.
+// Only macOS reports every state.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MicrophoneAccess {
+    Granted,
+    /// The user turned it off; only they can turn it back on in system settings.
+    Denied,
+    /// Blocked by policy, e.g. parental controls or an administrator.
+    Restricted,
+    /// Not asked yet; the first capture will prompt.
+    NotDetermined,
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::MicrophoneAccess;
+    use block2::RcBlock;
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: *mut AnyObject;
+    }
+
+    const PRIVACY_SETTINGS_URL: &str = "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone";
+
+    pub fn check() -> MicrophoneAccess {
+        let Some(cls) = AnyClass::get("AVCaptureDevice") else { return MicrophoneAccess::Granted };
+        // AVAuthorizationStatus
+        let status: isize = unsafe { objc2::msg_send![cls, authorizationStatusForMediaType: AVMediaTypeAudio] };
+        match status {
+            0 => MicrophoneAccess::NotDetermined,
+            1 => MicrophoneAccess::Restricted,
+            2 => MicrophoneAccess::Denied,
+            _ => MicrophoneAccess::Granted,
+        }
+    }
+
+    /// Show the system prompt and wait for the answer. macOS only prompts once, so after a
+    /// refusal this opens the Microphone privacy pane instead.
+    pub fn request(handle: &tauri::AppHandle) -> MicrophoneAccess {
+        match check() {
+            MicrophoneAccess::NotDetermined => {}
+            MicrophoneAccess::Denied => {
+                super::open_settings(handle, PRIVACY_SETTINGS_URL);
+                return MicrophoneAccess::Denied;
+            }
+            status => return status,
+        }
+        let Some(cls) = AnyClass::get("AVCaptureDevice") else { return MicrophoneAccess::Granted };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handler = RcBlock::new(move |granted: Bool| {
+            let _ = tx.send(granted.as_bool());
+        });
+        unsafe {
+            let _: () = objc2::msg_send![cls, requestAccessForMediaType: AVMediaTypeAudio, completionHandler: &*handler];
+        }
+        match rx.recv() {
+            Ok(true) => MicrophoneAccess::Granted,
+            Ok(false) => MicrophoneAccess::Denied,
+            Err(_) => check(),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::MicrophoneAccess;
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    const CONSENT_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore\\microphone");
+    const NON_PACKAGED_KEY: PCWSTR =
+        w!("Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore\\microphone\\NonPackaged");
+    const PRIVACY_SETTINGS_URL: &str = "ms-settings:privacy-microphone";
+
+    /// Read a consent `Value` string, which is `Allow` or `Deny`; `None` when unset.
+    fn consent(root: HKEY, key: PCWSTR) -> Option<bool> {
+        let mut buffer = [0u16; 16];
+        let mut size = std::mem::size_of_val(&buffer) as u32;
+        let status = unsafe {
+            RegGetValueW(
+                root,
+                key,
+                w!("Value"),
+                RRF_RT_REG_SZ,
+                None,
+                Some(buffer.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return None;
+        }
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len]) == "Allow")
+    }
+
+    /// Desktop apps are covered by the device-wide switch, the per-user switch, and the
+    /// "let desktop apps access your microphone" switch, in that order.
+    pub fn check() -> MicrophoneAccess {
+        if consent(HKEY_LOCAL_MACHINE, CONSENT_KEY) == Some(false) {
+            return MicrophoneAccess::Restricted;
+        }
+        if consent(HKEY_CURRENT_USER, CONSENT_KEY) == Some(false)
+            || consent(HKEY_CURRENT_USER, NON_PACKAGED_KEY) == Some(false)
+        {
+            return MicrophoneAccess::Denied;
+        }
+        MicrophoneAccess::Granted
+    }
+
+    /// Desktop apps cannot raise a consent prompt, so send the user to the privacy page.
+    pub fn request(handle: &tauri::AppHandle) -> MicrophoneAccess {
+        let status = check();
+        if status != MicrophoneAccess::Granted {
+            super::open_settings(handle, PRIVACY_SETTINGS_URL);
+        }
+        status
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::MicrophoneAccess;
+
+    /// Linux has no per-app microphone permission outside of sandboxes.
+    pub fn check() -> MicrophoneAccess {
+        MicrophoneAccess::Granted
+    }
+
+    pub fn request(_handle: &tauri::AppHandle) -> MicrophoneAccess {
+        MicrophoneAccess::Granted
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn open_settings(handle: &tauri::AppHandle, url: &str) {
+    use tauri_plugin_opener::OpenerExt;
+    if let Err(e) = handle.opener().open_url(url, None::<&str>) {
+        log::warn!("Could not open microphone privacy settings: {e}");
+    }
+}
+
+/// Whether a recording would be refused by the OS. An undecided permission is let through
+/// because starting the capture is what shows the prompt.
+pub fn is_blocked() -> bool {
+    matches!(platform::check(), MicrophoneAccess::Denied | MicrophoneAccess::Restricted)
+}
+
+#[tauri::command]
+pub fn check_microphone_access() -> MicrophoneAccess {
+    platform::check()
+}
+
+/// Ask for microphone access, or open the system privacy settings when it can no longer
+/// be granted from a prompt. Resolves once the user has answered, with the status afterwards.
+#[tauri::command]
+pub async fn request_microphone_access(app: tauri::AppHandle) -> Result<MicrophoneAccess, String> {
+    tauri::async_runtime::spawn_blocking(move || platform::request(&app))
+        .await
+        .map_err(|e| format!("Microphone access request failed: {e}"))
+}
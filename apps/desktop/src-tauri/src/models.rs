@@ -0,0 +1,374 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+const MODELS_DIR: &str = "models";
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// Models offered for download, as `(id, approximate size in MB)`. Each is `ggml-<id>.bin`.
+const CATALOG: &[(&str, u64)] = &[
+    ("tiny", 75),
+    ("tiny.en", 75),
+    ("base", 142),
+    ("base.en", 142),
+    ("small", 466),
+    ("small.en", 466),
+    ("medium", 1533),
+    ("medium.en", 1533),
+    ("large-v3-turbo", 1624),
+];
+
+/// Cancel flags for downloads in progress, keyed by model id.
+static DOWNLOADS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn downloads() -> std::sync::MutexGuard<'static, HashMap<String, Arc<AtomicBool>>> {
+    DOWNLOADS.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperModel {
+    pub id: String,
+    pub path: PathBuf,
+    pub approx_size_mb: u64,
+    pub downloaded: bool,
+    /// Bytes of an interrupted download that the next attempt resumes from.
+    pub partial_bytes: u64,
+    pub downloading: bool,
+    /// Whether this is the model local transcription currently uses.
+    pub in_use: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadProgress {
+    id: String,
+    downloaded: u64,
+    total: Option<u64>,
+    /// `verifying`, `done`, `cancelled` or `failed` once the transfer has finished.
+    state: &'static str,
+}
+
+fn dir(handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let base = handle.path().app_data_dir().map_err(|e| format!("No app data dir: {e}"))?;
+    Ok(base.join(MODELS_DIR))
+}
+
+fn file_name(id: &str) -> Result<String, String> {
+    CATALOG
+        .iter()
+        .any(|(known, _)| *known == id)
+        .then(|| format!("ggml-{id}.bin"))
+        .ok_or_else(|| format!("Unknown Whisper model {id}"))
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    path.with_extension("bin.part")
+}
+
+fn emit(handle: &tauri::AppHandle, id: &str, downloaded: u64, total: Option<u64>, state: &'static str) {
+    let _ = handle.emit(
+        "model-download-progress",
+        DownloadProgress { id: id.to_string(), downloaded, total, state },
+    );
+}
+
+/// Hugging Face reports the SHA256 of LFS files in `X-Linked-Etag` on the redirect to
+/// the CDN, so the checksum comes from the same source as the file rather than a table here.
+fn expected_sha256(url: &str) -> Result<String, String> {
+    let agent = ureq::AgentBuilder::new().redirects(0).timeout(Duration::from_secs(30)).build();
+    let response = match agent.head(url).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(status, response)) if (300..400).contains(&status) => response,
+        Err(e) => return Err(format!("Failed to reach {url}: {e}")),
+    };
+    linked_sha256(response.header("x-linked-etag"))
+}
+
+fn linked_sha256(etag: Option<&str>) -> Result<String, String> {
+    etag.map(|etag| etag.trim_matches('"').to_lowercase())
+        .filter(|etag| etag.len() == 64 && etag.bytes().all(|b| b.is_ascii_hexdigit()))
+        .ok_or_else(|| "The model server did not report a checksum".to_string())
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_LEN];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// What a ranged request's status means for the `.part` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Resume {
+    /// 206: the body continues where the partial file ends.
+    Append,
+    /// The server ignored the range, so the body is the whole file.
+    Restart,
+    /// 416: the partial file is already complete.
+    Verify,
+}
+
+fn resume(status: u16) -> Resume {
+    match status {
+        206 => Resume::Append,
+        416 => Resume::Verify,
+        _ => Resume::Restart,
+    }
+}
+
+fn open_part(part: &Path, resume: Resume) -> Result<std::fs::File, String> {
+    let append = resume == Resume::Append;
+    std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(part)
+        .map_err(|e| format!("Failed to open {}: {e}", part.display()))
+}
+
+/// Fetch `id` into the models dir, resuming a `.part` file left by an earlier attempt.
+/// The file only gets its final name once the checksum matches.
+fn download(handle: &tauri::AppHandle, id: &str, cancel: &AtomicBool) -> Result<PathBuf, String> {
+    let name = file_name(id)?;
+    let dir = dir(handle)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let path = dir.join(&name);
+    let part = partial_path(&path);
+    let url = format!("{MODEL_BASE_URL}/{name}");
+
+    let expected = expected_sha256(&url)?;
+    let mut offset = std::fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+
+    let mut request = ureq::get(&url);
+    if offset > 0 {
+        request = request.set("Range", &format!("bytes={offset}-"));
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(416, response)) => response,
+        Err(e) => return Err(format!("Failed to download {name}: {e}")),
+    };
+    let resume = resume(response.status());
+    match resume {
+        Resume::Verify => return finish(handle, id, &part, &path, &expected, offset),
+        Resume::Restart => offset = 0,
+        Resume::Append => log::info!("Resuming {name} at {offset} bytes"),
+    }
+    let total = response
+        .header("Content-Length")
+        .and_then(|len| len.parse::<u64>().ok())
+        .map(|len| len + offset);
+
+    let mut file = open_part(&part, resume)?;
+
+    let mut reader = response.into_reader();
+    let mut buf = vec![0u8; CHUNK_LEN];
+    let mut downloaded = offset;
+    let mut last_progress = Instant::now();
+    loop {
+        if cancel.load(Ordering::SeqCst) {
+            emit(handle, id, downloaded, total, "cancelled");
+            return Err("Download cancelled".to_string());
+        }
+        let n = reader.read(&mut buf).map_err(|e| format!("Download of {name} interrupted: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buf[..n]).map_err(|e| format!("Failed to write {}: {e}", part.display()))?;
+        downloaded += n as u64;
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            emit(handle, id, downloaded, total, "downloading");
+            last_progress = Instant::now();
+        }
+    }
+    file.flush().map_err(|e| format!("Failed to write {}: {e}", part.display()))?;
+    drop(file);
+
+    finish(handle, id, &part, &path, &expected, downloaded)
+}
+
+fn finish(handle: &tauri::AppHandle, id: &str, part: &Path, path: &Path, expected: &str, size: u64) -> Result<PathBuf, String> {
+    emit(handle, id, size, Some(size), "verifying");
+    verify(id, part, path, expected)?;
+    emit(handle, id, size, Some(size), "done");
+    Ok(path.to_path_buf())
+}
+
+/// Move `part` to `path` if its checksum matches, otherwise delete it.
+fn verify(id: &str, part: &Path, path: &Path, expected: &str) -> Result<(), String> {
+    if sha256_file(part)? != expected {
+        // A corrupt partial would fail every resume, so start over next time.
+        let _ = std::fs::remove_file(part);
+        return Err(format!("Checksum mismatch for {id}, the download was discarded"));
+    }
+    std::fs::rename(part, path).map_err(|e| format!("Failed to move {}: {e}", path.display()))
+}
+
+#[tauri::command]
+pub fn list_whisper_models(app: tauri::AppHandle) -> Result<Vec<WhisperModel>, String> {
+    let dir = dir(&app)?;
+    let in_use = crate::config::read(&app, |c| c.whisper_model.clone());
+    let active = downloads();
+    CATALOG
+        .iter()
+        .map(|(id, approx_size_mb)| {
+            let path = dir.join(file_name(id)?);
+            Ok(WhisperModel {
+                id: id.to_string(),
+                approx_size_mb: *approx_size_mb,
+                downloaded: path.is_file(),
+                partial_bytes: std::fs::metadata(partial_path(&path)).map(|m| m.len()).unwrap_or(0),
+                downloading: active.contains_key(*id),
+                in_use: in_use.as_deref() == Some(path.as_path()),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Download a catalog model, reporting `model-download-progress`. Resolves to the model
+/// path, which can then be passed to `set_stt_backend`.
+#[tauri::command]
+pub async fn download_whisper_model(app: tauri::AppHandle, id: String) -> Result<PathBuf, String> {
+    file_name(&id)?;
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut active = downloads();
+        if active.contains_key(&id) {
+            return Err(format!("{id} is already downloading"));
+        }
+        active.insert(id.clone(), cancel.clone());
+    }
+
+    let task_id = id.clone();
+    let task_app = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || download(&task_app, &task_id, &cancel))
+        .await
+        .map_err(|e| format!("Model download task failed: {e}"))
+        .and_then(|r| r);
+
+    downloads().remove(&id);
+    if let Err(e) = &result {
+        log::warn!("Whisper model download failed: {e}");
+        emit(&app, &id, 0, None, "failed");
+    }
+    result
+}
+
+/// Stop a running download. The partial file is kept so the next attempt resumes.
+#[tauri::command]
+pub fn cancel_model_download(id: String) {
+    if let Some(cancel) = downloads().get(&id) {
+        cancel.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Remove a downloaded model and any partial download of it. The model local
+/// transcription is using cannot be deleted; switch away from it first.
+#[tauri::command]
+pub fn delete_whisper_model(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    if downloads().contains_key(&id) {
+        return Err(format!("{id} is still downloading"));
+    }
+    let path = dir(&app)?.join(file_name(&id)?);
+    if crate::config::read(&app, |c| c.whisper_model.as_deref() == Some(path.as_path())) {
+        return Err(format!("{id} is the active Whisper model"));
+    }
+    if crate::config::read(&app, |c| c.stt_power_policy.battery_model.as_deref() == Some(path.as_path())) {
+        return Err(format!("{id} is the Whisper model used on battery"));
+    }
+    for file in [partial_path(&path), path] {
+        match std::fs::remove_file(&file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to delete {}: {e}", file.display())),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spaceduck-models-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn only_names_catalog_models() {
+        assert_eq!(file_name("base.en").unwrap(), "ggml-base.en.bin");
+        assert_eq!(file_name("large-v3-turbo").unwrap(), "ggml-large-v3-turbo.bin");
+        assert!(file_name("huge").is_err());
+        assert!(file_name("../base").is_err());
+        assert!(file_name("").is_err());
+    }
+
+    #[test]
+    fn reads_the_checksum_from_the_linked_etag() {
+        let quoted = format!("\"{}\"", ABC_SHA256.to_uppercase());
+        assert_eq!(linked_sha256(Some(&quoted)).unwrap(), ABC_SHA256);
+        assert_eq!(linked_sha256(Some(ABC_SHA256)).unwrap(), ABC_SHA256);
+        assert!(linked_sha256(None).is_err());
+        assert!(linked_sha256(Some("\"abc123\"")).is_err());
+        assert!(linked_sha256(Some(&"z".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn maps_range_statuses_to_what_happens_to_the_partial() {
+        assert_eq!(resume(206), Resume::Append);
+        assert_eq!(resume(200), Resume::Restart);
+        assert_eq!(resume(416), Resume::Verify);
+    }
+
+    #[test]
+    fn appends_on_206_and_truncates_on_200() {
+        let dir = scratch("resume");
+        let part = dir.join("ggml-tiny.bin.part");
+        std::fs::write(&part, b"ab").unwrap();
+        open_part(&part, resume(206)).unwrap().write_all(b"c").unwrap();
+        assert_eq!(std::fs::read(&part).unwrap(), b"abc");
+        open_part(&part, resume(200)).unwrap().write_all(b"xy").unwrap();
+        assert_eq!(std::fs::read(&part).unwrap(), b"xy");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_a_verified_download_and_deletes_a_corrupt_one() {
+        let dir = scratch("verify");
+        let part = dir.join("ggml-tiny.bin.part");
+        let path = dir.join("ggml-tiny.bin");
+
+        std::fs::write(&part, b"abd").unwrap();
+        assert!(verify("tiny", &part, &path, ABC_SHA256).is_err());
+        assert!(!part.exists() && !path.exists());
+
+        std::fs::write(&part, b"abc").unwrap();
+        verify("tiny", &part, &path, ABC_SHA256).unwrap();
+        assert!(!part.exists());
+        assert_eq!(std::fs::read(&path).unwrap(), b"abc");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+// Only the macOS key monitor reports health so far.
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+/// This many re-enables inside `REENABLE_WINDOW` means the tap is effectively dead.
+const REENABLE_THRESHOLD: usize = 5;
+const REENABLE_WINDOW: Duration = Duration::from_secs(60);
+/// Consecutive failed installs before we stop assuming it is transient.
+const FAILURE_THRESHOLD: u64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DegradedReason {
+    /// macOS keeps disabling the tap, usually because the callback is too slow.
+    TapRepeatedlyDisabled,
+    /// The tap cannot be created, usually because Accessibility or Input Monitoring was revoked.
+    TapInstallFailing,
+}
+
+impl DegradedReason {
+    fn message(self) -> &'static str {
+        match self {
+            DegradedReason::TapRepeatedlyDisabled => {
+                "macOS keeps disabling the dictation key monitor; hotkeys may be unreliable"
+            }
+            DegradedReason::TapInstallFailing => {
+                "The dictation key monitor cannot start. Check Accessibility and Input Monitoring permissions"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorHealth {
+    pub supported: bool,
+    pub running: bool,
+    pub reenable_count: u64,
+    pub failure_count: u64,
+    pub last_error: Option<String>,
+    pub degraded: Option<DegradedReason>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DegradedPayload {
+    reason: DegradedReason,
+    message: &'static str,
+}
+
+#[derive(Default)]
+struct History {
+    recent_reenables: VecDeque<Instant>,
+    consecutive_failures: u64,
+    last_error: Option<String>,
+    degraded: Option<DegradedReason>,
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static REENABLE_COUNT: AtomicU64 = AtomicU64::new(0);
+static FAILURE_COUNT: AtomicU64 = AtomicU64::new(0);
+static HISTORY: Mutex<History> = Mutex::new(History {
+    recent_reenables: VecDeque::new(),
+    consecutive_failures: 0,
+    last_error: None,
+    degraded: None,
+});
+
+fn history() -> MutexGuard<'static, History> {
+    HISTORY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn degrade(handle: &tauri::AppHandle, history: &mut History, reason: DegradedReason) {
+    if history.degraded == Some(reason) {
+        return;
+    }
+    history.degraded = Some(reason);
+    log::error!("Hotkey monitor degraded: {}", reason.message());
+    let _ = handle.emit("dictation:degraded", DegradedPayload { reason, message: reason.message() });
+}
+
+/// The tap is installed and enabled. Clears an install-failure degradation.
+pub fn record_started() {
+    RUNNING.store(true, Ordering::SeqCst);
+    let mut history = history();
+    history.consecutive_failures = 0;
+    if history.degraded == Some(DegradedReason::TapInstallFailing) {
+        history.degraded = None;
+    }
+}
+
+/// macOS disabled the tap and we turned it back on.
+pub fn record_reenabled(handle: &tauri::AppHandle) {
+    REENABLE_COUNT.fetch_add(1, Ordering::SeqCst);
+    let now = Instant::now();
+    let mut history = history();
+    history.recent_reenables.push_back(now);
+    while history
+        .recent_reenables
+        .front()
+        .is_some_and(|t| now.duration_since(*t) > REENABLE_WINDOW)
+    {
+        history.recent_reenables.pop_front();
+    }
+    if history.recent_reenables.len() >= REENABLE_THRESHOLD {
+        degrade(handle, &mut history, DegradedReason::TapRepeatedlyDisabled);
+    }
+}
+
+/// The tap stopped or could not be installed.
+pub fn record_failure(handle: &tauri::AppHandle, error: &str) {
+    RUNNING.store(false, Ordering::SeqCst);
+    FAILURE_COUNT.fetch_add(1, Ordering::SeqCst);
+    let mut history = history();
+    history.consecutive_failures += 1;
+    history.last_error = Some(error.to_string());
+    if history.consecutive_failures >= FAILURE_THRESHOLD {
+        degrade(handle, &mut history, DegradedReason::TapInstallFailing);
+    }
+}
+
+#[tauri::command]
+pub fn get_hotkey_monitor_health() -> MonitorHealth {
+    let history = history();
+    MonitorHealth {
+        supported: cfg!(target_os = "macos"),
+        running: RUNNING.load(Ordering::SeqCst),
+        reenable_count: REENABLE_COUNT.load(Ordering::SeqCst),
+        failure_count: FAILURE_COUNT.load(Ordering::SeqCst),
+        last_error: history.last_error.clone(),
+        degraded: history.degraded,
+    }
+}
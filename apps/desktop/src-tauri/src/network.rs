@@ -0,0 +1,252 @@
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use tauri::Emitter;
+
+/// The OS callbacks get no user data we can use, so the handle lives here.
+static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static STATUS: Mutex<Option<NetworkStatus>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NetworkInterface {
+    Wifi,
+    Cellular,
+    Wired,
+    /// A VPN, a tether the OS cannot classify, and the like.
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+    pub online: bool,
+    /// The interface traffic goes out on, when online.
+    pub interface: Option<NetworkInterface>,
+    /// A cellular, hotspot or otherwise capped connection, where large downloads should wait.
+    pub metered: bool,
+}
+
+impl NetworkStatus {
+    #[cfg_attr(target_os = "macos", allow(dead_code))]
+    const OFFLINE: NetworkStatus = NetworkStatus { online: false, interface: None, metered: false };
+}
+
+/// Record `status` and emit `network-changed` when it differs from the last one. Queued
+/// transcriptions are retried as soon as the machine is back online.
+fn changed(status: NetworkStatus) {
+    let previous = STATUS.lock().unwrap_or_else(|e| e.into_inner()).replace(status.clone());
+    if previous.as_ref() == Some(&status) {
+        return;
+    }
+    log::info!("Network changed: {status:?}");
+    if status.online && previous.is_some_and(|previous| !previous.online) {
+        crate::stt_queue::wake();
+    }
+    if let Some(handle) = HANDLE.get() {
+        let _ = handle.emit("network-changed", status);
+    }
+}
+
+/// NWPathMonitor, which reports the current path as soon as it starts and again on every
+/// change, on a global dispatch queue.
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{NetworkInterface, NetworkStatus};
+    use block2::{Block, RcBlock};
+    use std::ffi::c_void;
+
+    /// nw_path_status_satisfied
+    const SATISFIED: i32 = 1;
+    const WIFI: i32 = 1;
+    const CELLULAR: i32 = 2;
+    const WIRED: i32 = 3;
+
+    #[link(name = "Network", kind = "framework")]
+    extern "C" {
+        fn nw_path_monitor_create() -> *mut c_void;
+        fn nw_path_monitor_set_update_handler(monitor: *mut c_void, handler: &Block<dyn Fn(*mut c_void)>);
+        fn nw_path_monitor_set_queue(monitor: *mut c_void, queue: *mut c_void);
+        fn nw_path_monitor_start(monitor: *mut c_void);
+        fn nw_path_get_status(path: *mut c_void) -> i32;
+        fn nw_path_is_expensive(path: *mut c_void) -> bool;
+        fn nw_path_is_constrained(path: *mut c_void) -> bool;
+        fn nw_path_uses_interface_type(path: *mut c_void, interface_type: i32) -> bool;
+    }
+
+    extern "C" {
+        fn dispatch_get_global_queue(identifier: isize, flags: usize) -> *mut c_void;
+    }
+
+    unsafe fn status(path: *mut c_void) -> NetworkStatus {
+        let online = nw_path_get_status(path) == SATISFIED;
+        let interface = online.then(|| {
+            [(WIFI, NetworkInterface::Wifi), (CELLULAR, NetworkInterface::Cellular), (WIRED, NetworkInterface::Wired)]
+                .into_iter()
+                .find(|(kind, _)| nw_path_uses_interface_type(path, *kind))
+                .map_or(NetworkInterface::Other, |(_, interface)| interface)
+        });
+        // Constrained is Low Data Mode, which the user turns on for capped networks.
+        let metered = online && (nw_path_is_expensive(path) || nw_path_is_constrained(path));
+        NetworkStatus { online, interface, metered }
+    }
+
+    pub fn start() {
+        unsafe {
+            let monitor = nw_path_monitor_create();
+            if monitor.is_null() {
+                log::warn!("Failed to watch the network: nw_path_monitor_create failed");
+                return;
+            }
+            let handler = RcBlock::new(|path: *mut c_void| super::changed(status(path)));
+            // The monitor copies the handler and runs for as long as the app does.
+            nw_path_monitor_set_update_handler(monitor, &handler);
+            nw_path_monitor_set_queue(monitor, dispatch_get_global_queue(0, 0));
+            nw_path_monitor_start(monitor);
+        }
+    }
+}
+
+/// The Network List Manager, through WinRT's NetworkInformation, which raises
+/// NetworkStatusChanged on a thread pool thread.
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{NetworkInterface, NetworkStatus};
+    use windows::Networking::Connectivity::{
+        NetworkConnectivityLevel, NetworkCostType, NetworkInformation, NetworkStatusChangedEventHandler,
+    };
+
+    /// IANA ifType for Ethernet.
+    const ETHERNET: u32 = 6;
+
+    fn current() -> NetworkStatus {
+        let Ok(profile) = NetworkInformation::GetInternetConnectionProfile() else { return NetworkStatus::OFFLINE };
+        let online = profile.GetNetworkConnectivityLevel().is_ok_and(|level| level == NetworkConnectivityLevel::InternetAccess);
+        if !online {
+            return NetworkStatus::OFFLINE;
+        }
+        let interface = if profile.IsWlanConnectionProfile().unwrap_or(false) {
+            NetworkInterface::Wifi
+        } else if profile.IsWwanConnectionProfile().unwrap_or(false) {
+            NetworkInterface::Cellular
+        } else if profile.NetworkAdapter().and_then(|adapter| adapter.IanaInterfaceType()).is_ok_and(|t| t == ETHERNET) {
+            NetworkInterface::Wired
+        } else {
+            NetworkInterface::Other
+        };
+        let metered = profile.GetConnectionCost().is_ok_and(|cost| {
+            let capped = cost
+                .NetworkCostType()
+                .is_ok_and(|kind| kind != NetworkCostType::Unrestricted && kind != NetworkCostType::Unknown);
+            capped || cost.Roaming().unwrap_or(false) || cost.OverDataLimit().unwrap_or(false)
+        });
+        NetworkStatus { online, interface: Some(interface), metered }
+    }
+
+    pub fn start() {
+        super::changed(current());
+        let handler = NetworkStatusChangedEventHandler::new(|_| {
+            super::changed(current());
+            Ok(())
+        });
+        // The registration lasts as long as the app; the token is only needed to remove it.
+        if let Err(e) = NetworkInformation::NetworkStatusChanged(&handler) {
+            log::warn!("Failed to watch the network: {e}");
+        }
+    }
+}
+
+/// A rtnetlink socket subscribed to link, address and route changes, checked against the
+/// default route. Linux has no standard notion of a metered connection outside
+/// NetworkManager, so `metered` stays false.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::{NetworkInterface, NetworkStatus};
+    use std::path::Path;
+
+    /// The interface of the default route, IPv4 or IPv6.
+    fn default_route_interface() -> Option<String> {
+        let ipv4 = std::fs::read_to_string("/proc/net/route").unwrap_or_default();
+        let ipv4 = ipv4.lines().skip(1).find_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let up = fields.get(3).and_then(|flags| u32::from_str_radix(flags, 16).ok()).is_some_and(|f| f & 1 != 0);
+            (fields.get(1) == Some(&"00000000") && up).then(|| fields[0].to_string())
+        });
+        ipv4.or_else(|| {
+            let ipv6 = std::fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default();
+            ipv6.lines().find_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let default = fields.first().is_some_and(|dest| dest.bytes().all(|b| b == b'0')) && fields.get(1) == Some(&"00");
+                let interface = fields.last().filter(|name| **name != "lo")?;
+                default.then(|| interface.to_string())
+            })
+        })
+    }
+
+    fn current() -> NetworkStatus {
+        let Some(name) = default_route_interface() else { return NetworkStatus::OFFLINE };
+        let sys = Path::new("/sys/class/net").join(&name);
+        let interface = if sys.join("wireless").exists() {
+            NetworkInterface::Wifi
+        } else if name.starts_with("wwan") {
+            NetworkInterface::Cellular
+        } else if sys.join("device").exists() {
+            NetworkInterface::Wired
+        } else {
+            NetworkInterface::Other
+        };
+        NetworkStatus { online: true, interface: Some(interface), metered: false }
+    }
+
+    pub fn start() {
+        super::changed(current());
+        let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_RAW | libc::SOCK_CLOEXEC, libc::NETLINK_ROUTE) };
+        if fd < 0 {
+            log::warn!("Failed to watch the network: {}", std::io::Error::last_os_error());
+            return;
+        }
+        let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as u16;
+        address.nl_groups = (libc::RTMGRP_LINK
+            | libc::RTMGRP_IPV4_IFADDR
+            | libc::RTMGRP_IPV6_IFADDR
+            | libc::RTMGRP_IPV4_ROUTE
+            | libc::RTMGRP_IPV6_ROUTE) as u32;
+        let bound = unsafe {
+            libc::bind(
+                fd,
+                &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as u32,
+            )
+        };
+        if bound < 0 {
+            log::warn!("Failed to watch the network: {}", std::io::Error::last_os_error());
+            unsafe { libc::close(fd) };
+            return;
+        }
+        std::thread::spawn(move || {
+            // What changed does not matter, only what the default route looks like after it.
+            let mut buffer = [0u8; 8192];
+            loop {
+                let read = unsafe { libc::recv(fd, buffer.as_mut_ptr().cast(), buffer.len(), 0) };
+                if read < 0 && std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                    log::warn!("Stopped watching the network: {}", std::io::Error::last_os_error());
+                    break;
+                }
+                super::changed(current());
+            }
+            unsafe { libc::close(fd) };
+        });
+    }
+}
+
+/// Watch for the machine going on or offline and for the connection changing.
+pub fn start(handle: &tauri::AppHandle) {
+    let _ = HANDLE.set(handle.clone());
+    platform::start();
+}
+
+/// The connection as last reported, or `None` before the first report.
+#[tauri::command]
+pub fn get_network_status() -> Option<NetworkStatus> {
+    STATUS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
@@ -0,0 +1,388 @@
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::Manager;
+use tungstenite::Message;
+
+/// The gateway session this listener connects as; it never sends, so it gets no chat.
+const SENDER: &str = "desktop-notifications";
+const READ_POLL: Duration = Duration::from_secs(30);
+/// Wait before reconnecting, e.g. while the sidecar is still starting or restarting.
+const RECONNECT: Duration = Duration::from_secs(5);
+const MAX_BODY_CHARS: usize = 200;
+/// How often held notifications check whether Do Not Disturb has ended.
+const DND_POLL: Duration = Duration::from_secs(30);
+/// Past this many, the oldest held notification is dropped.
+const MAX_HELD: usize = 20;
+
+/// The OS click callbacks get no user data we can use, so the handle lives here.
+static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+/// Notifications waiting for Do Not Disturb to end, oldest first.
+static HELD: Mutex<Vec<Held>> = Mutex::new(Vec::new());
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+
+struct Held {
+    title: String,
+    body: String,
+    conversation_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum ServerMessage {
+    #[serde(rename = "task.completed", rename_all = "camelCase")]
+    TaskCompleted { task_id: String, name: String, route: String, conversation_id: Option<String>, result: Option<String> },
+    #[serde(rename = "task.progress", rename_all = "camelCase")]
+    TaskProgress { task_id: String, state: String },
+    #[serde(other)]
+    Other,
+}
+
+/// Whether the user is looking at the app, in which case a finished task shows up there.
+fn app_focused(app: &tauri::AppHandle) -> bool {
+    app.webview_windows().values().any(|w| w.is_focused().unwrap_or(false))
+}
+
+fn on_message(app: &tauri::AppHandle, text: &str) {
+    let (task_id, name, route, conversation_id, result) = match serde_json::from_str(text) {
+        Ok(ServerMessage::TaskCompleted { task_id, name, route, conversation_id, result }) => {
+            (task_id, name, route, conversation_id, result)
+        }
+        Ok(ServerMessage::TaskProgress { task_id, state }) => {
+            crate::task_progress::progress(app, task_id, &state);
+            return;
+        }
+        Ok(ServerMessage::Other) | Err(_) => return,
+    };
+    crate::task_progress::finished(app, &task_id);
+    // Reminders always alert; other results only when nobody would see them come in.
+    let wanted = match route.as_str() {
+        "notify" => true,
+        "silent" => false,
+        _ => !app_focused(app),
+    };
+    if !wanted || !crate::config::read(app, |c| c.notifications_enabled) {
+        return;
+    }
+    let body: String = result.unwrap_or_else(|| "Task complete".to_string()).chars().take(MAX_BODY_CHARS).collect();
+    deliver(app, Held { title: name, body: body.trim().to_string(), conversation_id }, route == "notify");
+}
+
+/// Show `notification` unless Do Not Disturb is on, in which case it is held until it
+/// ends or dropped, as configured. Reminders get through when the user allowed that.
+fn deliver(app: &tauri::AppHandle, notification: Held, reminder: bool) {
+    let (during, reminders_override) = crate::config::read(app, |c| (c.notifications_during_dnd, c.reminders_override_dnd));
+    if !crate::dnd::status().active || (reminder && reminders_override) {
+        notify(app, &notification.title, &notification.body, notification.conversation_id);
+        return;
+    }
+    match during {
+        crate::dnd::DuringDnd::Suppress => log::debug!("Dropped a notification during Do Not Disturb"),
+        crate::dnd::DuringDnd::Queue => {
+            let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+            if held.len() >= MAX_HELD {
+                held.remove(0);
+            }
+            held.push(notification);
+            drop(held);
+            flush_when_quiet_ends(app);
+        }
+    }
+}
+
+/// Show the held notifications once Do Not Disturb is off.
+fn flush_when_quiet_ends(app: &tauri::AppHandle) {
+    if FLUSHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        while crate::dnd::status().active {
+            std::thread::sleep(DND_POLL);
+        }
+        FLUSHING.store(false, Ordering::SeqCst);
+        let held = std::mem::take(&mut *HELD.lock().unwrap_or_else(|e| e.into_inner()));
+        for notification in held {
+            notify(&app, &notification.title, &notification.body, notification.conversation_id);
+        }
+    });
+}
+
+/// Listen to the gateway for background events for as long as the app runs, reconnecting
+/// when it goes away. Task progress is relayed to the Dock and taskbar.
+pub fn start(app: &tauri::AppHandle) {
+    let _ = HANDLE.set(app.clone());
+    platform::init();
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        let gateway = crate::config::read(&app, |c| c.gateway.clone());
+        match crate::gateway_stream::connect(&gateway, &format!("/ws?senderId={SENDER}"), READ_POLL) {
+            Ok(mut socket) => {
+                let wakes = crate::sleep_wake::wakes();
+                loop {
+                    match socket.read() {
+                        Ok(Message::Text(text)) => on_message(&app, &text),
+                        Ok(Message::Close(_)) => break,
+                        Ok(_) => {}
+                        Err(tungstenite::Error::Io(e))
+                            if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+                        {
+                            // A connection from before a sleep may be dead without having noticed.
+                            if crate::sleep_wake::wakes() != wakes {
+                                log::debug!("Reconnecting the notification listener after sleep");
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            log::debug!("Notification listener disconnected: {e}");
+                            break;
+                        }
+                    }
+                }
+                // Tasks that finish while we are away would never be cleared.
+                crate::task_progress::reset(&app);
+            }
+            Err(e) => log::debug!("Notification listener cannot reach the gateway: {e}"),
+        }
+        std::thread::sleep(RECONNECT);
+    });
+}
+
+/// A notification was clicked.
+fn activate(conversation_id: Option<String>) {
+    if let Some(app) = HANDLE.get() {
+        crate::conversation_windows::show(app, conversation_id);
+    }
+}
+
+/// Show a native notification; clicking it opens `conversation_id`.
+pub fn notify(app: &tauri::AppHandle, title: &str, body: &str, conversation_id: Option<String>) {
+    if let Err(e) = platform::show(app, title, body, conversation_id) {
+        log::warn!("Failed to show a notification: {e}");
+    }
+}
+
+/// NSUserNotificationCenter, with a delegate that hears clicks and shows notifications
+/// even while the app is frontmost.
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::declare::ClassBuilder;
+    use objc2::runtime::{AnyClass, AnyObject, Bool, Sel};
+    use std::ffi::CString;
+
+    const CONVERSATION_KEY: &std::ffi::CStr = c"conversationId";
+
+    unsafe fn ns_string(text: &str) -> *mut AnyObject {
+        let text = CString::new(text.replace('\0', "")).unwrap_or_default();
+        objc2::msg_send![objc2::class!(NSString), stringWithUTF8String: text.as_ptr()]
+    }
+
+    unsafe fn conversation_key() -> *mut AnyObject {
+        objc2::msg_send![objc2::class!(NSString), stringWithUTF8String: CONVERSATION_KEY.as_ptr()]
+    }
+
+    extern "C" fn did_activate(_this: &AnyObject, _sel: Sel, _center: *mut AnyObject, notification: *mut AnyObject) {
+        let id = unsafe {
+            let info: *mut AnyObject = objc2::msg_send![notification, userInfo];
+            if info.is_null() {
+                None
+            } else {
+                let value: *mut AnyObject = objc2::msg_send![info, objectForKey: conversation_key()];
+                crate::frontmost::nsstring_to_string(value)
+            }
+        };
+        super::activate(id);
+    }
+
+    extern "C" fn should_present(_this: &AnyObject, _sel: Sel, _center: *mut AnyObject, _n: *mut AnyObject) -> Bool {
+        Bool::YES
+    }
+
+    fn center() -> *mut AnyObject {
+        unsafe { objc2::msg_send![objc2::class!(NSUserNotificationCenter), defaultUserNotificationCenter] }
+    }
+
+    pub fn init() {
+        let Some(mut builder) = ClassBuilder::new("SpaceduckNotificationDelegate", objc2::class!(NSObject)) else {
+            return;
+        };
+        unsafe {
+            builder.add_method(
+                objc2::sel!(userNotificationCenter:didActivateNotification:),
+                did_activate as extern "C" fn(_, _, _, _),
+            );
+            builder.add_method(
+                objc2::sel!(userNotificationCenter:shouldPresentNotification:),
+                should_present as extern "C" fn(_, _, _, _) -> _,
+            );
+        }
+        let class: &AnyClass = builder.register();
+        // The center holds its delegate weakly; this one lives as long as the app.
+        unsafe {
+            let delegate: *mut AnyObject = objc2::msg_send![class, new];
+            let _: () = objc2::msg_send![center(), setDelegate: delegate];
+        }
+    }
+
+    pub fn show(app: &tauri::AppHandle, title: &str, body: &str, conversation_id: Option<String>) -> Result<(), String> {
+        let (title, body) = (title.to_string(), body.to_string());
+        app.run_on_main_thread(move || unsafe {
+            let notification: *mut AnyObject = objc2::msg_send![objc2::class!(NSUserNotification), new];
+            let _: () = objc2::msg_send![notification, setTitle: ns_string(&title)];
+            let _: () = objc2::msg_send![notification, setInformativeText: ns_string(&body)];
+            if let Some(id) = conversation_id {
+                let info: *mut AnyObject = objc2::msg_send![
+                    objc2::class!(NSDictionary),
+                    dictionaryWithObject: ns_string(&id),
+                    forKey: conversation_key()
+                ];
+                let _: () = objc2::msg_send![notification, setUserInfo: info];
+            }
+            let _: () = objc2::msg_send![center(), deliverNotification: notification];
+            let _: () = objc2::msg_send![notification, release];
+        })
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// A toast under the app's AppUserModelID, which the installer registers as the bundle
+/// identifier.
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::TypedEventHandler;
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+
+    pub fn init() {}
+
+    pub fn show(app: &tauri::AppHandle, title: &str, body: &str, conversation_id: Option<String>) -> Result<(), String> {
+        let xml = format!(
+            "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual></toast>",
+            escape(title),
+            escape(body)
+        );
+        let err = |e: windows::core::Error| format!("Toast failed: {e}");
+        let document = XmlDocument::new().map_err(err)?;
+        document.LoadXml(&HSTRING::from(xml)).map_err(err)?;
+        let toast = ToastNotification::CreateToastNotification(&document).map_err(err)?;
+        toast
+            .Activated(&TypedEventHandler::new(move |_, _| {
+                super::activate(conversation_id.clone());
+                Ok(())
+            }))
+            .map_err(err)?;
+        let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(&app.config().identifier))
+            .map_err(err)?;
+        notifier.Show(&toast).map_err(err)?;
+        // Toasts are not kept by the notifier; without this the click handler would go
+        // with it. One small object per notification.
+        std::mem::forget(toast);
+        Ok(())
+    }
+}
+
+/// org.freedesktop.Notifications over the session bus, listening for its default action.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use gtk::gio;
+    use gtk::glib::{self, ToVariant};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    const BUS_NAME: &str = "org.freedesktop.Notifications";
+    const OBJECT_PATH: &str = "/org/freedesktop/Notifications";
+
+    /// Conversation to open for each notification id the server handed back.
+    static PENDING: Mutex<Vec<(u32, Option<String>)>> = Mutex::new(Vec::new());
+
+    fn connection() -> Result<gio::DBusConnection, String> {
+        gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE).map_err(|e| format!("No session bus: {e}"))
+    }
+
+    pub fn init() {
+        let connection = match connection() {
+            Ok(connection) => connection,
+            Err(e) => {
+                log::warn!("Notification clicks unavailable: {e}");
+                return;
+            }
+        };
+        // The subscription lasts as long as the connection, which is shared and never closed.
+        let _ = connection.signal_subscribe(
+            Some(BUS_NAME),
+            Some(BUS_NAME),
+            None,
+            Some(OBJECT_PATH),
+            None,
+            gio::DBusSignalFlags::NONE,
+            |_, _, _, _, signal, parameters| {
+                let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+                match signal {
+                    "ActionInvoked" => {
+                        let Some((id, _action)) = parameters.get::<(u32, String)>() else { return };
+                        if let Some(index) = pending.iter().position(|(n, _)| *n == id) {
+                            let (_, conversation_id) = pending.remove(index);
+                            drop(pending);
+                            super::activate(conversation_id);
+                        }
+                    }
+                    "NotificationClosed" => {
+                        if let Some((id, _reason)) = parameters.get::<(u32, u32)>() {
+                            pending.retain(|(n, _)| *n != id);
+                        }
+                    }
+                    _ => {}
+                }
+            },
+        );
+    }
+
+    pub fn show(_app: &tauri::AppHandle, title: &str, body: &str, conversation_id: Option<String>) -> Result<(), String> {
+        let hints: HashMap<String, glib::Variant> = HashMap::new();
+        let parameters = (
+            "spaceduck",
+            0u32,
+            "spaceduck",
+            title,
+            body,
+            vec!["default", "Open"],
+            hints,
+            -1i32,
+        )
+            .to_variant();
+        let reply = connection()?
+            .call_sync(
+                Some(BUS_NAME),
+                OBJECT_PATH,
+                BUS_NAME,
+                "Notify",
+                Some(&parameters),
+                Some(glib::VariantTy::new("(u)").expect("static type")),
+                gio::DBusCallFlags::NONE,
+                -1,
+                gio::Cancellable::NONE,
+            )
+            .map_err(|e| format!("Notify failed: {e}"))?;
+        if let Some((id,)) = reply.get::<(u32,)>() {
+            PENDING.lock().unwrap_or_else(|e| e.into_inner()).push((id, conversation_id));
+        }
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub fn get_notifications_enabled(app: tauri::AppHandle) -> bool {
+    crate::config::read(&app, |c| c.notifications_enabled)
+}
+
+/// Turn native notifications for background events on or off.
+#[tauri::command]
+pub fn set_notifications_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.notifications_enabled = enabled)
+}
@@ -0,0 +1,332 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// An image to read text from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OcrSource {
+    /// A file, such as a screenshot.
+    Path(PathBuf),
+    /// Whatever image is on the clipboard.
+    Clipboard,
+}
+
+/// Where a line sits, as fractions of the image's width and height from its top left.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Bounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrLine {
+    pub text: String,
+    /// From 0 to 1, when the engine reports one.
+    pub confidence: Option<f32>,
+    pub bounds: Bounds,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrText {
+    /// Every line in the engine's reading order, joined by newlines.
+    pub text: String,
+    pub lines: Vec<OcrLine>,
+    /// `vision`, `windows` or `tesseract`.
+    pub engine: &'static str,
+}
+
+/// Vision's VNRecognizeTextRequest.
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{Bounds, OcrLine};
+    use objc2::encode::{Encode, Encoding};
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+
+    #[link(name = "Vision", kind = "framework")]
+    extern "C" {}
+
+    /// VNRequestTextRecognitionLevelAccurate
+    const LEVEL_ACCURATE: isize = 0;
+
+    #[repr(C)]
+    struct Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    }
+
+    unsafe impl Encode for Rect {
+        const ENCODING: Encoding = Encoding::Struct(
+            "CGRect",
+            &[
+                Encoding::Struct("CGPoint", &[f64::ENCODING, f64::ENCODING]),
+                Encoding::Struct("CGSize", &[f64::ENCODING, f64::ENCODING]),
+            ],
+        );
+    }
+
+    unsafe fn lines(request: *mut AnyObject) -> Vec<OcrLine> {
+        let results: *mut AnyObject = objc2::msg_send![request, results];
+        if results.is_null() {
+            return Vec::new();
+        }
+        let count: usize = objc2::msg_send![results, count];
+        let mut lines = Vec::with_capacity(count);
+        for i in 0..count {
+            let observation: *mut AnyObject = objc2::msg_send![results, objectAtIndex: i];
+            let candidates: *mut AnyObject = objc2::msg_send![observation, topCandidates: 1usize];
+            let candidate: *mut AnyObject = objc2::msg_send![candidates, firstObject];
+            if candidate.is_null() {
+                continue;
+            }
+            let text: *mut AnyObject = objc2::msg_send![candidate, string];
+            let Some(text) = crate::frontmost::nsstring_to_string(text) else { continue };
+            let confidence: f32 = objc2::msg_send![candidate, confidence];
+            // Normalized, with the origin at the bottom left.
+            let rect: Rect = objc2::msg_send![observation, boundingBox];
+            lines.push(OcrLine {
+                text,
+                confidence: Some(confidence),
+                bounds: Bounds { x: rect.x, y: 1.0 - rect.y - rect.height, width: rect.width, height: rect.height },
+            });
+        }
+        lines
+    }
+
+    pub fn recognize(image: &[u8]) -> Result<(Vec<OcrLine>, &'static str), String> {
+        let (Some(handler_class), Some(request_class)) =
+            (AnyClass::get("VNImageRequestHandler"), AnyClass::get("VNRecognizeTextRequest"))
+        else {
+            return Err("Text recognition needs macOS 10.15 or later".to_string());
+        };
+        unsafe {
+            let data: *mut AnyObject = objc2::msg_send![
+                objc2::class!(NSData),
+                dataWithBytes: image.as_ptr().cast::<std::ffi::c_void>(),
+                length: image.len()
+            ];
+            let options: *mut AnyObject = objc2::msg_send![objc2::class!(NSDictionary), dictionary];
+            let handler: *mut AnyObject = objc2::msg_send![handler_class, alloc];
+            let handler: *mut AnyObject = objc2::msg_send![handler, initWithData: data, options: options];
+            if handler.is_null() {
+                return Err("Failed to read the image".to_string());
+            }
+            let request: *mut AnyObject = objc2::msg_send![request_class, new];
+            let _: () = objc2::msg_send![request, setRecognitionLevel: LEVEL_ACCURATE];
+            let _: () = objc2::msg_send![request, setUsesLanguageCorrection: Bool::YES];
+            let requests: *mut AnyObject = objc2::msg_send![objc2::class!(NSArray), arrayWithObject: request];
+            let mut error: *mut AnyObject = std::ptr::null_mut();
+            let performed: Bool = objc2::msg_send![handler, performRequests: requests, error: &mut error];
+            let result = if performed.as_bool() {
+                Ok((lines(request), "vision"))
+            } else {
+                let description: *mut AnyObject =
+                    if error.is_null() { std::ptr::null_mut() } else { objc2::msg_send![error, localizedDescription] };
+                Err(format!(
+                    "Text recognition failed: {}",
+                    crate::frontmost::nsstring_to_string(description).unwrap_or_else(|| "unknown error".to_string())
+                ))
+            };
+            let _: () = objc2::msg_send![request, release];
+            let _: () = objc2::msg_send![handler, release];
+            result
+        }
+    }
+}
+
+/// Windows.Media.Ocr in the user's languages, or Tesseract when none of them has an OCR
+/// language pack.
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{Bounds, OcrLine};
+    use windows::Graphics::Imaging::BitmapDecoder;
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Storage::Streams::{DataWriter, InMemoryRandomAccessStream};
+
+    pub fn recognize(image: &[u8]) -> Result<(Vec<OcrLine>, &'static str), String> {
+        let Ok(engine) = OcrEngine::TryCreateFromUserProfileLanguages() else {
+            return super::tesseract(image).map(|lines| (lines, "tesseract"));
+        };
+        let err = |e: windows::core::Error| format!("Text recognition failed: {e}");
+        let writer = DataWriter::new().map_err(err)?;
+        writer.WriteBytes(image).map_err(err)?;
+        let stream = InMemoryRandomAccessStream::new().map_err(err)?;
+        stream.WriteAsync(&writer.DetachBuffer().map_err(err)?).map_err(err)?.get().map_err(err)?;
+        stream.Seek(0).map_err(err)?;
+        let bitmap = BitmapDecoder::CreateAsync(&stream)
+            .and_then(|decoding| decoding.get())
+            .and_then(|decoder| decoder.GetSoftwareBitmapAsync())
+            .and_then(|decoding| decoding.get())
+            .map_err(|e| format!("Failed to read the image: {e}"))?;
+        let (width, height) = (bitmap.PixelWidth().map_err(err)? as f64, bitmap.PixelHeight().map_err(err)? as f64);
+        let max = OcrEngine::MaxImageDimension().map_err(err)? as f64;
+        if width > max || height > max {
+            return Err(format!("The image is too large to read (max {max} pixels a side)"));
+        }
+        let result = engine.RecognizeAsync(&bitmap).map_err(err)?.get().map_err(err)?;
+        let mut lines = Vec::new();
+        for line in result.Lines().map_err(err)? {
+            let text = line.Text().map_err(err)?.to_string();
+            // A line's box is the union of its words'.
+            let (mut left, mut top, mut right, mut bottom) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+            for word in line.Words().map_err(err)? {
+                let rect = word.BoundingRect().map_err(err)?;
+                left = left.min(rect.X);
+                top = top.min(rect.Y);
+                right = right.max(rect.X + rect.Width);
+                bottom = bottom.max(rect.Y + rect.Height);
+            }
+            if right < left {
+                continue;
+            }
+            lines.push(OcrLine {
+                text,
+                confidence: None,
+                bounds: Bounds {
+                    x: left as f64 / width,
+                    y: top as f64 / height,
+                    width: (right - left) as f64 / width,
+                    height: (bottom - top) as f64 / height,
+                },
+            });
+        }
+        Ok((lines, "windows"))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::OcrLine;
+
+    pub fn recognize(image: &[u8]) -> Result<(Vec<OcrLine>, &'static str), String> {
+        super::tesseract(image).map(|lines| (lines, "tesseract"))
+    }
+}
+
+/// Run the `tesseract` command line tool, if it is installed, and group the words of its
+/// TSV output into lines.
+#[cfg(not(target_os = "macos"))]
+fn tesseract(image: &[u8]) -> Result<Vec<OcrLine>, String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut command = Command::new("tesseract");
+    command.args(["stdin", "stdout", "tsv"]).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        /// CREATE_NO_WINDOW
+        const NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(NO_WINDOW);
+    }
+    let mut child = command.spawn().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => "No text recognition is available; install Tesseract to enable it".to_string(),
+        _ => format!("Failed to run tesseract: {e}"),
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(image).map_err(|e| format!("Failed to pass the image to tesseract: {e}"))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("tesseract failed: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("tesseract could not read the image ({})", output.status));
+    }
+
+    // level page block paragraph line word left top width height confidence text
+    let (mut page_width, mut page_height) = (1.0, 1.0);
+    let mut lines: Vec<TesseractLine> = Vec::new();
+    for row in String::from_utf8_lossy(&output.stdout).lines().skip(1) {
+        let fields: Vec<&str> = row.splitn(12, '\t').collect();
+        let [level, _, block, paragraph, line, _, left, top, width, height, confidence, text] = fields[..] else {
+            continue;
+        };
+        let number = |field: &str| field.trim().parse::<f64>().unwrap_or(0.0);
+        let (left, top, width, height) = (number(left), number(top), number(width), number(height));
+        match level {
+            "1" => (page_width, page_height) = (width.max(1.0), height.max(1.0)),
+            "5" if !text.trim().is_empty() => {
+                let key = (number(block) as u32, number(paragraph) as u32, number(line) as u32);
+                let confidence = number(confidence) as f32 / 100.0;
+                match lines.last_mut() {
+                    Some(last) if last.key == key => {
+                        last.words.push(text.trim().to_string());
+                        last.confidence += confidence;
+                        last.right = last.right.max(left + width);
+                        last.top = last.top.min(top);
+                        last.bottom = last.bottom.max(top + height);
+                    }
+                    _ => lines.push(TesseractLine {
+                        key,
+                        words: vec![text.trim().to_string()],
+                        confidence,
+                        left,
+                        top,
+                        right: left + width,
+                        bottom: top + height,
+                    }),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(lines
+        .into_iter()
+        .map(|line| OcrLine {
+            confidence: Some(line.confidence / line.words.len() as f32),
+            text: line.words.join(" "),
+            bounds: Bounds {
+                x: line.left / page_width,
+                y: line.top / page_height,
+                width: (line.right - line.left) / page_width,
+                height: (line.bottom - line.top) / page_height,
+            },
+        })
+        .collect())
+}
+
+/// A line of Tesseract's words, in pixels, while they are gathered.
+#[cfg(not(target_os = "macos"))]
+struct TesseractLine {
+    /// Block, paragraph and line number.
+    key: (u32, u32, u32),
+    words: Vec<String>,
+    /// Summed over the words.
+    confidence: f32,
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+}
+
+/// The encoded image behind `source`.
+fn image_bytes(app: &tauri::AppHandle, source: &OcrSource) -> Result<Vec<u8>, String> {
+    match source {
+        OcrSource::Path(path) => std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display())),
+        OcrSource::Clipboard => {
+            let image = app.clipboard().read_image().map_err(|_| "There is no image on the clipboard".to_string())?;
+            let capture = crate::screenshot::Capture { width: image.width(), height: image.height(), rgba: image.rgba().to_vec() };
+            capture.to_png()
+        }
+    }
+}
+
+/// Read the text in an image on this machine, without sending it anywhere.
+pub fn recognize(app: &tauri::AppHandle, source: &OcrSource) -> Result<OcrText, String> {
+    let image = image_bytes(app, source)?;
+    let (lines, engine) = platform::recognize(&image)?;
+    let text = lines.iter().map(|line| line.text.as_str()).collect::<Vec<_>>().join("\n");
+    Ok(OcrText { text, lines, engine })
+}
+
+/// Read the text in a screenshot or the clipboard's image, with where each line is.
+#[tauri::command]
+pub async fn ocr_image(app: tauri::AppHandle, source: OcrSource) -> Result<OcrText, String> {
+    tauri::async_runtime::spawn_blocking(move || recognize(&app, &source))
+        .await
+        .map_err(|e| format!("Text recognition failed: {e}"))?
+}
@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use tauri_plugin_opener::OpenerExt;
+
+/// Files the OS would run rather than show, which `open_path` only reveals.
+const RUNNABLE: &[&str] = &[
+    "app", "appimage", "bat", "cmd", "com", "command", "desktop", "exe", "jar", "lnk", "msi", "ps1", "scr", "sh", "tool",
+    "vbs", "workflow",
+];
+
+/// The folders the app writes to: data, config, cache and logs.
+fn allowed_dirs(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let resolver = app.path();
+    [resolver.app_data_dir(), resolver.app_config_dir(), resolver.app_cache_dir(), resolver.app_log_dir()]
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .collect()
+}
+
+/// `path` with links and `..` resolved, if it exists inside one of `allowed_dirs`.
+fn validate(app: &tauri::AppHandle, path: &Path) -> Result<PathBuf, String> {
+    let resolved = path.canonicalize().map_err(|e| format!("{}: {e}", path.display()))?;
+    if !allowed_dirs(app).iter().any(|dir| resolved.starts_with(dir)) {
+        return Err(format!("{} is outside spaceduck's data folders", path.display()));
+    }
+    Ok(resolved)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+fn is_runnable(path: &Path) -> bool {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    RUNNABLE.contains(&extension.as_str()) || is_executable(path)
+}
+
+/// Open a file or folder the app wrote, such as a generated file or a log, in its default
+/// app. Anything outside the app's own folders is refused, and programs and scripts are
+/// revealed instead of run.
+#[tauri::command]
+pub fn open_path(app: tauri::AppHandle, path: PathBuf) -> Result<(), String> {
+    let path = validate(&app, &path)?;
+    if is_runnable(&path) {
+        log::info!("Revealing {} instead of running it", path.display());
+        return reveal(&app, &path);
+    }
+    app.opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .map_err(|e| format!("Failed to open {}: {e}", path.display()))
+}
+
+fn reveal(app: &tauri::AppHandle, path: &Path) -> Result<(), String> {
+    app.opener().reveal_item_in_dir(path).map_err(|e| format!("Failed to show {}: {e}", path.display()))
+}
+
+/// Show a file the app wrote selected in Finder, Explorer or the Linux file manager.
+#[tauri::command]
+pub fn reveal_in_file_manager(app: tauri::AppHandle, path: PathBuf) -> Result<(), String> {
+    let path = validate(&app, &path)?;
+    reveal(&app, &path)
+}
@@ -0,0 +1,550 @@
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::Manager;
+
+const MAX_HIDE_DELAY_MS: u32 = 60_000;
+/// Logical size of the expanded panel at scale 1; it is never narrower than the pill.
+const EXPANDED_SIZE: (f64, f64) = (420.0, 180.0);
+const WIDTH_RANGE: RangeInclusive<f64> = 160.0..=800.0;
+const HEIGHT_RANGE: RangeInclusive<f64> = 32.0..=200.0;
+const SCALE_RANGE: RangeInclusive<f64> = 0.5..=3.0;
+/// How long growing or shrinking takes, and in how many steps.
+const RESIZE_DURATION: Duration = Duration::from_millis(160);
+const RESIZE_STEPS: u32 = 8;
+
+/// When the dictation pill is on screen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PillConfig {
+    /// Only show the pill during global dictations; otherwise it stays up all the time.
+    pub auto_hide: bool,
+    /// How long the pill stays after a dictation is delivered, so errors can be read.
+    pub hide_delay_ms: u32,
+    /// Let clicks through to the app underneath while recording.
+    pub click_through: bool,
+    /// Where the pill goes on a display it was never dragged on.
+    pub placement: PillPlacement,
+    /// Keep the pill on every Space or virtual desktop, so switching mid-dictation does
+    /// not leave it behind.
+    pub all_spaces: bool,
+    /// Compact size at scale 1, in logical pixels.
+    pub width: f64,
+    pub height: f64,
+    /// Enlarges the pill and everything in it, for large displays or low vision.
+    pub scale: f64,
+}
+
+impl Default for PillConfig {
+    fn default() -> Self {
+        Self {
+            auto_hide: true,
+            hide_delay_ms: 2500,
+            click_through: true,
+            placement: PillPlacement::default(),
+            all_spaces: true,
+            width: 280.0,
+            height: 48.0,
+            scale: 1.0,
+        }
+    }
+}
+
+/// Where the pill is put on the display under the cursor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PillPlacement {
+    /// Above the Dock or taskbar.
+    #[default]
+    BottomCenter,
+    /// At the top, under the menu bar and notch on macOS.
+    TopCenter,
+    /// Next to the mouse pointer, wherever it is when dictation starts.
+    NearCursor,
+}
+
+/// Bumped whenever the pill is shown, so a hide scheduled for an earlier dictation
+/// does not take down the one that followed it.
+static SHOWN: AtomicU64 = AtomicU64::new(0);
+/// Bumped for every resize, so a newer one stops the animation of an older one.
+static RESIZING: AtomicU64 = AtomicU64::new(0);
+static EXPANDED: AtomicBool = AtomicBool::new(false);
+
+/// The pill as a small bar, or as a panel with the live transcript and a cancel button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PillState {
+    Compact,
+    Expanded,
+}
+
+fn window(handle: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    handle.get_webview_window("dictation")
+}
+
+impl PillConfig {
+    /// Logical size of the compact pill, scale applied.
+    pub fn compact_size(&self) -> (f64, f64) {
+        (self.width * self.scale, self.height * self.scale)
+    }
+
+    fn expanded_size(&self) -> (f64, f64) {
+        (EXPANDED_SIZE.0.max(self.width) * self.scale, EXPANDED_SIZE.1.max(self.height) * self.scale)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.hide_delay_ms > MAX_HIDE_DELAY_MS {
+            return Err(format!("The hide delay must be at most {MAX_HIDE_DELAY_MS} ms"));
+        }
+        if !WIDTH_RANGE.contains(&self.width) || !HEIGHT_RANGE.contains(&self.height) {
+            return Err(format!(
+                "The pill must be {}-{} wide and {}-{} high",
+                WIDTH_RANGE.start(),
+                WIDTH_RANGE.end(),
+                HEIGHT_RANGE.start(),
+                HEIGHT_RANGE.end()
+            ));
+        }
+        if !SCALE_RANGE.contains(&self.scale) {
+            return Err(format!("The pill scale must be {}-{}", SCALE_RANGE.start(), SCALE_RANGE.end()));
+        }
+        Ok(())
+    }
+}
+
+/// Logical size of the compact pill, scale applied.
+pub fn compact_size(handle: &tauri::AppHandle) -> (f64, f64) {
+    crate::config::read(handle, |c| c.pill.compact_size())
+}
+
+/// Size the pill window and zoom its content by the configured scale. An expanded pill
+/// takes the new size when it shrinks back.
+pub fn apply_size(handle: &tauri::AppHandle, pill: &tauri::WebviewWindow) {
+    let config = crate::config::read(handle, |c| c.pill.clone());
+    if let Err(e) = pill.set_zoom(config.scale) {
+        log::warn!("Failed to zoom the pill: {e}");
+    }
+    if !EXPANDED.load(Ordering::SeqCst) {
+        RESIZING.fetch_add(1, Ordering::SeqCst);
+        let (width, height) = config.compact_size();
+        let _ = pill.set_size(tauri::LogicalSize::new(width, height));
+    }
+}
+
+/// Lift the pill above full-screen apps and presentations. Always-on-top alone is the
+/// floating level, which macOS leaves behind on the desktop Space when an app goes full
+/// screen; as a full-screen auxiliary at the pop-up menu level it shows on full-screen
+/// Spaces too. With `all_spaces` it is on every Space at once, otherwise it is brought
+/// onto the active one each time it is shown.
+#[cfg(target_os = "macos")]
+pub fn make_overlay(pill: &tauri::WebviewWindow, all_spaces: bool) -> Result<(), String> {
+    use cocoa::appkit::{NSWindow, NSWindowCollectionBehavior};
+    use cocoa::base::id;
+
+    /// NSPopUpMenuWindowLevel
+    const OVERLAY_LEVEL: i64 = 101;
+
+    let ns_window = pill.ns_window().map_err(|e| e.to_string())? as id;
+    unsafe {
+        let all = NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces;
+        let active = NSWindowCollectionBehavior::NSWindowCollectionBehaviorMoveToActiveSpace;
+        // The two are exclusive; AppKit throws if both are set.
+        let behavior = (ns_window.collectionBehavior() & !(all | active))
+            | if all_spaces { all } else { active }
+            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary
+            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorStationary
+            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorIgnoresCycle;
+        ns_window.setCollectionBehavior_(behavior);
+        ns_window.setLevel_(OVERLAY_LEVEL);
+    }
+    Ok(())
+}
+
+/// Make the pill a topmost window that never takes activation, so it is kept out of
+/// Alt+Tab and stays above borderless full-screen apps. With `all_spaces` it is also a
+/// tool window, which Windows shows on every virtual desktop.
+#[cfg(target_os = "windows")]
+pub fn make_overlay(pill: &tauri::WebviewWindow, all_spaces: bool) -> Result<(), String> {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetWindowLongPtrW, GWL_EXSTYLE, WS_EX_APPWINDOW, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
+        WS_EX_TOPMOST,
+    };
+
+    let hwnd = pill.hwnd().map_err(|e| e.to_string())?;
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        let tool = if all_spaces { WS_EX_TOOLWINDOW.0 } else { 0 };
+        let style = (style & !((WS_EX_APPWINDOW.0 | WS_EX_TOOLWINDOW.0) as isize))
+            | (tool | WS_EX_NOACTIVATE.0 | WS_EX_TOPMOST.0) as isize;
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style);
+    }
+    raise(pill);
+    Ok(())
+}
+
+/// Linux has no level above always-on-top to lift the pill to; sticky windows are on
+/// every workspace.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn make_overlay(pill: &tauri::WebviewWindow, all_spaces: bool) -> Result<(), String> {
+    pill.set_visible_on_all_workspaces(all_spaces).map_err(|e| e.to_string())
+}
+
+/// Windows puts any window made topmost later, a full-screen app's included, above ours,
+/// so the pill goes back to the top of that band whenever it is shown.
+#[cfg(target_os = "windows")]
+fn raise(pill: &tauri::WebviewWindow) {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SetWindowPos, HWND_TOPMOST, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
+    };
+
+    let Ok(hwnd) = pill.hwnd() else { return };
+    let flags = SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_FRAMECHANGED;
+    if let Err(e) = unsafe { SetWindowPos(hwnd, Some(HWND_TOPMOST), 0, 0, 0, 0, flags) } {
+        log::debug!("Failed to raise the pill: {e}");
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn raise(_pill: &tauri::WebviewWindow) {}
+
+/// Gap between the pill and the Dock or taskbar, the menu bar or the cursor, in points.
+const BOTTOM_GAP: f64 = 16.0;
+const TOP_GAP: f64 = 8.0;
+const CURSOR_GAP: f64 = 24.0;
+
+/// The notch's height, or 0 on displays without one (and before macOS 12).
+#[cfg(target_os = "macos")]
+unsafe fn notch_height(screen: cocoa::base::id) -> f64 {
+    use objc2::runtime::{AnyObject, Sel};
+    use objc2::{Encode, Encoding};
+
+    #[repr(C)]
+    struct NSEdgeInsets {
+        top: f64,
+        left: f64,
+        bottom: f64,
+        right: f64,
+    }
+    unsafe impl Encode for NSEdgeInsets {
+        const ENCODING: Encoding =
+            Encoding::Struct("NSEdgeInsets", &[f64::ENCODING, f64::ENCODING, f64::ENCODING, f64::ENCODING]);
+    }
+
+    let screen: *mut AnyObject = screen.cast();
+    let responds: bool = objc2::msg_send![screen, respondsToSelector: Sel::register("safeAreaInsets")];
+    if !responds {
+        return 0.0;
+    }
+    let insets: NSEdgeInsets = objc2::msg_send![screen, safeAreaInsets];
+    insets.top
+}
+
+/// Put the pill where the user last dragged it on the display under the cursor, or else
+/// where the placement preset says on that display.
+#[cfg(target_os = "macos")]
+pub fn reposition(app: &tauri::AppHandle) {
+    use cocoa::appkit::NSScreen;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::{NSArray, NSPoint, NSRect};
+
+    let Some(pill) = app.get_webview_window("dictation") else { return };
+    if let Some(position) = crate::pill_position::saved(app) {
+        let _ = pill.set_position(position);
+        return;
+    }
+    let placement = crate::config::read(app, |c| c.pill.placement);
+    let (pill_w, pill_h) = compact_size(app);
+
+    unsafe {
+        let mouse: NSPoint = cocoa::appkit::NSEvent::mouseLocation(nil);
+        let screens = NSScreen::screens(nil);
+        let count = NSArray::count(screens);
+        if count == 0 {
+            return;
+        }
+        let contains = |frame: NSRect| {
+            mouse.x >= frame.origin.x
+                && mouse.x <= frame.origin.x + frame.size.width
+                && mouse.y >= frame.origin.y
+                && mouse.y <= frame.origin.y + frame.size.height
+        };
+        let screen: id = (0..count)
+            .map(|i| NSArray::objectAtIndex(screens, i))
+            .find(|&s| contains(NSScreen::frame(s)))
+            .unwrap_or_else(|| NSScreen::mainScreen(nil));
+        if screen == nil {
+            return;
+        }
+        let frame = NSScreen::frame(screen);
+        let visible = NSScreen::visibleFrame(screen);
+        let centre_x = frame.origin.x + (frame.size.width - pill_w) / 2.0;
+
+        // Cocoa's y grows upwards from the bottom of the primary display, which is always
+        // the first screen (not mainScreen, which follows focus); the pill's y is for its
+        // bottom edge here and flipped to its top edge, y down, at the end.
+        let (x, y) = match placement {
+            // The visible frame leaves out the Dock wherever it is, and the sliver it keeps
+            // when it hides itself.
+            PillPlacement::BottomCenter => {
+                (visible.origin.x + (visible.size.width - pill_w) / 2.0, visible.origin.y + BOTTOM_GAP)
+            }
+            PillPlacement::TopCenter => {
+                // Below the menu bar, or the notch when the menu bar hides itself.
+                let top = (visible.origin.y + visible.size.height)
+                    .min(frame.origin.y + frame.size.height - notch_height(screen));
+                (centre_x, top - TOP_GAP - pill_h)
+            }
+            PillPlacement::NearCursor => {
+                let x = (mouse.x - pill_w / 2.0)
+                    .clamp(visible.origin.x, visible.origin.x + visible.size.width - pill_w);
+                let below = mouse.y - CURSOR_GAP - pill_h;
+                let y = if below >= visible.origin.y { below } else { mouse.y + CURSOR_GAP };
+                (x, y.min(visible.origin.y + visible.size.height - pill_h))
+            }
+        };
+        let primary_h = NSScreen::frame(NSArray::objectAtIndex(screens, 0)).size.height;
+        let _ = pill.set_position(tauri::LogicalPosition::new(x, primary_h - y - pill_h));
+    }
+}
+
+/// The work area already leaves out a taskbar that stays up, but takes in one that hides
+/// itself and pops up over the pill; this is how much of `monitor`'s bottom edge such a
+/// taskbar covers. Windows only reports the primary display's taskbar.
+#[cfg(target_os = "windows")]
+fn hidden_taskbar_height(monitor: &tauri::Monitor) -> i32 {
+    use windows::Win32::UI::Shell::{SHAppBarMessage, ABE_BOTTOM, ABM_GETSTATE, ABM_GETTASKBARPOS, ABS_AUTOHIDE, APPBARDATA};
+
+    let mut data = APPBARDATA { cbSize: std::mem::size_of::<APPBARDATA>() as u32, ..Default::default() };
+    unsafe {
+        if SHAppBarMessage(ABM_GETSTATE, &mut data) as u32 & ABS_AUTOHIDE == 0 {
+            return 0;
+        }
+        if SHAppBarMessage(ABM_GETTASKBARPOS, &mut data) == 0 || data.uEdge != ABE_BOTTOM {
+            return 0;
+        }
+    }
+    let (origin, size) = (monitor.position(), monitor.size());
+    let rect = data.rc;
+    let on_monitor = rect.left < origin.x + size.width as i32
+        && rect.right > origin.x
+        && rect.bottom > origin.y
+        && rect.top < origin.y + size.height as i32;
+    if on_monitor {
+        rect.bottom - rect.top
+    } else {
+        0
+    }
+}
+
+/// Panels are left out of the work area whether they hide or not.
+#[cfg(target_os = "linux")]
+fn hidden_taskbar_height(_monitor: &tauri::Monitor) -> i32 {
+    0
+}
+
+/// Put the pill where the user last dragged it on the display under the cursor, or else
+/// where the placement preset says within the work area of the display under the cursor,
+/// falling back to the primary display where the cursor position is unknown (Wayland).
+#[cfg(not(target_os = "macos"))]
+pub fn reposition(app: &tauri::AppHandle) {
+    let Some(pill) = app.get_webview_window("dictation") else { return };
+    if let Some(position) = crate::pill_position::saved(app) {
+        let _ = pill.set_position(position);
+        return;
+    }
+    let cursor = app.cursor_position().ok();
+    let monitor = cursor
+        .as_ref()
+        .and_then(|p| app.monitor_from_point(p.x, p.y).ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten());
+    let Some(monitor) = monitor else { return };
+
+    let placement = crate::config::read(app, |c| c.pill.placement);
+    let scale = monitor.scale_factor();
+    let (pill_w, pill_h) = compact_size(app);
+    let (pill_w, pill_h) = ((pill_w * scale) as i32, (pill_h * scale) as i32);
+    let gap = |points: f64| (points * scale) as i32;
+    let area = monitor.work_area();
+    let (left, top) = (area.position.x, area.position.y);
+    let (right, bottom) = (left + area.size.width as i32, top + area.size.height as i32 - hidden_taskbar_height(&monitor));
+    let centre_x = left + (area.size.width as i32 - pill_w) / 2;
+
+    let (x, y) = match (placement, cursor) {
+        (PillPlacement::TopCenter, _) => (centre_x, top + gap(TOP_GAP)),
+        (PillPlacement::NearCursor, Some(cursor)) => {
+            let x = (cursor.x as i32 - pill_w / 2).clamp(left, (right - pill_w).max(left));
+            let below = cursor.y as i32 + gap(CURSOR_GAP);
+            let y = if below + pill_h <= bottom { below } else { cursor.y as i32 - gap(CURSOR_GAP) - pill_h };
+            (x, y.max(top))
+        }
+        _ => (centre_x, bottom - pill_h - gap(BOTTOM_GAP)),
+    };
+    let _ = pill.set_position(tauri::PhysicalPosition::new(x, y));
+}
+
+/// A global dictation started: bring the pill up where it belongs.
+pub fn show(handle: &tauri::AppHandle) {
+    SHOWN.fetch_add(1, Ordering::SeqCst);
+    let click_through = crate::config::read(handle, |c| c.pill.click_through);
+    if let Some(pill) = window(handle) {
+        // A dictation that ended expanded must not leave the next one starting that way.
+        if EXPANDED.swap(false, Ordering::SeqCst) {
+            apply_size(handle, &pill);
+        }
+        reposition(handle);
+        let _ = pill.set_ignore_cursor_events(click_through);
+        let _ = pill.show();
+        raise(&pill);
+    }
+}
+
+/// Whether all of the pill is on one display.
+fn on_screen(handle: &tauri::AppHandle, pill: &tauri::WebviewWindow) -> bool {
+    let (Ok(position), Ok(size)) = (pill.outer_position(), pill.outer_size()) else { return false };
+    handle.available_monitors().unwrap_or_default().iter().any(|m| {
+        let (origin, extent) = (m.position(), m.size());
+        position.x >= origin.x
+            && position.y >= origin.y
+            && position.x + size.width as i32 <= origin.x + extent.width as i32
+            && position.y + size.height as i32 <= origin.y + extent.height as i32
+    })
+}
+
+/// Displays were added, removed, moved or rescaled: size the pill for its display's scale
+/// again and put it back in place. One showing a dictation is left alone while it is
+/// still on screen, so it does not jump under the cursor mid-sentence.
+pub fn displays_changed(handle: &tauri::AppHandle) {
+    let Some(pill) = window(handle) else { return };
+    apply_size(handle, &pill);
+    if crate::dictation::is_active() && on_screen(handle, &pill) {
+        return;
+    }
+    reposition(handle);
+}
+
+/// A global dictation is over, delivered or not: hide the pill after the configured delay
+/// unless another one starts first.
+pub fn idle(handle: &tauri::AppHandle) {
+    // The result may need a click, and the pill can be dragged again.
+    if let Some(pill) = window(handle) {
+        let _ = pill.set_ignore_cursor_events(false);
+    }
+    let config = crate::config::read(handle, |c| c.pill.clone());
+    if !config.auto_hide {
+        return;
+    }
+    let shown = SHOWN.load(Ordering::SeqCst);
+    let handle = handle.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(config.hide_delay_ms as u64));
+        if SHOWN.load(Ordering::SeqCst) != shown || crate::dictation::is_active() {
+            return;
+        }
+        if let Some(pill) = window(&handle) {
+            let _ = pill.hide();
+        }
+    });
+}
+
+/// Make the pill purely visual, passing clicks to the app underneath, or interactive again,
+/// as when it expands to show something that can be clicked.
+#[tauri::command]
+pub fn set_pill_click_through(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let pill = window(&app).ok_or("The pill window is gone")?;
+    pill.set_ignore_cursor_events(enabled).map_err(|e| format!("Failed to change click-through: {e}"))
+}
+
+/// Grow the pill into the transcript panel or shrink it back, keeping the middle of its
+/// bottom edge where it is so it stays put above the dock.
+#[tauri::command]
+pub fn set_pill_state(app: tauri::AppHandle, state: PillState) -> Result<(), String> {
+    let pill = window(&app).ok_or("The pill window is gone")?;
+    let expanded = state == PillState::Expanded;
+    if EXPANDED.swap(expanded, Ordering::SeqCst) == expanded {
+        return Ok(());
+    }
+    let scale = pill.scale_factor().map_err(|e| e.to_string())?;
+    let position = pill.outer_position().map_err(|e| e.to_string())?;
+    let size = pill.outer_size().map_err(|e| e.to_string())?;
+    let config = crate::config::read(&app, |c| c.pill.clone());
+    let (width, height) = if expanded { config.expanded_size() } else { config.compact_size() };
+    let from = (size.width as f64, size.height as f64);
+    let to = (width * scale, height * scale);
+    let centre_x = position.x as f64 + from.0 / 2.0;
+    let bottom = position.y as f64 + from.1;
+    if expanded {
+        // The cancel button has to be clickable.
+        let _ = pill.set_ignore_cursor_events(false);
+    }
+
+    let generation = RESIZING.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || {
+        for step in 1..=RESIZE_STEPS {
+            if RESIZING.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let t = step as f64 / RESIZE_STEPS as f64;
+            // Ease out, so the change starts quick and lands softly.
+            let t = 1.0 - (1.0 - t).powi(3);
+            let w = from.0 + (to.0 - from.0) * t;
+            let h = from.1 + (to.1 - from.1) * t;
+            let _ = pill.set_size(tauri::PhysicalSize::new(w.round() as u32, h.round() as u32));
+            let _ = pill.set_position(tauri::PhysicalPosition::new(
+                (centre_x - w / 2.0).round() as i32,
+                (bottom - h).round() as i32,
+            ));
+            std::thread::sleep(RESIZE_DURATION / RESIZE_STEPS);
+        }
+    });
+    Ok(())
+}
+
+/// Switch the placement preset. Spots the pill was dragged to are forgotten, since they
+/// would otherwise win over the preset on every display.
+#[tauri::command]
+pub fn set_pill_placement(app: tauri::AppHandle, placement: PillPlacement) -> Result<(), String> {
+    crate::config::update(&app, |c| {
+        c.pill.placement = placement;
+        c.pill_positions.clear();
+    })?;
+    reposition(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_pill_config(app: tauri::AppHandle) -> PillConfig {
+    crate::config::read(&app, |c| c.pill.clone())
+}
+
+/// Resize the pill live: `width` and `height` at scale 1, in logical pixels, and `scale`
+/// enlarging the pill and its content together.
+#[tauri::command]
+pub fn set_pill_size(app: tauri::AppHandle, width: f64, height: f64, scale: f64) -> Result<(), String> {
+    let config = PillConfig { width, height, scale, ..crate::config::read(&app, |c| c.pill.clone()) };
+    config.validate()?;
+    crate::config::update(&app, |c| c.pill = config)?;
+    let pill = window(&app).ok_or("The pill window is gone")?;
+    apply_size(&app, &pill);
+    if !EXPANDED.load(Ordering::SeqCst) {
+        reposition(&app);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_pill_config(app: tauri::AppHandle, config: PillConfig) -> Result<(), String> {
+    config.validate()?;
+    let (auto_hide, all_spaces) = (config.auto_hide, config.all_spaces);
+    crate::config::update(&app, |c| c.pill = config)?;
+    if let Some(pill) = window(&app) {
+        make_overlay(&pill, all_spaces).map_err(|e| format!("Failed to change the pill's Spaces: {e}"))?;
+        apply_size(&app, &pill);
+        if !auto_hide {
+            let _ = pill.show();
+        } else if !crate::dictation::is_active() {
+            let _ = pill.hide();
+        }
+    }
+    Ok(())
+}
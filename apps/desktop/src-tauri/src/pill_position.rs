@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+/// A move that has been still this long ends the drag.
+const SETTLE: Duration = Duration::from_millis(500);
+
+/// Where the user dragged the pill on one display, in logical pixels from its top left.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PillPosition {
+    /// Display name as the OS reports it.
+    pub monitor: String,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Set from `start_pill_drag` until the pill stops moving, so our own repositioning is
+/// not saved as a custom spot.
+static DRAGGING: AtomicBool = AtomicBool::new(false);
+static LAST_MOVE: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn last_move() -> std::sync::MutexGuard<'static, Option<Instant>> {
+    LAST_MOVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start moving the pill with the mouse; its new spot is remembered for that display.
+#[tauri::command]
+pub fn start_pill_drag(app: tauri::AppHandle, window: tauri::WebviewWindow) -> Result<(), String> {
+    if !DRAGGING.swap(true, Ordering::SeqCst) {
+        *last_move() = Some(Instant::now());
+        std::thread::spawn(move || {
+            while last_move().is_some_and(|t| t.elapsed() < SETTLE) {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            DRAGGING.store(false, Ordering::SeqCst);
+            if let Err(e) = save(&app) {
+                log::warn!("Failed to save the pill position: {e}");
+            }
+        });
+    }
+    window.start_dragging().map_err(|e| format!("Failed to start dragging: {e}"))
+}
+
+/// The pill's `Moved` events; they only count while a drag is under way.
+pub fn moved() {
+    if DRAGGING.load(Ordering::SeqCst) {
+        *last_move() = Some(Instant::now());
+    }
+}
+
+fn save(app: &tauri::AppHandle) -> Result<(), String> {
+    let pill = app.get_webview_window("dictation").ok_or("The pill window is gone")?;
+    let monitor = pill
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or("The pill is not on any display")?;
+    let name = monitor.name().cloned().ok_or("The display has no name")?;
+    let scale = monitor.scale_factor();
+    let position = pill.outer_position().map_err(|e| e.to_string())?;
+    let saved = PillPosition {
+        monitor: name,
+        x: (position.x - monitor.position().x) as f64 / scale,
+        y: (position.y - monitor.position().y) as f64 / scale,
+    };
+    crate::config::update(app, |c| {
+        c.pill_positions.retain(|p| p.monitor != saved.monitor);
+        c.pill_positions.push(saved);
+    })
+}
+
+/// Where the pill was dragged to on the display under the cursor, if it ever was.
+pub fn saved(app: &tauri::AppHandle) -> Option<tauri::PhysicalPosition<i32>> {
+    let cursor = app.cursor_position().ok()?;
+    let monitor = app.monitor_from_point(cursor.x, cursor.y).ok()??;
+    let name = monitor.name()?;
+    let saved = crate::config::read(app, |c| c.pill_positions.iter().find(|p| &p.monitor == name).cloned())?;
+    let scale = monitor.scale_factor();
+    Some(tauri::PhysicalPosition::new(
+        monitor.position().x + (saved.x * scale) as i32,
+        monitor.position().y + (saved.y * scale) as i32,
+    ))
+}
+
+/// Forget the dragged positions and go back to placing the pill above the dock.
+#[tauri::command]
+pub fn reset_pill_positions(app: tauri::AppHandle) -> Result<(), String> {
+    crate::config::update(&app, |c| c.pill_positions.clear())?;
+    crate::pill::reposition(&app);
+    Ok(())
+}
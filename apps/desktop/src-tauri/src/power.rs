@@ -0,0 +1,243 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// How often the power source is checked for `battery-changed`.
+const POLL: Duration = Duration::from_secs(30);
+/// At or below this charge, on battery, background work backs off like in Low Power Mode.
+const LOW_LEVEL: u8 = 20;
+
+static STATUS: Mutex<Option<BatteryStatus>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryStatus {
+    /// Charge from 0 to 100; `None` without a battery.
+    pub level: Option<u8>,
+    pub charging: bool,
+    /// Running on battery rather than mains power; `None` where the platform does not say.
+    pub on_battery: Option<bool>,
+    /// macOS Low Power Mode, Windows battery saver, or the low-power platform profile on Linux.
+    pub low_power_mode: bool,
+}
+
+impl BatteryStatus {
+    pub fn unplugged(&self) -> bool {
+        self.on_battery == Some(true)
+    }
+
+    /// Whether background work should back off: Low Power Mode is on, or the battery is
+    /// running low.
+    pub fn saving_power(&self) -> bool {
+        self.low_power_mode || (self.unplugged() && self.level.is_some_and(|level| level <= LOW_LEVEL))
+    }
+}
+
+/// Whether the machine is running on battery. `None` when the platform does not say,
+/// e.g. on a desktop without a battery.
+#[cfg(target_os = "macos")]
+pub fn on_battery() -> Option<bool> {
+    use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
+    use core_foundation::string::{CFString, CFStringRef};
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+        fn IOPSGetProvidingPowerSourceType(snapshot: CFTypeRef) -> CFStringRef;
+    }
+
+    unsafe {
+        let info = IOPSCopyPowerSourcesInfo();
+        if info.is_null() {
+            return None;
+        }
+        let source = IOPSGetProvidingPowerSourceType(info);
+        let result = (!source.is_null()).then(|| CFString::wrap_under_get_rule(source) == "Battery Power");
+        CFRelease(info);
+        result
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn on_battery() -> Option<bool> {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+/// Reads sysfs: on battery when there is a battery and no mains supply is online.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn on_battery() -> Option<bool> {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+    let mut battery = false;
+    let mut mains_online = false;
+    for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+        match read(entry.path().join("type")).as_deref() {
+            Some("Battery") => battery = true,
+            Some("Mains") => mains_online |= read(entry.path().join("online")).as_deref() == Some("1"),
+            _ => {}
+        }
+    }
+    battery.then_some(!mains_online)
+}
+
+/// Charge and whether it is charging, from the internal battery's power source description.
+#[cfg(target_os = "macos")]
+fn battery_level() -> (Option<u8>, bool) {
+    use core_foundation::array::{CFArray, CFArrayRef};
+    use core_foundation::base::{CFRelease, CFType, CFTypeRef, TCFType};
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+        fn IOPSCopyPowerSourcesList(blob: CFTypeRef) -> CFArrayRef;
+        fn IOPSGetPowerSourceDescription(blob: CFTypeRef, source: CFTypeRef) -> CFDictionaryRef;
+    }
+
+    unsafe {
+        let info = IOPSCopyPowerSourcesInfo();
+        if info.is_null() {
+            return (None, false);
+        }
+        let list = IOPSCopyPowerSourcesList(info);
+        let mut result = (None, false);
+        if !list.is_null() {
+            let sources: CFArray<CFType> = CFArray::wrap_under_create_rule(list);
+            for source in sources.iter() {
+                let description = IOPSGetPowerSourceDescription(info, source.as_CFTypeRef());
+                if description.is_null() {
+                    continue;
+                }
+                let description: CFDictionary<CFString, CFType> = CFDictionary::wrap_under_get_rule(description);
+                let number = |key: &'static str| {
+                    description.find(CFString::from_static_string(key)).and_then(|v| v.downcast::<CFNumber>()).and_then(|n| n.to_i64())
+                };
+                let is_battery = description
+                    .find(CFString::from_static_string("Type"))
+                    .and_then(|v| v.downcast::<CFString>())
+                    .is_some_and(|kind| kind == "InternalBattery");
+                let (Some(current), Some(max)) = (number("Current Capacity"), number("Max Capacity")) else { continue };
+                if !is_battery || max <= 0 {
+                    continue;
+                }
+                let charging = description
+                    .find(CFString::from_static_string("Is Charging"))
+                    .and_then(|v| v.downcast::<CFBoolean>())
+                    .is_some_and(bool::from);
+                result = (Some((current * 100 / max).clamp(0, 100) as u8), charging);
+                break;
+            }
+        }
+        CFRelease(info);
+        result
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn low_power_mode() -> bool {
+    use objc2::runtime::{AnyObject, Bool};
+
+    unsafe {
+        let info: *mut AnyObject = objc2::msg_send![objc2::class!(NSProcessInfo), processInfo];
+        // Low Power Mode came to the Mac in macOS 12.
+        let supported: Bool = objc2::msg_send![info, respondsToSelector: objc2::sel!(isLowPowerModeEnabled)];
+        supported.as_bool() && {
+            let enabled: Bool = objc2::msg_send![info, isLowPowerModeEnabled];
+            enabled.as_bool()
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn power_status() -> Option<windows::Win32::System::Power::SYSTEM_POWER_STATUS> {
+    let mut status = windows::Win32::System::Power::SYSTEM_POWER_STATUS::default();
+    unsafe { windows::Win32::System::Power::GetSystemPowerStatus(&mut status) }.ok()?;
+    Some(status)
+}
+
+#[cfg(target_os = "windows")]
+fn battery_level() -> (Option<u8>, bool) {
+    /// BatteryFlag bits.
+    const CHARGING: u8 = 8;
+    const NO_BATTERY: u8 = 128;
+    const UNKNOWN: u8 = 255;
+
+    match power_status() {
+        Some(status) if status.BatteryFlag != UNKNOWN && status.BatteryFlag & NO_BATTERY == 0 => {
+            let level = (status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent);
+            (level, status.BatteryFlag & CHARGING != 0)
+        }
+        _ => (None, false),
+    }
+}
+
+/// Battery saver, reported in SystemStatusFlag.
+#[cfg(target_os = "windows")]
+fn low_power_mode() -> bool {
+    power_status().is_some_and(|status| status.SystemStatusFlag == 1)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn battery_level() -> (Option<u8>, bool) {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else { return (None, false) };
+    for entry in entries.flatten() {
+        if read(entry.path().join("type")).as_deref() != Some("Battery") {
+            continue;
+        }
+        let Some(level) = read(entry.path().join("capacity")).and_then(|c| c.parse::<u8>().ok()) else { continue };
+        let charging = read(entry.path().join("status")).as_deref() == Some("Charging");
+        return (Some(level.min(100)), charging);
+    }
+    (None, false)
+}
+
+/// The ACPI platform profile, which power-profiles-daemon and TLP set for power saving.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn low_power_mode() -> bool {
+    std::fs::read_to_string("/sys/firmware/acpi/platform_profile").is_ok_and(|profile| profile.trim() == "low-power")
+}
+
+fn read_status() -> BatteryStatus {
+    let (level, charging) = battery_level();
+    BatteryStatus { level, charging, on_battery: on_battery(), low_power_mode: low_power_mode() }
+}
+
+/// The power source as of the last check, read now if it has not been yet.
+pub fn status() -> BatteryStatus {
+    let mut cached = STATUS.lock().unwrap_or_else(|e| e.into_inner());
+    cached.get_or_insert_with(read_status).clone()
+}
+
+/// Emit `battery-changed` whenever the charge, the power source or Low Power Mode changes,
+/// checking every `POLL`.
+pub fn start(handle: &tauri::AppHandle) {
+    let handle = handle.clone();
+    std::thread::spawn(move || loop {
+        let current = read_status();
+        let previous = STATUS.lock().unwrap_or_else(|e| e.into_inner()).replace(current.clone());
+        if previous.as_ref() != Some(&current) {
+            if previous.as_ref().map(BatteryStatus::saving_power) != Some(current.saving_power()) {
+                log::info!("Power saving {}", if current.saving_power() { "on" } else { "off" });
+            }
+            let _ = handle.emit("battery-changed", current);
+        }
+        std::thread::sleep(POLL);
+    });
+}
+
+#[tauri::command]
+pub fn get_battery_status() -> BatteryStatus {
+    status()
+}
@@ -0,0 +1,71 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::audio::PIPELINE_RATE;
+use crate::resample::Converter;
+
+/// A microphone stream kept open between dictations, remembering only the last few
+/// hundred milliseconds so a word spoken just before the trigger still makes it in.
+/// The audio stays in this buffer and is overwritten continuously; it is only used when
+/// a dictation starts. The OS shows the microphone as in use the whole time.
+pub struct PreRoll {
+    _stream: cpal::Stream,
+    ring: Arc<Mutex<VecDeque<f32>>>,
+}
+
+impl PreRoll {
+    pub fn open(handle: &tauri::AppHandle, duration_ms: u32, on_lost: fn()) -> Result<Self, String> {
+        let capacity = (duration_ms as u64 * PIPELINE_RATE as u64 / 1000) as usize;
+        let ring = Arc::new(Mutex::new(VecDeque::with_capacity(capacity)));
+        let (device, config) = crate::audio::input(handle)?;
+        let mut converter = Converter::new(config.sample_rate().0, PIPELINE_RATE)?;
+        let mut converted = Vec::new();
+        let sink = ring.clone();
+        let stream = crate::audio::open_stream(
+            &device,
+            &config,
+            move |mono| {
+                converted.clear();
+                converter.process(mono, &mut converted);
+                keep_last(&mut sink.lock().unwrap_or_else(|e| e.into_inner()), &converted, capacity);
+            },
+            on_lost,
+        )?;
+        Ok(Self { _stream: stream, ring })
+    }
+
+    /// The buffered audio at `PIPELINE_RATE`, leaving the buffer empty.
+    pub fn take(&self) -> Vec<f32> {
+        self.ring.lock().unwrap_or_else(|e| e.into_inner()).drain(..).collect()
+    }
+}
+
+/// Append `samples` to `ring`, dropping the oldest so at most `capacity` remain.
+fn keep_last(ring: &mut VecDeque<f32>, samples: &[f32], capacity: usize) {
+    ring.extend(samples.iter().copied());
+    let excess = ring.len().saturating_sub(capacity);
+    ring.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_latest_audio() {
+        let mut ring = VecDeque::with_capacity(4);
+        keep_last(&mut ring, &[1.0, 2.0, 3.0], 4);
+        assert_eq!(ring, [1.0, 2.0, 3.0]);
+        keep_last(&mut ring, &[4.0, 5.0], 4);
+        assert_eq!(ring, [2.0, 3.0, 4.0, 5.0]);
+        keep_last(&mut ring, &[6.0, 7.0, 8.0, 9.0, 10.0, 11.0], 4);
+        assert_eq!(ring, [8.0, 9.0, 10.0, 11.0]);
+    }
+
+    #[test]
+    fn holds_nothing_without_capacity() {
+        let mut ring = VecDeque::new();
+        keep_last(&mut ring, &[1.0, 2.0], 0);
+        assert!(ring.is_empty());
+    }
+}
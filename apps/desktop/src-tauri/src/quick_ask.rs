@@ -0,0 +1,166 @@
+use tauri::{Emitter, Manager};
+
+pub const LABEL: &str = "quick-ask";
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 360.0;
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeltaPayload {
+    delta: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DonePayload {
+    answer: Option<String>,
+    error: Option<String>,
+}
+
+/// Create the quick ask window the first time it is needed. It stays around hidden, so
+/// later shortcuts only have to move and show it.
+fn window(app: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        return Some(window);
+    }
+    let url = if cfg!(debug_assertions) {
+        tauri::WebviewUrl::External("http://localhost:1420/?window=quick-ask".parse().unwrap())
+    } else {
+        tauri::WebviewUrl::App("index.html?window=quick-ask".into())
+    };
+    let window = tauri::WebviewWindowBuilder::new(app, LABEL, url)
+        .title("Ask spaceduck")
+        .inner_size(WIDTH, HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()
+        .map_err(|e| log::error!("Failed to create quick ask window: {e}"))
+        .ok()?;
+    window.on_window_event({
+        let window = window.clone();
+        move |event| {
+            if let tauri::WindowEvent::Focused(false) = event {
+                let _ = window.hide();
+            }
+        }
+    });
+    Some(window)
+}
+
+/// Centre the quick ask window horizontally on the display under the cursor, a third of
+/// the way down like Spotlight, then show and focus it.
+pub fn show(app: &tauri::AppHandle) {
+    crate::context::remember(app);
+    let Some(window) = window(app) else { return };
+    let monitor = app
+        .cursor_position()
+        .ok()
+        .and_then(|p| app.monitor_from_point(p.x, p.y).ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten());
+    if let Some(monitor) = monitor {
+        let scale = monitor.scale_factor();
+        let area = monitor.work_area();
+        let x = area.position.x + (area.size.width as i32 - (WIDTH * scale) as i32) / 2;
+        let y = area.position.y + area.size.height as i32 / 3 - (HEIGHT * scale) as i32 / 2;
+        let _ = window.set_position(tauri::PhysicalPosition::new(x, y.max(area.position.y)));
+    }
+    let _ = window.show();
+    let _ = window.set_focus();
+    let _ = window.emit("quick-ask:shown", ());
+}
+
+pub fn toggle(app: &tauri::AppHandle) {
+    match app.get_webview_window(LABEL) {
+        Some(window) if window.is_visible().unwrap_or(false) => {
+            let _ = window.hide();
+        }
+        _ => show(app),
+    }
+}
+
+/// Register the quick ask shortcut from the config at launch.
+pub fn start(app: &tauri::AppHandle) {
+    let Some(shortcut) = crate::config::read(app, |c| c.quick_ask_shortcut.clone()) else { return };
+    if let Err(e) = crate::register_shortcut(app, &shortcut, toggle) {
+        log::warn!("Quick ask shortcut unavailable: {e}");
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct Prefill {
+    pub text: String,
+    /// Ask right away instead of waiting for Enter.
+    pub submit: bool,
+    /// A screenshot is attached; its thumbnail comes from `get_quick_ask_screenshot`.
+    pub screenshot: bool,
+}
+
+/// Text a link or the Services menu started quick ask with, until the window picks it up.
+static PREFILL: std::sync::Mutex<Option<Prefill>> = std::sync::Mutex::new(None);
+
+/// Show quick ask with `text` already typed in, and asked if `submit`.
+pub fn prefill(app: &tauri::AppHandle, text: String, submit: bool) {
+    crate::screenshot_ask::detach();
+    set_prefill(app, Prefill { text, submit, screenshot: false });
+}
+
+pub fn set_prefill(app: &tauri::AppHandle, prefill: Prefill) {
+    *PREFILL.lock().unwrap_or_else(|e| e.into_inner()) = Some(prefill);
+    show(app);
+    let _ = app.emit_to(LABEL, "quick-ask:prefill", ());
+}
+
+/// The text filled in from outside, once; a window that was just created missed the event.
+#[tauri::command]
+pub fn take_quick_ask_prefill() -> Option<Prefill> {
+    PREFILL.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Escape in the quick ask window.
+#[tauri::command]
+pub fn hide_quick_ask(app: tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// Send a quick ask prompt to the assistant, with the text of any attached screenshot.
+/// The answer streams back to the window as
+/// `quick-ask:delta` events and ends with `quick-ask:done`.
+#[tauri::command]
+pub fn submit_quick_ask(app: tauri::AppHandle, prompt: String) -> Result<(), String> {
+    if prompt.trim().is_empty() {
+        return Err("Nothing to ask".to_string());
+    }
+    std::thread::spawn(move || {
+        let prompt = crate::context::with_context(&app, &crate::screenshot_ask::take_into(&prompt));
+        let result = crate::assistant::ask_streaming(&app, "desktop-quick-ask", &prompt, |delta| {
+            let _ = app.emit_to(LABEL, "quick-ask:delta", DeltaPayload { delta: delta.to_string() });
+        });
+        let payload = match result {
+            Ok(answer) => DonePayload { answer: Some(answer), error: None },
+            Err(e) => {
+                log::warn!("Quick ask failed: {e}");
+                DonePayload { answer: None, error: Some(e) }
+            }
+        };
+        let _ = app.emit_to(LABEL, "quick-ask:done", payload);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_quick_ask_shortcut(app: tauri::AppHandle) -> Option<String> {
+    crate::config::read(&app, |c| c.quick_ask_shortcut.clone())
+}
+
+/// Change the global shortcut that toggles the quick ask window; `None` turns it off.
+#[tauri::command]
+pub fn set_quick_ask_shortcut(app: tauri::AppHandle, shortcut: Option<String>) -> Result<(), String> {
+    let previous = crate::config::read(&app, |c| c.quick_ask_shortcut.clone());
+    crate::replace_shortcut(&app, previous.as_deref(), shortcut.as_deref(), toggle)?;
+    crate::config::update(&app, |c| c.quick_ask_shortcut = shortcut)
+}
@@ -0,0 +1,46 @@
+use rubato::{FftFixedInOut, Resampler};
+
+/// Streaming mono sample-rate converter. Input arrives in whatever block sizes the device
+/// delivers; it is buffered into the resampler's fixed chunks, adding roughly 10 ms of latency.
+/// Equal rates pass straight through.
+pub struct Converter {
+    resampler: Option<FftFixedInOut<f32>>,
+    pending: Vec<f32>,
+}
+
+impl Converter {
+    pub fn new(from: u32, to: u32) -> Result<Self, String> {
+        let resampler = if from == to {
+            None
+        } else {
+            let chunk = (from as usize / 100).max(1);
+            let resampler = FftFixedInOut::new(from as usize, to as usize, chunk, 1)
+                .map_err(|e| format!("Cannot convert {from} Hz to {to} Hz: {e}"))?;
+            Some(resampler)
+        };
+        Ok(Self { resampler, pending: Vec::new() })
+    }
+
+    pub fn process(&mut self, samples: &[f32], out: &mut Vec<f32>) {
+        let Some(resampler) = self.resampler.as_mut() else {
+            out.extend_from_slice(samples);
+            return;
+        };
+        self.pending.extend_from_slice(samples);
+
+        let mut consumed = 0;
+        loop {
+            let needed = resampler.input_frames_next();
+            if self.pending.len() - consumed < needed {
+                break;
+            }
+            let chunk = &self.pending[consumed..consumed + needed];
+            match resampler.process(&[chunk], None) {
+                Ok(mut channels) => out.append(&mut channels[0]),
+                Err(e) => log::warn!("Resampling failed: {e}"),
+            }
+            consumed += needed;
+        }
+        self.pending.drain(..consumed);
+    }
+}
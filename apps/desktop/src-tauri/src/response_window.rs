@@ -0,0 +1,285 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+const LABEL: &str = "response";
+const WIDTH: f64 = 380.0;
+const MIN_HEIGHT: f64 = 72.0;
+const MAX_HEIGHT: f64 = 440.0;
+/// Space between the window and the pill, in logical pixels.
+const PILL_GAP: f64 = 12.0;
+/// Space between the window and the pointer, in logical pixels.
+const CURSOR_GAP: f64 = 16.0;
+
+/// Bumped for every question, so a slower earlier answer cannot stream into a later one.
+static ASKED: AtomicU64 = AtomicU64::new(0);
+/// Pinned, the window stays up when something else is clicked; Escape still closes it.
+static PINNED: AtomicBool = AtomicBool::new(false);
+/// Whether the window sits above the pill or the pointer and so grows upwards.
+static ABOVE_ANCHOR: AtomicBool = AtomicBool::new(true);
+/// The window that had focus when the answer opened, given back on Escape.
+static TARGET: Mutex<Option<crate::frontmost::FocusTarget>> = Mutex::new(None);
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QuestionPayload {
+    question: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeltaPayload {
+    delta: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DonePayload {
+    answer: Option<String>,
+    error: Option<String>,
+}
+
+/// The question in a global dictation that starts with one of the ask phrases, or `None`
+/// for one that should be typed. Spacing and punctuation are ignored when matching, since
+/// engines write "Hey, Spaceduck" and "hey space duck" alike.
+pub fn question(handle: &tauri::AppHandle, text: &str) -> Option<String> {
+    let phrases = crate::config::read(handle, |c| c.ask_phrases.clone());
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let letters = |s: &str| s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>();
+    phrases.iter().find_map(|phrase| {
+        let wanted = letters(phrase);
+        if wanted.is_empty() {
+            return None;
+        }
+        let mut heard = String::new();
+        for (i, word) in words.iter().enumerate() {
+            heard.push_str(&letters(word));
+            if heard.len() >= wanted.len() {
+                let rest = words[i + 1..].join(" ");
+                let rest = rest.trim_start_matches(|c: char| !c.is_alphanumeric());
+                return (heard == wanted && !rest.is_empty()).then(|| rest.to_string());
+            }
+        }
+        None
+    })
+}
+
+/// Create the window the first time it is needed; it is hidden, not closed, afterwards.
+fn window(handle: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    if let Some(window) = handle.get_webview_window(LABEL) {
+        return Some(window);
+    }
+    let url = if cfg!(debug_assertions) {
+        tauri::WebviewUrl::External("http://localhost:1420/?window=response".parse().unwrap())
+    } else {
+        tauri::WebviewUrl::App("index.html?window=response".into())
+    };
+    let window = tauri::WebviewWindowBuilder::new(handle, LABEL, url)
+        .title("spaceduck")
+        .inner_size(WIDTH, MIN_HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()
+        .map_err(|e| log::error!("Failed to create the response window: {e}"))
+        .ok()?;
+    window.on_window_event({
+        let window = window.clone();
+        move |event| {
+            match event {
+                tauri::WindowEvent::Focused(false) if !PINNED.load(Ordering::SeqCst) => {
+                    let _ = window.hide();
+                }
+                tauri::WindowEvent::Focused(true) => crate::tray::set_response_ready(window.app_handle(), false),
+                _ => {}
+            }
+        }
+    });
+    Some(window)
+}
+
+/// Put the window just above the pill, or below it when the pill is at the top of the
+/// display, centred on it.
+fn place(handle: &tauri::AppHandle, window: &tauri::WebviewWindow, height: f64) {
+    let Some(pill) = handle.get_webview_window("dictation") else { return };
+    let (Ok(position), Ok(size), Ok(Some(monitor))) = (pill.outer_position(), pill.outer_size(), pill.current_monitor())
+    else {
+        return;
+    };
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let (width, height, gap) = ((WIDTH * scale) as i32, (height * scale) as i32, (PILL_GAP * scale) as i32);
+    let above = position.y - gap - height >= area.position.y;
+    ABOVE_ANCHOR.store(above, Ordering::SeqCst);
+    let left = area.position.x;
+    let right = left + area.size.width as i32 - width;
+    let x = (position.x + size.width as i32 / 2 - width / 2).clamp(left, right.max(left));
+    let y = if above { position.y - gap - height } else { position.y + size.height as i32 + gap };
+    let _ = window.set_position(tauri::PhysicalPosition::new(x, y));
+}
+
+/// Put the window below the pointer, or above it when there is no room below, keeping
+/// it on the display under the pointer.
+fn place_near_cursor(handle: &tauri::AppHandle, window: &tauri::WebviewWindow, height: f64) {
+    let Ok(cursor) = handle.cursor_position() else { return };
+    let Ok(Some(monitor)) = handle.monitor_from_point(cursor.x, cursor.y) else { return };
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let (width, height, gap) = ((WIDTH * scale) as i32, (height * scale) as i32, (CURSOR_GAP * scale) as i32);
+    let (cursor_x, cursor_y) = (cursor.x as i32, cursor.y as i32);
+    let bottom = area.position.y + area.size.height as i32;
+    // Room for the window at its tallest, so a long answer does not run off the display.
+    let above = cursor_y + gap + (MAX_HEIGHT * scale) as i32 > bottom && cursor_y - gap - height >= area.position.y;
+    ABOVE_ANCHOR.store(above, Ordering::SeqCst);
+    let left = area.position.x;
+    let right = left + area.size.width as i32 - width;
+    let x = (cursor_x - width / 2).clamp(left, right.max(left));
+    let y = if above { cursor_y - gap - height } else { cursor_y + gap };
+    let _ = window.set_position(tauri::PhysicalPosition::new(x, y));
+}
+
+/// Show the window with `question` at the top, placed by `place`, and return the number
+/// of the question so only its answer streams in.
+fn open(
+    handle: &tauri::AppHandle,
+    question: &str,
+    place: fn(&tauri::AppHandle, &tauri::WebviewWindow, f64),
+) -> Option<u64> {
+    let window = window(handle)?;
+    let asked = ASKED.fetch_add(1, Ordering::SeqCst) + 1;
+    *TARGET.lock().unwrap_or_else(|e| e.into_inner()) = crate::frontmost::focus_target();
+    let _ = window.set_size(tauri::LogicalSize::new(WIDTH, MIN_HEIGHT));
+    place(handle, &window, MIN_HEIGHT);
+    let _ = window.emit_to(LABEL, "response:question", QuestionPayload { question: question.to_string() });
+    let _ = window.show();
+    let _ = window.set_focus();
+    Some(asked)
+}
+
+/// Stream the assistant's answer to the prompt `prompt` builds into the window, unless
+/// another question has been asked by then.
+fn stream(handle: &tauri::AppHandle, asked: u64, prompt: impl FnOnce(&tauri::AppHandle) -> String + Send + 'static) {
+    let handle = handle.clone();
+    std::thread::spawn(move || {
+        let current = || ASKED.load(Ordering::SeqCst) == asked;
+        let prompt = prompt(&handle);
+        let result = crate::assistant::ask_streaming(&handle, "desktop-voice", &prompt, |delta| {
+            if current() {
+                let _ = handle.emit_to(LABEL, "response:delta", DeltaPayload { delta: delta.to_string() });
+            }
+        });
+        if !current() {
+            return;
+        }
+        let payload = match result {
+            Ok(answer) => DonePayload { answer: Some(answer), error: None },
+            Err(e) => {
+                log::warn!("Question to the assistant failed: {e}");
+                DonePayload { answer: None, error: Some(e) }
+            }
+        };
+        let _ = handle.emit_to(LABEL, "response:done", payload);
+        // Dismissed before the answer came in; flag it in the tray instead.
+        let seen = handle.get_webview_window(LABEL).is_some_and(|w| w.is_visible().unwrap_or(false));
+        if !seen {
+            crate::tray::set_response_ready(&handle, true);
+        }
+    });
+}
+
+/// Show `question` near the pill and stream the assistant's answer into it.
+pub fn ask(handle: &tauri::AppHandle, question: String) {
+    let Some(asked) = open(handle, &question, place) else { return };
+    stream(handle, asked, move |handle| crate::context::with_context(handle, &question));
+}
+
+/// How much of the selection heads the window; the whole of it goes to the assistant.
+const EXPLAIN_QUESTION_CHARS: usize = 120;
+
+/// Show the start of `selection` near the pointer and stream an explanation of it.
+/// `source` is the name of the app it was selected in.
+pub fn explain(handle: &tauri::AppHandle, selection: String, source: Option<String>) {
+    let mut question: String = selection.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((cut, _)) = question.char_indices().nth(EXPLAIN_QUESTION_CHARS) {
+        question.truncate(cut);
+        question.push('…');
+    }
+    let from = source.map(|name| format!(" from {name}")).unwrap_or_default();
+    let prompt = format!("Explain this text{from} briefly and clearly:\n\"\"\"\n{selection}\n\"\"\"");
+    ask_near_cursor(handle, &format!("Explain \u{201c}{question}\u{201d}"), prompt);
+}
+
+/// Show `title` near the pointer and stream the assistant's answer to `prompt` into it.
+pub fn ask_near_cursor(handle: &tauri::AppHandle, title: &str, prompt: String) {
+    let Some(asked) = open(handle, title, place_near_cursor) else { return };
+    stream(handle, asked, move |_| prompt);
+}
+
+/// Show `title` near the pointer with `message` in place of an answer, for a question
+/// that could not be asked.
+pub fn fail_near_cursor(handle: &tauri::AppHandle, title: &str, message: &str) {
+    if open(handle, title, place_near_cursor).is_none() {
+        return;
+    }
+    let payload = DonePayload { answer: None, error: Some(message.to_string()) };
+    let _ = handle.emit_to(LABEL, "response:done", payload);
+}
+
+/// Bring back an answer that was dismissed before it finished.
+pub fn reveal(handle: &tauri::AppHandle) {
+    let Some(window) = handle.get_webview_window(LABEL) else { return };
+    *TARGET.lock().unwrap_or_else(|e| e.into_inner()) = crate::frontmost::focus_target();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// Fit the window to the answer as it grows, `height` being the content's in logical
+/// pixels. The edge next to the pill or the pointer stays put.
+#[tauri::command]
+pub fn resize_response_window(app: tauri::AppHandle, height: f64) -> Result<(), String> {
+    let window = app.get_webview_window(LABEL).ok_or("The response window is gone")?;
+    let height = height.clamp(MIN_HEIGHT, MAX_HEIGHT);
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let new_height = (height * scale) as i32;
+    window.set_size(tauri::LogicalSize::new(WIDTH, height)).map_err(|e| e.to_string())?;
+    if ABOVE_ANCHOR.load(Ordering::SeqCst) {
+        let y = position.y + size.height as i32 - new_height;
+        window.set_position(tauri::PhysicalPosition::new(position.x, y)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Escape or the close button: hide the answer and give focus back to where it was.
+#[tauri::command]
+pub fn dismiss_response_window(app: tauri::AppHandle) {
+    PINNED.store(false, Ordering::SeqCst);
+    // An answer still streaming is dropped rather than filling the hidden window.
+    ASKED.fetch_add(1, Ordering::SeqCst);
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.hide();
+    }
+    if let Some(target) = TARGET.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        crate::frontmost::refocus(target);
+    }
+}
+
+#[tauri::command]
+pub fn set_response_window_pinned(pinned: bool) {
+    PINNED.store(pinned, Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub fn get_ask_phrases(app: tauri::AppHandle) -> Vec<String> {
+    crate::config::read(&app, |c| c.ask_phrases.clone())
+}
+
+/// Set the phrases that turn a global dictation into a question; empty turns it off.
+#[tauri::command]
+pub fn set_ask_phrases(app: tauri::AppHandle, phrases: Vec<String>) -> Result<(), String> {
+    let phrases: Vec<String> = phrases.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+    crate::config::update(&app, |c| c.ask_phrases = phrases)
+}
@@ -0,0 +1,441 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+const REGION_LABEL: &str = "screenshot-region";
+/// Long enough for the window that asked to be gone from the screen before it is captured.
+pub const HIDE_DELAY: Duration = Duration::from_millis(250);
+/// The error when the region overlay is dismissed without a selection.
+pub const CANCELLED: &str = "Screenshot cancelled";
+/// Screenshots are only kept until they have been attached or read.
+const KEEP_FOR: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptureMode {
+    /// A rectangle the user drags out on the display under the cursor.
+    Region,
+    /// The frontmost window that isn't spaceduck's.
+    Window,
+    /// The whole display under the cursor.
+    FullScreen,
+}
+
+/// Part of the region overlay, as fractions of its width and height.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Region {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Captured pixels, RGBA with no padding between rows.
+pub struct Capture {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Capture {
+    fn crop(&self, region: Region) -> Option<Capture> {
+        let x = (region.x.clamp(0.0, 1.0) * self.width as f64).round() as u32;
+        let y = (region.y.clamp(0.0, 1.0) * self.height as f64).round() as u32;
+        let width = ((region.width * self.width as f64).round() as u32).min(self.width - x);
+        let height = ((region.height * self.height as f64).round() as u32).min(self.height - y);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+        for row in y..y + height {
+            let start = (row as usize * self.width as usize + x as usize) * 4;
+            rgba.extend_from_slice(&self.rgba[start..start + width as usize * 4]);
+        }
+        Some(Capture { width, height, rgba })
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgba))
+            .map_err(|e| format!("Failed to encode the screenshot: {e}"))?;
+        Ok(png)
+    }
+}
+
+/// The region overlay waiting for the user, with the screen it shows.
+struct PendingRegion {
+    png: Vec<u8>,
+    reply: mpsc::Sender<Option<Region>>,
+}
+
+static PENDING: Mutex<Option<PendingRegion>> = Mutex::new(None);
+
+/// CGWindowList, which takes points in global display coordinates.
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Capture;
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::{CFString, CFStringRef};
+    use core_graphics::display::CGDisplay;
+    use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+    use core_graphics::image::CGImage;
+    use core_graphics::window::{
+        copy_window_info, create_image, kCGNullWindowID, kCGWindowImageBestResolution,
+        kCGWindowImageBoundsIgnoreFraming, kCGWindowLayer, kCGWindowListExcludeDesktopElements,
+        kCGWindowListOptionIncludingWindow, kCGWindowListOptionOnScreenOnly, kCGWindowNumber, kCGWindowOwnerPID,
+    };
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        static CGRectNull: CGRect;
+        fn CGPreflightScreenCaptureAccess() -> bool;
+        fn CGRequestScreenCaptureAccess() -> bool;
+    }
+
+    pub fn has_access() -> bool {
+        unsafe { CGPreflightScreenCaptureAccess() }
+    }
+
+    /// Prompts the first time; after that macOS only points the user at System Settings.
+    pub fn request_access() {
+        unsafe { CGRequestScreenCaptureAccess() };
+    }
+
+    /// CGImages from the window server are BGRA, premultiplied, with padded rows.
+    fn to_capture(image: CGImage) -> Result<Capture, String> {
+        if image.bits_per_pixel() != 32 {
+            return Err(format!("Unsupported screenshot format ({} bits per pixel)", image.bits_per_pixel()));
+        }
+        let (width, height, stride) = (image.width(), image.height(), image.bytes_per_row());
+        let data = image.data();
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for row in data.bytes().chunks(stride).take(height) {
+            for pixel in row[..width * 4].chunks_exact(4) {
+                rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+            }
+        }
+        Ok(Capture { width: width as u32, height: height as u32, rgba })
+    }
+
+    pub fn capture_rect(x: i32, y: i32, width: u32, height: u32, scale: f64) -> Result<Capture, String> {
+        let bounds = CGRect::new(
+            &CGPoint::new(x as f64 / scale, y as f64 / scale),
+            &CGSize::new(width as f64 / scale, height as f64 / scale),
+        );
+        let image = CGDisplay::screenshot(bounds, kCGWindowListOptionOnScreenOnly, kCGNullWindowID, kCGWindowImageBestResolution)
+            .ok_or("Failed to capture the screen")?;
+        to_capture(image)
+    }
+
+    fn number(info: &CFDictionary<CFString, CFType>, key: CFStringRef) -> Option<i64> {
+        let key = unsafe { CFString::wrap_under_get_rule(key) };
+        info.find(&key)?.downcast::<CFNumber>()?.to_i64()
+    }
+
+    pub fn capture_front_window() -> Result<Capture, String> {
+        let own = std::process::id() as i64;
+        let windows = copy_window_info(kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements, kCGNullWindowID)
+            .ok_or("Failed to list windows")?;
+        // Front to back, so the first ordinary window of another app is the one in front.
+        let id = windows
+            .iter()
+            .find_map(|item| {
+                let info = unsafe { CFDictionary::<CFString, CFType>::wrap_under_get_rule(*item as CFDictionaryRef) };
+                let ordinary = unsafe { number(&info, kCGWindowLayer) == Some(0) && number(&info, kCGWindowOwnerPID) != Some(own) };
+                if ordinary {
+                    unsafe { number(&info, kCGWindowNumber) }
+                } else {
+                    None
+                }
+            })
+            .ok_or("There is no window to capture")?;
+        let image = create_image(
+            unsafe { CGRectNull },
+            kCGWindowListOptionIncludingWindow,
+            id as u32,
+            kCGWindowImageBoundsIgnoreFraming | kCGWindowImageBestResolution,
+        )
+        .ok_or("Failed to capture the window")?;
+        to_capture(image)
+    }
+}
+
+/// GDI, reading back from the screen in physical pixels.
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::Capture;
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED, DWMWA_EXTENDED_FRAME_BOUNDS};
+    use windows::Win32::Graphics::Gdi::{
+        BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits, ReleaseDC,
+        SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CAPTUREBLT, DIB_RGB_COLORS, SRCCOPY,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetTopWindow, GetWindow, GetWindowLongW, GetWindowTextLengthW, GetWindowThreadProcessId, IsIconic,
+        IsWindowVisible, GWL_EXSTYLE, GW_HWNDNEXT, WS_EX_TOOLWINDOW,
+    };
+
+    /// Windows has no screen capture permission.
+    pub fn has_access() -> bool {
+        true
+    }
+
+    pub fn request_access() {}
+
+    pub fn capture_rect(x: i32, y: i32, width: u32, height: u32, _scale: f64) -> Result<Capture, String> {
+        let (w, h) = (width as i32, height as i32);
+        let mut rgba = vec![0u8; width as usize * height as usize * 4];
+        unsafe {
+            let screen = GetDC(None);
+            if screen.is_invalid() {
+                return Err("Failed to open the screen".to_string());
+            }
+            let memory = CreateCompatibleDC(Some(screen));
+            let bitmap = CreateCompatibleBitmap(screen, w, h);
+            let previous = SelectObject(memory, bitmap.into());
+            let copied = BitBlt(memory, 0, 0, w, h, Some(screen), x, y, SRCCOPY | CAPTUREBLT);
+            let mut info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: w,
+                    // Top-down rows.
+                    biHeight: -h,
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let rows = if copied.is_ok() {
+                GetDIBits(memory, bitmap, 0, height, Some(rgba.as_mut_ptr().cast()), &mut info, DIB_RGB_COLORS)
+            } else {
+                0
+            };
+            SelectObject(memory, previous);
+            let _ = DeleteObject(bitmap.into());
+            let _ = DeleteDC(memory);
+            ReleaseDC(None, screen);
+            copied.map_err(|e| format!("Failed to capture the screen: {e}"))?;
+            if rows == 0 {
+                return Err("Failed to read the captured screen".to_string());
+            }
+        }
+        // BGRx to RGBA.
+        for pixel in rgba.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+            pixel[3] = 255;
+        }
+        Ok(Capture { width, height, rgba })
+    }
+
+    /// Visible, titled, not minimized, not a tool window, and not hidden by DWM like
+    /// suspended UWP apps.
+    unsafe fn is_ordinary(window: HWND) -> bool {
+        let mut cloaked = 0u32;
+        let is_cloaked = DwmGetWindowAttribute(window, DWMWA_CLOAKED, (&mut cloaked as *mut u32).cast(), 4).is_ok()
+            && cloaked != 0;
+        IsWindowVisible(window).as_bool()
+            && !IsIconic(window).as_bool()
+            && GetWindowTextLengthW(window) > 0
+            && GetWindowLongW(window, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 == 0
+            && !is_cloaked
+    }
+
+    pub fn capture_front_window() -> Result<Capture, String> {
+        let own = std::process::id();
+        unsafe {
+            // Top to bottom in z-order.
+            let mut next = GetTopWindow(None).ok();
+            while let Some(window) = next {
+                let mut pid = 0u32;
+                GetWindowThreadProcessId(window, Some(&mut pid));
+                let mut rect = RECT::default();
+                if pid != own
+                    && is_ordinary(window)
+                    && DwmGetWindowAttribute(
+                        window,
+                        DWMWA_EXTENDED_FRAME_BOUNDS,
+                        (&mut rect as *mut RECT).cast(),
+                        std::mem::size_of::<RECT>() as u32,
+                    )
+                    .is_ok()
+                    && rect.right > rect.left
+                    && rect.bottom > rect.top
+                {
+                    let (width, height) = ((rect.right - rect.left) as u32, (rect.bottom - rect.top) as u32);
+                    return capture_rect(rect.left, rect.top, width, height, 1.0);
+                }
+                next = GetWindow(window, GW_HWNDNEXT).ok();
+            }
+        }
+        Err("There is no window to capture".to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::Capture;
+
+    pub fn has_access() -> bool {
+        true
+    }
+
+    pub fn request_access() {}
+
+    pub fn capture_rect(_x: i32, _y: i32, _width: u32, _height: u32, _scale: f64) -> Result<Capture, String> {
+        Err("Screenshots are not supported on Linux yet".to_string())
+    }
+
+    pub fn capture_front_window() -> Result<Capture, String> {
+        Err("Screenshots are not supported on Linux yet".to_string())
+    }
+}
+
+fn cursor_monitor(app: &tauri::AppHandle) -> Result<tauri::Monitor, String> {
+    app.cursor_position()
+        .ok()
+        .and_then(|p| app.monitor_from_point(p.x, p.y).ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten())
+        .ok_or_else(|| "No display to capture".to_string())
+}
+
+fn capture_monitor(monitor: &tauri::Monitor) -> Result<Capture, String> {
+    let (position, size) = (monitor.position(), monitor.size());
+    platform::capture_rect(position.x, position.y, size.width, size.height, monitor.scale_factor())
+}
+
+/// Show `screen` frozen over `monitor` and wait for the user to drag out a region of it.
+fn select_region(app: &tauri::AppHandle, monitor: &tauri::Monitor, screen: &Capture) -> Result<Capture, String> {
+    let (reply, answer) = mpsc::channel();
+    {
+        let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.is_some() {
+            return Err("A screenshot is already being taken".to_string());
+        }
+        *pending = Some(PendingRegion { png: screen.to_png()?, reply });
+    }
+    let url = if cfg!(debug_assertions) {
+        tauri::WebviewUrl::External("http://localhost:1420/?window=screenshot-region".parse().unwrap())
+    } else {
+        tauri::WebviewUrl::App("index.html?window=screenshot-region".into())
+    };
+    let window = tauri::WebviewWindowBuilder::new(app, REGION_LABEL, url)
+        .title("Screenshot")
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()
+        .map_err(|e| {
+            finish(None);
+            format!("Failed to open the region overlay: {e}")
+        })?;
+    // Closed some other way than Enter or Escape.
+    window.on_window_event(|event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            finish(None);
+        }
+    });
+    let _ = window.set_position(*monitor.position());
+    let _ = window.set_size(*monitor.size());
+    let _ = window.show();
+    let _ = window.set_focus();
+
+    let region = answer.recv().ok().flatten();
+    let _ = window.close();
+    region.and_then(|region| screen.crop(region)).ok_or_else(|| CANCELLED.to_string())
+}
+
+/// Capture the screen, asking for Screen Recording permission first when macOS needs it.
+pub fn capture(app: &tauri::AppHandle, mode: CaptureMode) -> Result<Capture, String> {
+    if !platform::has_access() {
+        platform::request_access();
+        return Err("spaceduck needs Screen Recording permission to take screenshots. Allow it in System Settings → Privacy & Security → Screen Recording, then try again.".to_string());
+    }
+    match mode {
+        CaptureMode::Window => platform::capture_front_window(),
+        CaptureMode::FullScreen => capture_monitor(&cursor_monitor(app)?),
+        CaptureMode::Region => {
+            let monitor = cursor_monitor(app)?;
+            let screen = capture_monitor(&monitor)?;
+            select_region(app, &monitor, &screen)
+        }
+    }
+}
+
+/// Write `capture` to the app's cache as a PNG, clearing out old screenshots.
+pub fn save(app: &tauri::AppHandle, capture: &Capture) -> Result<PathBuf, String> {
+    let dir = app.path().app_cache_dir().map_err(|e| format!("No cache dir: {e}"))?.join("screenshots");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+        let old = entry.metadata().and_then(|m| m.modified()).is_ok_and(|t| t.elapsed().is_ok_and(|age| age > KEEP_FOR));
+        if old {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    let path = dir.join(format!("screenshot-{millis}.png"));
+    std::fs::write(&path, capture.to_png()?).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// The region overlay's answer: the part of the screen to keep, or `None` to cancel.
+fn finish(region: Option<Region>) {
+    if let Some(pending) = PENDING.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        let _ = pending.reply.send(region);
+    }
+}
+
+/// Take a screenshot and return the path of the PNG. The window that asked is hidden
+/// while the screen is captured.
+#[tauri::command]
+pub async fn capture_screenshot(
+    app: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    mode: CaptureMode,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let hidden = window.is_visible().unwrap_or(false) && window.hide().is_ok();
+        if hidden {
+            std::thread::sleep(HIDE_DELAY);
+        }
+        let result = capture(&app, mode).and_then(|capture| save(&app, &capture));
+        if hidden {
+            let _ = window.show();
+        }
+        let path = result?;
+        log::info!("Saved a {mode:?} screenshot to {}", path.display());
+        Ok(path.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| format!("Screenshot failed: {e}"))?
+}
+
+#[tauri::command]
+pub fn check_screen_recording_access() -> bool {
+    platform::has_access()
+}
+
+/// The frozen screen the region overlay draws on.
+#[tauri::command]
+pub fn get_screenshot_region_image() -> Result<tauri::ipc::Response, String> {
+    let pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    let pending = pending.as_ref().ok_or("No screenshot in progress")?;
+    Ok(tauri::ipc::Response::new(pending.png.clone()))
+}
+
+#[tauri::command]
+pub fn finish_screenshot_region(region: Option<Region>) {
+    finish(region);
+}
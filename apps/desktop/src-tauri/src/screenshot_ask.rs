@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+const TITLE: &str = "Screenshot and ask";
+/// What is asked when `screenshot_ask_auto_submit` is on.
+const AUTO_PROMPT: &str = "Explain this";
+/// OCR of a dense screen can run long; the rest would only crowd out the question.
+const MAX_TEXT_CHARS: usize = 8000;
+
+/// The screenshot attached to quick ask, until it is asked about or removed.
+struct Attached {
+    path: PathBuf,
+    text: String,
+}
+
+static ATTACHED: Mutex<Option<Attached>> = Mutex::new(None);
+
+fn attached() -> std::sync::MutexGuard<'static, Option<Attached>> {
+    ATTACHED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Drop the attached screenshot, e.g. because quick ask was filled in with something else.
+pub fn detach() {
+    attached().take();
+}
+
+/// `prompt` with the text read from the attached screenshot, which goes with this one
+/// question only. The gateway takes text, so the image itself never leaves the machine.
+pub fn take_into(prompt: &str) -> String {
+    match attached().take() {
+        Some(screenshot) => format!("{prompt}\n\nText in the attached screenshot:\n\"\"\"\n{}\n\"\"\"", screenshot.text),
+        None => prompt.to_string(),
+    }
+}
+
+/// Drag out a region, read its text and open quick ask with the screenshot attached,
+/// asking straight away when `screenshot_ask_auto_submit` is on.
+fn screenshot_and_ask(app: &tauri::AppHandle) {
+    let app = app.clone();
+    // The region overlay waits for the user, which must not hold up the shortcut handler.
+    std::thread::spawn(move || {
+        if let Some(window) = app.get_webview_window(crate::quick_ask::LABEL).filter(|w| w.is_visible().unwrap_or(false)) {
+            let _ = window.hide();
+            std::thread::sleep(crate::screenshot::HIDE_DELAY);
+        }
+        let result = crate::screenshot::capture(&app, crate::screenshot::CaptureMode::Region).and_then(|capture| {
+            let path = crate::screenshot::save(&app, &capture)?;
+            let ocr = crate::ocr::recognize(&app, &crate::ocr::OcrSource::Path(path.clone()))?;
+            Ok((path, ocr.text))
+        });
+        let (path, text) = match result {
+            Ok((_, text)) if text.trim().is_empty() => {
+                crate::response_window::fail_near_cursor(&app, TITLE, "No text was found in that part of the screen.");
+                return;
+            }
+            Ok(screenshot) => screenshot,
+            Err(e) if e == crate::screenshot::CANCELLED => return,
+            Err(e) => {
+                log::warn!("Screenshot and ask failed: {e}");
+                crate::response_window::fail_near_cursor(&app, TITLE, &e);
+                return;
+            }
+        };
+        log::info!("Asking about a screenshot ({} chars of text)", text.chars().count());
+        let text = text.trim().chars().take(MAX_TEXT_CHARS).collect();
+        *attached() = Some(Attached { path, text });
+        let submit = crate::config::read(&app, |c| c.screenshot_ask_auto_submit);
+        let prompt = if submit { AUTO_PROMPT.to_string() } else { String::new() };
+        crate::quick_ask::set_prefill(&app, crate::quick_ask::Prefill { text: prompt, submit, screenshot: true });
+    });
+}
+
+/// Register the screenshot-and-ask shortcut from the config at launch.
+pub fn start(app: &tauri::AppHandle) {
+    let Some(shortcut) = crate::config::read(app, |c| c.screenshot_ask_shortcut.clone()) else { return };
+    if let Err(e) = crate::register_shortcut(app, &shortcut, screenshot_and_ask) {
+        log::warn!("Screenshot and ask shortcut unavailable: {e}");
+    }
+}
+
+#[tauri::command]
+pub fn get_screenshot_ask_shortcut(app: tauri::AppHandle) -> Option<String> {
+    crate::config::read(&app, |c| c.screenshot_ask_shortcut.clone())
+}
+
+/// Change the global shortcut that asks about a part of the screen; `None` turns it off.
+#[tauri::command]
+pub fn set_screenshot_ask_shortcut(app: tauri::AppHandle, shortcut: Option<String>) -> Result<(), String> {
+    let previous = crate::config::read(&app, |c| c.screenshot_ask_shortcut.clone());
+    crate::replace_shortcut(&app, previous.as_deref(), shortcut.as_deref(), screenshot_and_ask)?;
+    crate::config::update(&app, |c| c.screenshot_ask_shortcut = shortcut)
+}
+
+/// Ask "explain this" as soon as the screenshot is taken, instead of waiting for a question.
+#[tauri::command]
+pub fn set_screenshot_ask_auto_submit(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.screenshot_ask_auto_submit = enabled)
+}
+
+/// The PNG attached to quick ask, for its thumbnail.
+#[tauri::command]
+pub fn get_quick_ask_screenshot() -> Result<tauri::ipc::Response, String> {
+    let path = attached().as_ref().map(|screenshot| screenshot.path.clone()).ok_or("No screenshot is attached")?;
+    let png = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(tauri::ipc::Response::new(png))
+}
+
+/// The remove button on quick ask's thumbnail.
+#[tauri::command]
+pub fn detach_quick_ask_screenshot() {
+    detach();
+}
@@ -0,0 +1,57 @@
+use objc2::declare::ClassBuilder;
+use objc2::runtime::{AnyClass, AnyObject, Sel};
+use std::sync::OnceLock;
+
+const MAX_TEXT_LEN: usize = 4000;
+
+/// AppKit calls the provider with nothing of ours, so the handle lives here.
+static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    fn NSUpdateDynamicServices();
+}
+
+/// "Ask spaceduck about selection" was picked in another app's Services menu. The selector
+/// is NSMessage from the NSServices entry in Info.plist, with AppKit's arguments.
+extern "C" fn ask_spaceduck(
+    _this: &AnyObject,
+    _sel: Sel,
+    pasteboard: *mut AnyObject,
+    _user_data: *mut AnyObject,
+    _error: *mut *mut AnyObject,
+) {
+    let text = unsafe {
+        let kind: *mut AnyObject =
+            objc2::msg_send![objc2::class!(NSString), stringWithUTF8String: c"public.utf8-plain-text".as_ptr()];
+        let value: *mut AnyObject = objc2::msg_send![pasteboard, stringForType: kind];
+        crate::frontmost::nsstring_to_string(value)
+    };
+    let Some(app) = HANDLE.get() else { return };
+    let text: String = text.unwrap_or_default().trim().chars().take(MAX_TEXT_LEN).collect();
+    if text.is_empty() {
+        return;
+    }
+    log::info!("Asking about a selection from the Services menu ({} chars)", text.chars().count());
+    let submit = crate::config::read(app, |c| c.services_auto_submit);
+    crate::quick_ask::prefill(app, text, submit);
+}
+
+/// Offer the Services menu entry declared in Info.plist. Must run on the main thread.
+pub fn start(app: &tauri::AppHandle) {
+    let _ = HANDLE.set(app.clone());
+    let Some(mut builder) = ClassBuilder::new("SpaceduckServicesProvider", objc2::class!(NSObject)) else {
+        return;
+    };
+    unsafe {
+        builder.add_method(objc2::sel!(askSpaceduck:userData:error:), ask_spaceduck as extern "C" fn(_, _, _, _, _));
+    }
+    let class: &AnyClass = builder.register();
+    // NSApplication keeps the provider; it lives as long as the app.
+    unsafe {
+        let provider: *mut AnyObject = objc2::msg_send![class, new];
+        let application: *mut AnyObject = objc2::msg_send![objc2::class!(NSApplication), sharedApplication];
+        let _: () = objc2::msg_send![application, setServicesProvider: provider];
+        NSUpdateDynamicServices();
+    }
+}
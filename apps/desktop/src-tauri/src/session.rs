@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+const JOURNAL_FILE: &str = "session.json";
+/// Written once windows have stopped changing for this long.
+const SETTLE: Duration = Duration::from_millis(1000);
+
+/// What was on screen, rewritten as it changes so it survives a crash.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionJournal {
+    /// Set on a normal quit; still false at the next launch means the app died.
+    pub clean_exit: bool,
+    pub windows: Vec<JournalWindow>,
+}
+
+/// The main window or a conversation window, in physical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalWindow {
+    pub label: String,
+    /// The conversation the window was showing, if any.
+    pub conversation_id: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub visible: bool,
+}
+
+/// The journal left by a run that did not exit cleanly, until it is restored.
+static PREVIOUS: Mutex<Option<SessionJournal>> = Mutex::new(None);
+/// Conversation shown in each window, as its webview reports it.
+static CONVERSATIONS: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+static WRITING: AtomicBool = AtomicBool::new(false);
+/// Set on a normal quit, so a write still settling does not undo the clean mark.
+static EXITED: AtomicBool = AtomicBool::new(false);
+static LAST_CHANGE: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn last_change() -> std::sync::MutexGuard<'static, Option<Instant>> {
+    LAST_CHANGE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn journal_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| format!("No app data dir: {e}"))?;
+    Ok(dir.join(JOURNAL_FILE))
+}
+
+fn is_journaled(label: &str) -> bool {
+    label == "main" || crate::conversation_windows::is_conversation_window(label)
+}
+
+fn snapshot(app: &tauri::AppHandle) -> Vec<JournalWindow> {
+    let conversations = CONVERSATIONS.lock().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or_default();
+    let mut windows: Vec<JournalWindow> = app
+        .webview_windows()
+        .into_iter()
+        .filter(|(label, _)| is_journaled(label))
+        .filter_map(|(label, window)| {
+            let position = window.outer_position().ok()?;
+            let size = window.inner_size().ok()?;
+            let conversation_id = conversations
+                .get(&label)
+                .cloned()
+                .or_else(|| crate::conversation_windows::conversation_of(&label));
+            Some(JournalWindow {
+                conversation_id,
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                visible: window.is_visible().unwrap_or(true),
+                label,
+            })
+        })
+        .collect();
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    windows
+}
+
+/// Replace the journal in one step, so a crash mid-write leaves the old one whole.
+fn write(app: &tauri::AppHandle, journal: &SessionJournal) -> Result<(), String> {
+    let path = journal_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    let json = serde_json::to_string_pretty(journal).map_err(|e| format!("Failed to serialize session: {e}"))?;
+    let part = path.with_extension("json.part");
+    std::fs::write(&part, json).map_err(|e| format!("Failed to write {}: {e}", part.display()))?;
+    std::fs::rename(&part, &path).map_err(|e| format!("Failed to move {}: {e}", path.display()))
+}
+
+/// Keep the journal of a run that crashed for `restore_previous_session`, then start this
+/// run's. Call once the windows opened at launch exist.
+pub fn start(app: &tauri::AppHandle) {
+    let previous = journal_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str::<SessionJournal>(&raw).ok())
+        .filter(|journal| !journal.clean_exit && !journal.windows.is_empty());
+    if previous.is_some() {
+        log::warn!("The last session did not exit cleanly");
+    }
+    *PREVIOUS.lock().unwrap_or_else(|e| e.into_inner()) = previous;
+    if let Err(e) = write(app, &SessionJournal { clean_exit: false, windows: snapshot(app) }) {
+        log::warn!("Failed to start the session journal: {e}");
+    }
+}
+
+/// A journaled window opened, closed, moved, resized or changed conversation; write the
+/// journal once things settle.
+pub fn changed(app: &tauri::AppHandle) {
+    *last_change() = Some(Instant::now());
+    if WRITING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        while last_change().is_some_and(|t| t.elapsed() < SETTLE) {
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        WRITING.store(false, Ordering::SeqCst);
+        if EXITED.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Err(e) = write(&app, &SessionJournal { clean_exit: false, windows: snapshot(&app) }) {
+            log::warn!("Failed to write the session journal: {e}");
+        }
+    });
+}
+
+/// The app is quitting normally. The windows are still journaled as they were, in case a
+/// later launch wants them, but marked clean so it does not offer to restore them.
+pub fn clean_exit(app: &tauri::AppHandle) {
+    EXITED.store(true, Ordering::SeqCst);
+    let journal = journal_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str::<SessionJournal>(&raw).ok())
+        .unwrap_or_default();
+    if let Err(e) = write(app, &SessionJournal { clean_exit: true, ..journal }) {
+        log::warn!("Failed to close the session journal: {e}");
+    }
+}
+
+/// A webview reporting the conversation it shows.
+#[tauri::command]
+pub fn set_window_conversation(app: tauri::AppHandle, window: tauri::WebviewWindow, conversation_id: Option<String>) {
+    {
+        let mut conversations = CONVERSATIONS.lock().unwrap_or_else(|e| e.into_inner());
+        let conversations = conversations.get_or_insert_with(HashMap::new);
+        match conversation_id {
+            Some(id) => conversations.insert(window.label().to_string(), id),
+            None => conversations.remove(window.label()),
+        };
+    }
+    changed(&app);
+}
+
+/// The windows of a session that ended in a crash, if the last one did and they have not
+/// been restored yet.
+#[tauri::command]
+pub fn get_previous_session() -> Option<SessionJournal> {
+    PREVIOUS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Reopen the windows of the session that crashed, where they were, on the conversations
+/// they showed.
+#[tauri::command]
+pub fn restore_previous_session(app: tauri::AppHandle) -> Result<(), String> {
+    let previous = PREVIOUS.lock().unwrap_or_else(|e| e.into_inner()).take();
+    let previous = previous.ok_or("There is no session to restore")?;
+    for saved in &previous.windows {
+        let window = if saved.label == "main" {
+            app.get_webview_window("main")
+        } else if let Some(id) = &saved.conversation_id {
+            match crate::conversation_windows::open(&app, id) {
+                Ok(window) => Some(window),
+                Err(e) => {
+                    log::warn!("Failed to reopen conversation {id}: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let Some(window) = window else { continue };
+        let _ = window.set_size(tauri::PhysicalSize::new(saved.width, saved.height));
+        let _ = window.set_position(tauri::PhysicalPosition::new(saved.x, saved.y));
+        if saved.visible {
+            let _ = window.show();
+        }
+        if let (true, Some(id)) = (saved.label == "main", &saved.conversation_id) {
+            let _ = window.emit_to("main", "open-conversation", id.clone());
+        }
+    }
+    changed(&app);
+    Ok(())
+}
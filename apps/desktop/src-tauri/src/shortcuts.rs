@@ -0,0 +1,116 @@
+use objc2::declare::ClassBuilder;
+use objc2::runtime::{AnyClass, AnyObject, Sel};
+use std::ffi::CString;
+use std::sync::OnceLock;
+
+const MAX_TEXT_LEN: usize = 4000;
+/// errOSAGeneralError, which Shortcuts shows with our message.
+const GENERAL_ERROR: isize = -2700;
+
+/// Cocoa scripting creates the commands itself and gives them nothing of ours, so the
+/// handle lives here.
+static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// A script command, passed to the thread that answers it. Only messaged on the main
+/// thread, where it was retained.
+struct Command(*mut AnyObject);
+unsafe impl Send for Command {}
+
+unsafe fn ns_string(text: &str) -> *mut AnyObject {
+    let text = CString::new(text.replace('\0', "")).unwrap_or_default();
+    objc2::msg_send![objc2::class!(NSString), stringWithUTF8String: text.as_ptr()]
+}
+
+/// Answer `command` with what the assistant says to `prompt`, without blocking the main
+/// thread: the command is suspended until the answer arrives, then resumed with it or
+/// with the error.
+fn answer(app: &tauri::AppHandle, command: *mut AnyObject, prompt: String) -> *mut AnyObject {
+    let command = Command(unsafe {
+        let _: () = objc2::msg_send![command, suspendExecution];
+        objc2::msg_send![command, retain]
+    });
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let answer = crate::assistant::ask(&app, "desktop-shortcuts", &prompt);
+        let result = app.run_on_main_thread(move || unsafe {
+            let command = command;
+            let result = match answer {
+                Ok(text) => ns_string(&text),
+                Err(e) => {
+                    log::warn!("Shortcuts request failed: {e}");
+                    fail(command.0, &e)
+                }
+            };
+            let _: () = objc2::msg_send![command.0, resumeExecutionWithResult: result];
+            let _: () = objc2::msg_send![command.0, release];
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to answer a Shortcuts request: {e}");
+        }
+    });
+    // Ignored while the command is suspended.
+    std::ptr::null_mut()
+}
+
+/// Report `message` as the command's error; returns the nil result that goes with it.
+unsafe fn fail(command: *mut AnyObject, message: &str) -> *mut AnyObject {
+    let _: () = objc2::msg_send![command, setScriptErrorNumber: GENERAL_ERROR];
+    let _: () = objc2::msg_send![command, setScriptErrorString: ns_string(message)];
+    std::ptr::null_mut()
+}
+
+/// `ask "…"`: the answer to the question, from the same assistant as the chat window.
+extern "C" fn perform_ask(command: *mut AnyObject, _sel: Sel) -> *mut AnyObject {
+    let Some(app) = HANDLE.get() else { return std::ptr::null_mut() };
+    let question = unsafe {
+        let parameter: *mut AnyObject = objc2::msg_send![command, directParameter];
+        crate::frontmost::nsstring_to_string(parameter)
+    };
+    let question: String = question.unwrap_or_default().trim().chars().take(MAX_TEXT_LEN).collect();
+    if question.is_empty() {
+        return unsafe { fail(command, "Ask needs a question.") };
+    }
+    log::info!("Asking from Shortcuts ({} chars)", question.chars().count());
+    answer(app, command, question)
+}
+
+/// `start dictation`: starts a dictation, or stops the one running, as the hotkey would.
+extern "C" fn perform_dictate(_this: *mut AnyObject, _sel: Sel) -> *mut AnyObject {
+    if let Some(app) = HANDLE.get() {
+        crate::dictation::toggle(
+            app,
+            crate::dictation::TriggerSource::Shortcuts,
+            crate::dictation::DictationTarget::Auto,
+        );
+    }
+    std::ptr::null_mut()
+}
+
+/// `summarize clipboard`: a short summary of the copied text.
+extern "C" fn perform_summarize(command: *mut AnyObject, _sel: Sel) -> *mut AnyObject {
+    let Some(app) = HANDLE.get() else { return std::ptr::null_mut() };
+    match crate::clipboard::summary_prompt(app) {
+        Ok(prompt) => answer(app, command, prompt),
+        Err(e) => unsafe { fail(command, &e) },
+    }
+}
+
+/// Register the command classes named in spaceduck.sdef, which Shortcuts reaches through
+/// "Run AppleScript" and scripts through `tell application "Spaceduck"`. Must run on the
+/// main thread, before the first Apple event arrives.
+pub fn start(app: &tauri::AppHandle) {
+    let _ = HANDLE.set(app.clone());
+    for (name, perform) in [
+        ("SpaceduckAskCommand", perform_ask as extern "C" fn(_, _) -> _),
+        ("SpaceduckDictateCommand", perform_dictate),
+        ("SpaceduckSummarizeCommand", perform_summarize),
+    ] {
+        let Some(mut builder) = ClassBuilder::new(name, objc2::class!(NSScriptCommand)) else {
+            continue;
+        };
+        unsafe {
+            builder.add_method(objc2::sel!(performDefaultImplementation), perform);
+        }
+        let _: &AnyClass = builder.register();
+    }
+}
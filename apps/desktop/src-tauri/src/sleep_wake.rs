@@ -0,0 +1,184 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::Emitter;
+
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+/// The network and the gateway can take a few seconds to come back after a wake.
+const HEALTH_ATTEMPTS: u32 = 3;
+const HEALTH_RETRY: Duration = Duration::from_secs(2);
+
+/// The OS callbacks get no user data we can use, so the handle lives here.
+static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+/// Set when sleep paused capture, so waking only resumes what sleep paused.
+static PAUSED_FOR_SLEEP: AtomicBool = AtomicBool::new(false);
+/// Bumped on every wake, so long-lived gateway connections know to reconnect.
+static WAKES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumedPayload {
+    /// Whether the gateway answered its health check, possibly after a restart.
+    gateway_healthy: bool,
+}
+
+/// How many times the machine has woken since launch.
+pub fn wakes() -> u64 {
+    WAKES.load(Ordering::SeqCst)
+}
+
+fn gateway_healthy(handle: &tauri::AppHandle) -> bool {
+    let gateway = crate::config::read(handle, |c| c.gateway.clone());
+    let url = format!("{}/api/health", gateway.url.trim_end_matches('/'));
+    (0..HEALTH_ATTEMPTS).any(|attempt| {
+        if attempt > 0 {
+            std::thread::sleep(HEALTH_RETRY);
+        }
+        ureq::get(&url).timeout(HEALTH_TIMEOUT).call().is_ok()
+    })
+}
+
+/// Stop listening before the machine sleeps, so a recording is not cut off halfway.
+fn will_sleep() {
+    let Some(handle) = HANDLE.get() else { return };
+    log::info!("System is going to sleep");
+    if !crate::dictation::is_capture_paused() {
+        PAUSED_FOR_SLEEP.store(true, Ordering::SeqCst);
+        crate::dictation::set_capture_paused(handle.clone(), true);
+    }
+}
+
+/// Resume listening, make sure the gateway survived, restarting our own when it did not
+/// answer, and tell the windows to reconnect with `system-resumed`.
+fn did_wake() {
+    let Some(handle) = HANDLE.get() else { return };
+    log::info!("System woke from sleep");
+    WAKES.fetch_add(1, Ordering::SeqCst);
+    if PAUSED_FOR_SLEEP.swap(false, Ordering::SeqCst) {
+        crate::dictation::set_capture_paused(handle.clone(), false);
+    }
+    let handle = handle.clone();
+    std::thread::spawn(move || {
+        let mut healthy = gateway_healthy(&handle);
+        if !healthy && (crate::is_gateway_running() || crate::has_gateway_stopped()) {
+            log::warn!("The gateway did not answer after sleep; restarting it");
+            crate::restart_gateway(handle.clone());
+            healthy = gateway_healthy(&handle);
+        }
+        let _ = handle.emit("system-resumed", ResumedPayload { gateway_healthy: healthy });
+    });
+}
+
+/// NSWorkspace's sleep and wake notifications. Call on the main thread, whose run loop
+/// delivers them.
+#[cfg(target_os = "macos")]
+mod platform {
+    use block2::RcBlock;
+    use objc2::runtime::{AnyClass, AnyObject};
+
+    pub fn start() {
+        unsafe {
+            let Some(workspace_cls) = AnyClass::get("NSWorkspace") else { return };
+            let workspace: *mut AnyObject = objc2::msg_send![workspace_cls, sharedWorkspace];
+            let center: *mut AnyObject = objc2::msg_send![workspace, notificationCenter];
+            let Some(string_cls) = AnyClass::get("NSString") else { return };
+            for (name, on_notification) in [
+                (c"NSWorkspaceWillSleepNotification", super::will_sleep as fn()),
+                (c"NSWorkspaceDidWakeNotification", super::did_wake as fn()),
+            ] {
+                let name: *mut AnyObject = objc2::msg_send![string_cls, stringWithUTF8String: name.as_ptr()];
+                let block = RcBlock::new(move |_notification: *mut AnyObject| on_notification());
+                // The center keeps the block and the observer token for as long as the app runs.
+                let _: *mut AnyObject = objc2::msg_send![
+                    center,
+                    addObserverForName: name,
+                    object: std::ptr::null_mut::<AnyObject>(),
+                    queue: std::ptr::null_mut::<AnyObject>(),
+                    usingBlock: &*block
+                ];
+            }
+        }
+    }
+}
+
+/// PowerRegisterSuspendResumeNotification, whose callback runs on a system thread.
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Power::{PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS};
+    use windows::Win32::UI::WindowsAndMessaging::{DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND};
+
+    unsafe extern "system" fn on_power(_context: *const std::ffi::c_void, kind: u32, _setting: *const std::ffi::c_void) -> u32 {
+        // Every resume sends PBT_APMRESUMEAUTOMATIC; PBT_APMRESUMESUSPEND follows only
+        // when a user woke it, so it is left alone.
+        match kind {
+            PBT_APMSUSPEND => super::will_sleep(),
+            PBT_APMRESUMEAUTOMATIC => super::did_wake(),
+            _ => {}
+        }
+        0
+    }
+
+    pub fn start() {
+        // Windows keeps using the parameters for as long as the registration lasts.
+        let parameters = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+            Callback: Some(on_power),
+            Context: std::ptr::null_mut(),
+        }));
+        let mut registration = std::ptr::null_mut();
+        let recipient = HANDLE(parameters as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS as *mut std::ffi::c_void);
+        let status = unsafe { PowerRegisterSuspendResumeNotification(DEVICE_NOTIFY_CALLBACK, recipient, &mut registration) };
+        if let Err(e) = status.ok() {
+            log::warn!("Failed to watch for sleep and wake: {e}");
+        }
+    }
+}
+
+/// logind's PrepareForSleep signal, through `gdbus monitor`. Without an inhibitor lock
+/// the machine may be asleep before capture has paused.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use std::io::BufRead;
+    use std::process::{Command, Stdio};
+
+    pub fn start() {
+        let child = Command::new("gdbus")
+            .args([
+                "monitor",
+                "--system",
+                "--dest",
+                "org.freedesktop.login1",
+                "--object-path",
+                "/org/freedesktop/login1",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                log::info!("Not watching for sleep and wake: {e}");
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else { return };
+        std::thread::spawn(move || {
+            for line in std::io::BufReader::new(stdout).lines().map_while(Result::ok) {
+                if line.contains("PrepareForSleep (true") {
+                    super::will_sleep();
+                } else if line.contains("PrepareForSleep (false") {
+                    super::did_wake();
+                }
+            }
+            let _ = child.wait();
+            log::warn!("Stopped watching for sleep and wake");
+        });
+    }
+}
+
+/// Pause capture before the machine sleeps and recover the gateway when it wakes.
+pub fn start(handle: &tauri::AppHandle) {
+    let _ = HANDLE.set(handle.clone());
+    platform::start();
+}
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use tauri::Emitter;
+
+/// Longest trigger the typed-text buffer can match.
+const MAX_TRIGGER_CHARS: usize = 32;
+
+/// What a trigger turns into.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnippetContent {
+    Text(String),
+    /// Sent to the assistant; its answer is inserted.
+    Prompt(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    /// Typed anywhere, e.g. `;addr`, it is replaced with `content`.
+    pub trigger: String,
+    pub content: SnippetContent,
+}
+
+/// Expansion of typed triggers in any app. Needs the native key monitor, so macOS and
+/// Windows only.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SnippetsConfig {
+    pub enabled: bool,
+    pub snippets: Vec<Snippet>,
+    /// Apps, by bundle id or executable name, where triggers are left alone.
+    pub disabled_apps: Vec<String>,
+}
+
+impl SnippetsConfig {
+    fn validate(&self) -> Result<(), String> {
+        for snippet in &self.snippets {
+            let len = snippet.trigger.chars().count();
+            if !(2..=MAX_TRIGGER_CHARS).contains(&len) {
+                return Err(format!("Snippet triggers must be 2 to {MAX_TRIGGER_CHARS} characters: {:?}", snippet.trigger));
+            }
+            if snippet.trigger.chars().any(char::is_whitespace) {
+                return Err(format!("Snippet triggers cannot contain spaces: {:?}", snippet.trigger));
+            }
+        }
+        for (i, snippet) in self.snippets.iter().enumerate() {
+            if self.snippets[..i].iter().any(|s| s.trigger == snippet.trigger) {
+                return Err(format!("Duplicate snippet trigger {:?}", snippet.trigger));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SnippetPayload {
+    trigger: String,
+    error: Option<String>,
+}
+
+/// The last characters typed since the caret last moved some other way.
+static TYPED: Mutex<String> = Mutex::new(String::new());
+/// Set while a snippet is being expanded, so its own keystrokes are not matched.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+static EXPANDING: AtomicBool = AtomicBool::new(false);
+
+fn typed() -> std::sync::MutexGuard<'static, String> {
+    TYPED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The key monitors report each character typed. Expands a snippet when the text typed
+/// so far ends with its trigger.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn key_typed(handle: &tauri::AppHandle, c: char) {
+    if EXPANDING.load(Ordering::SeqCst) || crate::insertion::is_inserting() {
+        return;
+    }
+    let config = crate::config::read(handle, |c| c.snippets.clone());
+    if !config.enabled || config.snippets.is_empty() {
+        return;
+    }
+    let Some(snippet) = push(&mut typed(), c, &config.snippets) else { return };
+    let app = crate::frontmost::frontmost_app().and_then(|a| a.bundle_id);
+    if app.is_some_and(|app| config.disabled_apps.iter().any(|d| d.eq_ignore_ascii_case(&app))) {
+        return;
+    }
+    if EXPANDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let handle = handle.clone();
+    // Injecting from the key monitor's callback would stall every keystroke on the system.
+    std::thread::spawn(move || {
+        let error = expand(&handle, &snippet).err();
+        if let Some(e) = &error {
+            log::warn!("Snippet {} failed: {e}", snippet.trigger);
+        }
+        let _ = handle.emit("snippet-expanded", SnippetPayload { trigger: snippet.trigger, error });
+        EXPANDING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Add `c` to the typed text, keeping the last `MAX_TRIGGER_CHARS`, and return the snippet
+/// whose trigger it now ends with. The text is cleared on a match.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+fn push(typed: &mut String, c: char, snippets: &[Snippet]) -> Option<Snippet> {
+    typed.push(c);
+    let excess = typed.chars().count().saturating_sub(MAX_TRIGGER_CHARS);
+    if excess > 0 {
+        let cut = typed.char_indices().nth(excess).map_or(typed.len(), |(i, _)| i);
+        typed.drain(..cut);
+    }
+    let snippet = snippets.iter().find(|s| !s.trigger.is_empty() && typed.ends_with(&s.trigger)).cloned();
+    if snippet.is_some() {
+        typed.clear();
+    }
+    snippet
+}
+
+/// Backspace removes the last typed character from the buffer.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn key_deleted() {
+    typed().pop();
+}
+
+/// Any other key, a click or a shortcut may move the caret, so what came before no
+/// longer counts towards a trigger.
+pub fn reset() {
+    typed().clear();
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn expand(handle: &tauri::AppHandle, snippet: &Snippet) -> Result<(), String> {
+    // The trigger goes first, so a slow assistant answer visibly has something happening.
+    crate::insertion::delete_typed(snippet.trigger.chars().count())?;
+    let text = match &snippet.content {
+        SnippetContent::Text(text) => text.clone(),
+        SnippetContent::Prompt(prompt) => {
+            let _ = handle.emit("snippet-expanding", SnippetPayload { trigger: snippet.trigger.clone(), error: None });
+            crate::assistant::ask(handle, "desktop-snippets", prompt)?
+        }
+    };
+    match crate::insertion::insert(handle, &text) {
+        crate::insertion::InsertOutcome::Failed { reason } => Err(reason),
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn get_snippets_config(app: tauri::AppHandle) -> SnippetsConfig {
+    crate::config::read(&app, |c| c.snippets.clone())
+}
+
+#[tauri::command]
+pub fn set_snippets_config(app: tauri::AppHandle, config: SnippetsConfig) -> Result<(), String> {
+    config.validate()?;
+    reset();
+    crate::config::update(&app, |c| c.snippets = config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(trigger: &str) -> Snippet {
+        Snippet { trigger: trigger.to_string(), content: SnippetContent::Text(format!("{trigger} expanded")) }
+    }
+
+    fn type_all(typed: &mut String, text: &str, snippets: &[Snippet]) -> Option<String> {
+        text.chars().filter_map(|c| push(typed, c, snippets)).map(|s| s.trigger).last()
+    }
+
+    #[test]
+    fn keeps_the_last_characters_on_char_boundaries() {
+        let mut typed = String::new();
+        type_all(&mut typed, &"日本語".repeat(20), &[]);
+        assert_eq!(typed.chars().count(), MAX_TRIGGER_CHARS);
+        assert!(typed.ends_with("日本語"));
+        assert!(typed.starts_with("本語"));
+    }
+
+    #[test]
+    fn matches_triggers_at_the_end_and_clears() {
+        let snippets = [snippet(";addr"), snippet(";sig")];
+        let mut typed = String::new();
+        assert_eq!(type_all(&mut typed, "see ;add", &snippets), None);
+        assert_eq!(type_all(&mut typed, "r", &snippets).as_deref(), Some(";addr"));
+        assert!(typed.is_empty());
+        assert_eq!(type_all(&mut typed, "x;sigh", &snippets).as_deref(), Some(";sig"));
+        assert_eq!(typed, "h");
+    }
+
+    #[test]
+    fn matches_a_trigger_typed_after_a_long_run() {
+        let snippets = [snippet(";é")];
+        let mut typed = String::new();
+        assert_eq!(type_all(&mut typed, &format!("{};é", "ü".repeat(100)), &snippets).as_deref(), Some(";é"));
+    }
+
+    #[test]
+    fn validates_trigger_length_spaces_and_duplicates() {
+        let config = |triggers: &[&str]| SnippetsConfig {
+            snippets: triggers.iter().map(|t| snippet(t)).collect(),
+            ..Default::default()
+        };
+        assert!(config(&[";a", ";addr", &"é".repeat(MAX_TRIGGER_CHARS)]).validate().is_ok());
+        assert!(config(&[";"]).validate().is_err());
+        assert!(config(&[&"a".repeat(MAX_TRIGGER_CHARS + 1)]).validate().is_err());
+        assert!(config(&[";my addr"]).validate().is_err());
+        assert!(config(&[";addr\t"]).validate().is_err());
+        assert!(config(&[";addr", ";sig", ";addr"]).validate().is_err());
+    }
+}
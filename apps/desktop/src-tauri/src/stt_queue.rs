@@ -0,0 +1,265 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager};
+
+use crate::audio::Recording;
+use crate::dictation::DictationMode;
+use crate::transcription::{SttBackend, Transcript};
+use crate::transcripts::{Cue, SessionKind};
+
+const QUEUE_DIR: &str = "pending-transcriptions";
+/// How often the backend is probed while something is waiting.
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+/// Used instead of `RETRY_INTERVAL` on battery.
+const BATTERY_RETRY_INTERVAL: Duration = Duration::from_secs(120);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Recordings that still fail after this many attempts with the backend up are given up on,
+/// since the problem is then the audio rather than the connection.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Wakes the retry thread early, e.g. when something was queued or a retry was requested.
+static WAKE: OnceLock<Mutex<Sender<()>>> = OnceLock::new();
+/// Failed attempts per queued id since launch.
+static ATTEMPTS: Mutex<Option<HashMap<String, u32>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTranscription {
+    /// File name, used to address the recording in the other commands.
+    pub id: String,
+    /// Unix time in milliseconds.
+    pub recorded_at: u64,
+    pub duration_ms: u64,
+    pub mode: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueueChangedPayload {
+    pending: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueuedResultPayload {
+    id: String,
+    text: String,
+    mode: String,
+    language: Option<String>,
+    recorded_at: u64,
+    session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueuedFailedPayload {
+    id: String,
+    message: String,
+}
+
+fn dir(handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let base = handle.path().app_data_dir().map_err(|e| format!("No app data dir: {e}"))?;
+    Ok(base.join(QUEUE_DIR))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+/// Queued recordings are named `<unix ms>-<mode>.wav`, like the archive.
+fn parse(path: &Path) -> Option<PendingTranscription> {
+    let id = path.file_name()?.to_str()?.to_string();
+    let (recorded_at, mode) = id.strip_suffix(".wav")?.split_once('-')?;
+    let size = std::fs::metadata(path).ok()?.len();
+    Some(PendingTranscription {
+        recorded_at: recorded_at.parse().ok()?,
+        mode: mode.to_string(),
+        duration_ms: size.saturating_sub(44) / 2 * 1000 / crate::audio::PIPELINE_RATE as u64,
+        id,
+    })
+}
+
+/// Oldest first, which is the order they are retried in.
+fn entries(handle: &tauri::AppHandle) -> Result<Vec<PendingTranscription>, String> {
+    let dir = dir(handle)?;
+    let read = match std::fs::read_dir(&dir) {
+        Ok(read) => read,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {e}", dir.display())),
+    };
+    let mut pending: Vec<PendingTranscription> = read.filter_map(|e| parse(&e.ok()?.path())).collect();
+    pending.sort_by_key(|p| p.recorded_at);
+    Ok(pending)
+}
+
+fn emit_changed(handle: &tauri::AppHandle) {
+    let pending = entries(handle).map(|e| e.len()).unwrap_or_default();
+    let _ = handle.emit("stt-queue-changed", QueueChangedPayload { pending });
+}
+
+/// Have the queue try again straight away, e.g. because the network is back.
+pub fn wake() {
+    if let Some(tx) = WAKE.get() {
+        let _ = tx.lock().unwrap_or_else(|e| e.into_inner()).send(());
+    }
+}
+
+/// Keep a recording whose transcription failed so it can be retried later.
+/// Returns whether it was saved.
+pub fn enqueue(handle: &tauri::AppHandle, recording: &Recording, mode: DictationMode) -> bool {
+    let result = dir(handle).and_then(|dir| {
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        let mode = match mode {
+            DictationMode::Chat => "chat",
+            DictationMode::Global => "global",
+        };
+        let path = dir.join(format!("{}-{mode}.wav", now_ms()));
+        std::fs::write(&path, recording.to_wav()).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    });
+    match result {
+        Ok(()) => {
+            emit_changed(handle);
+            wake();
+            true
+        }
+        Err(e) => {
+            log::error!("Could not queue recording for retry: {e}");
+            false
+        }
+    }
+}
+
+/// Whether the configured backend looks usable. The gateway is probed via `/api/health`;
+/// a local model is always assumed to be.
+fn backend_available(handle: &tauri::AppHandle) -> bool {
+    let (backend, gateway) = crate::config::read(handle, |c| (c.stt_backend, c.gateway.clone()));
+    if backend == SttBackend::Local {
+        return true;
+    }
+    let url = format!("{}/api/health", gateway.url.trim_end_matches('/'));
+    ureq::get(&url).timeout(HEALTH_TIMEOUT).call().is_ok()
+}
+
+/// Transcribe the queue in order, stopping at the first failure so a dropped connection
+/// does not burn through every item's attempts.
+fn drain(handle: &tauri::AppHandle) -> Result<(), String> {
+    for pending in entries(handle)? {
+        let path = dir(handle)?.join(&pending.id);
+        let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let result = Recording::from_wav(&bytes).and_then(|r| {
+            crate::transcription::transcribe_with_fallback(handle, &r).map(|t| (t, r.duration_ms()))
+        });
+        match result {
+            Ok((Transcript { text, language, .. }, duration_ms)) => {
+                let text = crate::voice_commands::apply(handle, &text);
+                let cue = Cue { start_ms: 0, end_ms: duration_ms as u64, speaker: None, text: text.clone() };
+                let session_id = crate::transcripts::record(SessionKind::Dictation, language.clone(), vec![cue]);
+                let _ = handle.emit(
+                    "dictation:queued-result",
+                    QueuedResultPayload {
+                        id: pending.id.clone(),
+                        text,
+                        mode: pending.mode,
+                        language,
+                        recorded_at: pending.recorded_at,
+                        session_id,
+                    },
+                );
+                remove(&path)?;
+                forget(&pending.id);
+                emit_changed(handle);
+            }
+            Err(e) => {
+                let attempts = {
+                    let mut attempts = ATTEMPTS.lock().unwrap_or_else(|e| e.into_inner());
+                    let count = attempts.get_or_insert_with(HashMap::new).entry(pending.id.clone()).or_default();
+                    *count += 1;
+                    *count
+                };
+                if attempts >= MAX_ATTEMPTS {
+                    log::warn!("Giving up on queued recording {} after {attempts} attempts: {e}", pending.id);
+                    let _ = handle.emit("dictation:queued-failed", QueuedFailedPayload { id: pending.id.clone(), message: e });
+                    remove(&path)?;
+                    forget(&pending.id);
+                    emit_changed(handle);
+                    continue;
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn remove(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete {}: {e}", path.display())),
+    }
+}
+
+fn forget(id: &str) {
+    if let Some(attempts) = ATTEMPTS.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        attempts.remove(id);
+    }
+}
+
+/// Retry queued recordings whenever the backend is reachable, every `RETRY_INTERVAL`
+/// (`BATTERY_RETRY_INTERVAL` on battery) or sooner when woken. Results arrive as `dictation:queued-result` and are never pasted,
+/// since the focused app has long since changed.
+/// This function blocks forever, so call it from a dedicated thread.
+pub fn start(handle: tauri::AppHandle) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    WAKE.set(Mutex::new(tx)).map_err(|_| "Transcription queue already running".to_string())?;
+    emit_changed(&handle);
+    loop {
+        let has_pending = entries(&handle).is_ok_and(|e| !e.is_empty());
+        if has_pending && backend_available(&handle) {
+            if let Err(e) = drain(&handle) {
+                log::warn!("Queued transcription failed, will retry: {e}");
+            }
+        }
+        let interval = if crate::power::status().unplugged() { BATTERY_RETRY_INTERVAL } else { RETRY_INTERVAL };
+        match rx.recv_timeout(interval) {
+            Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    Err("Transcription queue channel closed".to_string())
+}
+
+#[tauri::command]
+pub fn list_pending_transcriptions(app: tauri::AppHandle) -> Result<Vec<PendingTranscription>, String> {
+    entries(&app)
+}
+
+/// Retry the queue now instead of waiting for the next interval.
+#[tauri::command]
+pub fn retry_pending_transcriptions() {
+    wake();
+}
+
+/// Drop the given queued recordings, or all of them when `ids` is `None`. Returns how many were removed.
+#[tauri::command]
+pub fn discard_pending_transcriptions(app: tauri::AppHandle, ids: Option<Vec<String>>) -> Result<usize, String> {
+    let pending = entries(&app)?;
+    let targets: Vec<&PendingTranscription> = match &ids {
+        Some(ids) => ids
+            .iter()
+            .map(|id| pending.iter().find(|p| &p.id == id).ok_or_else(|| format!("Queued recording {id} not found")))
+            .collect::<Result<_, _>>()?,
+        None => pending.iter().collect(),
+    };
+    let dir = dir(&app)?;
+    for target in &targets {
+        remove(&dir.join(&target.id))?;
+        forget(&target.id);
+    }
+    emit_changed(&app);
+    Ok(targets.len())
+}
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::Manager;
+
+/// Background tasks the gateway reported running that have not finished.
+static RUNNING: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+/// Set from the UI, e.g. for a long download; wins over the running tasks.
+static PROGRESS: Mutex<Option<u8>> = Mutex::new(None);
+static BADGE: Mutex<Option<String>> = Mutex::new(None);
+
+/// What the Dock or taskbar should show.
+struct Indicator {
+    progress: Option<ProgressBarState>,
+    badge: Option<String>,
+}
+
+fn lock<T>(mutex: &'static Mutex<T>) -> std::sync::MutexGuard<'static, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn indicator() -> Indicator {
+    let running = lock(&RUNNING).as_ref().map_or(0, HashSet::len);
+    let progress = match *lock(&PROGRESS) {
+        Some(percent) => Some(ProgressBarState { status: Some(ProgressBarStatus::Normal), progress: Some(percent.min(100) as u64) }),
+        None if running > 0 => Some(ProgressBarState { status: Some(ProgressBarStatus::Indeterminate), progress: None }),
+        None => None,
+    };
+    let badge = lock(&BADGE).clone().or_else(|| (running > 0).then(|| running.to_string()));
+    Indicator { progress, badge }
+}
+
+/// The Dock's badge takes text; the app icon is app-wide.
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn set_badge(window: &tauri::WebviewWindow, badge: Option<String>) -> tauri::Result<()> {
+        window.set_badge_label(badge)
+    }
+}
+
+/// The taskbar has no badge text, only an overlay icon on the window's button, so any
+/// badge shows as a dot.
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::image::Image;
+
+    const SIZE: u32 = 16;
+    const COLOR: [u8; 3] = [0x1E, 0x88, 0xE5];
+
+    fn dot() -> Image<'static> {
+        let radius = SIZE as f32 / 2.0;
+        let mut rgba = vec![0u8; (SIZE * SIZE * 4) as usize];
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let distance = ((x as f32 + 0.5 - radius).powi(2) + (y as f32 + 0.5 - radius).powi(2)).sqrt();
+                if distance <= radius {
+                    let i = ((y * SIZE + x) * 4) as usize;
+                    rgba[i..i + 4].copy_from_slice(&[COLOR[0], COLOR[1], COLOR[2], 0xFF]);
+                }
+            }
+        }
+        Image::new_owned(rgba, SIZE, SIZE)
+    }
+
+    pub fn set_badge(window: &tauri::WebviewWindow, badge: Option<String>) -> tauri::Result<()> {
+        window.set_overlay_icon(badge.map(|_| dot()))
+    }
+}
+
+/// Launcher badges through libunity, which only shows counts; other badges are left off.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub fn set_badge(window: &tauri::WebviewWindow, badge: Option<String>) -> tauri::Result<()> {
+        window.set_badge_count(badge.and_then(|badge| badge.parse().ok()))
+    }
+}
+
+/// Show the current progress and badge on the main window's Dock icon or taskbar button.
+fn sync(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else { return };
+    let Indicator { progress, badge } = indicator();
+    let progress = progress.unwrap_or(ProgressBarState { status: Some(ProgressBarStatus::None), progress: None });
+    if let Err(e) = window.set_progress_bar(progress) {
+        log::debug!("Failed to set the progress bar: {e}");
+    }
+    if let Err(e) = platform::set_badge(&window, badge) {
+        log::debug!("Failed to set the badge: {e}");
+    }
+}
+
+/// A `task.progress` message from the gateway: `running` counts the task until it
+/// completes or runs out of retries.
+pub fn progress(app: &tauri::AppHandle, task_id: String, state: &str) {
+    {
+        let mut running = lock(&RUNNING);
+        let running = running.get_or_insert_with(HashSet::new);
+        match state {
+            "running" => running.insert(task_id),
+            _ => running.remove(&task_id),
+        };
+    }
+    sync(app);
+}
+
+/// A `task.completed` message from the gateway.
+pub fn finished(app: &tauri::AppHandle, task_id: &str) {
+    let removed = lock(&RUNNING).as_mut().is_some_and(|running| running.remove(task_id));
+    if removed {
+        sync(app);
+    }
+}
+
+/// Forget the running tasks, e.g. because the gateway went away and will not report them.
+pub fn reset(app: &tauri::AppHandle) {
+    if lock(&RUNNING).take().is_some_and(|running| !running.is_empty()) {
+        sync(app);
+    }
+}
+
+/// Fill the Dock icon's or taskbar button's progress bar to `percent`, or hand it back to
+/// the gateway's background tasks with `None`.
+#[tauri::command]
+pub fn set_taskbar_progress(app: tauri::AppHandle, percent: Option<u8>) {
+    *lock(&PROGRESS) = percent;
+    sync(&app);
+}
+
+/// Badge the Dock icon with `label`, or put back the count of running background tasks
+/// with `None`. Windows shows any badge as a dot; Linux launchers only show numbers.
+#[tauri::command]
+pub fn set_dock_badge(app: tauri::AppHandle, label: Option<String>) {
+    *lock(&BADGE) = label.filter(|label| !label.is_empty());
+    sync(&app);
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::Emitter;
+
+use crate::audio::Recording;
+use crate::dictation::DictationMode;
+use crate::gateway_stream::GatewayStream;
+use crate::transcripts::{Cue, SessionKind};
+
+mod engines;
+pub mod pending;
+pub mod settings;
+
+pub use engines::{transcribe_with_fallback, TimedSegment, Transcript, TranscriptionEngine, WordConfidence};
+use engines::{confidence, engine, translate};
+
+const DEFAULT_GATEWAY_URL: &str = "http://localhost:3000";
+
+/// Partial passes are skipped while one is still running, so slow engines just update less often.
+static PARTIAL_IN_FLIGHT: AtomicBool = AtomicBool::new(false);
+
+/// Which engine handles native recordings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SttBackend {
+    /// On-device Whisper; needs `whisperModel` to point at a downloaded model.
+    Local,
+    /// POST to the gateway's `/api/stt/transcribe`, which may call a cloud provider.
+    #[default]
+    Gateway,
+}
+
+/// Which Whisper model to load depending on the power source.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SttPowerPolicy {
+    /// Use `battery_model` while unplugged or in Low Power Mode and `whisperModel` otherwise.
+    pub enabled: bool,
+    /// A smaller model such as base or small, which is faster and lighter on the battery.
+    pub battery_model: Option<PathBuf>,
+}
+
+/// Where native recordings are sent. The webview keeps its own copy in localStorage
+/// and pushes changes here with `set_gateway_connection`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GatewayConfig {
+    pub url: String,
+    pub token: Option<String>,
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self { url: DEFAULT_GATEWAY_URL.to_string(), token: None }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PartialPayload {
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultPayload {
+    text: String,
+    mode: &'static str,
+    language: Option<String>,
+    /// Pass to `export_transcript` to save this dictation with timestamps.
+    session_id: String,
+    /// `text` in the dictation's `translateTo` language; this is what gets pasted.
+    translation: Option<String>,
+    segments: Vec<TimedSegment>,
+    /// Overall certainty from 0.0 to 1.0, when the engine reports one.
+    confidence: Option<f32>,
+    /// A global dictation that was not pasted because `confidence` fell below the
+    /// configured threshold or `confirm_before_insert` is on. Paste it with
+    /// `confirm_pending_paste`.
+    needs_confirmation: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslationErrorPayload {
+    mode: &'static str,
+    message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ErrorPayload {
+    message: String,
+}
+
+fn suffix(mode: DictationMode) -> &'static str {
+    match mode {
+        DictationMode::Chat => "chat",
+        DictationMode::Global => "global",
+    }
+}
+
+pub fn emit_error(handle: &tauri::AppHandle, mode: DictationMode, message: &str) {
+    let _ = handle.emit(
+        &format!("dictation:error-{}", suffix(mode)),
+        ErrorPayload { message: message.to_string() },
+    );
+}
+
+/// Transcribe the audio so far and emit `dictation:partial`, unless the recording has
+/// ended by the time the engine returns. Partials only use the configured engine; a
+/// failing pass is simply skipped.
+pub fn submit_partial(handle: &tauri::AppHandle, recording: Recording, session: u64) {
+    if PARTIAL_IN_FLIGHT.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let handle = handle.clone();
+    std::thread::spawn(move || {
+        let result = engine(&handle).and_then(|engine| engine.transcribe(&recording));
+        PARTIAL_IN_FLIGHT.store(false, Ordering::SeqCst);
+        match result {
+            Ok(Transcript { text, .. }) if !text.is_empty() && crate::audio::is_current_session(session) => {
+                let text = crate::voice_commands::apply(&handle, &text);
+                let _ = handle.emit("dictation:partial", PartialPayload { text });
+            }
+            Ok(_) => {}
+            Err(e) => log::debug!("Partial transcription failed: {e}"),
+        }
+    });
+}
+
+/// Whether native recordings should be streamed to the gateway while they are captured.
+pub fn streams_to_gateway(handle: &tauri::AppHandle) -> bool {
+    crate::config::read(handle, |c| c.stt_streaming && c.stt_backend == SttBackend::Gateway)
+}
+
+/// Transcribe `recording` off-thread, falling back to the other engine if needed, and emit
+/// `dictation:result`. Chat mode leaves the text to the input box; global mode is also
+/// pasted straight into the focused app. A streamed recording uses the gateway's final
+/// transcript, and is uploaded the usual way only if streaming failed. With `translate_to`
+/// the translation is pasted instead, or the original if translating fails. A global
+/// dictation that starts with an ask phrase is a question for the assistant instead.
+pub fn submit(
+    handle: &tauri::AppHandle,
+    recording: Recording,
+    mode: DictationMode,
+    stream: Option<GatewayStream>,
+    translate_to: Option<String>,
+) {
+    let handle = handle.clone();
+    std::thread::spawn(move || {
+        let streamed = stream.and_then(|stream| {
+            stream
+                .finish()
+                .map_err(|e| log::warn!("Streaming transcription failed, uploading instead: {e}"))
+                .ok()
+        });
+        let result = match streamed {
+            Some(transcript) => Ok(transcript),
+            None => transcribe_with_fallback(&handle, &recording),
+        };
+        let Transcript { text, language, segments } = match result {
+            Ok(transcript) => transcript,
+            Err(e) => {
+                log::error!("Transcription failed: {e}");
+                let message = if crate::stt_queue::enqueue(&handle, &recording, mode) {
+                    format!("{e}. The recording was saved and will be transcribed when the service is back.")
+                } else {
+                    e
+                };
+                emit_error(&handle, mode, &message);
+                if mode == DictationMode::Global {
+                    crate::pill::idle(&handle);
+                }
+                return;
+            }
+        };
+        let text = crate::voice_commands::apply(&handle, &text);
+        // Streamed results carry no timings, so the whole recording becomes one cue.
+        let cues = if segments.is_empty() {
+            vec![Cue { start_ms: 0, end_ms: recording.duration_ms() as u64, speaker: None, text: text.clone() }]
+        } else {
+            segments.iter().cloned().map(Cue::from).collect()
+        };
+        let session_id = crate::transcripts::record(SessionKind::Dictation, language.clone(), cues);
+
+        let translation = match translate_to {
+            None => None,
+            Some(_) if text.trim().is_empty() => None,
+            Some(target) if language.as_deref() == Some(target.as_str()) => Some(text.clone()),
+            Some(_) => match translate(&handle, &recording, language.as_deref()) {
+                Ok(translated) => Some(crate::voice_commands::apply(&handle, &translated)),
+                Err(e) => {
+                    log::warn!("Translation failed, using the original: {e}");
+                    let _ = handle.emit("dictation:translation-error", TranslationErrorPayload { mode: suffix(mode), message: e });
+                    None
+                }
+            },
+        };
+        let output = translation.clone().unwrap_or_else(|| text.clone());
+
+        let confidence = confidence(&segments);
+        let (threshold, always_confirm) = crate::config::read(&handle, |c| (c.paste_confirm_below, c.confirm_before_insert));
+        let question = (mode == DictationMode::Global).then(|| crate::response_window::question(&handle, &output)).flatten();
+        let paste = mode == DictationMode::Global && !output.trim().is_empty() && question.is_none();
+        let unsure = matches!((confidence, threshold), (Some(c), Some(t)) if c < t);
+        let needs_confirmation = paste && (always_confirm || unsure);
+        if needs_confirmation {
+            log::info!("Holding back a dictation with confidence {confidence:?} for confirmation");
+            pending::hold(&handle, output.clone());
+        }
+
+        let _ = handle.emit(
+            "dictation:result",
+            ResultPayload {
+                text,
+                mode: suffix(mode),
+                language,
+                session_id,
+                translation,
+                segments,
+                confidence,
+                needs_confirmation,
+            },
+        );
+        if let Some(question) = question {
+            crate::response_window::ask(&handle, question);
+        }
+        if paste && !needs_confirmation {
+            match crate::insertion::insert(&handle, &output) {
+                crate::insertion::InsertOutcome::Pasted => {}
+                crate::insertion::InsertOutcome::CopiedOnly => {
+                    emit_error(&handle, mode, "The dictation did not reach the focused app; it is on the clipboard");
+                }
+                crate::insertion::InsertOutcome::Failed { reason } => {
+                    log::error!("{reason}");
+                    emit_error(&handle, mode, &reason);
+                }
+            }
+        }
+        if mode == DictationMode::Global {
+            crate::pill::idle(&handle);
+        }
+    });
+}
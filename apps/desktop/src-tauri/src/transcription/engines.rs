@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Emitter;
+
+use super::{GatewayConfig, SttBackend};
+use crate::audio::Recording;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The Whisper model last handed to an engine, to notice when the power policy swaps it.
+static ACTIVE_MODEL: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// What an engine made of a recording.
+pub struct Transcript {
+    pub text: String,
+    /// ISO 639-1 code of the spoken language, when the engine reports one.
+    pub language: Option<String>,
+    /// Timed pieces of `text`; empty when the engine does not report timings.
+    pub segments: Vec<TimedSegment>,
+}
+
+/// A stretch of a transcript with its offsets into the recording.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimedSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    /// Mean log-probability of the segment's tokens; closer to 0 is more certain.
+    pub avg_logprob: Option<f32>,
+    /// How likely the segment is silence or noise that was transcribed anyway.
+    pub no_speech_prob: Option<f32>,
+    /// Per-word probabilities, for highlighting what the engine was unsure of.
+    /// Only local Whisper reports these.
+    pub words: Vec<WordConfidence>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordConfidence {
+    pub text: String,
+    /// 0.0 to 1.0; the least confident token when a word spans several.
+    pub probability: f32,
+}
+
+/// Geometric mean of the token probabilities across `segments`, or `None` when the
+/// engine reported none.
+pub(super) fn confidence(segments: &[TimedSegment]) -> Option<f32> {
+    let (sum, count) = segments
+        .iter()
+        .filter_map(|s| Some((s.avg_logprob?, s.words.len().max(1))))
+        .fold((0.0, 0), |(sum, count), (logprob, n)| (sum + logprob * n as f32, count + n));
+    (count > 0).then(|| (sum / count as f32).exp())
+}
+
+/// Turns a finished recording into text.
+pub trait TranscriptionEngine {
+    /// Short identifier used in logs and events.
+    fn name(&self) -> &'static str;
+    fn transcribe(&self, recording: &Recording) -> Result<Transcript, String>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelSwitchedPayload {
+    model: PathBuf,
+    on_battery: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FallbackPayload {
+    from: &'static str,
+    to: &'static str,
+    reason: String,
+}
+
+#[derive(Deserialize)]
+struct TranscribeResponse {
+    text: Option<String>,
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<ResponseSegment>,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+/// Gateway segments are timed in seconds.
+#[derive(Deserialize)]
+struct ResponseSegment {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+/// The Whisper model to use right now: `whisperModel`, or the power policy's battery model
+/// while unplugged or in Low Power Mode. Emits `stt-model-switched` when this differs from the last call.
+pub fn whisper_model(handle: &tauri::AppHandle) -> Option<PathBuf> {
+    let (model, policy) = crate::config::read(handle, |c| (c.whisper_model.clone(), c.stt_power_policy.clone()));
+    let power = crate::power::status();
+    let on_battery = policy.enabled && (power.unplugged() || power.low_power_mode);
+    let model = match policy.battery_model {
+        Some(battery_model) if on_battery => Some(battery_model),
+        _ => model,
+    }?;
+
+    let mut active = ACTIVE_MODEL.lock().unwrap_or_else(|e| e.into_inner());
+    if active.as_ref() != Some(&model) {
+        // The first resolution after launch is not a switch.
+        if active.is_some() {
+            log::info!("Switching Whisper model to {} (on battery: {on_battery})", model.display());
+            let _ = handle.emit("stt-model-switched", ModelSwitchedPayload { model: model.clone(), on_battery });
+        }
+        *active = Some(model.clone());
+    }
+    Some(model)
+}
+
+fn build(handle: &tauri::AppHandle, backend: SttBackend) -> Result<Box<dyn TranscriptionEngine>, String> {
+    let (gateway, language, gpu, prompt) = crate::config::read(handle, |c| {
+        let prompt = vocabulary_prompt(&c.vocabulary);
+        (c.gateway.clone(), c.dictation_language.clone(), c.whisper_gpu, prompt)
+    });
+    match backend {
+        SttBackend::Local => {
+            let model_path = whisper_model(handle).ok_or_else(|| "No local Whisper model configured".to_string())?;
+            Ok(Box::new(crate::whisper::WhisperEngine { model_path, language, gpu, prompt, translate: false }))
+        }
+        SttBackend::Gateway => Ok(Box::new(GatewayEngine { gateway, language, prompt })),
+    }
+}
+
+/// Whisper-style initial prompt listing the user's vocabulary. Models imitate the
+/// spelling they see in the prompt, which is what biases them towards these words.
+fn vocabulary_prompt(vocabulary: &[String]) -> Option<String> {
+    (!vocabulary.is_empty()).then(|| format!("{}.", vocabulary.join(", ")))
+}
+
+pub(super) fn engine(handle: &tauri::AppHandle) -> Result<Box<dyn TranscriptionEngine>, String> {
+    build(handle, crate::config::read(handle, |c| c.stt_backend))
+}
+
+/// The configured engine, then the other one if fallback is on and it is usable.
+/// A local fallback needs a model to have been chosen at some point.
+fn engines(handle: &tauri::AppHandle) -> Result<Vec<Box<dyn TranscriptionEngine>>, String> {
+    let (backend, fallback) = crate::config::read(handle, |c| (c.stt_backend, c.stt_fallback));
+    let mut engines = vec![build(handle, backend)?];
+    if fallback {
+        let other = match backend {
+            SttBackend::Local => SttBackend::Gateway,
+            SttBackend::Gateway => SttBackend::Local,
+        };
+        match build(handle, other) {
+            Ok(engine) => engines.push(engine),
+            Err(e) => log::debug!("No fallback engine: {e}"),
+        }
+    }
+    Ok(engines)
+}
+
+/// Try each engine in turn, emitting `dictation:stt-fallback` whenever one fails and the
+/// next takes over. Returns the last error if none succeed.
+pub fn transcribe_with_fallback(handle: &tauri::AppHandle, recording: &Recording) -> Result<Transcript, String> {
+    let engines = engines(handle)?;
+    let mut last_error = String::new();
+    for (i, engine) in engines.iter().enumerate() {
+        log::debug!("Transcribing {}ms with {}", recording.duration_ms(), engine.name());
+        match engine.transcribe(recording) {
+            Ok(transcript) => return Ok(transcript),
+            Err(e) => {
+                log::warn!("{} transcription failed: {e}", engine.name());
+                if let Some(next) = engines.get(i + 1) {
+                    let _ = handle.emit(
+                        "dictation:stt-fallback",
+                        FallbackPayload { from: engine.name(), to: next.name(), reason: e.clone() },
+                    );
+                }
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+/// Translate the speech in `recording` with the local model. Whisper translates from the
+/// audio itself, so this is a second pass over the recording whichever backend transcribed it.
+/// `spoken` skips language detection when the first pass already reported it.
+pub(super) fn translate(handle: &tauri::AppHandle, recording: &Recording, spoken: Option<&str>) -> Result<String, String> {
+    let (configured, gpu) = crate::config::read(handle, |c| (c.dictation_language.clone(), c.whisper_gpu));
+    let model_path = whisper_model(handle).ok_or_else(|| "Translation needs a local Whisper model".to_string())?;
+    let language = spoken.filter(|l| whisper_rs::get_lang_id(l).is_some()).map(str::to_string).or(configured);
+    let engine = crate::whisper::WhisperEngine { model_path, language, gpu, prompt: None, translate: true };
+    engine.transcribe(recording).map(|t| t.text)
+}
+
+/// Forwards audio to the sidecar gateway.
+struct GatewayEngine {
+    gateway: GatewayConfig,
+    language: Option<String>,
+    prompt: Option<String>,
+}
+
+impl TranscriptionEngine for GatewayEngine {
+    fn name(&self) -> &'static str {
+        "gateway"
+    }
+
+    fn transcribe(&self, recording: &Recording) -> Result<Transcript, String> {
+        let url = format!("{}/api/stt/transcribe", self.gateway.url.trim_end_matches('/'));
+
+        let mut request = ureq::post(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .set("Content-Type", "audio/wav");
+        // The gateway detects the language itself unless given a hint.
+        if let Some(language) = &self.language {
+            request = request.set("X-STT-Language", language);
+        }
+        if let Some(prompt) = &self.prompt {
+            request = request.query("prompt", prompt);
+        }
+        if let Some(token) = &self.gateway.token {
+            request = request.set("Authorization", &format!("Bearer {token}"));
+        }
+
+        let response = match request.send_bytes(&recording.to_wav()) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                let body: Option<TranscribeResponse> = response.into_json().ok();
+                let message = body
+                    .and_then(|b| b.message.or(b.error))
+                    .unwrap_or_else(|| format!("Gateway returned {status}"));
+                return Err(message);
+            }
+            Err(e) => return Err(format!("Failed to reach gateway: {e}")),
+        };
+
+        let body: TranscribeResponse = response
+            .into_json()
+            .map_err(|e| format!("Malformed transcription response: {e}"))?;
+        let segments = body
+            .segments
+            .into_iter()
+            .map(|s| TimedSegment {
+                start_ms: (s.start * 1000.0) as u64,
+                end_ms: (s.end * 1000.0) as u64,
+                text: s.text.trim().to_string(),
+                avg_logprob: None,
+                no_speech_prob: None,
+                words: Vec::new(),
+            })
+            .collect();
+        Ok(Transcript { text: body.text.unwrap_or_default(), language: body.language, segments })
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Mutex;
+
+/// A global dictation held back for confirmation because the engine was unsure of it.
+static PENDING_PASTE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Keep `text` back and ask for confirmation in the overlay.
+pub(super) fn hold(handle: &tauri::AppHandle, text: String) {
+    *PENDING_PASTE.lock().unwrap_or_else(|e| e.into_inner()) = Some(text);
+    crate::confirm_overlay::show(handle);
+}
+
+/// The dictation waiting for confirmation, shown by the confirmation overlay.
+#[tauri::command]
+pub fn get_pending_paste() -> Option<String> {
+    PENDING_PASTE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Paste the dictation held back for confirmation. Returns whether there was one.
+/// Closes the confirmation overlay first, so the text goes to the app it covered.
+#[tauri::command]
+pub fn confirm_pending_paste(app: tauri::AppHandle) -> Result<bool, String> {
+    let pending = PENDING_PASTE.lock().unwrap_or_else(|e| e.into_inner()).take();
+    crate::confirm_overlay::close(&app);
+    let Some(text) = pending else { return Ok(false) };
+    match crate::insertion::insert(&app, &text) {
+        crate::insertion::InsertOutcome::Failed { reason } => Err(reason),
+        _ => Ok(true),
+    }
+}
+
+#[tauri::command]
+pub fn discard_pending_paste(app: tauri::AppHandle) -> bool {
+    let discarded = PENDING_PASTE.lock().unwrap_or_else(|e| e.into_inner()).take().is_some();
+    crate::confirm_overlay::close(&app);
+    discarded
+}
+
+#[tauri::command]
+pub fn get_paste_confirmation_threshold(app: tauri::AppHandle) -> Option<f32> {
+    crate::config::read(&app, |c| c.paste_confirm_below)
+}
+
+/// Global dictations less confident than `threshold` (0.0 to 1.0) wait for
+/// `confirm_pending_paste` instead of pasting; `None` pastes unless
+/// `confirm_before_insert` is on.
+#[tauri::command]
+pub fn set_paste_confirmation_threshold(app: tauri::AppHandle, threshold: Option<f32>) -> Result<(), String> {
+    if threshold.is_some_and(|t| !(0.0..=1.0).contains(&t)) {
+        return Err("The confirmation threshold must be between 0 and 1".to_string());
+    }
+    crate::config::update(&app, |c| c.paste_confirm_below = threshold)
+}
+
+#[tauri::command]
+pub fn get_confirm_before_insert(app: tauri::AppHandle) -> bool {
+    crate::config::read(&app, |c| c.confirm_before_insert)
+}
+
+/// Hold every global dictation in the confirmation overlay, whatever its confidence.
+#[tauri::command]
+pub fn set_confirm_before_insert(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.confirm_before_insert = enabled)
+}
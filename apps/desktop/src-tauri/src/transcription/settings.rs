@@ -0,0 +1,177 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::{GatewayConfig, SttBackend, SttPowerPolicy};
+
+/// How the dictation language setting spells "detect it per recording".
+const AUTO_LANGUAGE: &str = "auto";
+/// Whisper only reads the last ~224 prompt tokens, so a longer list would just be cut off.
+const MAX_VOCABULARY: usize = 100;
+const MAX_VOCABULARY_ENTRY_LEN: usize = 64;
+/// Whisper's translate task only produces English.
+const TRANSLATION_TARGETS: &[&str] = &["en"];
+
+#[tauri::command]
+pub fn get_gateway_connection(app: tauri::AppHandle) -> GatewayConfig {
+    crate::config::read(&app, |c| c.gateway.clone())
+}
+
+#[tauri::command]
+pub fn set_gateway_connection(app: tauri::AppHandle, url: String, token: Option<String>) -> Result<(), String> {
+    let url = url.trim().trim_end_matches('/').to_string();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!("Invalid gateway URL: {url}"));
+    }
+    let token = token.filter(|t| !t.is_empty());
+    crate::config::update(&app, |c| c.gateway = GatewayConfig { url, token })
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SttSettings {
+    pub backend: SttBackend,
+    pub whisper_model: Option<PathBuf>,
+    pub partial_results: bool,
+    pub fallback: bool,
+    pub streaming: bool,
+}
+
+#[tauri::command]
+pub fn get_stt_settings(app: tauri::AppHandle) -> SttSettings {
+    crate::config::read(&app, |c| SttSettings {
+        backend: c.stt_backend,
+        whisper_model: c.whisper_model.clone(),
+        partial_results: c.partial_results,
+        fallback: c.stt_fallback,
+        streaming: c.stt_streaming,
+    })
+}
+
+/// Stream audio to the gateway's `/api/stt/stream` while recording instead of uploading
+/// the clip afterwards. Only applies to the gateway backend; if the socket fails the
+/// recording is still uploaded once it ends.
+#[tauri::command]
+pub fn set_stt_streaming(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.stt_streaming = enabled)
+}
+
+/// The language passed to the STT engine, or `auto` to let it detect one per recording.
+#[tauri::command]
+pub fn get_dictation_language(app: tauri::AppHandle) -> String {
+    crate::config::read(&app, |c| c.dictation_language.clone()).unwrap_or_else(|| AUTO_LANGUAGE.to_string())
+}
+
+/// Accepts `auto` or a language code Whisper knows, e.g. `es` or `en`.
+#[tauri::command]
+pub fn set_dictation_language(app: tauri::AppHandle, language: String) -> Result<(), String> {
+    let language = language.trim().to_lowercase();
+    let language = if language == AUTO_LANGUAGE {
+        None
+    } else if whisper_rs::get_lang_id(&language).is_some() {
+        Some(language)
+    } else {
+        return Err(format!("Unknown language: {language}"));
+    };
+    crate::config::update(&app, |c| c.dictation_language = language)
+}
+
+#[tauri::command]
+pub fn get_stt_power_policy(app: tauri::AppHandle) -> SttPowerPolicy {
+    crate::config::read(&app, |c| c.stt_power_policy.clone())
+}
+
+/// Takes effect from the next transcription, which is also when `stt-model-switched` fires.
+#[tauri::command]
+pub fn set_stt_power_policy(app: tauri::AppHandle, policy: SttPowerPolicy) -> Result<(), String> {
+    if policy.enabled {
+        let model = policy.battery_model.as_ref().ok_or_else(|| "Choose a model to use on battery".to_string())?;
+        if !model.is_file() {
+            return Err(format!("Model not found: {}", model.display()));
+        }
+    }
+    crate::config::update(&app, |c| c.stt_power_policy = policy)
+}
+
+/// The language dictations are translated into, or `None` to paste them as spoken.
+#[tauri::command]
+pub fn get_dictation_translation(app: tauri::AppHandle) -> Option<String> {
+    crate::config::read(&app, |c| c.translate_to.clone())
+}
+
+/// Applies from the next dictation on. Translating needs a local Whisper model.
+#[tauri::command]
+pub fn set_dictation_translation(app: tauri::AppHandle, language: Option<String>) -> Result<(), String> {
+    let language = language.map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty());
+    if let Some(language) = &language {
+        if !TRANSLATION_TARGETS.contains(&language.as_str()) {
+            return Err(format!("Cannot translate into {language}; only English is supported"));
+        }
+        if crate::config::read(&app, |c| c.whisper_model.is_none()) {
+            return Err("Choose a local Whisper model to translate dictations".to_string());
+        }
+    }
+    crate::config::update(&app, |c| c.translate_to = language)
+}
+
+/// Words and names the STT engine should spell the user's way.
+#[tauri::command]
+pub fn list_vocabulary(app: tauri::AppHandle) -> Vec<String> {
+    crate::config::read(&app, |c| c.vocabulary.clone())
+}
+
+#[tauri::command]
+pub fn add_vocabulary_word(app: tauri::AppHandle, word: String) -> Result<Vec<String>, String> {
+    let word: String = word.chars().filter(|c| !c.is_control()).collect::<String>().trim().to_string();
+    if word.is_empty() || word.len() > MAX_VOCABULARY_ENTRY_LEN {
+        return Err(format!("Vocabulary entries must be 1 to {MAX_VOCABULARY_ENTRY_LEN} characters"));
+    }
+    let mut result = Ok(());
+    crate::config::update(&app, |c| {
+        if c.vocabulary.iter().any(|w| w.eq_ignore_ascii_case(&word)) {
+            return;
+        }
+        if c.vocabulary.len() >= MAX_VOCABULARY {
+            result = Err(format!("The vocabulary is limited to {MAX_VOCABULARY} entries"));
+            return;
+        }
+        c.vocabulary.push(word);
+    })?;
+    result.map(|()| list_vocabulary(app))
+}
+
+#[tauri::command]
+pub fn remove_vocabulary_word(app: tauri::AppHandle, word: String) -> Result<Vec<String>, String> {
+    crate::config::update(&app, |c| c.vocabulary.retain(|w| !w.eq_ignore_ascii_case(word.trim())))?;
+    Ok(list_vocabulary(app))
+}
+
+/// Retry failed transcriptions on the other backend instead of reporting an error.
+#[tauri::command]
+pub fn set_stt_fallback(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.stt_fallback = enabled)
+}
+
+/// Stream interim text while recording. Each pass re-sends the whole buffer, which is
+/// cheap locally but adds load on the gateway backend.
+#[tauri::command]
+pub fn set_partial_results(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::config::update(&app, |c| c.partial_results = enabled)
+}
+
+/// Pick the engine for native recordings. Choosing `local` requires an existing model file.
+#[tauri::command]
+pub fn set_stt_backend(app: tauri::AppHandle, backend: SttBackend, whisper_model: Option<PathBuf>) -> Result<(), String> {
+    if let Some(path) = &whisper_model {
+        if !path.is_file() {
+            return Err(format!("Whisper model not found: {}", path.display()));
+        }
+    }
+    let whisper_model = whisper_model.or_else(|| crate::config::read(&app, |c| c.whisper_model.clone()));
+    if backend == SttBackend::Local && whisper_model.is_none() {
+        return Err("Choose a Whisper model before switching to local transcription".to_string());
+    }
+    crate::config::update(&app, |c| {
+        c.stt_backend = backend;
+        c.whisper_model = whisper_model;
+    })
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::transcription::TimedSegment;
+
+/// Older sessions are forgotten beyond this and can no longer be exported.
+const MAX_SESSIONS: usize = 50;
+
+/// Recent dictations and meetings, newest last, kept so they can be exported with timestamps.
+static SESSIONS: Mutex<VecDeque<Session>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionKind {
+    Dictation,
+    Meeting,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Json,
+}
+
+/// One timed line of a session's transcript.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Who said it, for meetings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+impl From<TimedSegment> for Cue {
+    fn from(segment: TimedSegment) -> Self {
+        Self { start_ms: segment.start_ms, end_ms: segment.end_ms, speaker: None, text: segment.text }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Session {
+    id: String,
+    kind: SessionKind,
+    /// Unix time in milliseconds.
+    recorded_at: u64,
+    language: Option<String>,
+    cues: Vec<Cue>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+/// Remember a finished transcript and return the id to export it by.
+pub fn record(kind: SessionKind, language: Option<String>, cues: Vec<Cue>) -> String {
+    let recorded_at = now_ms();
+    let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    // Two sessions can finish in the same millisecond, e.g. a meeting and a dictation.
+    let mut id = recorded_at.to_string();
+    let mut suffix = 1;
+    while sessions.iter().any(|s| s.id == id) {
+        id = format!("{recorded_at}-{suffix}");
+        suffix += 1;
+    }
+    sessions.push_back(Session { id: id.clone(), kind, recorded_at, language, cues });
+    while sessions.len() > MAX_SESSIONS {
+        sessions.pop_front();
+    }
+    id
+}
+
+fn timestamp(ms: u64, fraction_separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{fraction_separator}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+fn to_srt(cues: &[Cue]) -> String {
+    let mut out = String::new();
+    for (i, cue) in cues.iter().enumerate() {
+        let text = match &cue.speaker {
+            Some(speaker) => format!("{speaker}: {}", cue.text),
+            None => cue.text.clone(),
+        };
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{text}\n\n",
+            i + 1,
+            timestamp(cue.start_ms, ','),
+            timestamp(cue.end_ms, ',')
+        ));
+    }
+    out
+}
+
+fn to_vtt(cues: &[Cue]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues {
+        let text = match &cue.speaker {
+            Some(speaker) => format!("<v {speaker}>{}", cue.text),
+            None => cue.text.clone(),
+        };
+        out.push_str(&format!("{} --> {}\n{text}\n\n", timestamp(cue.start_ms, '.'), timestamp(cue.end_ms, '.')));
+    }
+    out
+}
+
+/// Write a recent dictation or meeting transcript to `path` as SRT, WebVTT, or JSON.
+#[tauri::command]
+pub fn export_transcript(session_id: String, format: ExportFormat, path: PathBuf) -> Result<(), String> {
+    let session = SESSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|s| s.id == session_id)
+        .cloned()
+        .ok_or_else(|| format!("Transcript {session_id} not found"))?;
+    let contents = match format {
+        ExportFormat::Srt => to_srt(&session.cues),
+        ExportFormat::Vtt => to_vtt(&session.cues),
+        ExportFormat::Json => serde_json::to_string_pretty(&session)
+            .map_err(|e| format!("Failed to serialize transcript: {e}"))?,
+    };
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(start_ms: u64, end_ms: u64, speaker: Option<&str>, text: &str) -> Cue {
+        Cue { start_ms, end_ms, speaker: speaker.map(str::to_string), text: text.to_string() }
+    }
+
+    #[test]
+    fn formats_timestamps_with_hours_and_milliseconds() {
+        assert_eq!(timestamp(0, ','), "00:00:00,000");
+        assert_eq!(timestamp(61_005, '.'), "00:01:01.005");
+        assert_eq!(timestamp(3_600_000 + 59 * 60_000 + 59_999, ','), "01:59:59,999");
+        assert_eq!(timestamp(100 * 3_600_000, '.'), "100:00:00.000");
+    }
+
+    #[test]
+    fn writes_numbered_srt_cues() {
+        let cues = [cue(0, 1500, None, "Hello."), cue(1500, 62_250, Some("Sam"), "Hi there.")];
+        assert_eq!(
+            to_srt(&cues),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello.\n\n2\n00:00:01,500 --> 00:01:02,250\nSam: Hi there.\n\n"
+        );
+    }
+
+    #[test]
+    fn writes_vtt_with_header_and_voice_tags() {
+        let cues = [cue(0, 1500, None, "Hello."), cue(1500, 2000, Some("Sam"), "Hi.")];
+        assert_eq!(
+            to_vtt(&cues),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nHello.\n\n00:00:01.500 --> 00:00:02.000\n<v Sam>Hi.\n\n"
+        );
+    }
+
+    #[test]
+    fn writes_headers_only_without_cues() {
+        assert_eq!(to_srt(&[]), "");
+        assert_eq!(to_vtt(&[]), "WEBVTT\n\n");
+    }
+}
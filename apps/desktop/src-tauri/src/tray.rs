@@ -0,0 +1,226 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{Manager, Wry};
+
+const TRAY_ID: &str = "main";
+/// How long the recording dot stays on, then off.
+const PULSE: Duration = Duration::from_millis(600);
+/// Badge colours, RGB.
+const RECORDING_COLOR: [u8; 3] = [0xE5, 0x39, 0x35];
+const WARNING_COLOR: [u8; 3] = [0xF5, 0xA6, 0x23];
+const RESPONSE_COLOR: [u8; 3] = [0x1E, 0x88, 0xE5];
+
+/// The menu entries whose state follows the rest of the app.
+struct TrayItems {
+    paused: CheckMenuItem<Wry>,
+    gateway: MenuItem<Wry>,
+    answer: MenuItem<Wry>,
+}
+
+static ITEMS: OnceLock<TrayItems> = OnceLock::new();
+static ICONS: OnceLock<Icons> = OnceLock::new();
+/// An answer came in while nothing was showing it.
+static RESPONSE_READY: AtomicBool = AtomicBool::new(false);
+static PULSING: AtomicBool = AtomicBool::new(false);
+
+/// What the icon shows, most urgent first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Recording,
+    GatewayDown,
+    ResponseReady,
+    Idle,
+}
+
+fn status() -> Status {
+    if crate::dictation::is_active() {
+        Status::Recording
+    } else if crate::has_gateway_stopped() {
+        Status::GatewayDown
+    } else if RESPONSE_READY.load(Ordering::SeqCst) {
+        Status::ResponseReady
+    } else {
+        Status::Idle
+    }
+}
+
+/// The app icon, plain and with each badge.
+struct Icons {
+    idle: Image<'static>,
+    recording: Image<'static>,
+    warning: Image<'static>,
+    response: Image<'static>,
+}
+
+/// `icon` with a dot in its bottom-right corner, ringed in white so it reads on dark and
+/// light menu bars alike.
+fn badged(icon: &Image<'_>, color: [u8; 3]) -> Image<'static> {
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+    let size = width.min(height) as f32;
+    let radius = size * 0.22;
+    let ring = radius + (size * 0.05).max(1.0);
+    let (cx, cy) = (width as f32 - ring, height as f32 - ring);
+    for y in 0..height {
+        for x in 0..width {
+            let distance = ((x as f32 + 0.5 - cx).powi(2) + (y as f32 + 0.5 - cy).powi(2)).sqrt();
+            let fill = if distance <= radius {
+                color
+            } else if distance <= ring {
+                [0xFF, 0xFF, 0xFF]
+            } else {
+                continue;
+            };
+            let i = ((y * width + x) * 4) as usize;
+            rgba[i..i + 4].copy_from_slice(&[fill[0], fill[1], fill[2], 0xFF]);
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+fn icons(app: &tauri::AppHandle) -> Option<&'static Icons> {
+    if let Some(icons) = ICONS.get() {
+        return Some(icons);
+    }
+    let icon = app.default_window_icon()?;
+    Some(ICONS.get_or_init(|| Icons {
+        idle: Image::new_owned(icon.rgba().to_vec(), icon.width(), icon.height()),
+        recording: badged(icon, RECORDING_COLOR),
+        warning: badged(icon, WARNING_COLOR),
+        response: badged(icon, RESPONSE_COLOR),
+    }))
+}
+
+fn set_icon(app: &tauri::AppHandle, icon: &Image<'static>) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_icon(Some(icon.clone()));
+    }
+}
+
+/// Blink the recording dot until the recording ends, then settle on whatever comes next.
+fn pulse(app: &tauri::AppHandle) {
+    if PULSING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut on = true;
+        while status() == Status::Recording {
+            if let Some(icons) = icons(&app) {
+                set_icon(&app, if on { &icons.recording } else { &icons.idle });
+            }
+            on = !on;
+            std::thread::sleep(PULSE);
+        }
+        PULSING.store(false, Ordering::SeqCst);
+        sync(&app);
+    });
+}
+
+fn open_main_window(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else { return };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// Show the log folder in the file manager.
+pub fn open_logs(app: &tauri::AppHandle) {
+    use tauri_plugin_opener::OpenerExt;
+    let Some(dir) = crate::logging::dir() else {
+        log::warn!("Logging is not set up; there is nothing to open");
+        return;
+    };
+    if let Err(e) = app.opener().open_path(dir.to_string_lossy(), None::<&str>) {
+        log::warn!("Failed to open the log folder: {e}");
+    }
+}
+
+fn on_menu_event(app: &tauri::AppHandle, id: &str) {
+    match id {
+        "pause" => crate::dictation::set_capture_paused(app.clone(), !crate::dictation::is_capture_paused()),
+        "open" => open_main_window(app),
+        "answer" => crate::response_window::reveal(app),
+        "quick-ask" => crate::quick_ask::show(app),
+        "restart-gateway" => crate::restart_gateway(app.clone()),
+        "logs" => open_logs(app),
+        "quit" => app.exit(0),
+        _ => {}
+    }
+}
+
+/// Put the icon in the menu bar or notification area. Call before anything calls `sync`.
+pub fn create(app: &tauri::AppHandle) -> Result<(), String> {
+    let err = |e: tauri::Error| format!("Failed to create the tray menu: {e}");
+    let gateway = MenuItem::with_id(app, "gateway", "Gateway stopped", false, None::<&str>).map_err(err)?;
+    let paused = CheckMenuItem::with_id(app, "pause", "Pause Listening", true, false, None::<&str>).map_err(err)?;
+    let answer = MenuItem::with_id(app, "answer", "Show Answer", false, None::<&str>).map_err(err)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &gateway,
+            &PredefinedMenuItem::separator(app).map_err(err)?,
+            &paused,
+            &MenuItem::with_id(app, "open", "Open spaceduck", true, None::<&str>).map_err(err)?,
+            &MenuItem::with_id(app, "quick-ask", "Quick Ask", true, None::<&str>).map_err(err)?,
+            &answer,
+            &PredefinedMenuItem::separator(app).map_err(err)?,
+            &MenuItem::with_id(app, "restart-gateway", "Restart Gateway", true, None::<&str>).map_err(err)?,
+            &MenuItem::with_id(app, "logs", "Open Logs", true, None::<&str>).map_err(err)?,
+            &PredefinedMenuItem::separator(app).map_err(err)?,
+            &MenuItem::with_id(app, "quit", "Quit spaceduck", true, None::<&str>).map_err(err)?,
+        ],
+    )
+    .map_err(err)?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("spaceduck")
+        .menu(&menu)
+        .on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app).map_err(err)?;
+    let _ = ITEMS.set(TrayItems { paused, gateway, answer });
+    sync(app);
+    Ok(())
+}
+
+/// Flag, or clear, an answer nobody has seen yet.
+pub fn set_response_ready(app: &tauri::AppHandle, ready: bool) {
+    if RESPONSE_READY.swap(ready, Ordering::SeqCst) != ready {
+        sync(app);
+    }
+}
+
+/// Bring the icon and menu in line with recording, the pause flag, the gateway sidecar
+/// and unseen answers.
+pub fn sync(app: &tauri::AppHandle) {
+    let Some(items) = ITEMS.get() else { return };
+    let paused = crate::dictation::is_capture_paused();
+    let _ = items.paused.set_checked(paused);
+    let gateway = if crate::is_gateway_running() { "Gateway running" } else { "Gateway stopped" };
+    let _ = items.gateway.set_text(gateway);
+    let _ = items.answer.set_enabled(RESPONSE_READY.load(Ordering::SeqCst));
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let tooltip = if paused { format!("spaceduck · paused · {gateway}") } else { format!("spaceduck · {gateway}") };
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+    let status = status();
+    if status == Status::Recording {
+        pulse(app);
+    } else if !PULSING.load(Ordering::SeqCst) {
+        if let Some(icons) = icons(app) {
+            let icon = match status {
+                Status::GatewayDown => &icons.warning,
+                Status::ResponseReady => &icons.response,
+                Status::Recording | Status::Idle => &icons.idle,
+            };
+            set_icon(app, icon);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use windows::core::BSTR;
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+use windows::Win32::UI::Accessibility::{
+    CUIAutomation, IUIAutomation, IUIAutomationTextEditPattern, IUIAutomationTextPattern, IUIAutomationValuePattern, TextPatternRangeEndpoint_End,
+    TextPatternRangeEndpoint_Start, TextUnit_Character, UIA_TextEditPatternId, UIA_TextPatternId, UIA_ValuePatternId,
+};
+
+/// Insert `text` at the caret of the focused control through UI Automation.
+/// UIA has no "insert" call, so this reads the text around the selection with
+/// TextPattern, writes the combined value back with ValuePattern, and moves the caret
+/// to the end of the insertion. Only controls exposing both patterns qualify, which
+/// in practice means plain edit boxes; anything else returns `Ok(false)` so the caller
+/// can fall back to pasting.
+pub fn insert(text: &str) -> Result<bool, String> {
+    unsafe {
+        // Already initialised in another mode is fine; UIA works from either apartment.
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("Failed to create UI Automation client: {e}"))?;
+        let Ok(element) = automation.GetFocusedElement() else { return Ok(false) };
+
+        let Ok(value) = element.GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId) else {
+            return Ok(false);
+        };
+        if value.CurrentIsReadOnly().map(|b| b.as_bool()).unwrap_or(true) {
+            return Ok(false);
+        }
+        let Ok(pattern) = element.GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId) else {
+            return Ok(false);
+        };
+        let selections = pattern.GetSelection().map_err(|e| format!("Failed to read the selection: {e}"))?;
+        if selections.Length().unwrap_or(0) == 0 {
+            return Ok(false);
+        }
+        let selection = selections.GetElement(0).map_err(|e| format!("Failed to read the selection: {e}"))?;
+        let document = pattern.DocumentRange().map_err(|e| format!("Failed to read the document: {e}"))?;
+
+        let before = document.Clone().map_err(|e| e.to_string())?;
+        before
+            .MoveEndpointByRange(TextPatternRangeEndpoint_End, &selection, TextPatternRangeEndpoint_Start)
+            .map_err(|e| e.to_string())?;
+        let after = document.Clone().map_err(|e| e.to_string())?;
+        after
+            .MoveEndpointByRange(TextPatternRangeEndpoint_Start, &selection, TextPatternRangeEndpoint_End)
+            .map_err(|e| e.to_string())?;
+        let prefix = before.GetText(-1).map_err(|e| e.to_string())?.to_string();
+        let suffix = after.GetText(-1).map_err(|e| e.to_string())?.to_string();
+
+        value
+            .SetValue(&BSTR::from(format!("{prefix}{text}{suffix}")))
+            .map_err(|e| format!("ValuePattern.SetValue failed: {e}"))?;
+
+        // Setting the value resets the caret; put it back after the inserted text.
+        if let Ok(document) = pattern.DocumentRange() {
+            let caret = document;
+            let _ = caret.MoveEndpointByRange(TextPatternRangeEndpoint_End, &caret, TextPatternRangeEndpoint_Start);
+            let offset = (prefix.chars().count() + text.chars().count()) as i32;
+            if caret.Move(TextUnit_Character, offset).is_ok() {
+                let _ = caret.Select();
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Whether the focused control is a password box.
+pub fn focused_is_password() -> bool {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let Ok(automation) = CoCreateInstance::<_, IUIAutomation>(&CUIAutomation, None, CLSCTX_INPROC_SERVER) else {
+            return false;
+        };
+        automation
+            .GetFocusedElement()
+            .and_then(|element| element.CurrentIsPassword())
+            .is_ok_and(|password| password.as_bool())
+    }
+}
+
+/// Whether an input method is composing text in the focused control. Only controls
+/// exposing TextEditPattern, which TSF-aware ones do, can tell.
+pub fn focused_is_composing() -> bool {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let Ok(automation) = CoCreateInstance::<_, IUIAutomation>(&CUIAutomation, None, CLSCTX_INPROC_SERVER) else {
+            return false;
+        };
+        automation
+            .GetFocusedElement()
+            .and_then(|element| element.GetCurrentPatternAs::<IUIAutomationTextEditPattern>(UIA_TextEditPatternId))
+            .and_then(|pattern| pattern.GetActiveComposition())
+            .and_then(|range| range.GetText(-1))
+            .is_ok_and(|text| !text.is_empty())
+    }
+}
+
+/// Value of the focused control, for checking whether a paste landed.
+pub fn focused_value() -> Option<String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+        let element = automation.GetFocusedElement().ok()?;
+        let value = element.GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId).ok()?;
+        value.CurrentValue().ok().map(|v| v.to_string())
+    }
+}
+
+/// Delete `text` if it is exactly what precedes the caret in the focused control. UIA
+/// cannot edit through a text range, so this selects it and presses Backspace. Returns
+/// `Ok(false)` when the control cannot be read that way or the text is no longer there.
+pub fn delete_before_caret(text: &str) -> Result<bool, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("Failed to create UI Automation client: {e}"))?;
+        let Ok(element) = automation.GetFocusedElement() else { return Ok(false) };
+        let Ok(pattern) = element.GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId) else {
+            return Ok(false);
+        };
+        let Ok(selections) = pattern.GetSelection() else { return Ok(false) };
+        if selections.Length().unwrap_or(0) != 1 {
+            return Ok(false);
+        }
+        let caret = selections.GetElement(0).map_err(|e| e.to_string())?;
+        if !caret.GetText(-1).map_err(|e| e.to_string())?.is_empty() {
+            return Ok(false);
+        }
+        let len = text.chars().count() as i32;
+        let moved = caret
+            .MoveEndpointByUnit(TextPatternRangeEndpoint_Start, TextUnit_Character, -len)
+            .map_err(|e| e.to_string())?;
+        if moved != -len || caret.GetText(-1).map_err(|e| e.to_string())? != text {
+            return Ok(false);
+        }
+        caret.Select().map_err(|e| format!("Failed to select the dictation: {e}"))?;
+        crate::win_inject::backspaces(1)?;
+        Ok(true)
+    }
+}
+
+/// Up to `len` characters on each side of the selection in the focused control.
+pub fn caret_context(len: i32) -> Option<(String, String)> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+        let element = automation.GetFocusedElement().ok()?;
+        let pattern = element.GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId).ok()?;
+        let selections = pattern.GetSelection().ok()?;
+        if selections.Length().ok()? != 1 {
+            return None;
+        }
+        let selection = selections.GetElement(0).ok()?;
+
+        let before = selection.Clone().ok()?;
+        before.MoveEndpointByRange(TextPatternRangeEndpoint_End, &selection, TextPatternRangeEndpoint_Start).ok()?;
+        before.MoveEndpointByUnit(TextPatternRangeEndpoint_Start, TextUnit_Character, -len).ok()?;
+        let after = selection.Clone().ok()?;
+        after.MoveEndpointByRange(TextPatternRangeEndpoint_Start, &selection, TextPatternRangeEndpoint_End).ok()?;
+        after.MoveEndpointByUnit(TextPatternRangeEndpoint_End, TextUnit_Character, len).ok()?;
+        Some((before.GetText(-1).ok()?.to_string(), after.GetText(-1).ok()?.to_string()))
+    }
+}
+
+/// Up to `max_len` characters selected in the focused control, unless it is a password
+/// field.
+pub fn selected_text(max_len: i32) -> Option<String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+        let element = automation.GetFocusedElement().ok()?;
+        if element.CurrentIsPassword().is_ok_and(|password| password.as_bool()) {
+            return None;
+        }
+        let pattern = element.GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId).ok()?;
+        let selections = pattern.GetSelection().ok()?;
+        let mut text = String::new();
+        for i in 0..selections.Length().ok()? {
+            text.push_str(&selections.GetElement(i).ok()?.GetText(max_len).ok()?.to_string());
+        }
+        (!text.trim().is_empty()).then_some(text)
+    }
+}
@@ -0,0 +1,238 @@
+use webrtc_vad::{SampleRate, Vad, VadMode};
+
+/// webrtc-vad only takes 10/20/30 ms frames at fixed rates; 30 ms at 16 kHz is the steadiest.
+const RATE: u32 = 16_000;
+const FRAME_MS: u32 = 30;
+const FRAME_LEN: usize = (RATE * FRAME_MS / 1000) as usize;
+/// A hands-free recording that never hears speech gives up after this long.
+const NO_SPEECH_TIMEOUT_MS: u32 = 10_000;
+/// An utterance ends after this much silence.
+const UTTERANCE_GAP_MS: u32 = 300;
+/// Shorter bursts are clicks and breaths rather than words.
+const MIN_UTTERANCE_MS: u32 = 240;
+
+/// Decides when a hands-free recording has gone quiet for long enough to stop.
+pub struct SilenceDetector {
+    vad: Vad,
+    pending: Vec<i16>,
+    silence_limit_ms: u32,
+    heard_voice: bool,
+    trailing_silence_ms: u32,
+    elapsed_ms: u32,
+}
+
+impl SilenceDetector {
+    pub fn new(silence_limit_ms: u32) -> Self {
+        Self {
+            vad: Vad::new_with_rate_and_mode(SampleRate::Rate16kHz, VadMode::Aggressive),
+            pending: Vec::with_capacity(FRAME_LEN * 2),
+            silence_limit_ms,
+            heard_voice: false,
+            trailing_silence_ms: 0,
+            elapsed_ms: 0,
+        }
+    }
+
+    /// Feed newly captured mono audio. Returns true once the recording should stop.
+    pub fn feed(&mut self, samples: &[f32], sample_rate: u32) -> bool {
+        let converted;
+        let samples = if sample_rate == RATE {
+            samples
+        } else {
+            converted = crate::audio::resample_linear(samples, sample_rate, RATE);
+            &converted
+        };
+        self.pending.extend(samples.iter().map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+
+        let frames = self.pending.len() / FRAME_LEN;
+        for frame in self.pending.chunks_exact(FRAME_LEN) {
+            self.elapsed_ms += FRAME_MS;
+            if self.vad.is_voice_segment(frame).unwrap_or(false) {
+                self.heard_voice = true;
+                self.trailing_silence_ms = 0;
+            } else {
+                self.trailing_silence_ms += FRAME_MS;
+            }
+        }
+        self.pending.drain(..frames * FRAME_LEN);
+
+        if self.heard_voice {
+            self.trailing_silence_ms >= self.silence_limit_ms
+        } else {
+            self.elapsed_ms >= NO_SPEECH_TIMEOUT_MS.max(self.silence_limit_ms)
+        }
+    }
+}
+
+/// A stretch of speech and where it started, in samples since the segmenter was created.
+pub struct Utterance {
+    pub start: usize,
+    pub samples: Vec<f32>,
+}
+
+/// Cuts a continuous 16 kHz stream into utterances. Only speech ever reaches the caller,
+/// which keeps the wake-word listener and meeting transcription cheap.
+pub struct Segmenter {
+    vad: Vad,
+    pending: Vec<f32>,
+    current: Vec<f32>,
+    current_start: usize,
+    /// Samples fed so far, excluding `pending`.
+    position: usize,
+    voiced_ms: u32,
+    trailing_silence_ms: u32,
+    max_ms: u32,
+    /// Whether utterances over `max_ms` are cut and kept rather than discarded.
+    split_long: bool,
+}
+
+impl Segmenter {
+    /// Drops utterances longer than `max_ms`.
+    pub fn new(max_ms: u32) -> Self {
+        Self {
+            vad: Vad::new_with_rate_and_mode(SampleRate::Rate16kHz, VadMode::Aggressive),
+            pending: Vec::with_capacity(FRAME_LEN * 2),
+            current: Vec::new(),
+            current_start: 0,
+            position: 0,
+            voiced_ms: 0,
+            trailing_silence_ms: 0,
+            max_ms,
+            split_long: false,
+        }
+    }
+
+    /// Cuts utterances at `max_ms` instead of dropping them.
+    pub fn splitting(max_ms: u32) -> Self {
+        Self { split_long: true, ..Self::new(max_ms) }
+    }
+
+    /// Feed 16 kHz mono audio and collect any utterances that ended within it.
+    pub fn feed(&mut self, samples: &[f32], utterances: &mut Vec<Utterance>) {
+        self.pending.extend_from_slice(samples);
+        let frames = self.pending.len() / FRAME_LEN;
+        for i in 0..frames {
+            let frame = &self.pending[i * FRAME_LEN..(i + 1) * FRAME_LEN];
+            let pcm: Vec<i16> = frame.iter().map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect();
+            let voiced = self.vad.is_voice_segment(&pcm).unwrap_or(false);
+            if !voiced && self.current.is_empty() {
+                continue;
+            }
+            if self.current.is_empty() {
+                self.current_start = self.position + i * FRAME_LEN;
+            }
+            self.current.extend_from_slice(frame);
+            if voiced {
+                self.voiced_ms += FRAME_MS;
+                self.trailing_silence_ms = 0;
+            } else {
+                self.trailing_silence_ms += FRAME_MS;
+            }
+
+            let too_long = self.current.len() as u32 * 1000 / RATE > self.max_ms;
+            let ended = self.trailing_silence_ms >= UTTERANCE_GAP_MS;
+            if too_long || ended {
+                self.take(!too_long || self.split_long, utterances);
+            }
+        }
+        self.pending.drain(..frames * FRAME_LEN);
+        self.position += frames * FRAME_LEN;
+    }
+
+    /// Emit whatever utterance is still open, e.g. when the stream ends.
+    pub fn flush(&mut self, utterances: &mut Vec<Utterance>) {
+        self.take(true, utterances);
+    }
+
+    fn take(&mut self, keep: bool, utterances: &mut Vec<Utterance>) {
+        let samples = std::mem::take(&mut self.current);
+        if keep && self.voiced_ms >= MIN_UTTERANCE_MS {
+            utterances.push(Utterance { start: self.current_start, samples });
+        }
+        self.voiced_ms = 0;
+        self.trailing_silence_ms = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A buzzy 150 Hz tone with harmonics, which the VAD hears as voice.
+    fn voice(ms: u32) -> Vec<f32> {
+        (0..(RATE * ms / 1000) as usize)
+            .map(|i| {
+                let t = i as f32 / RATE as f32;
+                (1..8).map(|h| (2.0 * std::f32::consts::PI * 150.0 * h as f32 * t).sin() / h as f32).sum::<f32>() * 0.3
+            })
+            .collect()
+    }
+
+    fn silence(ms: u32) -> Vec<f32> {
+        vec![0.0; (RATE * ms / 1000) as usize]
+    }
+
+    fn segment(segmenter: &mut Segmenter, parts: &[Vec<f32>]) -> Vec<Utterance> {
+        let mut utterances = Vec::new();
+        for part in parts {
+            segmenter.feed(part, &mut utterances);
+        }
+        utterances
+    }
+
+    #[test]
+    fn ignores_silence() {
+        let utterances = segment(&mut Segmenter::new(10_000), &[silence(2000)]);
+        assert!(utterances.is_empty());
+    }
+
+    #[test]
+    fn cuts_speech_at_pauses() {
+        let parts = [silence(300), voice(600), silence(600), voice(900), silence(600)];
+        let utterances = segment(&mut Segmenter::new(10_000), &parts);
+        assert_eq!(utterances.len(), 2);
+        assert_eq!(utterances[0].start, (RATE * 300 / 1000) as usize);
+        assert_eq!(utterances[1].start, (RATE * 1500 / 1000) as usize);
+        assert!(utterances[1].samples.len() >= voice(900).len());
+    }
+
+    #[test]
+    fn accepts_audio_in_any_chunk_size() {
+        let audio: Vec<f32> = [silence(300), voice(600), silence(600)].concat();
+        let mut segmenter = Segmenter::new(10_000);
+        let mut utterances = Vec::new();
+        for chunk in audio.chunks(37) {
+            segmenter.feed(chunk, &mut utterances);
+        }
+        assert_eq!(utterances.len(), 1);
+        assert_eq!(utterances[0].start, (RATE * 300 / 1000) as usize);
+    }
+
+    #[test]
+    fn drops_clicks() {
+        let utterances = segment(&mut Segmenter::new(10_000), &[voice(90), silence(600)]);
+        assert!(utterances.is_empty());
+    }
+
+    #[test]
+    fn drops_or_splits_long_utterances() {
+        let parts = [voice(2000), silence(600)];
+        // The rest of the speech, with the pause that ends it, runs past the limit as well.
+        assert!(segment(&mut Segmenter::new(1000), &parts).is_empty());
+
+        let split = segment(&mut Segmenter::splitting(1000), &parts);
+        assert_eq!(split.len(), 2);
+        assert_eq!(split[0].start, 0);
+        assert_eq!(split[1].start, split[0].samples.len());
+    }
+
+    #[test]
+    fn flushes_the_open_utterance() {
+        let mut segmenter = Segmenter::new(10_000);
+        let mut utterances = segment(&mut segmenter, &[voice(600)]);
+        assert!(utterances.is_empty());
+        segmenter.flush(&mut utterances);
+        assert_eq!(utterances.len(), 1);
+        assert_eq!(utterances[0].start, 0);
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+/// Spoken editing commands applied to transcripts before they are shown or pasted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VoiceCommandsConfig {
+    pub enabled: bool,
+    /// Also read a lone "comma", "period", "colon" or "semicolon" as punctuation. Off by
+    /// default, since those words turn up in ordinary speech.
+    pub spoken_punctuation: bool,
+    /// User phrases that expand to literal text, checked before the built-ins.
+    pub custom: Vec<CustomCommand>,
+}
+
+impl Default for VoiceCommandsConfig {
+    fn default() -> Self {
+        Self { enabled: true, spoken_punctuation: false, custom: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomCommand {
+    pub phrase: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Builtin {
+    /// Attaches to the previous word, replacing any punctuation Whisper put there.
+    Punctuation(&'static str),
+    Break(&'static str),
+    DeleteSentence,
+    DeleteWord,
+    CapsSentence,
+}
+
+const BUILTINS: &[(&str, Builtin)] = &[
+    ("new line", Builtin::Break("\n")),
+    ("new paragraph", Builtin::Break("\n\n")),
+    ("full stop", Builtin::Punctuation(".")),
+    ("question mark", Builtin::Punctuation("?")),
+    ("exclamation mark", Builtin::Punctuation("!")),
+    ("exclamation point", Builtin::Punctuation("!")),
+    ("scratch that", Builtin::DeleteSentence),
+    ("delete last sentence", Builtin::DeleteSentence),
+    ("delete last word", Builtin::DeleteWord),
+    ("all caps that", Builtin::CapsSentence),
+];
+
+/// Single words that are commands only with `spoken_punctuation`.
+const SPOKEN_PUNCTUATION: &[(&str, Builtin)] = &[
+    ("comma", Builtin::Punctuation(",")),
+    ("period", Builtin::Punctuation(".")),
+    ("colon", Builtin::Punctuation(":")),
+    ("semicolon", Builtin::Punctuation(";")),
+];
+
+enum Action<'a> {
+    Builtin(Builtin),
+    Text(&'a str),
+}
+
+/// A word as it is matched: lowercase with surrounding punctuation removed.
+fn key(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+fn trim_trailing_punctuation(out: &mut String) {
+    let len = out.trim_end_matches([' ', ',', '.', ';', ':', '!', '?']).len();
+    out.truncate(len);
+}
+
+/// Byte offset where the last sentence in `out` begins.
+fn sentence_start(out: &str) -> usize {
+    out.rfind(['.', '!', '?', '\n']).map(|i| i + 1).unwrap_or(0)
+}
+
+fn push_text(out: &mut String, text: &str) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push(' ');
+    }
+    out.push_str(text);
+}
+
+fn apply_builtin(out: &mut String, builtin: Builtin) {
+    match builtin {
+        Builtin::Punctuation(mark) => {
+            trim_trailing_punctuation(out);
+            out.push_str(mark);
+        }
+        Builtin::Break(text) => {
+            let len = out.trim_end_matches(' ').len();
+            out.truncate(len);
+            out.push_str(text);
+        }
+        Builtin::DeleteSentence => {
+            trim_trailing_punctuation(out);
+            out.truncate(sentence_start(out));
+            let len = out.trim_end_matches(' ').len();
+            out.truncate(len);
+        }
+        Builtin::DeleteWord => {
+            trim_trailing_punctuation(out);
+            let start = out.rfind([' ', '\n']).map(|i| i + 1).unwrap_or(0);
+            out.truncate(start);
+            let len = out.trim_end_matches(' ').len();
+            out.truncate(len);
+        }
+        Builtin::CapsSentence => {
+            let start = sentence_start(out.trim_end_matches(['.', '!', '?', ' ']));
+            let upper = out[start..].to_uppercase();
+            out.truncate(start);
+            out.push_str(&upper);
+        }
+    }
+}
+
+/// Rewrite `text`, interpreting command phrases instead of inserting them literally.
+/// The longest matching phrase wins, so "new paragraph" is never read as "new" + text.
+pub fn process(text: &str, config: &VoiceCommandsConfig) -> String {
+    if !config.enabled {
+        return text.to_string();
+    }
+
+    let builtins = BUILTINS.iter().chain(SPOKEN_PUNCTUATION.iter().filter(|_| config.spoken_punctuation));
+    let mut grammar: Vec<(Vec<String>, Action)> = config
+        .custom
+        .iter()
+        .map(|c| (c.phrase.split_whitespace().map(key).collect(), Action::Text(c.text.as_str())))
+        .chain(builtins.map(|(phrase, b)| (phrase.split(' ').map(str::to_string).collect(), Action::Builtin(*b))))
+        .filter(|(words, _): &(Vec<String>, Action)| !words.is_empty() && words.iter().all(|w| !w.is_empty()))
+        .collect();
+    // Stable, so custom phrases still beat built-ins of the same length.
+    grammar.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let keys: Vec<String> = words.iter().map(|w| key(w)).collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < words.len() {
+        let matched = grammar
+            .iter()
+            .find(|(phrase, _)| keys.get(i..i + phrase.len()).is_some_and(|window| window == phrase.as_slice()));
+        match matched {
+            Some((phrase, action)) => {
+                match action {
+                    Action::Builtin(builtin) => apply_builtin(&mut out, *builtin),
+                    Action::Text(text) => push_text(&mut out, text),
+                }
+                i += phrase.len();
+            }
+            None => {
+                push_text(&mut out, words[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+pub fn apply(handle: &tauri::AppHandle, text: &str) -> String {
+    crate::config::read(handle, |c| process(text, &c.voice_commands))
+}
+
+#[tauri::command]
+pub fn get_voice_commands(app: tauri::AppHandle) -> VoiceCommandsConfig {
+    crate::config::read(&app, |c| c.voice_commands.clone())
+}
+
+#[tauri::command]
+pub fn set_voice_commands(app: tauri::AppHandle, config: VoiceCommandsConfig) -> Result<(), String> {
+    if let Some(empty) = config.custom.iter().find(|c| c.phrase.split_whitespace().map(key).all(|w| w.is_empty())) {
+        return Err(format!("Voice command phrase {:?} has no words", empty.phrase));
+    }
+    crate::config::update(&app, |c| c.voice_commands = config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> VoiceCommandsConfig {
+        VoiceCommandsConfig::default()
+    }
+
+    #[test]
+    fn leaves_ordinary_words_alone_by_default() {
+        let text = "Put a comma after the period, then a colon.";
+        assert_eq!(process(text, &config()), text);
+    }
+
+    #[test]
+    fn reads_single_word_punctuation_when_enabled() {
+        let config = VoiceCommandsConfig { spoken_punctuation: true, ..config() };
+        assert_eq!(process("Hello comma world period", &config), "Hello, world.");
+        assert_eq!(process("Note colon buy milk semicolon eggs", &config), "Note: buy milk; eggs");
+    }
+
+    #[test]
+    fn replaces_whisper_punctuation_before_a_mark() {
+        assert_eq!(process("Is it done, question mark", &config()), "Is it done?");
+        assert_eq!(process("Stop. full stop", &config()), "Stop.");
+    }
+
+    #[test]
+    fn breaks_lines_without_trailing_spaces() {
+        assert_eq!(process("Dear Sam, new line thanks new paragraph Bye", &config()), "Dear Sam,\nthanks\n\nBye");
+    }
+
+    #[test]
+    fn deletes_the_last_sentence_and_word() {
+        assert_eq!(process("Keep this. Drop this scratch that", &config()), "Keep this.");
+        assert_eq!(process("one two three delete last word", &config()), "one two");
+    }
+
+    #[test]
+    fn capitalises_the_last_sentence() {
+        assert_eq!(process("Fine. do it now all caps that", &config()), "Fine. DO IT NOW");
+    }
+
+    #[test]
+    fn matches_the_longest_phrase_and_custom_before_builtins() {
+        let config = VoiceCommandsConfig {
+            custom: vec![
+                CustomCommand { phrase: "new line".to_string(), text: "<br>".to_string() },
+                CustomCommand { phrase: "my email".to_string(), text: "sam@example.com".to_string() },
+            ],
+            ..config()
+        };
+        assert_eq!(process("write to My Email new line now", &config), "write to sam@example.com <br> now");
+        assert_eq!(process("a new paragraph b", &config), "a\n\nb");
+    }
+
+    #[test]
+    fn does_nothing_when_disabled() {
+        let config = VoiceCommandsConfig { enabled: false, ..config() };
+        assert_eq!(process("one new line two", &config), "one new line two");
+    }
+}
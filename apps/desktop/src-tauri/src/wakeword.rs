@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::audio::{Recording, PIPELINE_RATE};
+use crate::dictation::{self, DictationTarget, TriggerSource};
+use crate::resample::Converter;
+use crate::transcription::TranscriptionEngine;
+use crate::vad::Segmenter;
+use crate::whisper::WhisperEngine;
+
+const DEFAULT_PHRASE: &str = "hey duck";
+/// Wake phrases are two or three words; anything longer is ordinary speech.
+const MAX_UTTERANCE_MS: u32 = 2_500;
+const POLL: Duration = Duration::from_millis(100);
+const REOPEN_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether the microphone is currently open for the wake word.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Set from the audio thread when the listening device disappears.
+static LOST: AtomicBool = AtomicBool::new(false);
+
+/// Always-on listening for a spoken phrase that starts a hands-free global dictation.
+/// Runs entirely on-device with a local Whisper model, and only on speech the VAD lets through.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WakeWordConfig {
+    pub enabled: bool,
+    pub phrase: String,
+    /// Model used to check utterances; a tiny model is plenty. `None` uses `whisperModel`.
+    pub model: Option<PathBuf>,
+    /// Stop listening whenever the machine is unplugged, not only in Low Power Mode or
+    /// when the battery runs low.
+    pub pause_on_battery: bool,
+}
+
+impl Default for WakeWordConfig {
+    fn default() -> Self {
+        Self { enabled: false, phrase: DEFAULT_PHRASE.to_string(), model: None, pause_on_battery: false }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ActivePayload {
+    active: bool,
+}
+
+/// Lowercase words only, so "Hey, Duck!" matches "hey duck".
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The open microphone and the 16 kHz audio it delivers.
+struct Listener {
+    _stream: cpal::Stream,
+    audio: Receiver<Vec<f32>>,
+    segmenter: Segmenter,
+    engine: WhisperEngine,
+    phrase: String,
+}
+
+impl Listener {
+    fn open(handle: &tauri::AppHandle, config: &WakeWordConfig, model_path: PathBuf) -> Result<Self, String> {
+        let (device, stream_config) = crate::audio::input(handle)?;
+        let mut converter = Converter::new(stream_config.sample_rate().0, PIPELINE_RATE)?;
+        let (tx, audio) = mpsc::channel();
+        let mut converted = Vec::new();
+        let stream = crate::audio::open_stream(
+            &device,
+            &stream_config,
+            move |mono| {
+                converted.clear();
+                converter.process(mono, &mut converted);
+                let _ = tx.send(converted.clone());
+            },
+            || LOST.store(true, Ordering::SeqCst),
+        )?;
+        let gpu = crate::config::read(handle, |c| c.whisper_gpu);
+        Ok(Self {
+            _stream: stream,
+            audio,
+            segmenter: Segmenter::new(MAX_UTTERANCE_MS),
+            engine: WhisperEngine { model_path, language: Some("en".to_string()), gpu, prompt: None, translate: false },
+            phrase: normalize(&config.phrase),
+        })
+    }
+
+    /// Wait up to `POLL` for audio and return whether any utterance contained the phrase.
+    fn heard_phrase(&mut self) -> bool {
+        let mut utterances = Vec::new();
+        match self.audio.recv_timeout(POLL) {
+            Ok(samples) => self.segmenter.feed(&samples, &mut utterances),
+            Err(RecvTimeoutError::Timeout) => return false,
+            Err(RecvTimeoutError::Disconnected) => {
+                LOST.store(true, Ordering::SeqCst);
+                return false;
+            }
+        }
+        for utterance in utterances {
+            let recording = Recording { samples: utterance.samples, sample_rate: PIPELINE_RATE };
+            match self.engine.transcribe(&recording) {
+                Ok(transcript) if normalize(&transcript.text).contains(&self.phrase) => return true,
+                Ok(transcript) => log::debug!("Wake word: ignoring {:?}", transcript.text),
+                Err(e) => log::warn!("Wake word check failed: {e}"),
+            }
+        }
+        false
+    }
+}
+
+fn set_active(handle: &tauri::AppHandle, active: bool) {
+    if ACTIVE.swap(active, Ordering::SeqCst) != active {
+        let _ = handle.emit("wakeword-active", ActivePayload { active });
+    }
+}
+
+/// Run the wake-word listener on the current thread. The microphone is only open while
+/// the feature is on, capture is not paused, no dictation is running and the machine is
+/// not saving power; turning it off
+/// closes the stream within `POLL`. This function blocks forever, so call it from a
+/// dedicated thread.
+pub fn start(handle: tauri::AppHandle) -> Result<(), String> {
+    let mut listener: Option<Listener> = None;
+
+    loop {
+        let (config, fallback_model) = crate::config::read(&handle, |c| (c.wake_word.clone(), c.whisper_model.clone()));
+        let model = config.model.clone().or(fallback_model);
+        let lost = LOST.swap(false, Ordering::SeqCst);
+        let power = crate::power::status();
+        let saving_power = power.saving_power() || (config.pause_on_battery && power.unplugged());
+        let wanted = config.enabled
+            && model.is_some()
+            && !saving_power
+            && !dictation::is_capture_paused()
+            && !dictation::is_active();
+
+        if lost && listener.take().is_some() {
+            log::warn!("Wake word input device disconnected");
+        }
+        if !wanted {
+            listener = None;
+        }
+        set_active(&handle, listener.is_some());
+
+        let Some(model) = model.filter(|_| wanted) else {
+            std::thread::sleep(POLL);
+            continue;
+        };
+        if listener.is_none() {
+            match Listener::open(&handle, &config, model) {
+                Ok(opened) => {
+                    log::info!("Listening for wake word {:?}", config.phrase);
+                    listener = Some(opened);
+                    set_active(&handle, true);
+                }
+                Err(e) => {
+                    log::warn!("Wake word listener failed to start: {e}");
+                    std::thread::sleep(REOPEN_DELAY);
+                    continue;
+                }
+            }
+        }
+
+        if listener.as_mut().is_some_and(Listener::heard_phrase) {
+            log::info!("Wake word detected");
+            // Release the microphone before the dictation opens its own stream.
+            listener = None;
+            set_active(&handle, false);
+            dictation::toggle(&handle, TriggerSource::WakeWord, DictationTarget::Global);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_wake_word(app: tauri::AppHandle) -> WakeWordConfig {
+    crate::config::read(&app, |c| c.wake_word.clone())
+}
+
+/// Whether the microphone is open for the wake word right now.
+#[tauri::command]
+pub fn get_wake_word_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Enabling needs native capture, so hands-free auto-stop can end the dictation it starts,
+/// and a local Whisper model. Disabling closes the microphone at once.
+#[tauri::command]
+pub fn set_wake_word(app: tauri::AppHandle, config: WakeWordConfig) -> Result<(), String> {
+    if config.enabled {
+        if normalize(&config.phrase).is_empty() {
+            return Err("The wake phrase cannot be empty".to_string());
+        }
+        if let Some(path) = &config.model {
+            if !path.is_file() {
+                return Err(format!("Whisper model not found: {}", path.display()));
+            }
+        }
+        let (native_capture, has_model) =
+            crate::config::read(&app, |c| (c.audio.native_capture, c.whisper_model.is_some()));
+        if !native_capture {
+            return Err("Turn on native capture to use a wake word".to_string());
+        }
+        if config.model.is_none() && !has_model {
+            return Err("Choose a Whisper model before enabling the wake word".to_string());
+        }
+    }
+    crate::config::update(&app, |c| c.wake_word = config)
+}
@@ -0,0 +1,274 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperSegment, WhisperState};
+
+use crate::audio::Recording;
+use crate::transcription::{TimedSegment, Transcript, TranscriptionEngine, WordConfidence};
+
+/// Whisper models are trained on 16 kHz mono, which is what the capture pipeline produces.
+const SAMPLE_RATE: u32 = crate::audio::PIPELINE_RATE;
+/// Auto-detect only looks at the start of the recording; a few seconds of speech is plenty.
+const DETECT_SECONDS: usize = 5;
+
+/// Benchmarks run on this much synthetic audio; the encoder cost barely depends on content.
+const BENCHMARK_SECONDS: usize = 5;
+
+/// The GPU backend this build was compiled with, if any.
+pub const GPU_BACKEND: Option<&str> = if cfg!(target_os = "macos") {
+    Some("metal")
+} else if cfg!(feature = "cuda") {
+    Some("cuda")
+} else if cfg!(feature = "vulkan") {
+    Some("vulkan")
+} else {
+    None
+};
+
+/// The first GPU ggml can actually use, probed once: a build with a GPU backend can still
+/// run on a machine without the device or driver for it.
+static GPU_DEVICE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Ask ggml's backend registry for a GPU device, which fails over to nothing when the
+/// backend's driver or hardware is missing.
+fn probe_gpu() -> Option<String> {
+    use whisper_rs::whisper_rs_sys as sys;
+
+    GPU_BACKEND?;
+    // SAFETY: registry devices live as long as the process, and their descriptions are
+    // NUL-terminated strings they own.
+    unsafe {
+        let device = (0..sys::ggml_backend_dev_count()).map(|i| sys::ggml_backend_dev_get(i)).find(|&dev| {
+            !dev.is_null() && sys::ggml_backend_dev_type(dev) == sys::ggml_backend_dev_type_GGML_BACKEND_DEVICE_TYPE_GPU
+        })?;
+        Some(std::ffi::CStr::from_ptr(sys::ggml_backend_dev_description(device)).to_string_lossy().into_owned())
+    }
+}
+
+fn gpu_device() -> Option<&'static str> {
+    GPU_DEVICE
+        .get_or_init(|| {
+            let device = probe_gpu();
+            match &device {
+                Some(name) => log::info!("Whisper GPU: {name}"),
+                None => log::info!("No GPU for local transcription, running on the CPU"),
+            }
+            device
+        })
+        .as_deref()
+}
+
+/// Models kept loaded at once: enough for the wake word's model and the dictation model
+/// not to evict each other.
+const MAX_LOADED: usize = 2;
+
+/// Loading a model takes seconds and hundreds of MB, so keep the last few around, by path
+/// and GPU setting, the most recently used last.
+static MODELS: Mutex<Vec<(PathBuf, bool, Arc<WhisperContext>)>> = Mutex::new(Vec::new());
+
+fn create(path: &Path, gpu: bool) -> Result<WhisperContext, String> {
+    let path_str = path.to_str().ok_or_else(|| format!("Model path is not valid UTF-8: {}", path.display()))?;
+    let mut params = WhisperContextParameters::default();
+    params.use_gpu(gpu && gpu_device().is_some());
+    log::info!("Loading Whisper model {} (gpu: {gpu})", path.display());
+    WhisperContext::new_with_params(path_str, params)
+        .map_err(|e| format!("Failed to load Whisper model {}: {e}", path.display()))
+}
+
+fn load(path: &Path, gpu: bool) -> Result<Arc<WhisperContext>, String> {
+    let mut models = MODELS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(i) = models.iter().position(|(loaded, loaded_gpu, _)| loaded == path && *loaded_gpu == gpu) {
+        let entry = models.remove(i);
+        let ctx = entry.2.clone();
+        models.push(entry);
+        return Ok(ctx);
+    }
+    let ctx = Arc::new(create(path, gpu)?);
+    if models.len() >= MAX_LOADED {
+        models.remove(0);
+    }
+    models.push((path.to_path_buf(), gpu, ctx.clone()));
+    Ok(ctx)
+}
+
+fn params(language: &str) -> FullParams<'_, '_> {
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+    params.set_language(Some(language));
+    params
+}
+
+/// Join a segment's text tokens into words, each with its least confident token's
+/// probability, plus the mean token log-probability. Special tokens (timestamps,
+/// end of text) have ids from `eot` up and are skipped.
+fn words(segment: &WhisperSegment, eot: i32) -> (Vec<WordConfidence>, Option<f32>) {
+    let mut words: Vec<WordConfidence> = Vec::new();
+    let mut logprob_sum = 0.0;
+    let mut count = 0;
+    for i in 0..segment.n_tokens() {
+        let Some(token) = segment.get_token(i) else { continue };
+        let data = token.token_data();
+        if data.id >= eot {
+            continue;
+        }
+        let Ok(piece) = token.to_str_lossy() else { continue };
+        logprob_sum += data.plog;
+        count += 1;
+        // Whisper's BPE marks the start of a word with a leading space.
+        match words.last_mut() {
+            Some(word) if !piece.starts_with(' ') => {
+                word.text.push_str(&piece);
+                word.probability = word.probability.min(data.p);
+            }
+            _ => words.push(WordConfidence { text: piece.trim_start().to_string(), probability: data.p }),
+        }
+    }
+    words.retain(|w| !w.text.is_empty());
+    (words, (count > 0).then(|| logprob_sum / count as f32))
+}
+
+/// On-device transcription with a ggml/gguf Whisper model. Audio never leaves the machine.
+pub struct WhisperEngine {
+    pub model_path: PathBuf,
+    /// `None` detects the language from the opening seconds of each recording.
+    pub language: Option<String>,
+    /// Offload inference to the GPU when `GPU_BACKEND` finds one.
+    pub gpu: bool,
+    /// Initial prompt, used to bias spelling towards the user's vocabulary.
+    pub prompt: Option<String>,
+    /// Output English instead of the spoken language.
+    pub translate: bool,
+}
+
+/// Run Whisper's language detection on the start of `samples` and return the code.
+fn detect_language(state: &mut WhisperState, samples: &[f32]) -> Result<&'static str, String> {
+    let threads = std::thread::available_parallelism().map(|n| n.get().min(4)).unwrap_or(1);
+    let head = &samples[..samples.len().min(DETECT_SECONDS * SAMPLE_RATE as usize)];
+    state
+        .pcm_to_mel(head, threads)
+        .map_err(|e| format!("Failed to compute Whisper spectrogram: {e}"))?;
+    let (id, _) = state
+        .lang_detect(0, threads)
+        .map_err(|e| format!("Whisper language detection failed: {e}"))?;
+    whisper_rs::get_lang_str(id).ok_or_else(|| format!("Whisper detected unknown language id {id}"))
+}
+
+impl TranscriptionEngine for WhisperEngine {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn transcribe(&self, recording: &Recording) -> Result<Transcript, String> {
+        let ctx = load(&self.model_path, self.gpu)?;
+        let mut state = ctx.create_state().map_err(|e| format!("Failed to create Whisper state: {e}"))?;
+
+        let samples = if recording.sample_rate == SAMPLE_RATE {
+            recording.samples.clone()
+        } else {
+            crate::audio::resample_linear(&recording.samples, recording.sample_rate, SAMPLE_RATE)
+        };
+
+        let language = match &self.language {
+            Some(language) => language.clone(),
+            None => detect_language(&mut state, &samples)?.to_string(),
+        };
+
+        let mut params = params(&language);
+        if let Some(prompt) = &self.prompt {
+            params.set_initial_prompt(prompt);
+        }
+        params.set_translate(self.translate);
+        state
+            .full(params, &samples)
+            .map_err(|e| format!("Whisper inference failed: {e}"))?;
+
+        let eot = ctx.token_eot();
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        for segment in state.as_iter() {
+            let part = segment.to_str_lossy().map_err(|e| format!("Failed to read Whisper segment: {e}"))?;
+            text.push_str(&part);
+            let (words, avg_logprob) = words(&segment, eot);
+            // Whisper timestamps are in centiseconds.
+            segments.push(TimedSegment {
+                start_ms: segment.start_timestamp().max(0) as u64 * 10,
+                end_ms: segment.end_timestamp().max(0) as u64 * 10,
+                text: part.trim().to_string(),
+                avg_logprob,
+                no_speech_prob: Some(segment.no_speech_probability()),
+                words,
+            });
+        }
+        Ok(Transcript { text: text.trim().to_string(), language: Some(language), segments })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuSupport {
+    /// `metal`, `cuda` or `vulkan`; `None` means this build only runs on the CPU.
+    pub backend: Option<&'static str>,
+    /// The GPU the backend found on this machine; `None` when there is none to use.
+    pub device: Option<&'static str>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Benchmark {
+    pub cpu_ms: u64,
+    /// `None` when there is no GPU to compare against.
+    pub gpu_ms: Option<u64>,
+}
+
+/// Time one inference pass, excluding model load.
+fn time_inference(path: &Path, gpu: bool) -> Result<u64, String> {
+    let ctx = create(path, gpu)?;
+    let mut state = ctx.create_state().map_err(|e| format!("Failed to create Whisper state: {e}"))?;
+    // A quiet tone rather than silence, so the decoder does not bail out early.
+    let samples: Vec<f32> = (0..BENCHMARK_SECONDS * SAMPLE_RATE as usize)
+        .map(|i| (i as f32 * 0.37).sin() * 0.01)
+        .collect();
+    let started = std::time::Instant::now();
+    state
+        .full(params("en"), &samples)
+        .map_err(|e| format!("Whisper inference failed: {e}"))?;
+    Ok(started.elapsed().as_millis() as u64)
+}
+
+#[tauri::command]
+pub fn get_gpu_support(app: tauri::AppHandle) -> GpuSupport {
+    GpuSupport { backend: GPU_BACKEND, device: gpu_device(), enabled: crate::config::read(&app, |c| c.whisper_gpu) }
+}
+
+/// Takes effect from the next local transcription, which reloads the model.
+#[tauri::command]
+pub fn set_whisper_gpu(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled && GPU_BACKEND.is_none() {
+        return Err("This build has no GPU support for local transcription".to_string());
+    }
+    if enabled && gpu_device().is_none() {
+        return Err(format!("No GPU on this machine works with {}", GPU_BACKEND.unwrap_or_default()));
+    }
+    crate::config::update(&app, |c| c.whisper_gpu = enabled)
+}
+
+/// Compare CPU and GPU latency on the configured model (or `model_path`). Loads the
+/// model once per backend, so it can take a while on large models.
+#[tauri::command]
+pub async fn benchmark_whisper(app: tauri::AppHandle, model_path: Option<PathBuf>) -> Result<Benchmark, String> {
+    let path = model_path
+        .or_else(|| crate::config::read(&app, |c| c.whisper_model.clone()))
+        .ok_or_else(|| "Choose a Whisper model to benchmark".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let cpu_ms = time_inference(&path, false)?;
+        let gpu_ms = gpu_device().map(|_| time_inference(&path, true)).transpose()?;
+        log::info!("Whisper benchmark for {}: cpu {cpu_ms}ms, gpu {gpu_ms:?}ms", path.display());
+        Ok(Benchmark { cpu_ms, gpu_ms })
+    })
+    .await
+    .map_err(|e| format!("Benchmark task failed: {e}"))?
+}
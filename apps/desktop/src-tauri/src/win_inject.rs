@@ -0,0 +1,146 @@
+use windows::Win32::Foundation::{CloseHandle, GetLastError, HANDLE};
+use windows::Win32::Security::{GetTokenInformation, TokenElevation, TokenUIAccess, TOKEN_ELEVATION, TOKEN_QUERY};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, VIRTUAL_KEY,
+    VK_BACK, VK_CONTROL, VK_LWIN, VK_MENU, VK_RETURN, VK_RWIN, VK_SHIFT, VK_TAB,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+/// The virtual key for V is its ASCII code whatever the layout, unlike the character 'v',
+/// which does not exist on Cyrillic or Greek layouts.
+const VK_V: VIRTUAL_KEY = VIRTUAL_KEY(0x56);
+const VK_C: VIRTUAL_KEY = VIRTUAL_KEY(0x43);
+
+/// Process id of the window receiving keyboard input.
+pub fn foreground_pid() -> Option<u32> {
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(GetForegroundWindow(), Some(&mut pid)) };
+    (pid != 0).then_some(pid)
+}
+
+/// Read a 32-bit flag such as `TokenElevation` from a process token.
+unsafe fn token_flag(process: HANDLE, class: windows::Win32::Security::TOKEN_INFORMATION_CLASS) -> Option<bool> {
+    let mut token = HANDLE::default();
+    OpenProcessToken(process, TOKEN_QUERY, &mut token).ok()?;
+    let mut value = TOKEN_ELEVATION::default();
+    let mut len = 0u32;
+    let result = GetTokenInformation(
+        token,
+        class,
+        Some((&mut value as *mut TOKEN_ELEVATION).cast()),
+        std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+        &mut len,
+    );
+    let _ = CloseHandle(token);
+    result.ok().map(|_| value.TokenIsElevated != 0)
+}
+
+/// Whether the foreground window runs elevated while we do not. User Interface Privilege
+/// Isolation then drops our synthetic input without reporting an error, so it has to be
+/// checked up front. Builds signed with `uiAccess` are exempt.
+pub fn target_is_elevated() -> bool {
+    unsafe {
+        let own = GetCurrentProcess();
+        if token_flag(own, TokenElevation) == Some(true) || token_flag(own, TokenUIAccess) == Some(true) {
+            return false;
+        }
+        let Some(pid) = foreground_pid() else { return false };
+        // An elevated process usually refuses even this limited access to medium-integrity callers.
+        let process = match OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
+            Ok(process) => process,
+            Err(_) => return true,
+        };
+        let elevated = token_flag(process, TokenElevation).unwrap_or(false);
+        let _ = CloseHandle(process);
+        elevated
+    }
+}
+
+fn key(vk: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 { ki: KEYBDINPUT { wVk: vk, wScan: scan, dwFlags: flags, time: 0, dwExtraInfo: 0 } },
+    }
+}
+
+fn send(inputs: &[INPUT]) -> Result<(), String> {
+    let sent = unsafe { SendInput(inputs, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        return Err(format!("SendInput delivered {sent} of {} events: {:?}", inputs.len(), unsafe { GetLastError() }));
+    }
+    Ok(())
+}
+
+/// Press and release for one character. Line breaks and tabs go out as their keys,
+/// since many controls ignore U+000A and U+0009 sent as unicode packets.
+fn char_inputs(c: char, out: &mut Vec<INPUT>) {
+    let vk = match c {
+        '\n' => Some(VK_RETURN),
+        '\t' => Some(VK_TAB),
+        '\r' => return,
+        _ => None,
+    };
+    if let Some(vk) = vk {
+        out.push(key(vk, 0, KEYBD_EVENT_FLAGS(0)));
+        out.push(key(vk, 0, KEYEVENTF_KEYUP));
+        return;
+    }
+    // Characters outside the BMP are sent as their two surrogates.
+    let mut units = [0u16; 2];
+    for unit in c.encode_utf16(&mut units) {
+        out.push(key(VIRTUAL_KEY(0), *unit, KEYEVENTF_UNICODE));
+        out.push(key(VIRTUAL_KEY(0), *unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP));
+    }
+}
+
+/// Type `text` with `KEYEVENTF_UNICODE` packets, which bypass the keyboard layout.
+/// With no delay the whole text goes out in one `SendInput` call, which keeps real
+/// keystrokes from interleaving with it.
+pub fn type_text(text: &str, delay_ms: u32) -> Result<(), String> {
+    let mut inputs = Vec::new();
+    if delay_ms == 0 {
+        text.chars().for_each(|c| char_inputs(c, &mut inputs));
+        return send(&inputs);
+    }
+    let delay = std::time::Duration::from_millis(delay_ms as u64);
+    for c in text.chars() {
+        inputs.clear();
+        char_inputs(c, &mut inputs);
+        send(&inputs)?;
+        std::thread::sleep(delay);
+    }
+    Ok(())
+}
+
+/// Ctrl+V by virtual key code.
+pub fn paste() -> Result<(), String> {
+    send(&[
+        key(VK_CONTROL, 0, KEYBD_EVENT_FLAGS(0)),
+        key(VK_V, 0, KEYBD_EVENT_FLAGS(0)),
+        key(VK_V, 0, KEYEVENTF_KEYUP),
+        key(VK_CONTROL, 0, KEYEVENTF_KEYUP),
+    ])
+}
+
+/// Ctrl+C by virtual key code. Shift, Alt and Windows are let go first, since a copy
+/// sent from a global shortcut would otherwise pick up the ones still held for it.
+pub fn copy() -> Result<(), String> {
+    send(&[
+        key(VK_SHIFT, 0, KEYEVENTF_KEYUP),
+        key(VK_MENU, 0, KEYEVENTF_KEYUP),
+        key(VK_LWIN, 0, KEYEVENTF_KEYUP),
+        key(VK_RWIN, 0, KEYEVENTF_KEYUP),
+        key(VK_CONTROL, 0, KEYBD_EVENT_FLAGS(0)),
+        key(VK_C, 0, KEYBD_EVENT_FLAGS(0)),
+        key(VK_C, 0, KEYEVENTF_KEYUP),
+        key(VK_CONTROL, 0, KEYEVENTF_KEYUP),
+    ])
+}
+
+pub fn backspaces(count: usize) -> Result<(), String> {
+    let inputs: Vec<INPUT> = (0..count)
+        .flat_map(|_| [key(VK_BACK, 0, KEYBD_EVENT_FLAGS(0)), key(VK_BACK, 0, KEYEVENTF_KEYUP)])
+        .collect();
+    send(&inputs)
+}
@@ -0,0 +1,164 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetMessageW, SetWindowsHookExW, UnhookWindowsHookEx, KBDLLHOOKSTRUCT, MSG, MSLLHOOKSTRUCT, WH_KEYBOARD_LL,
+    WH_MOUSE_LL, WM_KEYDOWN, WM_LBUTTONDOWN, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_SYSKEYDOWN, WM_XBUTTONDOWN, WM_XBUTTONUP,
+};
+
+use crate::dictation::{self, Edge, MouseButton, TriggerSource};
+
+/// Low-level hook procs get no user data pointer, so the handle lives here.
+static HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+/// Whether the configured push-to-talk mouse button is currently held.
+static MOUSE_DOWN: AtomicBool = AtomicBool::new(false);
+
+const XBUTTON1: u16 = 0x0001;
+const XBUTTON2: u16 = 0x0002;
+const VK_MEDIA_PLAY_PAUSE: u32 = 0xB3;
+const VK_BACK: u32 = 0x08;
+const VK_SHIFT: i32 = 0x10;
+const VK_CAPITAL: i32 = 0x14;
+/// Return, Tab, Escape, Page Up/Down, End, Home, the arrows and Delete move or leave the
+/// caret, so anything typed before them no longer ends at it.
+const CARET_KEYS: [u32; 12] = [0x0D, 0x09, 0x1B, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x2E];
+/// Set on keystrokes sent with SendInput, including our own insertions.
+const LLKHF_INJECTED: u32 = 0x10;
+/// ToUnicodeEx flag that leaves the keyboard state, and so pending dead keys, alone.
+const TO_UNICODE_NO_STATE_CHANGE: u32 = 0x4;
+
+/// Install WH_MOUSE_LL and WH_KEYBOARD_LL hooks on the current thread and pump messages for them.
+/// Mirrors the macOS monitor's mouse trigger: the configured button starts a push-to-talk
+/// dictation on press and stops it on release. The keyboard hook watches play/pause and
+/// feeds typed characters to snippet expansion.
+/// This function blocks forever, so call it from a dedicated thread.
+pub fn start(handle: tauri::AppHandle) -> Result<(), String> {
+    let _ = HANDLE.set(handle);
+
+    unsafe {
+        let hook = SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), None, 0)
+            .map_err(|e| format!("SetWindowsHookExW failed: {e}"))?;
+        let keyboard_hook = SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), None, 0)
+            .map_err(|e| log::warn!("Keyboard hook failed, media key dictation unavailable: {e}"))
+            .ok();
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {}
+
+        if let Some(keyboard_hook) = keyboard_hook {
+            let _ = UnhookWindowsHookEx(keyboard_hook);
+        }
+        let _ = UnhookWindowsHookEx(hook);
+    }
+
+    Err("Input hook message loop exited unexpectedly".to_string())
+}
+
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        if let Some(handle) = HANDLE.get() {
+            let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+            if wparam.0 as u32 == WM_LBUTTONDOWN {
+                crate::snippets::reset();
+            }
+            handle_mouse(handle, wparam.0 as u32, (info.mouseData >> 16) as u16);
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        if let Some(handle) = HANDLE.get() {
+            let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+            if info.vkCode == VK_MEDIA_PLAY_PAUSE && handle_media_key(handle, wparam.0 as u32) {
+                return LRESULT(1);
+            }
+            if info.flags.0 & LLKHF_INJECTED == 0 {
+                track_snippet_keys(handle, wparam.0 as u32, info);
+            }
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Toggle dictation on play/pause. Returns whether the key should be swallowed so music
+/// players do not also react; it passes through while disabled or capture is paused.
+fn handle_media_key(handle: &tauri::AppHandle, message: u32) -> bool {
+    if dictation::is_capture_paused() {
+        return false;
+    }
+    let Some(trigger) = crate::config::read(handle, |c| c.dictation_media_key.clone()) else { return false };
+    if matches!(message, WM_KEYDOWN | WM_SYSKEYDOWN) {
+        dictation::post(handle, Edge::Toggle(TriggerSource::MediaKey, trigger.target));
+    }
+    true
+}
+
+/// Feed typed characters to snippet expansion. Shortcuts and caret movement start the
+/// typed text over.
+fn track_snippet_keys(handle: &tauri::AppHandle, message: u32, info: &KBDLLHOOKSTRUCT) {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetKeyState, GetKeyboardLayout, ToUnicodeEx, VK_CONTROL, VK_MENU};
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    match message {
+        WM_SYSKEYDOWN => return crate::snippets::reset(),
+        WM_KEYDOWN => {}
+        _ => return,
+    }
+    if info.vkCode == VK_BACK {
+        return crate::snippets::key_deleted();
+    }
+    unsafe {
+        if CARET_KEYS.contains(&info.vkCode) || GetKeyState(VK_CONTROL.0 as i32) < 0 || GetKeyState(VK_MENU.0 as i32) < 0 {
+            return crate::snippets::reset();
+        }
+        // The hook runs on our thread, whose keyboard state is not the foreground one's.
+        let mut state = [0u8; 256];
+        if GetKeyState(VK_SHIFT) < 0 {
+            state[VK_SHIFT as usize] = 0x80;
+        }
+        state[VK_CAPITAL as usize] = (GetKeyState(VK_CAPITAL) & 1) as u8;
+        let layout = GetKeyboardLayout(GetWindowThreadProcessId(GetForegroundWindow(), None));
+        let mut buffer = [0u16; 4];
+        let len = ToUnicodeEx(info.vkCode, info.scanCode, &state, &mut buffer, TO_UNICODE_NO_STATE_CHANGE, Some(layout));
+        // Negative for a dead key, which only produces its character with the next one.
+        if len <= 0 {
+            return;
+        }
+        for c in char::decode_utf16(buffer[..len as usize].iter().copied()).filter_map(Result::ok) {
+            if !c.is_control() {
+                crate::snippets::key_typed(handle, c);
+            }
+        }
+    }
+}
+
+fn handle_mouse(handle: &tauri::AppHandle, message: u32, xbutton: u16) {
+    let (button, is_down) = match message {
+        WM_MBUTTONDOWN => (MouseButton::Middle, true),
+        WM_MBUTTONUP => (MouseButton::Middle, false),
+        WM_XBUTTONDOWN | WM_XBUTTONUP => {
+            let button = match xbutton {
+                XBUTTON1 => MouseButton::Back,
+                XBUTTON2 => MouseButton::Forward,
+                _ => return,
+            };
+            (button, message == WM_XBUTTONDOWN)
+        }
+        _ => return,
+    };
+
+    let Some(trigger) = crate::config::read(handle, |c| c.dictation_mouse_button.clone()) else { return };
+    if trigger.button != button {
+        return;
+    }
+
+    if is_down {
+        if !MOUSE_DOWN.swap(true, Ordering::SeqCst) {
+            dictation::post(handle, Edge::Press(TriggerSource::Mouse, trigger.target));
+        }
+    } else if MOUSE_DOWN.swap(false, Ordering::SeqCst) {
+        dictation::post(handle, Edge::Release(TriggerSource::Mouse));
+    }
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Manager, PhysicalPosition, PhysicalSize};
+
+/// Saved once the window has stopped moving or resizing for this long.
+const SETTLE: Duration = Duration::from_millis(1000);
+/// Display setups remembered; the oldest is dropped beyond this.
+const MAX_LAYOUTS: usize = 10;
+/// How much of the window must be on some display for a saved position to be used as is.
+const MIN_VISIBLE: i32 = 100;
+
+/// The main window's geometry under one arrangement of displays, in physical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    /// Hash of the connected displays' names, positions, sizes and scales.
+    pub displays: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// A window frame or a display's work area, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Rect {
+    fn of(monitor: &tauri::Monitor) -> Self {
+        let area = monitor.work_area();
+        Rect { x: area.position.x, y: area.position.y, width: area.size.width, height: area.size.height }
+    }
+
+    /// Whether at least `MIN_VISIBLE` pixels of `self` are on `area` in both directions.
+    fn shows_on(&self, area: &Rect) -> bool {
+        let left = self.x.max(area.x);
+        let right = (self.x + self.width as i32).min(area.x + area.width as i32);
+        let top = self.y.max(area.y);
+        let bottom = (self.y + self.height as i32).min(area.y + area.height as i32);
+        right - left >= MIN_VISIBLE && bottom - top >= MIN_VISIBLE
+    }
+}
+
+static SAVING: AtomicBool = AtomicBool::new(false);
+static LAST_CHANGE: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn last_change() -> std::sync::MutexGuard<'static, Option<Instant>> {
+    LAST_CHANGE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Identifies the current display arrangement, so a laptop docked to two monitors and the
+/// same laptop on its own each reopen the window where it last was on them.
+fn displays_key(app: &tauri::AppHandle) -> Option<String> {
+    let monitors = app
+        .available_monitors()
+        .ok()?
+        .iter()
+        .map(|m| {
+            format!(
+                "{}@{},{} {}x{} {}",
+                m.name().map_or("", |n| n.as_str()),
+                m.position().x,
+                m.position().y,
+                m.size().width,
+                m.size().height,
+                m.scale_factor()
+            )
+        })
+        .collect();
+    layout_key(monitors)
+}
+
+/// Hash of the display descriptions in any order, or `None` without displays.
+fn layout_key(mut monitors: Vec<String>) -> Option<String> {
+    if monitors.is_empty() {
+        return None;
+    }
+    monitors.sort();
+    let digest = Sha256::digest(monitors.join("\n").as_bytes());
+    Some(digest.iter().take(8).map(|b| format!("{b:02x}")).collect())
+}
+
+/// Put the main window back where it was under the current displays, or else where it
+/// last was under any, pulled onto a display if that one is gone.
+pub fn restore(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else { return };
+    let key = displays_key(app);
+    let saved = crate::config::read(app, |c| saved_for(&c.window_geometry, key.as_deref()).cloned());
+    let Some(saved) = saved else { return };
+    let (position, size) = clamp(app, &saved);
+    let _ = window.set_size(size);
+    let _ = window.set_position(position);
+    if saved.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// The geometry saved under `key`, or else the most recently saved one.
+fn saved_for<'a>(geometries: &'a [WindowGeometry], key: Option<&str>) -> Option<&'a WindowGeometry> {
+    geometries.iter().find(|g| Some(g.displays.as_str()) == key).or(geometries.last())
+}
+
+/// Keep the geometry if enough of it is on a display; otherwise centre it on the primary
+/// display, shrunk to fit.
+fn clamp(app: &tauri::AppHandle, saved: &WindowGeometry) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let areas: Vec<Rect> = app.available_monitors().unwrap_or_default().iter().map(Rect::of).collect();
+    let primary = app.primary_monitor().ok().flatten().map(|m| Rect::of(&m));
+    let frame = Rect { x: saved.x, y: saved.y, width: saved.width, height: saved.height };
+    let frame = place(frame, &areas, primary);
+    (PhysicalPosition::new(frame.x, frame.y), PhysicalSize::new(frame.width, frame.height))
+}
+
+fn place(frame: Rect, areas: &[Rect], primary: Option<Rect>) -> Rect {
+    if areas.iter().any(|area| frame.shows_on(area)) {
+        return frame;
+    }
+    let Some(area) = primary.or_else(|| areas.first().copied()) else { return frame };
+    let width = frame.width.min(area.width);
+    let height = frame.height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) as i32 / 2,
+        y: area.y + (area.height - height) as i32 / 2,
+        width,
+        height,
+    }
+}
+
+/// The main window moved or resized; save its geometry once it settles.
+pub fn changed(app: &tauri::AppHandle) {
+    *last_change() = Some(Instant::now());
+    if SAVING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        while last_change().is_some_and(|t| t.elapsed() < SETTLE) {
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        SAVING.store(false, Ordering::SeqCst);
+        if let Err(e) = save(&app) {
+            log::warn!("Failed to save the window geometry: {e}");
+        }
+    });
+}
+
+fn save(app: &tauri::AppHandle) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("The main window is gone")?;
+    // Minimised windows report an off-screen position on Windows.
+    if window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(true) {
+        return Ok(());
+    }
+    let displays = displays_key(app).ok_or("No displays")?;
+    let maximized = window.is_maximized().unwrap_or(false);
+    let mut geometry = WindowGeometry { displays, x: 0, y: 0, width: 0, height: 0, maximized };
+    if maximized {
+        // Keep the size to restore to when it is unmaximised again.
+        let previous = crate::config::read(app, |c| {
+            c.window_geometry.iter().find(|g| g.displays == geometry.displays).cloned()
+        });
+        let Some(previous) = previous else { return Ok(()) };
+        geometry = WindowGeometry { maximized, ..previous };
+    } else {
+        let position = window.outer_position().map_err(|e| e.to_string())?;
+        let size = window.inner_size().map_err(|e| e.to_string())?;
+        geometry.x = position.x;
+        geometry.y = position.y;
+        geometry.width = size.width;
+        geometry.height = size.height;
+    }
+    crate::config::update(app, |c| {
+        c.window_geometry.retain(|g| g.displays != geometry.displays);
+        c.window_geometry.push(geometry);
+        let excess = c.window_geometry.len().saturating_sub(MAX_LAYOUTS);
+        c.window_geometry.drain(..excess);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAPTOP: Rect = Rect { x: 0, y: 25, width: 1440, height: 875 };
+    const MONITOR: Rect = Rect { x: 1440, y: 0, width: 2560, height: 1440 };
+
+    fn geometry(displays: &str, x: i32) -> WindowGeometry {
+        WindowGeometry { displays: displays.to_string(), x, y: 0, width: 800, height: 600, maximized: false }
+    }
+
+    #[test]
+    fn keys_the_display_arrangement_in_any_order() {
+        let laptop = "Built-in@0,0 2880x1800 2".to_string();
+        let monitor = "DELL@1440,0 2560x1440 1".to_string();
+        let docked = layout_key(vec![laptop.clone(), monitor.clone()]).unwrap();
+        assert_eq!(layout_key(vec![monitor.clone(), laptop.clone()]).unwrap(), docked);
+        assert_eq!(docked.len(), 16);
+        assert_ne!(layout_key(vec![laptop.clone()]).unwrap(), docked);
+        assert_ne!(layout_key(vec![laptop, "DELL@-2560,0 2560x1440 1".to_string()]).unwrap(), docked);
+        assert_eq!(layout_key(Vec::new()), None);
+    }
+
+    #[test]
+    fn falls_back_to_the_last_arrangement_when_a_display_is_removed() {
+        let saved = [geometry("docked", 2000), geometry("laptop", 100), geometry("travel", 300)];
+        assert_eq!(saved_for(&saved, Some("laptop")).unwrap().x, 100);
+        assert_eq!(saved_for(&saved, Some("unplugged")).unwrap().x, 300);
+        assert_eq!(saved_for(&saved, None).unwrap().x, 300);
+        assert!(saved_for(&[], Some("laptop")).is_none());
+    }
+
+    #[test]
+    fn keeps_a_window_that_is_on_some_display() {
+        let on_monitor = Rect { x: 2000, y: 200, width: 800, height: 600 };
+        assert_eq!(place(on_monitor, &[LAPTOP, MONITOR], Some(LAPTOP)), on_monitor);
+        let mostly_off = Rect { x: LAPTOP.width as i32 - 150, y: 100, width: 800, height: 600 };
+        assert_eq!(place(mostly_off, &[LAPTOP], Some(LAPTOP)), mostly_off);
+    }
+
+    #[test]
+    fn centres_an_off_screen_window_on_the_primary_display() {
+        // Last seen on a monitor that has since been unplugged.
+        let gone = Rect { x: 2000, y: 200, width: 800, height: 600 };
+        assert_eq!(place(gone, &[LAPTOP], Some(LAPTOP)), Rect { x: 320, y: 162, width: 800, height: 600 });
+        let barely = Rect { x: LAPTOP.width as i32 - 50, y: 100, width: 800, height: 600 };
+        assert_eq!(place(barely, &[LAPTOP], None), Rect { x: 320, y: 162, width: 800, height: 600 });
+        assert_eq!(place(gone, &[], None), gone);
+    }
+
+    #[test]
+    fn shrinks_an_off_screen_window_larger_than_the_work_area() {
+        let huge = Rect { x: 5000, y: 0, width: 2560, height: 1440 };
+        assert_eq!(place(huge, &[LAPTOP], Some(LAPTOP)), LAPTOP);
+    }
+}
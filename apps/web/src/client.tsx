@@ -1,11 +1,28 @@
 import "@spaceduck/ui/styles.css";
 import { createRoot } from "react-dom/client";
-import { App, DictationPill } from "@spaceduck/ui";
+import { App, ConfirmOverlay, DictationPill, QuickAsk, ResponseWindow, ScreenshotRegion } from "@spaceduck/ui";
 
 const root = document.getElementById("root");
 if (!root) throw new Error("Missing #root element");
 
 const params = new URLSearchParams(window.location.search);
-const isDictationWindow = params.get("window") === "dictation";
+const windowKind = params.get("window");
 
-createRoot(root).render(isDictationWindow ? <DictationPill /> : <App />);
+function windowFor(kind: string | null) {
+  switch (kind) {
+    case "dictation":
+      return <DictationPill />;
+    case "confirm":
+      return <ConfirmOverlay />;
+    case "quick-ask":
+      return <QuickAsk />;
+    case "response":
+      return <ResponseWindow />;
+    case "screenshot-region":
+      return <ScreenshotRegion />;
+    default:
+      return <App />;
+  }
+}
+
+createRoot(root).render(windowFor(windowKind));
@@ -32,6 +32,8 @@ export type WsServerEnvelope =
   | { v: 1; type: "conversation.deleted"; conversationId: string }
   | { v: 1; type: "conversation.renamed"; conversationId: string; title: string }
   | { v: 1; type: "run.active"; conversationIds: string[] }
+  | { v: 1; type: "task.completed"; taskId: string; name: string; route: string; conversationId?: string; result?: string }
+  | { v: 1; type: "task.progress"; taskId: string; name: string; state: "running" | "retrying" | "failed" }
   | { v: 1; type: "error"; code: string; message: string };
 
 export interface ConversationSummary {
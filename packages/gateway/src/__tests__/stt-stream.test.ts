@@ -0,0 +1,91 @@
+import { describe, it, expect } from "bun:test";
+import { readFile } from "node:fs/promises";
+import { ConsoleLogger } from "@spaceduck/core";
+import { createSttStreamHandler, createSttStreamState, wavHeader } from "../stt-stream";
+
+function createSocket() {
+  const sent: any[] = [];
+  return {
+    sent,
+    data: { stt: createSttStreamState() },
+    send(data: string) {
+      sent.push(JSON.parse(data));
+    },
+  };
+}
+
+function createHandler(text = "hello world") {
+  const calls: { bytes: number; languageHint?: string }[] = [];
+  const handler = createSttStreamHandler({
+    logger: new ConsoleLogger("error"),
+    transcriber: () => ({
+      maxBytes: 1024 * 1024,
+      provider: {
+        async transcribeFile(path: string, opts?: { languageHint?: string }) {
+          const wav = await readFile(path);
+          calls.push({ bytes: wav.byteLength, languageHint: opts?.languageHint });
+          return { text, language: "en" };
+        },
+      },
+    }),
+  });
+  return { handler, calls };
+}
+
+describe("STT stream", () => {
+  it("transcribes the recording on stop", async () => {
+    const { handler, calls } = createHandler();
+    const ws = createSocket();
+    await handler.message(ws, JSON.stringify({ type: "start", sampleRate: 16000, encoding: "pcm_s16le", language: "de" }));
+    await handler.message(ws, new Uint8Array(3200));
+    await handler.message(ws, JSON.stringify({ type: "stop" }));
+
+    expect(calls).toEqual([{ bytes: 44 + 3200, languageHint: "de" }]);
+    expect(ws.sent).toEqual([{ type: "final", text: "hello world", language: "en" }]);
+  });
+
+  it("sends partials as the recording grows", async () => {
+    const { handler } = createHandler("partial");
+    const ws = createSocket();
+    await handler.message(ws, JSON.stringify({ type: "start", sampleRate: 1000 }));
+    await handler.message(ws, new Uint8Array(8000));
+    await Bun.sleep(20);
+
+    expect(ws.sent).toEqual([{ type: "partial", text: "partial" }]);
+  });
+
+  it("finishes an empty recording without transcribing", async () => {
+    const { handler, calls } = createHandler();
+    const ws = createSocket();
+    await handler.message(ws, JSON.stringify({ type: "start" }));
+    await handler.message(ws, JSON.stringify({ type: "stop" }));
+
+    expect(calls).toHaveLength(0);
+    expect(ws.sent).toEqual([{ type: "final", text: "" }]);
+  });
+
+  it("rejects audio before start and unknown encodings", async () => {
+    const { handler } = createHandler();
+    const ws = createSocket();
+    await handler.message(ws, new Uint8Array(320));
+    await handler.message(ws, JSON.stringify({ type: "start", encoding: "opus" }));
+
+    expect(ws.sent.map((m) => m.type)).toEqual(["error", "error"]);
+  });
+
+  it("reports STT as unavailable on open", () => {
+    const handler = createSttStreamHandler({ logger: new ConsoleLogger("error"), transcriber: () => null });
+    const ws = createSocket();
+    handler.open(ws);
+
+    expect(ws.sent).toEqual([{ type: "error", message: "STT is not available" }]);
+  });
+
+  it("writes a mono 16-bit WAV header", () => {
+    const header = new DataView(wavHeader(3200, 16000).buffer);
+    expect(header.getUint32(4, true)).toBe(36 + 3200);
+    expect(header.getUint32(24, true)).toBe(16000);
+    expect(header.getUint32(28, true)).toBe(32000);
+    expect(header.getUint32(40, true)).toBe(3200);
+  });
+});
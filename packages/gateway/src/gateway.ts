@@ -14,6 +14,7 @@ import {
   type EmbeddingProvider,
   type Channel,
   type Message,
+  type WsServerEnvelope,
   ConsoleLogger,
   SimpleEventBus,
   DefaultContextBuilder,
@@ -37,7 +38,8 @@ import {
   reconcileVecMemories,
 } from "@spaceduck/memory-sqlite";
 import { RunLock } from "./run-lock";
-import { createWsHandler, type WsConnectionData } from "./ws-handler";
+import { BROADCAST_TOPIC, createWsHandler, type WsConnectionData } from "./ws-handler";
+import { createSttStreamHandler, createSttStreamState } from "./stt-stream";
 import { buildToolRegistry } from "./tool-registrations";
 import { createBrowserFrameTarget } from "./browser-frame-target";
 import { BrowserSessionPool } from "./browser-session-pool";
@@ -186,6 +188,35 @@ export class Gateway implements Lifecycle {
     this.browserPool = deps.browserPool;
     this.conversationIdRef = deps.conversationIdRef ?? { current: "" };
     this.browserFrame = deps.browserFrame ?? createBrowserFrameTarget();
+
+    // Scheduled task results go to every connected client, e.g. for desktop notifications.
+    deps.eventBus.on("task:completed", ({ task }) => {
+      const route = task.definition.resultRoute;
+      this.broadcast({
+        v: 1,
+        type: "task.completed",
+        taskId: task.id,
+        name: task.definition.name,
+        route: typeof route === "string" ? route : route.type,
+        conversationId: task.definition.conversationId,
+        result: task.resultText?.slice(0, 500),
+      });
+    });
+
+    // So native clients can show that background work is running, e.g. in the Dock.
+    deps.eventBus.on("task:started", ({ task }) => {
+      this.broadcast({ v: 1, type: "task.progress", taskId: task.id, name: task.definition.name, state: "running" });
+    });
+    deps.eventBus.on("task:failed", ({ task }) => {
+      this.broadcast({ v: 1, type: "task.progress", taskId: task.id, name: task.definition.name, state: "retrying" });
+    });
+    deps.eventBus.on("task:dead_letter", ({ task }) => {
+      this.broadcast({ v: 1, type: "task.progress", taskId: task.id, name: task.definition.name, state: "failed" });
+    });
+  }
+
+  private broadcast(envelope: WsServerEnvelope): void {
+    this.server?.publish(BROADCAST_TOPIC, JSON.stringify(envelope));
   }
 
   get status(): LifecycleStatus {
@@ -221,6 +252,14 @@ export class Gateway implements Lifecycle {
       conversationIdRef: this.conversationIdRef,
     });
 
+    const sttStream = createSttStreamHandler({
+      logger,
+      transcriber: () => {
+        const provider = this.activeSttProvider();
+        return provider ? { provider, maxBytes: this.stt.maxBytes } : null;
+      },
+    });
+
     this.server = Bun.serve<WsConnectionData>({
       port: config.port,
       development: false,
@@ -239,9 +278,9 @@ export class Gateway implements Lifecycle {
         return resp;
       },
       websocket: {
-        message: wsHandler.message,
-        open: wsHandler.open,
-        close: wsHandler.close,
+        message: (ws, raw) => (ws.data.stt ? sttStream.message(ws, raw) : wsHandler.message(ws, raw as string)),
+        open: (ws) => (ws.data.stt ? sttStream.open(ws) : wsHandler.open(ws)),
+        close: (ws, code) => (ws.data.stt ? sttStream.close(ws) : wsHandler.close(ws, code)),
       },
     });
 
@@ -678,6 +717,26 @@ export class Gateway implements Lifecycle {
       return new Response("WebSocket upgrade failed", { status: 400 });
     }
 
+    // Streaming STT upgrade (auth checked like /ws)
+    if (url.pathname === "/api/stt/stream") {
+      if (this.db && this.authRequired) {
+        const raw = extractToken(req);
+        if (!raw || !verifyToken(this.db, raw)) {
+          return Response.json({ error: "Unauthorized" }, { status: 401 });
+        }
+      }
+      const upgraded = server.upgrade(req, {
+        data: {
+          senderId: `stt-${Date.now().toString(36)}`,
+          channelId: "stt",
+          connectedAt: Date.now(),
+          stt: createSttStreamState(),
+        },
+      });
+      if (upgraded) return undefined as unknown as Response;
+      return new Response("WebSocket upgrade failed", { status: 400 });
+    }
+
     // Health endpoint
     if (req.method === "GET" && url.pathname === "/api/health") {
       const embedRef =
@@ -1582,11 +1641,18 @@ export class Gateway implements Lifecycle {
     }
   }
 
+  /** The STT backend in use, or null while it is unavailable. */
+  private activeSttProvider(): WhisperStt | AwsTranscribeStt | null {
+    if (!this.stt.available) return null;
+    return (this.activeSttBackend === "aws-transcribe" ? this.awsTranscribeStt : this.whisperStt) ?? null;
+  }
+
   private async handleTranscribe(req: Request): Promise<Response> {
     const { logger } = this.deps;
     const requestId = `stt_${Date.now().toString(36)}-${Math.random().toString(36).slice(2, 9)}`;
 
-    if (!this.stt.available || (!this.whisperStt && !this.awsTranscribeStt)) {
+    const sttProvider = this.activeSttProvider();
+    if (!sttProvider) {
       return Response.json(
         { requestId, error: "STT_UNAVAILABLE", message: `STT backend (${this.activeSttBackend}) is not available` },
         { status: 503 },
@@ -1614,6 +1680,8 @@ export class Gateway implements Lifecycle {
     }
 
     const languageHint = req.headers.get("x-stt-language") ?? undefined;
+    // The user's vocabulary, in the query string since it may not be ASCII.
+    const prompt = new URL(req.url).searchParams.get("prompt")?.slice(-MAX_STT_PROMPT_CHARS) || undefined;
     const ext = mimeToExt(mimeType);
     const filename = `spaceduck-stt-${Date.now()}-${randomBytes(6).toString("hex")}${ext}`;
     const tempPath = join(tmpdir(), filename);
@@ -1656,10 +1724,7 @@ export class Gateway implements Lifecycle {
       }
 
       const startTime = Date.now();
-      const sttProvider = this.activeSttBackend === "aws-transcribe"
-        ? this.awsTranscribeStt!
-        : this.whisperStt!;
-      const result = await sttProvider.transcribeFile(tempPath, { languageHint });
+      const result = await sttProvider.transcribeFile(tempPath, { languageHint, prompt });
       const durationMs = Date.now() - startTime;
 
       logger.info("STT transcribed", { requestId, durationMs, language: result.language, bytes });
@@ -1785,6 +1850,9 @@ const CHANNEL_REBUILD_PATHS = new Set([
   "/channels/whatsapp/enabled",
 ]);
 
+/** Whisper only reads the last ~224 tokens of its initial prompt, so longer ones keep their end. */
+const MAX_STT_PROMPT_CHARS = 1000;
+
 const STT_REBUILD_PATHS = new Set([
   "/stt/backend",
   "/stt/model",
@@ -0,0 +1,195 @@
+// Streaming STT over WebSocket: the client sends raw PCM as it records and gets
+// partial transcripts back, then one final transcript when it stops.
+//
+// Protocol:
+//   client → {"type":"start","sampleRate":16000,"encoding":"pcm_s16le","language":"en"|null}
+//   client → binary frames of mono s16le PCM
+//   client → {"type":"stop"}
+//   server → {"type":"partial","text"}* then {"type":"final","text","language"?} | {"type":"error","message"}
+
+import type { Logger } from "@spaceduck/core";
+import { unlink } from "node:fs/promises";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { randomBytes } from "node:crypto";
+
+/** Seconds of new audio between partial transcripts. */
+const PARTIAL_INTERVAL_SECONDS = 4;
+
+export interface SttStreamTranscriber {
+  transcribeFile(
+    path: string,
+    opts?: { languageHint?: string },
+  ): Promise<{ text: string; language?: string }>;
+}
+
+export interface SttStreamDeps {
+  readonly logger: Logger;
+  /** The active STT backend and its size limit, or null while STT is unavailable. */
+  readonly transcriber: () => { provider: SttStreamTranscriber; maxBytes: number } | null;
+}
+
+/** Per-connection state for an `/api/stt/stream` connection. */
+export interface SttStreamState {
+  sampleRate: number;
+  languageHint?: string;
+  chunks: Uint8Array[];
+  bytes: number;
+  /** Byte count the last partial was transcribed at. */
+  partialAt: number;
+  /** A partial transcription is in flight. */
+  busy: boolean;
+  started: boolean;
+  stopped: boolean;
+}
+
+export function createSttStreamState(): SttStreamState {
+  return { sampleRate: 16000, chunks: [], bytes: 0, partialAt: 0, busy: false, started: false, stopped: false };
+}
+
+type SttSocket = { send(data: string): void; close?(): void; data: { stt?: SttStreamState } };
+
+function send(ws: SttSocket, message: Record<string, unknown>): void {
+  ws.send(JSON.stringify(message));
+}
+
+/** A 44-byte RIFF header for mono 16-bit PCM. */
+export function wavHeader(dataBytes: number, sampleRate: number): Uint8Array {
+  const header = new DataView(new ArrayBuffer(44));
+  const ascii = (offset: number, text: string) => {
+    for (let i = 0; i < text.length; i++) header.setUint8(offset + i, text.charCodeAt(i));
+  };
+  ascii(0, "RIFF");
+  header.setUint32(4, 36 + dataBytes, true);
+  ascii(8, "WAVE");
+  ascii(12, "fmt ");
+  header.setUint32(16, 16, true);
+  header.setUint16(20, 1, true);
+  header.setUint16(22, 1, true);
+  header.setUint32(24, sampleRate, true);
+  header.setUint32(28, sampleRate * 2, true);
+  header.setUint16(32, 2, true);
+  header.setUint16(34, 16, true);
+  ascii(36, "data");
+  header.setUint32(40, dataBytes, true);
+  return new Uint8Array(header.buffer);
+}
+
+/**
+ * Create the handlers for `/api/stt/stream` connections. Each transcription runs the
+ * whole recording so far through the active backend, so partials get more accurate as
+ * the recording grows.
+ */
+export function createSttStreamHandler(deps: SttStreamDeps) {
+  const { logger } = deps;
+  const log = logger.child({ component: "SttStream" });
+
+  async function transcribe(state: SttStreamState) {
+    const active = deps.transcriber();
+    if (!active) throw new Error("STT is not available");
+    const tempPath = join(tmpdir(), `spaceduck-stt-stream-${Date.now()}-${randomBytes(6).toString("hex")}.wav`);
+    try {
+      await Bun.write(tempPath, new Blob([wavHeader(state.bytes, state.sampleRate), ...state.chunks]));
+      return await active.provider.transcribeFile(tempPath, { languageHint: state.languageHint });
+    } finally {
+      try {
+        await unlink(tempPath);
+      } catch {
+        // Best-effort cleanup
+      }
+    }
+  }
+
+  async function partial(ws: SttSocket, state: SttStreamState) {
+    state.busy = true;
+    state.partialAt = state.bytes;
+    try {
+      const result = await transcribe(state);
+      if (!state.stopped) send(ws, { type: "partial", text: result.text });
+    } catch (err) {
+      log.debug("Partial transcription failed", { error: err instanceof Error ? err.message : String(err) });
+    } finally {
+      state.busy = false;
+    }
+  }
+
+  async function finish(ws: SttSocket, state: SttStreamState) {
+    state.stopped = true;
+    if (state.bytes === 0) {
+      send(ws, { type: "final", text: "" });
+      return;
+    }
+    try {
+      const startTime = Date.now();
+      const result = await transcribe(state);
+      log.info("STT stream transcribed", { durationMs: Date.now() - startTime, bytes: state.bytes, language: result.language });
+      send(ws, { type: "final", text: result.text, language: result.language });
+    } catch (err) {
+      const message = err instanceof Error ? err.message : String(err);
+      log.warn("STT stream failed", { error: message });
+      send(ws, { type: "error", message });
+    }
+  }
+
+  return {
+    open(ws: SttSocket) {
+      if (!deps.transcriber()) {
+        send(ws, { type: "error", message: "STT is not available" });
+        ws.close?.();
+      }
+    },
+
+    async message(ws: SttSocket, raw: string | Buffer | Uint8Array) {
+      const state = ws.data.stt;
+      if (!state || state.stopped) return;
+
+      if (typeof raw !== "string") {
+        if (!state.started) {
+          send(ws, { type: "error", message: "Audio sent before start" });
+          return;
+        }
+        const active = deps.transcriber();
+        if (active && state.bytes + raw.byteLength > active.maxBytes) {
+          state.stopped = true;
+          send(ws, { type: "error", message: `Recording too large (max ${Math.round(active.maxBytes / 1024 / 1024)}MB)` });
+          return;
+        }
+        state.chunks.push(new Uint8Array(raw));
+        state.bytes += raw.byteLength;
+        const interval = PARTIAL_INTERVAL_SECONDS * state.sampleRate * 2;
+        if (!state.busy && state.bytes - state.partialAt >= interval) void partial(ws, state);
+        return;
+      }
+
+      let parsed: { type?: string; sampleRate?: number; encoding?: string; language?: string | null };
+      try {
+        parsed = JSON.parse(raw);
+      } catch {
+        send(ws, { type: "error", message: "Invalid JSON" });
+        return;
+      }
+      switch (parsed.type) {
+        case "start":
+          if (parsed.encoding && parsed.encoding !== "pcm_s16le") {
+            state.stopped = true;
+            send(ws, { type: "error", message: `Unsupported encoding: ${parsed.encoding}` });
+            return;
+          }
+          if (typeof parsed.sampleRate === "number" && parsed.sampleRate > 0) state.sampleRate = parsed.sampleRate;
+          state.languageHint = parsed.language ?? undefined;
+          state.started = true;
+          break;
+        case "stop":
+          await finish(ws, state);
+          break;
+        default:
+          send(ws, { type: "error", message: `Unknown message type: ${parsed.type}` });
+      }
+    },
+
+    close(ws: SttSocket) {
+      const state = ws.data.stt;
+      if (state) state.stopped = true;
+    },
+  };
+}
@@ -12,6 +12,7 @@ import type { AgentLoop } from "@spaceduck/core";
 import type { RunLock } from "./run-lock";
 import type { BrowserFrameTarget } from "./browser-frame-target";
 import type { BrowserSessionPool } from "./browser-session-pool";
+import type { SttStreamState } from "./stt-stream";
 
 function generateId(): string {
   return `${Date.now().toString(36)}-${Math.random().toString(36).slice(2, 9)}`;
@@ -34,8 +35,13 @@ export interface WsConnectionData {
   senderId: string;
   channelId: string;
   connectedAt: number;
+  /** Set on `/api/stt/stream` connections, which the STT stream handler serves instead. */
+  stt?: SttStreamState;
 }
 
+/** Pub/sub topic every connection joins, for broadcasts such as task results. */
+export const BROADCAST_TOPIC = "broadcast";
+
 function send(ws: { send(data: string): void }, envelope: WsServerEnvelope): void {
   ws.send(JSON.stringify(envelope));
 }
@@ -129,8 +135,9 @@ export function createWsHandler(deps: WsHandlerDeps) {
       }
     },
 
-    open(ws: { send(data: string): void; data: WsConnectionData }) {
+    open(ws: { send(data: string): void; subscribe?(topic: string): void; data: WsConnectionData }) {
       log.debug("Client connected", { senderId: ws.data.senderId });
+      ws.subscribe?.(BROADCAST_TOPIC);
       const activeIds = runLock.activeConversationIds;
       if (activeIds.length > 0) {
         send(ws, { v: 1, type: "run.active", conversationIds: activeIds });
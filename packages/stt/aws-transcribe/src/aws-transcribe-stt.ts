@@ -17,6 +17,8 @@ export interface TranscribeResult {
 
 export interface TranscribeOptions {
   languageHint?: string;
+  /** Ignored: Transcribe only takes vocabularies created in AWS ahead of time. */
+  prompt?: string;
   timeoutMs?: number;
 }
 
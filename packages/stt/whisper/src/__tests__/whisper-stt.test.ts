@@ -122,6 +122,29 @@ describe("WhisperStt", () => {
       expect(capturedArgs[0]).not.toContain("--language");
     });
 
+    it("passes --initial_prompt when a prompt is provided", async () => {
+      const capturedArgs: string[][] = [];
+      const stt = new WhisperStt({
+        runner: createFakeRunner({ capturedArgs }),
+      });
+
+      await stt.transcribeFile("/tmp/test.webm", { prompt: "Spaceduck, Tauri" });
+
+      const args = capturedArgs[0];
+      expect(args).toContain("--initial_prompt");
+      expect(args[args.indexOf("--initial_prompt") + 1]).toBe("Spaceduck, Tauri");
+    });
+
+    it("does not pass --initial_prompt when no prompt is provided", async () => {
+      const capturedArgs: string[][] = [];
+      const stt = new WhisperStt({
+        runner: createFakeRunner({ capturedArgs }),
+      });
+
+      await stt.transcribeFile("/tmp/test.webm");
+      expect(capturedArgs[0]).not.toContain("--initial_prompt");
+    });
+
     it("falls back language to languageHint when JSON has no language field", async () => {
       const stt = new WhisperStt({
         runner: createFakeRunner({
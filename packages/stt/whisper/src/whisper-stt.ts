@@ -18,6 +18,8 @@ export interface TranscribeResult {
 
 export interface TranscribeOptions {
   languageHint?: string;
+  /** Words and names to steer recognition towards, passed as Whisper's initial prompt. */
+  prompt?: string;
   model?: string;
   timeoutMs?: number;
 }
@@ -225,6 +227,10 @@ export class WhisperStt {
         args.push("--language", opts.languageHint);
       }
 
+      if (opts?.prompt) {
+        args.push("--initial_prompt", opts.prompt);
+      }
+
       let result: { exitCode: number; stderr: string };
       try {
         result = await this.runner(args, { timeoutMs: timeout });
@@ -10,7 +10,7 @@ import { TasksView } from "./components/tasks-view";
 import { MemoryView } from "./components/memory-view";
 import { DictationOverlay } from "./components/dictation-overlay";
 import { TooltipProvider } from "./ui/tooltip";
-import { Toaster } from "sonner";
+import { Toaster, toast } from "sonner";
 import type { ChatInputRecorderHandle } from "./components/chat-input";
 
 export type AppView = "onboarding" | "chat" | "settings" | "tasks" | "memory";
@@ -53,6 +53,8 @@ function AppInner() {
   const [setupBanner, setSetupBanner] = useState(false);
   const shouldConnect = view === "chat" || view === "settings" || view === "tasks" || view === "memory";
   const ws = useSpaceduckWs(shouldConnect);
+  // Set in windows the desktop app opens for a single conversation.
+  const windowConversationRef = useRef(new URLSearchParams(window.location.search).get("conversation"));
   const chatRecorderRef = useRef<ChatInputRecorderHandle | null>(null);
 
   const [dictationConfig, setDictationConfig] = useState<{
@@ -84,6 +86,53 @@ function AppInner() {
     fetchDictationConfig();
   }, [fetchDictationConfig]);
 
+  const { status, selectConversation } = ws;
+  useEffect(() => {
+    const conversationId = windowConversationRef.current;
+    if (status !== "connected" || !conversationId) return;
+    windowConversationRef.current = null;
+    selectConversation(conversationId);
+  }, [status, selectConversation]);
+
+  // Tell the desktop app what this window shows, so it can be reopened after a crash.
+  const { activeConversationId } = ws;
+  useEffect(() => {
+    const invoke = (window as any).__TAURI__?.core?.invoke;
+    invoke?.("set_window_conversation", { conversationId: activeConversationId }).catch(() => {});
+  }, [activeConversationId]);
+
+  useEffect(() => {
+    const tauri = (window as any).__TAURI__;
+    if (!tauri?.event?.listen) return;
+    const unlisten = tauri.event.listen("open-conversation", (e: { payload: string }) => {
+      windowConversationRef.current = e.payload;
+      if (status === "connected") {
+        windowConversationRef.current = null;
+        selectConversation(e.payload);
+      }
+    });
+    return () => { unlisten.then((u: () => void) => u()); };
+  }, [status, selectConversation]);
+
+  // Only the main window offers to bring back a session that ended in a crash.
+  useEffect(() => {
+    const tauri = (window as any).__TAURI__;
+    if (!tauri?.core?.invoke || new URLSearchParams(window.location.search).has("conversation")) return;
+    tauri.core.invoke("get_previous_session").then((session: { windows: unknown[] } | null) => {
+      if (!session?.windows.length) return;
+      toast("spaceduck didn't close properly last time", {
+        description: "Reopen the windows and conversations you had up?",
+        duration: Infinity,
+        action: {
+          label: "Restore",
+          onClick: () => {
+            tauri.core.invoke("restore_previous_session").catch((err: unknown) => toast.error(String(err)));
+          },
+        },
+      });
+    }).catch(() => {});
+  }, []);
+
   const dictation = useDictation({
     enabled: dictationConfig.enabled,
     hotkey: dictationConfig.hotkey,
@@ -6,6 +6,7 @@ import { Tooltip, TooltipContent, TooltipTrigger } from "../ui/tooltip";
 import { LiveWaveform } from "../ui/live-waveform";
 import type { Attachment } from "@spaceduck/core";
 import { useVoiceRecorder } from "../hooks/use-voice-recorder";
+import { toast } from "sonner";
 
 function getUploadUrl(): string {
   const stored = localStorage.getItem("spaceduck.gatewayUrl");
@@ -19,6 +20,7 @@ function getUploadUrl(): string {
 export interface ChatInputRecorderHandle {
   startRecording: () => void;
   stopAndTranscribe: () => void;
+  cancelRecording: () => void;
   isRecording: () => boolean;
 }
 
@@ -44,6 +46,8 @@ export function ChatInput({ onSend, disabled, isStreaming, sttAvailable, sttLang
   const [attachments, setAttachments] = useState<Attachment[]>([]);
   const [uploading, setUploading] = useState(false);
   const [dragOver, setDragOver] = useState(false);
+  // Files dropped on the desktop window or pill, which the Rust side uploads.
+  const [nativeUploads, setNativeUploads] = useState<Record<string, { filename: string; sent: number; total: number }>>({});
   const textareaRef = useRef<HTMLTextAreaElement>(null);
   const fileInputRef = useRef<HTMLInputElement>(null);
 
@@ -62,10 +66,56 @@ export function ChatInput({ onSend, disabled, isStreaming, sttAvailable, sttLang
       recorderRef.current = {
         startRecording: recorder.startRecording,
         stopAndTranscribe: recorder.stopAndTranscribe,
+        cancelRecording: recorder.cancel,
         isRecording: () => recorder.state === "recording",
       };
     }
-  }, [recorderRef, recorder.startRecording, recorder.stopAndTranscribe, recorder.state]);
+  }, [recorderRef, recorder.startRecording, recorder.stopAndTranscribe, recorder.cancel, recorder.state]);
+
+  // Chat dictations the desktop app recorded natively, and their failures.
+  useEffect(() => {
+    const listen = (window as any).__TAURI__?.event?.listen;
+    if (!listen) return;
+    const unsubs: Array<() => void> = [];
+    listen("dictation:result", (e: { payload: { mode: string; text: string; translation?: string | null } }) => {
+      if (e.payload.mode !== "chat") return;
+      const text = (e.payload.translation ?? e.payload.text).trim();
+      if (!text) return;
+      setValue((prev) => (prev ? prev + "\n" + text : text));
+      setTimeout(() => textareaRef.current?.focus(), 0);
+    }).then((u: () => void) => unsubs.push(u));
+    listen("dictation:error-chat", (e: { payload: { message: string } }) => {
+      toast.error(e.payload.message);
+    }).then((u: () => void) => unsubs.push(u));
+    return () => { unsubs.forEach((u) => u()); };
+  }, []);
+
+  // The desktop app asks for this when its global shortcut summons the window.
+  useEffect(() => {
+    const listen = (window as any).__TAURI__?.event?.listen;
+    if (!listen) return;
+    let unlisten: (() => void) | undefined;
+    listen("focus-chat-input", () => textareaRef.current?.focus()).then((u: () => void) => { unlisten = u; });
+    return () => unlisten?.();
+  }, []);
+
+  useEffect(() => {
+    const listen = (window as any).__TAURI__?.event?.listen;
+    if (!listen) return;
+    let unlisten: (() => void) | undefined;
+    listen("file-upload", (e: { payload: any }) => {
+      const { uploadId, filename, sent, total, state, attachment, error } = e.payload;
+      setNativeUploads((prev) => {
+        const next = { ...prev };
+        if (state === "uploading") next[uploadId] = { filename, sent, total };
+        else delete next[uploadId];
+        return next;
+      });
+      if (state === "done" && attachment) setAttachments((prev) => [...prev, attachment]);
+      if (state === "failed") toast.error(error ?? `Could not attach ${filename}`);
+    }).then((u: () => void) => { unlisten = u; });
+    return () => unlisten?.();
+  }, []);
 
   useEffect(() => {
     const el = textareaRef.current;
@@ -146,7 +196,9 @@ export function ChatInput({ onSend, disabled, isStreaming, sttAvailable, sttLang
     setDragOver(false);
   }
 
-  const canSend = (value.trim().length > 0 || attachments.length > 0) && !disabled && !isStreaming && !uploading;
+  const pendingUploads = Object.entries(nativeUploads);
+  const isUploading = uploading || pendingUploads.length > 0;
+  const canSend = (value.trim().length > 0 || attachments.length > 0) && !disabled && !isStreaming && !isUploading;
   const isRecording = recorder.state === "recording";
   const isProcessing = recorder.state === "processing";
   const showMic = sttAvailable && !isStreaming;
@@ -163,8 +215,18 @@ export function ChatInput({ onSend, disabled, isStreaming, sttAvailable, sttLang
     >
       <div className="max-w-3xl mx-auto flex flex-col gap-2">
         {/* Attachment pills */}
-        {attachments.length > 0 && (
+        {(attachments.length > 0 || pendingUploads.length > 0) && (
           <div className="flex flex-wrap gap-2">
+            {pendingUploads.map(([id, upload]) => (
+              <div
+                key={id}
+                className="flex items-center gap-1.5 rounded-lg border border-border bg-muted/30 px-2.5 py-1.5 text-xs text-muted-foreground"
+              >
+                <Loader2 size={14} className="animate-spin shrink-0" />
+                <span className="truncate max-w-[160px]">{upload.filename}</span>
+                <span>{upload.total ? Math.round((upload.sent / upload.total) * 100) : 0}%</span>
+              </div>
+            ))}
             {attachments.map((att) => (
               <div
                 key={att.id}
@@ -302,16 +364,16 @@ export function ChatInput({ onSend, disabled, isStreaming, sttAvailable, sttLang
                   <Button
                     type="button"
                     onClick={() => fileInputRef.current?.click()}
-                    disabled={disabled || uploading}
+                    disabled={disabled || isUploading}
                     size="icon"
                     variant="ghost"
                     className="h-9 w-9 shrink-0 rounded-xl"
                   >
-                    <Paperclip size={18} className={uploading ? "animate-pulse" : ""} />
+                    <Paperclip size={18} className={isUploading ? "animate-pulse" : ""} />
                   </Button>
                 </TooltipTrigger>
                 <TooltipContent>
-                  {uploading ? "Uploading…" : "Attach PDF"}
+                  {isUploading ? "Uploading…" : "Attach PDF"}
                 </TooltipContent>
               </Tooltip>
             </div>
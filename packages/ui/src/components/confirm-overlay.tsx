@@ -0,0 +1,63 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+
+function getInvoke() {
+  return (window as any).__TAURI__?.core?.invoke as
+    | ((cmd: string, args?: Record<string, unknown>) => Promise<any>)
+    | undefined;
+}
+
+/** Shows a held-back dictation; Enter pastes it into the app underneath, Escape discards it. */
+export function ConfirmOverlay() {
+  const [text, setText] = useState<string | null>(null);
+  const doneRef = useRef(false);
+
+  useEffect(() => {
+    getInvoke()?.("get_pending_paste")
+      .then((pending: string | null) => setText(pending))
+      .catch(() => {});
+  }, []);
+
+  const finish = useCallback(async (confirm: boolean) => {
+    const invoke = getInvoke();
+    if (!invoke || doneRef.current) return;
+    doneRef.current = true;
+    try {
+      await invoke(confirm ? "confirm_pending_paste" : "discard_pending_paste");
+    } catch (e) {
+      // Insertion failed after the overlay closed; nothing is left to show this.
+      console.error(e);
+    }
+  }, []);
+
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if (e.key === "Enter" && !e.shiftKey) {
+        e.preventDefault();
+        finish(true);
+      } else if (e.key === "Escape") {
+        e.preventDefault();
+        finish(false);
+      }
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, [finish]);
+
+  return (
+    <div className="w-screen h-screen p-1 select-none">
+      <div className="flex flex-col gap-2 w-full h-full rounded-xl px-4 py-3 bg-black/80 text-white shadow-lg backdrop-blur-md">
+        <p className="flex-1 overflow-y-auto text-sm leading-snug whitespace-pre-wrap select-text">
+          {text ?? ""}
+        </p>
+        <div className="flex justify-end gap-3 text-xs text-white/60">
+          <button type="button" className="hover:text-white" onClick={() => finish(false)}>
+            Esc to discard
+          </button>
+          <button type="button" className="text-white hover:text-white/80" onClick={() => finish(true)}>
+            Enter to paste
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -38,12 +38,21 @@ export function DictationPill() {
   const [state, setState] = useState<PillState>("idle");
   const [durationMs, setDurationMs] = useState(0);
   const [error, setError] = useState<string | null>(null);
+  // Live transcript of the recording; the pill grows into a panel once there is one.
+  const [transcript, setTranscript] = useState("");
+  // Without a transparent window the pill fills it, square, rather than floating.
+  const [opaque, setOpaque] = useState(false);
   const stateRef = useRef<PillState>("idle");
   const mediaRecorderRef = useRef<MediaRecorder | null>(null);
   const chunksRef = useRef<Blob[]>([]);
   const timerRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const startTimeRef = useRef<number>(0);
   const mimeTypeRef = useRef<string>("audio/webm");
+  const discardRef = useRef(false);
+  // The app records natively and pastes the result itself; the pill only shows progress.
+  const [native, setNative] = useState(false);
+  const nativeRef = useRef(false);
+  const [level, setLevel] = useState(0);
 
   const setTracked = useCallback((next: PillState) => {
     stateRef.current = next;
@@ -86,7 +95,9 @@ export function DictationPill() {
       const text = data.text ?? "";
       if (text) {
         const invoke = (window as any).__TAURI__?.core?.invoke;
-        if (invoke) await invoke("paste_transcription", { text });
+        const result = invoke ? await invoke("paste_transcription", { text }) : null;
+        if (result?.status === "failed") setError(result.reason);
+        else if (result?.status === "copiedOnly") setError("Couldn't paste into the focused app; the text is on your clipboard");
       }
 
       setTracked("idle");
@@ -113,7 +124,11 @@ export function DictationPill() {
     recorder.onstop = () => {
       const blob = new Blob(chunksRef.current, { type: mimeTypeRef.current });
       chunksRef.current = [];
-      if (blob.size > 0) {
+      if (discardRef.current) {
+        discardRef.current = false;
+        setTracked("idle");
+        setDurationMs(0);
+      } else if (blob.size > 0) {
         sendAndPasteRef.current(blob);
       } else {
         setTracked("idle");
@@ -143,25 +158,67 @@ export function DictationPill() {
     if (err instanceof Error) setError(err.message);
   }, []);
 
-  const startRecording = useCallback(() => {
+  const startRecording = useCallback((nativeCapture: boolean) => {
     if (stateRef.current !== "idle") return;
     setError(null);
+    setTranscript("");
+    nativeRef.current = nativeCapture;
+    setNative(nativeCapture);
+    setLevel(0);
     setTracked("recording");
+    if (nativeCapture) {
+      startTimeRef.current = Date.now();
+      setDurationMs(0);
+      timerRef.current = setInterval(() => setDurationMs(Date.now() - startTimeRef.current), 100);
+    }
   }, [setTracked]);
 
+  const finishNative = useCallback((message: string | null) => {
+    clearTimer();
+    if (message) setError(message);
+    setTracked("idle");
+    setDurationMs(0);
+  }, [clearTimer, setTracked]);
+
   const stopRecording = useCallback(() => {
     if (stateRef.current !== "recording") return;
     clearTimer();
+    if (nativeRef.current) {
+      setTracked("processing");
+      return;
+    }
     const recorder = mediaRecorderRef.current;
     if (recorder && recorder.state !== "inactive") recorder.stop();
     mediaRecorderRef.current = null;
   }, [clearTimer]);
 
+  const cancelRecording = useCallback(() => {
+    if (stateRef.current !== "recording") return;
+    clearTimer();
+    if (nativeRef.current) {
+      finishNative(null);
+      return;
+    }
+    const recorder = mediaRecorderRef.current;
+    mediaRecorderRef.current = null;
+    if (recorder && recorder.state !== "inactive") {
+      discardRef.current = true;
+      recorder.stop();
+    } else {
+      setTracked("idle");
+      setDurationMs(0);
+    }
+  }, [clearTimer, setTracked, finishNative]);
+
   const startRef = useRef(startRecording);
+  const finishNativeRef = useRef(finishNative);
+  finishNativeRef.current = finishNative;
   const stopRef = useRef(stopRecording);
+  const cancelRef = useRef(cancelRecording);
   const stopRecordingRef = useRef(stopRecording);
   startRef.current = startRecording;
   stopRef.current = stopRecording;
+  cancelRef.current = cancelRecording;
   stopRecordingRef.current = stopRecording;
 
   useEffect(() => {
@@ -170,14 +227,37 @@ export function DictationPill() {
 
     const unsubs: Array<() => void> = [];
 
-    tauriEvent.listen("dictation:start-global", () => {
-      startRef.current();
+    tauriEvent.listen("dictation:start-global", (e: { payload: { nativeCapture?: boolean } }) => {
+      startRef.current(e.payload?.nativeCapture ?? false);
     }).then((u: () => void) => unsubs.push(u));
 
     tauriEvent.listen("dictation:stop-global", () => {
       stopRef.current();
     }).then((u: () => void) => unsubs.push(u));
 
+    tauriEvent.listen("dictation:cancel-global", () => {
+      cancelRef.current();
+    }).then((u: () => void) => unsubs.push(u));
+
+    // Native recordings finish here: the text is already pasted, or the attempt failed.
+    tauriEvent.listen("dictation:result", (e: { payload: { mode: string } }) => {
+      if (nativeRef.current && e.payload.mode === "global") finishNativeRef.current(null);
+    }).then((u: () => void) => unsubs.push(u));
+
+    tauriEvent.listen("dictation:error-global", (e: { payload: { message: string } }) => {
+      if (nativeRef.current && stateRef.current !== "idle") finishNativeRef.current(e.payload.message);
+    }).then((u: () => void) => unsubs.push(u));
+
+    // Native capture meters the microphone itself, since the webview has no stream to analyse.
+    // The level is linear RMS; its square root lifts quiet speech into view.
+    tauriEvent.listen("dictation:level", (e: { payload: { rms: number } }) => {
+      if (nativeRef.current && stateRef.current === "recording") setLevel(Math.sqrt(e.payload.rms));
+    }).then((u: () => void) => unsubs.push(u));
+
+    tauriEvent.listen("dictation:partial", (e: { payload: { text: string } }) => {
+      if (stateRef.current === "recording") setTranscript(e.payload.text);
+    }).then((u: () => void) => unsubs.push(u));
+
     return () => { unsubs.forEach((u) => u()); };
   }, []);
 
@@ -185,6 +265,18 @@ export function DictationPill() {
     return () => { clearTimer(); };
   }, [clearTimer]);
 
+  const expanded = state === "recording" && transcript !== "";
+
+  useEffect(() => {
+    const invoke = (window as any).__TAURI__?.core?.invoke;
+    invoke?.("set_pill_state", { state: expanded ? "expanded" : "compact" }).catch(() => {});
+  }, [expanded]);
+
+  const cancel = useCallback(() => {
+    const invoke = (window as any).__TAURI__?.core?.invoke;
+    invoke?.("cancel_dictation").catch(() => {});
+  }, []);
+
   useEffect(() => {
     document.documentElement.style.background = "transparent";
     document.body.style.background = "transparent";
@@ -192,9 +284,13 @@ export function DictationPill() {
     const invoke = (window as any).__TAURI__?.core?.invoke;
     if (!invoke) return;
 
+    invoke("get_pill_transparent")
+      .then((transparent: boolean) => setOpaque(!transparent))
+      .catch(() => {});
+
     const handleMouseDown = (e: MouseEvent) => {
       if ((e.target as HTMLElement).closest("[data-no-drag]")) return;
-      invoke("plugin:window|start_dragging").catch(() => {});
+      invoke("start_pill_drag").catch(() => {});
     };
 
     document.addEventListener("mousedown", handleMouseDown);
@@ -209,38 +305,56 @@ export function DictationPill() {
         : "bg-black/60";
 
   return (
-    <div className="w-screen h-screen flex items-center justify-center p-1 select-none">
+    <div className={`w-screen h-screen flex items-center justify-center select-none ${opaque ? "bg-neutral-900" : "p-1"}`}>
       <div
-        className={`flex items-center justify-center gap-2 w-full h-full rounded-full px-4 text-white text-xs font-medium shadow-lg backdrop-blur-md transition-all duration-300 ${bg}`}
+        className={`flex flex-col w-full h-full text-white text-xs font-medium transition-all duration-300 ${opaque ? "" : `${expanded ? "rounded-2xl" : "rounded-full"} shadow-lg backdrop-blur-md ${bg}`}`}
       >
-        <LiveWaveform
-          active={state === "recording"}
-          processing={state === "processing"}
-          height={28}
-          barWidth={2}
-          barGap={1}
-          barColor="white"
-          fadeEdges={true}
-          fadeWidth={16}
-          mode="static"
-          sensitivity={1.2}
-          onStreamReady={stableOnStreamReady}
-          onError={stableOnError as any}
-        />
-        {state === "recording" && (
-          <span className="tabular-nums text-white/90 shrink-0">{formatDuration(durationMs)}</span>
-        )}
-        {state === "processing" && (
-          <span className="text-white/70 shrink-0">...</span>
-        )}
-        {state === "idle" && (
-          <span className="text-white/50 shrink-0">fn</span>
-        )}
-        {error && (
-          <span className="text-red-300 text-[10px] truncate max-w-[80px] shrink-0" title={error}>
-            {error}
-          </span>
+        {expanded && (
+          <p className="flex-1 min-h-0 overflow-hidden flex flex-col justify-end px-4 pt-3 text-sm font-normal leading-snug text-white/90">
+            {transcript}
+          </p>
         )}
+        <div className={`flex items-center justify-center gap-2 px-4 ${expanded ? "h-10 shrink-0" : "h-full"}`}>
+          <LiveWaveform
+            active={state === "recording"}
+            level={native ? level : undefined}
+            processing={state === "processing"}
+            height={28}
+            barWidth={2}
+            barGap={1}
+            barColor="white"
+            fadeEdges={true}
+            fadeWidth={16}
+            mode="static"
+            sensitivity={1.2}
+            onStreamReady={stableOnStreamReady}
+            onError={stableOnError as any}
+          />
+          {state === "recording" && (
+            <span className="tabular-nums text-white/90 shrink-0">{formatDuration(durationMs)}</span>
+          )}
+          {state === "processing" && (
+            <span className="text-white/70 shrink-0">...</span>
+          )}
+          {state === "idle" && (
+            <span className="text-white/50 shrink-0">fn</span>
+          )}
+          {error && (
+            <span className="text-red-300 text-[10px] truncate max-w-[80px] shrink-0" title={error}>
+              {error}
+            </span>
+          )}
+          {expanded && (
+            <button
+              type="button"
+              data-no-drag
+              onClick={cancel}
+              className="ml-auto rounded-full px-3 py-1 bg-white/10 hover:bg-white/20 text-white/90 shrink-0"
+            >
+              Cancel
+            </button>
+          )}
+        </div>
       </div>
     </div>
   );
@@ -20,6 +20,8 @@ export type LiveWaveformProps = HTMLAttributes<HTMLDivElement> & {
   historySize?: number
   updateRate?: number
   mode?: "scrolling" | "static"
+  /** Input level from 0 to 1 recorded elsewhere, e.g. by the desktop app; the microphone is left closed while set. */
+  level?: number
   onError?: (error: Error) => void
   onStreamReady?: (stream: MediaStream) => void
   onStreamEnd?: () => void
@@ -43,6 +45,7 @@ export const LiveWaveform = ({
   historySize = 60,
   updateRate = 30,
   mode = "static",
+  level,
   onError,
   onStreamReady,
   onStreamEnd,
@@ -64,6 +67,10 @@ export const LiveWaveform = ({
   const needsRedrawRef = useRef(true)
   const gradientCacheRef = useRef<CanvasGradient | null>(null)
   const lastWidthRef = useRef(0)
+  const external = level !== undefined
+  const levelRef = useRef(0)
+  levelRef.current = level ?? 0
+  const levelHistoryRef = useRef<number[]>([])
 
   const heightStyle = typeof height === "number" ? `${height}px` : height
 
@@ -226,7 +233,8 @@ export const LiveWaveform = ({
   }, [processing, active, barWidth, barGap, mode])
 
   useEffect(() => {
-    if (!active) {
+    if (!active || external) {
+      levelHistoryRef.current = []
       if (streamRef.current) {
         streamRef.current.getTracks().forEach((track) => track.stop())
         streamRef.current = null
@@ -308,6 +316,7 @@ export const LiveWaveform = ({
     }
   }, [
     active,
+    external,
     deviceId,
     fftSize,
     smoothingTimeConstant,
@@ -389,6 +398,25 @@ export const LiveWaveform = ({
             }
           }
           needsRedrawRef.current = true
+        } else if (external) {
+          const value = Math.min(1, Math.max(0.05, levelRef.current * sensitivity))
+          if (mode === "static") {
+            // Newest level in the middle, older ones spreading out to both edges.
+            const halfCount = Math.floor(rect.width / (barWidth + barGap) / 2)
+            const levels = levelHistoryRef.current
+            levels.unshift(value)
+            if (levels.length > halfCount) levels.length = halfCount
+            const newBars = [...levels].reverse().concat(levels)
+            staticBarsRef.current = newBars
+            lastActiveDataRef.current = newBars
+          } else {
+            historyRef.current.push(value)
+            lastActiveDataRef.current = [...historyRef.current]
+            if (historyRef.current.length > historySize) {
+              historyRef.current.shift()
+            }
+          }
+          needsRedrawRef.current = true
         }
       }
 
@@ -493,6 +521,7 @@ export const LiveWaveform = ({
     }
   }, [
     active,
+    external,
     processing,
     sensitivity,
     updateRate,
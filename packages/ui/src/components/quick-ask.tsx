@@ -0,0 +1,124 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+
+type Tauri = {
+  core?: { invoke: (cmd: string, args?: Record<string, unknown>) => Promise<any> };
+  event?: { listen: (event: string, cb: (e: { payload: any }) => void) => Promise<() => void> };
+};
+
+function tauri(): Tauri | undefined {
+  return (window as any).__TAURI__;
+}
+
+/** Spotlight-style prompt; the window itself is shown, placed and hidden from Rust. */
+export function QuickAsk() {
+  const [prompt, setPrompt] = useState("");
+  const [answer, setAnswer] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [pending, setPending] = useState(false);
+  const [screenshot, setScreenshot] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+  const submitRef = useRef<(text?: string) => void>(() => {});
+  /** The thumbnail stays up beside its answer; only that one question carried it. */
+  const screenshotSentRef = useRef(false);
+
+  const showScreenshot = useCallback((url: string | null) => {
+    screenshotSentRef.current = false;
+    setScreenshot((previous) => {
+      if (previous) URL.revokeObjectURL(previous);
+      return url;
+    });
+  }, []);
+
+  useEffect(() => {
+    const listen = tauri()?.event?.listen;
+    if (!listen) return;
+    const unsubs: Array<() => void> = [];
+    const takePrefill = () => {
+      tauri()?.core?.invoke("take_quick_ask_prefill").then(async (prefill: { text: string; submit: boolean; screenshot: boolean } | null) => {
+        if (prefill == null) return;
+        setPrompt(prefill.text);
+        showScreenshot(null);
+        if (prefill.screenshot) {
+          const png: ArrayBuffer = await tauri()!.core!.invoke("get_quick_ask_screenshot");
+          showScreenshot(URL.createObjectURL(new Blob([png], { type: "image/png" })));
+        }
+        if (prefill.submit) submitRef.current(prefill.text);
+      }).catch(() => {});
+    };
+    takePrefill();
+    listen("quick-ask:shown", () => inputRef.current?.select()).then((u) => unsubs.push(u));
+    listen("quick-ask:prefill", takePrefill).then((u) => unsubs.push(u));
+    listen("quick-ask:delta", (e) => setAnswer((a) => a + e.payload.delta)).then((u) => unsubs.push(u));
+    listen("quick-ask:done", (e) => {
+      setPending(false);
+      if (e.payload.error) setError(e.payload.error);
+      else setAnswer(e.payload.answer ?? "");
+    }).then((u) => unsubs.push(u));
+    return () => { unsubs.forEach((u) => u()); };
+  }, [showScreenshot]);
+
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if (e.key === "Escape") tauri()?.core?.invoke("hide_quick_ask").catch(() => {});
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, []);
+
+  const submit = useCallback(async (text: string = prompt) => {
+    const invoke = tauri()?.core?.invoke;
+    if (!invoke || pending || !text.trim()) return;
+    setAnswer("");
+    setError(null);
+    setPending(true);
+    if (screenshotSentRef.current) showScreenshot(null);
+    else screenshotSentRef.current = true;
+    try {
+      await invoke("submit_quick_ask", { prompt: text });
+    } catch (e) {
+      setPending(false);
+      setError(String(e));
+    }
+  }, [pending, prompt, showScreenshot]);
+  submitRef.current = submit;
+
+  const removeScreenshot = useCallback(() => {
+    showScreenshot(null);
+    tauri()?.core?.invoke("detach_quick_ask_screenshot").catch(() => {});
+    inputRef.current?.focus();
+  }, [showScreenshot]);
+
+  return (
+    <div className="w-screen h-screen flex flex-col rounded-xl bg-neutral-900 text-white shadow-2xl overflow-hidden">
+      <input
+        ref={inputRef}
+        autoFocus
+        value={prompt}
+        onChange={(e) => setPrompt(e.target.value)}
+        onKeyDown={(e) => {
+          if (e.key === "Enter") {
+            e.preventDefault();
+            submit();
+          }
+        }}
+        placeholder="Ask spaceduck…"
+        className="w-full px-5 py-4 bg-transparent text-lg outline-none border-b border-white/10 placeholder:text-white/40"
+      />
+      {screenshot && (
+        <div className="flex items-center gap-3 px-5 py-2 border-b border-white/10">
+          <img src={screenshot} alt="Attached screenshot" className="h-12 max-w-[8rem] rounded object-cover" />
+          <button
+            type="button"
+            onClick={removeScreenshot}
+            className="text-xs text-white/50 hover:text-white"
+          >
+            Remove
+          </button>
+        </div>
+      )}
+      <div className="flex-1 overflow-y-auto px-5 py-3 text-sm leading-relaxed whitespace-pre-wrap">
+        {error ? <span className="text-red-300">{error}</span> : answer || (pending && <span className="text-white/50">…</span>)}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,94 @@
+import { useEffect, useRef, useState } from "react";
+
+type Tauri = {
+  core?: { invoke: (cmd: string, args?: Record<string, unknown>) => Promise<any> };
+  event?: { listen: (event: string, cb: (e: { payload: any }) => void) => Promise<() => void> };
+};
+
+function tauri(): Tauri | undefined {
+  return (window as any).__TAURI__;
+}
+
+/** Answer to a spoken question, next to the pill; Rust places, sizes and hides the window. */
+export function ResponseWindow() {
+  const [question, setQuestion] = useState("");
+  const [answer, setAnswer] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [pending, setPending] = useState(false);
+  const [pinned, setPinned] = useState(false);
+  const contentRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    const listen = tauri()?.event?.listen;
+    if (!listen) return;
+    const unsubs: Array<() => void> = [];
+    listen("response:question", (e) => {
+      setQuestion(e.payload.question);
+      setAnswer("");
+      setError(null);
+      setPending(true);
+      setPinned(false);
+    }).then((u) => unsubs.push(u));
+    listen("response:delta", (e) => setAnswer((a) => a + e.payload.delta)).then((u) => unsubs.push(u));
+    listen("response:done", (e) => {
+      setPending(false);
+      if (e.payload.error) setError(e.payload.error);
+      else setAnswer(e.payload.answer ?? "");
+    }).then((u) => unsubs.push(u));
+    return () => { unsubs.forEach((u) => u()); };
+  }, []);
+
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if (e.key === "Escape") tauri()?.core?.invoke("dismiss_response_window").catch(() => {});
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, []);
+
+  useEffect(() => {
+    const content = contentRef.current;
+    const invoke = tauri()?.core?.invoke;
+    if (!content || !invoke) return;
+    const observer = new ResizeObserver(() => {
+      invoke("resize_response_window", { height: content.scrollHeight }).catch(() => {});
+    });
+    observer.observe(content);
+    return () => observer.disconnect();
+  }, []);
+
+  const togglePin = () => {
+    const next = !pinned;
+    setPinned(next);
+    tauri()?.core?.invoke("set_response_window_pinned", { pinned: next }).catch(() => {});
+  };
+
+  return (
+    <div className="w-screen h-screen rounded-xl bg-neutral-900 text-white shadow-2xl overflow-y-auto">
+      <div ref={contentRef} className="flex flex-col gap-2 px-4 py-3">
+        <div className="flex items-start gap-2">
+          <p className="flex-1 text-xs text-white/50 line-clamp-2">{question}</p>
+          <button
+            type="button"
+            onClick={togglePin}
+            title={pinned ? "Unpin" : "Keep on screen"}
+            className={`shrink-0 rounded px-1.5 text-xs ${pinned ? "bg-white/20 text-white" : "text-white/50 hover:text-white"}`}
+          >
+            {pinned ? "Pinned" : "Pin"}
+          </button>
+          <button
+            type="button"
+            onClick={() => tauri()?.core?.invoke("dismiss_response_window").catch(() => {})}
+            title="Close (Esc)"
+            className="shrink-0 px-1 text-xs text-white/50 hover:text-white"
+          >
+            ✕
+          </button>
+        </div>
+        <div className="text-sm leading-relaxed whitespace-pre-wrap">
+          {error ? <span className="text-red-300">{error}</span> : answer || (pending && <span className="text-white/50">…</span>)}
+        </div>
+      </div>
+    </div>
+  );
+}
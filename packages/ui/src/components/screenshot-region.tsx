@@ -0,0 +1,95 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+
+function getInvoke() {
+  return (window as any).__TAURI__?.core?.invoke as
+    | ((cmd: string, args?: Record<string, unknown>) => Promise<any>)
+    | undefined;
+}
+
+type Point = { x: number; y: number };
+
+/** The screen frozen under a crosshair; drag out a region to capture, Escape to cancel. */
+export function ScreenshotRegion() {
+  const [image, setImage] = useState<string | null>(null);
+  const [start, setStart] = useState<Point | null>(null);
+  const [end, setEnd] = useState<Point | null>(null);
+  const doneRef = useRef(false);
+
+  const finish = useCallback((region: { x: number; y: number; width: number; height: number } | null) => {
+    if (doneRef.current) return;
+    doneRef.current = true;
+    getInvoke()?.("finish_screenshot_region", { region }).catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    let url: string | null = null;
+    getInvoke()?.("get_screenshot_region_image")
+      .then((png: ArrayBuffer) => {
+        url = URL.createObjectURL(new Blob([png], { type: "image/png" }));
+        setImage(url);
+      })
+      .catch(() => finish(null));
+    return () => { if (url) URL.revokeObjectURL(url); };
+  }, [finish]);
+
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if (e.key === "Escape") {
+        e.preventDefault();
+        finish(null);
+      }
+    };
+    window.addEventListener("keydown", onKey);
+    return () => window.removeEventListener("keydown", onKey);
+  }, [finish]);
+
+  const rect = start && end
+    ? {
+        left: Math.min(start.x, end.x),
+        top: Math.min(start.y, end.y),
+        width: Math.abs(end.x - start.x),
+        height: Math.abs(end.y - start.y),
+      }
+    : null;
+
+  const onMouseUp = () => {
+    if (!rect || rect.width < 4 || rect.height < 4) {
+      setStart(null);
+      setEnd(null);
+      return;
+    }
+    finish({
+      x: rect.left / window.innerWidth,
+      y: rect.top / window.innerHeight,
+      width: rect.width / window.innerWidth,
+      height: rect.height / window.innerHeight,
+    });
+  };
+
+  return (
+    <div
+      className="relative w-screen h-screen overflow-hidden cursor-crosshair select-none bg-black"
+      onMouseDown={(e) => {
+        setStart({ x: e.clientX, y: e.clientY });
+        setEnd({ x: e.clientX, y: e.clientY });
+      }}
+      onMouseMove={(e) => { if (start) setEnd({ x: e.clientX, y: e.clientY }); }}
+      onMouseUp={onMouseUp}
+    >
+      {image && <img src={image} alt="" draggable={false} className="absolute inset-0 w-full h-full" />}
+      {rect ? (
+        <div
+          className="absolute border border-white"
+          style={{ ...rect, boxShadow: "0 0 0 9999px rgba(0, 0, 0, 0.45)" }}
+        />
+      ) : (
+        <div className="absolute inset-0 bg-black/45" />
+      )}
+      {!rect && (
+        <p className="absolute top-6 left-1/2 -translate-x-1/2 rounded-lg bg-black/70 px-3 py-1.5 text-xs text-white">
+          Drag to capture a region · Esc to cancel
+        </p>
+      )}
+    </div>
+  );
+}
@@ -1,7 +1,7 @@
 import { useState, useRef, useEffect } from "react";
 import { cn } from "../lib/utils";
 import type { ConversationSummary } from "@spaceduck/core";
-import { MessageSquarePlus, Trash2, MessageCircle, Settings, Sun, Moon, MoreHorizontal, Pencil, Loader2, ListTodo, Brain } from "lucide-react";
+import { MessageSquarePlus, Trash2, MessageCircle, Settings, Sun, Moon, MoreHorizontal, Pencil, Loader2, ListTodo, Brain, AppWindow } from "lucide-react";
 import uiPkg from "../../package.json";
 import { SpaceduckLogo } from "./spaceduck-logo";
 import { Button } from "../ui/button";
@@ -44,6 +44,9 @@ function timeAgo(ts: number): string {
   return `${Math.floor(seconds / 86400)}d ago`;
 }
 
+const invokeTauri: ((cmd: string, args?: Record<string, unknown>) => Promise<unknown>) | undefined =
+  typeof window !== "undefined" ? (window as any).__TAURI__?.core?.invoke : undefined;
+
 export function Sidebar({ conversations, activeId, streamingIds, unreadIds, onSelect, onCreate, onDelete, onRename, onOpenSettings, onOpenTasks, onOpenMemory }: SidebarProps) {
   const { resolved, setTheme } = useTheme();
   const { tasks, budget } = useTasks({ pollIntervalMs: 30_000 });
@@ -185,6 +188,17 @@ export function Sidebar({ conversations, activeId, streamingIds, unreadIds, onSe
                         <Pencil size={14} />
                         Rename
                       </DropdownMenuItem>
+                      {invokeTauri && (
+                        <DropdownMenuItem
+                          onClick={(e) => {
+                            e.stopPropagation();
+                            invokeTauri("open_conversation_window", { conversationId: conv.id }).catch(() => {});
+                          }}
+                        >
+                          <AppWindow size={14} />
+                          Open in new window
+                        </DropdownMenuItem>
+                      )}
                       <DropdownMenuItem
                         className="text-destructive focus:text-destructive"
                         onClick={(e) => {
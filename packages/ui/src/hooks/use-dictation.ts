@@ -28,8 +28,9 @@ function isTauriEnv(): boolean {
 // Stored on globalThis so the same listener survives module hot-replacement.
 interface DictationGlobal {
   fnListenerActive: boolean;
-  onChatStart: (() => void) | null;
+  onChatStart: ((payload: { nativeCapture?: boolean }) => void) | null;
   onChatStop: (() => void) | null;
+  onChatCancel: (() => void) | null;
 }
 
 const GLOBAL_KEY = "__spaceduck_dictation__";
@@ -41,6 +42,7 @@ function getDictationGlobal(): DictationGlobal {
       fnListenerActive: false,
       onChatStart: null,
       onChatStop: null,
+      onChatCancel: null,
     };
   }
   return g[GLOBAL_KEY];
@@ -58,12 +60,15 @@ function ensureFnListeners() {
     return;
   }
 
-  tauriEvent.listen("dictation:start-chat", () => {
-    dg.onChatStart?.();
+  tauriEvent.listen("dictation:start-chat", (e: { payload: { nativeCapture?: boolean } }) => {
+    dg.onChatStart?.(e.payload ?? {});
   });
   tauriEvent.listen("dictation:stop-chat", () => {
     dg.onChatStop?.();
   });
+  tauriEvent.listen("dictation:cancel-chat", () => {
+    dg.onChatCancel?.();
+  });
 }
 
 export function useDictation(opts: UseDictationOptions): UseDictationReturn {
@@ -86,16 +91,24 @@ export function useDictation(opts: UseDictationOptions): UseDictationReturn {
     ensureFnListeners();
     const dg = getDictationGlobal();
 
-    dg.onChatStart = () => {
-      opts.chatRecorderRef?.current?.startRecording();
+    // With native capture the app records and sends the text as `dictation:result`,
+    // which the chat input takes directly.
+    let native = false;
+    dg.onChatStart = ({ nativeCapture }) => {
+      native = nativeCapture ?? false;
+      if (!native) opts.chatRecorderRef?.current?.startRecording();
     };
     dg.onChatStop = () => {
-      opts.chatRecorderRef?.current?.stopAndTranscribe();
+      if (!native) opts.chatRecorderRef?.current?.stopAndTranscribe();
+    };
+    dg.onChatCancel = () => {
+      if (!native) opts.chatRecorderRef?.current?.cancelRecording();
     };
 
     return () => {
       dg.onChatStart = null;
       dg.onChatStop = null;
+      dg.onChatCancel = null;
     };
   }, [supported, opts.enabled, opts.hotkey, opts.chatRecorderRef]);
 
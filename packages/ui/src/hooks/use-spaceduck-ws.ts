@@ -233,6 +233,26 @@ export function useSpaceduckWs(enabled = true): UseSpaceduckWs {
     };
   }, [connect, enabled]);
 
+  // After the desktop app wakes from sleep the socket can look open while the
+  // gateway behind it is gone, so start over with a fresh connection.
+  useEffect(() => {
+    const listen = (window as any).__TAURI__?.event?.listen;
+    if (!enabled || !listen) return;
+    let unlisten: (() => void) | undefined;
+    listen("system-resumed", () => {
+      if (unmountedRef.current) return;
+      if (reconnectTimerRef.current) {
+        clearTimeout(reconnectTimerRef.current);
+        reconnectTimerRef.current = null;
+      }
+      wsRef.current?.close();
+      wsRef.current = null;
+      retriesRef.current = 0;
+      connect();
+    }).then((u: () => void) => { unlisten = u; });
+    return () => unlisten?.();
+  }, [connect, enabled]);
+
   function handleServerMessage(envelope: WsServerEnvelope) {
     switch (envelope.type) {
       case "conversation.list":
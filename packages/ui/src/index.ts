@@ -1,5 +1,9 @@
 export { App } from "./app";
+export { ConfirmOverlay } from "./components/confirm-overlay";
 export { DictationPill } from "./components/dictation-pill";
+export { QuickAsk } from "./components/quick-ask";
+export { ResponseWindow } from "./components/response-window";
+export { ScreenshotRegion } from "./components/screenshot-region";
 export { useSpaceduckWs } from "./hooks/use-spaceduck-ws";
 export type { ConnectionStatus, PendingStream, UseSpaceduckWs } from "./hooks/use-spaceduck-ws";
 export { ThemeProvider, useTheme } from "./hooks/use-theme";